@@ -0,0 +1,13 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::all)]
+
+use ethereum_types::H160;
+use primitives::evm::Erc20Metadata;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    pub trait AssetRegistryApi {
+        /// Returns the registered ERC20 tokens and their cached metadata.
+        fn erc20_assets() -> Vec<(H160, Erc20Metadata)>;
+    }
+}
@@ -0,0 +1,77 @@
+//! Asset registry rpc interface.
+
+use ethereum_types::H160;
+use jsonrpc_core::{Error, ErrorCode, Result};
+use jsonrpc_derive::rpc;
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_runtime::{generic::BlockId, traits::Block as BlockT};
+use std::{marker::PhantomData, sync::Arc};
+
+pub use module_asset_registry_rpc_runtime_api::AssetRegistryApi as AssetRegistryRuntimeApi;
+pub use rpc_impl_AssetRegistryApi::gen_server::AssetRegistryApi as AssetRegistryApiServer;
+
+/// A registered ERC20 token.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Erc20Asset {
+    /// Address of the contract.
+    pub address: H160,
+    /// Name of the token.
+    pub name: String,
+    /// Symbol of the token.
+    pub symbol: String,
+    /// Decimals of the token.
+    pub decimals: u8,
+}
+
+/// Asset registry rpc interface.
+#[rpc(server)]
+pub trait AssetRegistryApi<BlockHash> {
+    /// Returns the registered ERC20 tokens and their metadata.
+    #[rpc(name = "assetRegistry_erc20Assets")]
+    fn erc20_assets(&self, at: Option<BlockHash>) -> Result<Vec<Erc20Asset>>;
+}
+
+pub struct AssetRegistry<B, C> {
+    client: Arc<C>,
+    _marker: PhantomData<B>,
+}
+
+impl<B, C> AssetRegistry<B, C> {
+    pub fn new(client: Arc<C>) -> Self {
+        Self {
+            client,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<B, C> AssetRegistryApi<<B as BlockT>::Hash> for AssetRegistry<B, C>
+where
+    B: BlockT,
+    C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + 'static,
+    C::Api: AssetRegistryRuntimeApi<B>,
+{
+    fn erc20_assets(&self, at: Option<<B as BlockT>::Hash>) -> Result<Vec<Erc20Asset>> {
+        let api = self.client.runtime_api();
+        let at = BlockId::hash(at.unwrap_or_else(|| self.client.info().best_hash));
+
+        let assets = api.erc20_assets(&at).map_err(|err| Error {
+            code: ErrorCode::InternalError,
+            message: "Unable to query the registered ERC20 tokens.".into(),
+            data: Some(format!("{:?}", err).into()),
+        })?;
+
+        Ok(assets
+            .into_iter()
+            .map(|(address, metadata)| Erc20Asset {
+                address,
+                name: String::from_utf8_lossy(&metadata.name).into_owned(),
+                symbol: String::from_utf8_lossy(&metadata.symbol).into_owned(),
+                decimals: metadata.decimals,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,195 @@
+//! # Asset Registry
+//!
+//! The ERC20 tokens usable as `CurrencyId::ERC20`. Governance registers a
+//! contract, its name, symbol and decimals are read through the EVM bridge
+//! once and cached with the registration.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::unused_unit)]
+
+use frame_support::{pallet_prelude::*, traits::StorageVersion, transactional};
+use frame_system::pallet_prelude::*;
+use primitives::{
+    evm::{Erc20Metadata, EvmAddress},
+    Balance,
+};
+use sp_std::vec::Vec;
+use support::{EVMBridge, Erc20Registry, InvokeContext};
+
+mod default_weight;
+pub mod migrations;
+mod mock;
+mod tests;
+
+pub use module::*;
+
+pub trait WeightInfo {
+    fn register_erc20() -> Weight;
+    fn update_erc20() -> Weight;
+    fn deregister_erc20() -> Weight;
+}
+
+#[frame_support::pallet]
+pub mod module {
+    use super::*;
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type Event: From<Event> + IsType<<Self as frame_system::Config>::Event>;
+        /// Bridge reading the metadata of the ERC20 contracts.
+        type EVMBridge: EVMBridge<Self::AccountId, Balance>;
+        /// The origin which may register and deregister ERC20 tokens.
+        type RegisterOrigin: EnsureOrigin<Self::Origin>;
+        /// Weight information for extrinsics in this module.
+        type WeightInfo: WeightInfo;
+    }
+
+    #[pallet::error]
+    pub enum Error<T> {
+        /// The ERC20 token is already registered
+        AlreadyRegistered,
+        /// The ERC20 token is not registered
+        NotRegistered,
+        /// The contract doesn't implement the ERC20 metadata functions
+        InvalidErc20,
+    }
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(crate) fn deposit_event)]
+    pub enum Event {
+        /// ERC20 token registered. [contract, metadata]
+        Erc20Registered(EvmAddress, Erc20Metadata),
+        /// ERC20 token metadata refreshed. [contract, metadata]
+        Erc20Updated(EvmAddress, Erc20Metadata),
+        /// ERC20 token deregistered. [contract]
+        Erc20Deregistered(EvmAddress),
+    }
+
+    /// The registered ERC20 tokens and their cached metadata.
+    ///
+    /// Erc20Assets: map contract => Option<Erc20Metadata>
+    #[pallet::storage]
+    #[pallet::getter(fn erc20_assets)]
+    pub type Erc20Assets<T: Config> = StorageMap<_, Twox64Concat, EvmAddress, Erc20Metadata, OptionQuery>;
+
+    #[pallet::genesis_config]
+    pub struct GenesisConfig {
+        pub erc20_assets: Vec<(EvmAddress, Erc20Metadata)>,
+    }
+
+    #[cfg(feature = "std")]
+    impl Default for GenesisConfig {
+        fn default() -> Self {
+            GenesisConfig {
+                erc20_assets: Default::default(),
+            }
+        }
+    }
+
+    #[pallet::genesis_build]
+    impl<T: Config> GenesisBuild<T> for GenesisConfig {
+        fn build(&self) {
+            for (contract, metadata) in &self.erc20_assets {
+                Erc20Assets::<T>::insert(contract, metadata);
+            }
+        }
+    }
+
+    /// The current storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
+    #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
+    pub struct Pallet<T>(PhantomData<T>);
+
+    #[pallet::hooks]
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Register the ERC20 token at `contract`, caching its name, symbol
+        /// and decimals.
+        ///
+        /// The dispatch origin of this call must be `RegisterOrigin`.
+        #[pallet::weight(T::WeightInfo::register_erc20())]
+        #[transactional]
+        pub fn register_erc20(origin: OriginFor<T>, contract: EvmAddress) -> DispatchResultWithPostInfo {
+            T::RegisterOrigin::ensure_origin(origin)?;
+            ensure!(!Erc20Assets::<T>::contains_key(contract), Error::<T>::AlreadyRegistered);
+
+            let metadata = Self::read_metadata(contract)?;
+            Erc20Assets::<T>::insert(contract, &metadata);
+
+            Self::deposit_event(Event::Erc20Registered(contract, metadata));
+            Ok(().into())
+        }
+
+        /// Read the metadata of the registered ERC20 token at `contract`
+        /// again, e.g. after an upgrade of the contract.
+        ///
+        /// The dispatch origin of this call must be `RegisterOrigin`.
+        #[pallet::weight(T::WeightInfo::update_erc20())]
+        #[transactional]
+        pub fn update_erc20(origin: OriginFor<T>, contract: EvmAddress) -> DispatchResultWithPostInfo {
+            T::RegisterOrigin::ensure_origin(origin)?;
+            ensure!(Erc20Assets::<T>::contains_key(contract), Error::<T>::NotRegistered);
+
+            let metadata = Self::read_metadata(contract)?;
+            Erc20Assets::<T>::insert(contract, &metadata);
+
+            Self::deposit_event(Event::Erc20Updated(contract, metadata));
+            Ok(().into())
+        }
+
+        /// Deregister the ERC20 token at `contract`. Reserved and locked
+        /// balances of the token can still be released.
+        ///
+        /// The dispatch origin of this call must be `RegisterOrigin`.
+        #[pallet::weight(T::WeightInfo::deregister_erc20())]
+        pub fn deregister_erc20(origin: OriginFor<T>, contract: EvmAddress) -> DispatchResultWithPostInfo {
+            T::RegisterOrigin::ensure_origin(origin)?;
+            ensure!(Erc20Assets::<T>::contains_key(contract), Error::<T>::NotRegistered);
+
+            Erc20Assets::<T>::remove(contract);
+
+            Self::deposit_event(Event::Erc20Deregistered(contract));
+            Ok(().into())
+        }
+    }
+}
+
+impl<T: Config> Pallet<T> {
+    /// The registered ERC20 tokens and their metadata.
+    pub fn erc20_assets_list() -> Vec<(EvmAddress, Erc20Metadata)> {
+        Erc20Assets::<T>::iter().collect()
+    }
+
+    fn read_metadata(contract: EvmAddress) -> Result<Erc20Metadata, DispatchError> {
+        let context = InvokeContext {
+            contract,
+            sender: Default::default(),
+            origin: Default::default(),
+        };
+
+        let invalid = |_| Error::<T>::InvalidErc20;
+        Ok(Erc20Metadata {
+            name: T::EVMBridge::name(context).map_err(invalid)?,
+            symbol: T::EVMBridge::symbol(context).map_err(invalid)?,
+            decimals: T::EVMBridge::decimals(context).map_err(invalid)?,
+        })
+    }
+}
+
+impl<T: Config> Erc20Registry for Pallet<T> {
+    fn is_registered(contract: EvmAddress) -> bool {
+        Erc20Assets::<T>::contains_key(contract)
+    }
+
+    fn metadata(contract: EvmAddress) -> Option<Erc20Metadata> {
+        Self::erc20_assets(contract)
+    }
+
+    fn contracts() -> Vec<EvmAddress> {
+        Erc20Assets::<T>::iter_keys().collect()
+    }
+}
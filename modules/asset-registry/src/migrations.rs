@@ -0,0 +1,42 @@
+//! Storage migrations of the asset registry module.
+
+use crate::*;
+use frame_support::traits::OnRuntimeUpgrade;
+
+/// Register the ERC20 tokens used as `CurrencyId::ERC20` before the
+/// registry, which accepted any contract.
+pub mod v1 {
+    use super::*;
+
+    /// Register the contracts of `Contracts`, chosen by governance like the
+    /// ones of `register_erc20`. The contracts not implementing the ERC20
+    /// metadata functions are skipped.
+    pub struct RegisterErc20Contracts<T, Contracts>(PhantomData<(T, Contracts)>);
+
+    impl<T, Contracts> OnRuntimeUpgrade for RegisterErc20Contracts<T, Contracts>
+    where
+        T: Config,
+        Contracts: Get<Vec<EvmAddress>>,
+    {
+        fn on_runtime_upgrade() -> Weight {
+            let mut weight = T::DbWeight::get().reads(1);
+            if StorageVersion::get::<Pallet<T>>() >= 1 {
+                return weight;
+            }
+
+            for contract in Contracts::get() {
+                if Erc20Assets::<T>::contains_key(contract) {
+                    continue;
+                }
+                if let Ok(metadata) = Pallet::<T>::read_metadata(contract) {
+                    Erc20Assets::<T>::insert(contract, &metadata);
+                    Pallet::<T>::deposit_event(Event::Erc20Registered(contract, metadata));
+                }
+                weight = weight.saturating_add(T::WeightInfo::register_erc20());
+            }
+
+            StorageVersion::new(1).put::<Pallet<T>>();
+            weight.saturating_add(T::DbWeight::get().writes(1))
+        }
+    }
+}
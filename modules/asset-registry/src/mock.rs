@@ -0,0 +1,178 @@
+//! Mocks for the asset registry module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{construct_runtime, ord_parameter_types, parameter_types};
+use frame_system::EnsureSignedBy;
+use sp_core::H256;
+use sp_runtime::{testing::Header, traits::IdentityLookup};
+use sp_std::str::FromStr;
+
+pub type AccountId = u128;
+pub type BlockNumber = u64;
+
+pub const ALICE: AccountId = 1;
+pub const COUNCIL: AccountId = 2;
+
+mod asset_registry {
+    pub use super::super::*;
+}
+
+parameter_types! {
+    pub const BlockHashCount: u64 = 250;
+}
+
+impl frame_system::Config for Runtime {
+    type BaseCallFilter = frame_support::traits::Everything;
+    type Origin = Origin;
+    type Call = Call;
+    type Index = u64;
+    type BlockNumber = BlockNumber;
+    type Hash = H256;
+    type Hashing = ::sp_runtime::traits::BlakeTwo256;
+    type AccountId = AccountId;
+    type Lookup = IdentityLookup<Self::AccountId>;
+    type Header = Header;
+    type Event = Event;
+    type BlockHashCount = BlockHashCount;
+    type DbWeight = ();
+    type BlockWeights = ();
+    type BlockLength = ();
+    type Version = ();
+    type PalletInfo = PalletInfo;
+    type AccountData = ();
+    type OnNewAccount = ();
+    type OnKilledAccount = ();
+    type SystemWeightInfo = ();
+    type SS58Prefix = ();
+    type OnSetCode = ();
+}
+
+pub fn erc20_address() -> EvmAddress {
+    EvmAddress::from_str("2000000000000000000000000000000000000001").unwrap()
+}
+
+pub fn not_erc20_address() -> EvmAddress {
+    EvmAddress::from_str("2000000000000000000000000000000000000002").unwrap()
+}
+
+parameter_types! {
+    pub static Erc20Decimals: u8 = 18;
+}
+
+/// Answers the metadata calls of `erc20_address()`, every other contract
+/// reverts.
+pub struct MockEVMBridge;
+impl EVMBridge<AccountId, Balance> for MockEVMBridge {
+    fn name(context: InvokeContext) -> Result<Vec<u8>, DispatchError> {
+        Self::ensure_erc20(context)?;
+        Ok(b"Ovr Dollar".to_vec())
+    }
+
+    fn symbol(context: InvokeContext) -> Result<Vec<u8>, DispatchError> {
+        Self::ensure_erc20(context)?;
+        Ok(b"OUSD".to_vec())
+    }
+
+    fn decimals(context: InvokeContext) -> Result<u8, DispatchError> {
+        Self::ensure_erc20(context)?;
+        Ok(Erc20Decimals::get())
+    }
+
+    fn total_supply(_context: InvokeContext) -> Result<Balance, DispatchError> {
+        unimplemented!()
+    }
+
+    fn balance_of(_context: InvokeContext, _address: EvmAddress) -> Result<Balance, DispatchError> {
+        unimplemented!()
+    }
+
+    fn transfer(_context: InvokeContext, _to: EvmAddress, _value: Balance) -> DispatchResult {
+        unimplemented!()
+    }
+
+    fn approve(_context: InvokeContext, _spender: EvmAddress, _value: Balance) -> DispatchResult {
+        unimplemented!()
+    }
+
+    fn allowance(_context: InvokeContext, _owner: EvmAddress, _spender: EvmAddress) -> Result<Balance, DispatchError> {
+        unimplemented!()
+    }
+
+    fn transfer_from(_context: InvokeContext, _from: EvmAddress, _to: EvmAddress, _value: Balance) -> DispatchResult {
+        unimplemented!()
+    }
+
+    fn get_origin() -> Option<AccountId> {
+        None
+    }
+
+    fn set_origin(_origin: AccountId) {}
+}
+
+impl MockEVMBridge {
+    fn ensure_erc20(context: InvokeContext) -> DispatchResult {
+        if context.contract == erc20_address() {
+            Ok(())
+        } else {
+            Err(DispatchError::Other("execution revert"))
+        }
+    }
+}
+
+ord_parameter_types! {
+    pub const CouncilAccount: AccountId = COUNCIL;
+}
+
+impl Config for Runtime {
+    type Event = Event;
+    type EVMBridge = MockEVMBridge;
+    type RegisterOrigin = EnsureSignedBy<CouncilAccount, AccountId>;
+    type WeightInfo = ();
+}
+pub type AssetRegistryModule = Pallet<Runtime>;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
+type Block = frame_system::mocking::MockBlock<Runtime>;
+
+construct_runtime!(
+    pub enum Runtime where
+        Block = Block,
+        NodeBlock = Block,
+        UncheckedExtrinsic = UncheckedExtrinsic,
+    {
+        System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
+        AssetRegistry: asset_registry::{Pallet, Call, Storage, Config, Event},
+    }
+);
+
+#[derive(Default)]
+pub struct ExtBuilder {
+    erc20_assets: Vec<(EvmAddress, Erc20Metadata)>,
+}
+
+impl ExtBuilder {
+    pub fn erc20_assets(mut self, erc20_assets: Vec<(EvmAddress, Erc20Metadata)>) -> Self {
+        self.erc20_assets = erc20_assets;
+        self
+    }
+
+    pub fn build(self) -> sp_io::TestExternalities {
+        let mut t = frame_system::GenesisConfig::default()
+            .build_storage::<Runtime>()
+            .unwrap();
+
+        GenesisBuild::<Runtime>::assimilate_storage(
+            &asset_registry::GenesisConfig {
+                erc20_assets: self.erc20_assets,
+            },
+            &mut t,
+        )
+        .unwrap();
+
+        let mut ext = sp_io::TestExternalities::new(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+}
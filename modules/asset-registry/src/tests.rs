@@ -0,0 +1,144 @@
+//! Unit tests for the asset registry module.
+
+#![cfg(test)]
+
+use super::*;
+use frame_support::{assert_noop, assert_ok, parameter_types, traits::OnRuntimeUpgrade};
+use mock::{
+    erc20_address, not_erc20_address, AssetRegistryModule, Erc20Decimals, Event, ExtBuilder, Origin, Runtime, System,
+    ALICE, COUNCIL,
+};
+use sp_runtime::traits::BadOrigin;
+
+fn ousd_metadata(decimals: u8) -> Erc20Metadata {
+    Erc20Metadata {
+        name: b"Ovr Dollar".to_vec(),
+        symbol: b"OUSD".to_vec(),
+        decimals,
+    }
+}
+
+#[test]
+fn register_erc20_should_work() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert!(!AssetRegistryModule::is_registered(erc20_address()));
+
+        assert_noop!(
+            AssetRegistryModule::register_erc20(Origin::signed(ALICE), erc20_address()),
+            BadOrigin
+        );
+        assert_ok!(AssetRegistryModule::register_erc20(
+            Origin::signed(COUNCIL),
+            erc20_address()
+        ));
+        let event = Event::AssetRegistry(crate::Event::Erc20Registered(erc20_address(), ousd_metadata(18)));
+        assert!(System::events().iter().any(|record| record.event == event));
+
+        assert!(AssetRegistryModule::is_registered(erc20_address()));
+        assert_eq!(
+            AssetRegistryModule::erc20_assets(erc20_address()),
+            Some(ousd_metadata(18))
+        );
+        assert_eq!(
+            AssetRegistryModule::erc20_assets_list(),
+            vec![(erc20_address(), ousd_metadata(18))]
+        );
+
+        assert_noop!(
+            AssetRegistryModule::register_erc20(Origin::signed(COUNCIL), erc20_address()),
+            Error::<Runtime>::AlreadyRegistered
+        );
+    });
+}
+
+#[test]
+fn register_erc20_should_fail_for_non_erc20() {
+    ExtBuilder::default().build().execute_with(|| {
+        assert_noop!(
+            AssetRegistryModule::register_erc20(Origin::signed(COUNCIL), not_erc20_address()),
+            Error::<Runtime>::InvalidErc20
+        );
+        assert!(!AssetRegistryModule::is_registered(not_erc20_address()));
+    });
+}
+
+#[test]
+fn update_erc20_should_work() {
+    ExtBuilder::default()
+        .erc20_assets(vec![(erc20_address(), ousd_metadata(18))])
+        .build()
+        .execute_with(|| {
+            assert_noop!(
+                AssetRegistryModule::update_erc20(Origin::signed(COUNCIL), not_erc20_address()),
+                Error::<Runtime>::NotRegistered
+            );
+
+            // the cached metadata isn't refreshed until `update_erc20`
+            Erc20Decimals::set(6);
+            assert_eq!(AssetRegistryModule::metadata(erc20_address()), Some(ousd_metadata(18)));
+
+            assert_ok!(AssetRegistryModule::update_erc20(
+                Origin::signed(COUNCIL),
+                erc20_address()
+            ));
+            let event = Event::AssetRegistry(crate::Event::Erc20Updated(erc20_address(), ousd_metadata(6)));
+            assert!(System::events().iter().any(|record| record.event == event));
+            assert_eq!(AssetRegistryModule::metadata(erc20_address()), Some(ousd_metadata(6)));
+        });
+}
+
+#[test]
+fn deregister_erc20_should_work() {
+    ExtBuilder::default()
+        .erc20_assets(vec![(erc20_address(), ousd_metadata(18))])
+        .build()
+        .execute_with(|| {
+            assert!(AssetRegistryModule::is_registered(erc20_address()));
+
+            assert_noop!(
+                AssetRegistryModule::deregister_erc20(Origin::signed(ALICE), erc20_address()),
+                BadOrigin
+            );
+            assert_ok!(AssetRegistryModule::deregister_erc20(
+                Origin::signed(COUNCIL),
+                erc20_address()
+            ));
+            let event = Event::AssetRegistry(crate::Event::Erc20Deregistered(erc20_address()));
+            assert!(System::events().iter().any(|record| record.event == event));
+
+            assert!(!AssetRegistryModule::is_registered(erc20_address()));
+            assert_eq!(AssetRegistryModule::metadata(erc20_address()), None);
+            assert_noop!(
+                AssetRegistryModule::deregister_erc20(Origin::signed(COUNCIL), erc20_address()),
+                Error::<Runtime>::NotRegistered
+            );
+        });
+}
+
+#[test]
+fn register_erc20_contracts_migration_should_work() {
+    parameter_types! {
+        pub Contracts: Vec<EvmAddress> = vec![erc20_address(), not_erc20_address()];
+    }
+
+    ExtBuilder::default().build().execute_with(|| {
+        StorageVersion::new(0).put::<AssetRegistryModule>();
+
+        migrations::v1::RegisterErc20Contracts::<Runtime, Contracts>::on_runtime_upgrade();
+        assert_eq!(
+            AssetRegistryModule::erc20_assets_list(),
+            vec![(erc20_address(), ousd_metadata(18))]
+        );
+        let event = Event::AssetRegistry(crate::Event::Erc20Registered(erc20_address(), ousd_metadata(18)));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(StorageVersion::get::<AssetRegistryModule>(), 1);
+
+        // only migrated once
+        assert_ok!(AssetRegistryModule::deregister_erc20(
+            Origin::signed(COUNCIL),
+            erc20_address()
+        ));
+        migrations::v1::RegisterErc20Contracts::<Runtime, Contracts>::on_runtime_upgrade();
+        assert!(!AssetRegistryModule::is_registered(erc20_address()));
+    });
+}
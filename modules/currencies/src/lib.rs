@@ -9,7 +9,7 @@ use frame_support::{
     pallet_prelude::*,
     traits::{
         Currency as PalletCurrency, ExistenceRequirement, LockableCurrency as PalletLockableCurrency,
-        ReservableCurrency as PalletReservableCurrency, WithdrawReasons,
+        ReservableCurrency as PalletReservableCurrency, StorageVersion, WithdrawReasons,
     },
 };
 use frame_system::pallet_prelude::*;
@@ -24,19 +24,20 @@ use primitives::{
     evm::{AddressMapping, EvmAddress},
     CurrencyId, TokenSymbol,
 };
-use sp_io::hashing::blake2_256;
 use sp_runtime::{
     traits::{CheckedSub, MaybeSerializeDeserialize, Saturating, StaticLookup, Zero},
     DispatchError, DispatchResult,
 };
 use sp_std::{
+    collections::btree_map::BTreeMap,
     convert::{TryFrom, TryInto},
     fmt::Debug,
     marker, result,
 };
-use support::{EVMBridge, InvokeContext};
+use support::{EVMBridge, Erc20Registry, InvokeContext, MultiCurrencyAllowance};
 
 mod default_weight;
+pub mod migrations;
 mod mock;
 mod tests;
 
@@ -78,6 +79,25 @@ pub mod module {
         /// Mapping from address to account id.
         type AddressMapping: AddressMapping<Self::AccountId>;
         type EVMBridge: EVMBridge<Self::AccountId, BalanceOf<Self>>;
+
+        /// The EVM address holding the reserved and locked ERC20 tokens.
+        ///
+        /// The held balances are booked at the transferred amount, so tokens
+        /// taking a fee on transfer must not be registered: the holding
+        /// account would hold less than it owes.
+        #[pallet::constant]
+        type Erc20HoldingAccount: Get<EvmAddress>;
+
+        /// The account receiving the slashed reserved ERC20 tokens, they
+        /// can't be burned.
+        #[pallet::constant]
+        type Erc20SlashBeneficiary: Get<Self::AccountId>;
+
+        /// The registered ERC20 tokens, other `ERC20` currencies are rejected.
+        type Erc20Registry: Erc20Registry;
+
+        /// The accounts whose ERC20 reserves are moved by the `v1` migration.
+        type Erc20ReserveAccounts: migrations::v1::Erc20ReserveAccounts<Self::AccountId>;
     }
 
     #[pallet::error]
@@ -90,6 +110,10 @@ pub mod module {
         ERC20InvalidOperation,
         /// EVM account not found
         EvmAccountNotFound,
+        /// The ERC20 token is not registered
+        Erc20NotRegistered,
+        /// The allowance is too low
+        AllowanceTooLow,
     }
 
     #[pallet::event]
@@ -103,13 +127,82 @@ pub mod module {
         Deposited(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
         /// Withdraw success. [currency_id, who, amount]
         Withdrawn(CurrencyIdOf<T>, T::AccountId, BalanceOf<T>),
-    }
+        /// Approve success. [currency_id, owner, spender, amount]
+        Approved(CurrencyIdOf<T>, T::AccountId, T::AccountId, BalanceOf<T>),
+    }
+
+    /// The reserved ERC20 balances, held by `Erc20HoldingAccount`.
+    ///
+    /// Erc20ReservedBalances: double_map contract, AccountId => Balance
+    #[pallet::storage]
+    #[pallet::getter(fn erc20_reserved_balances)]
+    pub type Erc20ReservedBalances<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, EvmAddress, Twox64Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+    /// The ERC20 locks of an account. The largest lock is held by
+    /// `Erc20HoldingAccount` as far as the account's balance allows, see
+    /// `Erc20LockedBalances`.
+    ///
+    /// Erc20Locks: double_map contract, AccountId => BTreeMap<LockIdentifier,
+    /// Balance>
+    #[pallet::storage]
+    #[pallet::getter(fn erc20_locks)]
+    pub type Erc20Locks<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        EvmAddress,
+        Twox64Concat,
+        T::AccountId,
+        BTreeMap<LockIdentifier, BalanceOf<T>>,
+        ValueQuery,
+    >;
+
+    /// The locked ERC20 balances held by `Erc20HoldingAccount`. Locks larger
+    /// than the balance of the account only restrict the withdrawals through
+    /// this module for the part that isn't held.
+    ///
+    /// Erc20LockedBalances: double_map contract, AccountId => Balance
+    #[pallet::storage]
+    #[pallet::getter(fn erc20_locked_balances)]
+    pub type Erc20LockedBalances<T: Config> =
+        StorageDoubleMap<_, Twox64Concat, EvmAddress, Twox64Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
+    /// The allowances of the non-`ERC20` currencies, the ones of `ERC20`
+    /// currencies are kept by their contract.
+    ///
+    /// Allowances: double_map (currency_id, owner), spender => Balance
+    #[pallet::storage]
+    #[pallet::getter(fn allowances)]
+    pub type Allowances<T: Config> = StorageDoubleMap<
+        _,
+        Twox64Concat,
+        (CurrencyIdOf<T>, T::AccountId),
+        Twox64Concat,
+        T::AccountId,
+        BalanceOf<T>,
+        ValueQuery,
+    >;
+
+    /// The last account whose ERC20 reserves were moved by the `v1`
+    /// migration, present while the migration runs.
+    ///
+    /// Erc20ReservesMigration: Option<AccountId>
+    #[pallet::storage]
+    pub type Erc20ReservesMigration<T: Config> = StorageValue<_, Option<T::AccountId>, OptionQuery>;
+
+    /// The current storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
 
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(PhantomData<T>);
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_idle(_: T::BlockNumber, remaining_weight: Weight) -> Weight {
+            migrations::v1::migrate_erc20_reserves::<T>(remaining_weight)
+        }
+    }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
@@ -171,6 +264,140 @@ pub mod module {
     }
 }
 
+impl<T: Config> Pallet<T> {
+    /// Allow `spender` to transfer up to `amount` of `owner`'s `currency_id`
+    /// with `transfer_from`.
+    pub fn approve(
+        currency_id: CurrencyIdOf<T>,
+        owner: &T::AccountId,
+        spender: &T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        match currency_id {
+            CurrencyId::ERC20(contract) => {
+                Self::ensure_erc20_registered(contract)?;
+                let owner_address = T::AddressMapping::get_evm_address(owner).ok_or(Error::<T>::EvmAccountNotFound)?;
+                let spender_address = T::AddressMapping::get_or_create_evm_address(spender);
+                T::EVMBridge::approve(
+                    InvokeContext {
+                        contract,
+                        sender: owner_address,
+                        origin: owner_address,
+                    },
+                    spender_address,
+                    amount,
+                )?;
+            }
+            _ => Self::set_allowance(currency_id, owner, spender, amount),
+        }
+
+        Self::deposit_event(Event::Approved(currency_id, owner.clone(), spender.clone(), amount));
+        Ok(())
+    }
+
+    /// The amount of `owner`'s `currency_id` that `spender` is still allowed
+    /// to transfer.
+    pub fn allowance(currency_id: CurrencyIdOf<T>, owner: &T::AccountId, spender: &T::AccountId) -> BalanceOf<T> {
+        if let CurrencyId::ERC20(contract) = currency_id {
+            if !T::Erc20Registry::is_registered(contract) {
+                return Default::default();
+            }
+            if let (Some(owner_address), Some(spender_address)) = (
+                T::AddressMapping::get_evm_address(owner),
+                T::AddressMapping::get_evm_address(spender),
+            ) {
+                return T::EVMBridge::allowance(
+                    InvokeContext {
+                        contract,
+                        sender: Default::default(),
+                        origin: Default::default(),
+                    },
+                    owner_address,
+                    spender_address,
+                )
+                .unwrap_or_default();
+            }
+            return Default::default();
+        }
+        Self::allowances((currency_id, owner.clone()), spender)
+    }
+
+    /// Transfer `amount` of `currency_id` from `from` to `to` on behalf of
+    /// `spender`, consuming the allowance `from` approved to `spender`.
+    pub fn transfer_from(
+        currency_id: CurrencyIdOf<T>,
+        spender: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        amount: BalanceOf<T>,
+    ) -> DispatchResult {
+        if amount.is_zero() || from == to {
+            return Ok(());
+        }
+
+        match currency_id {
+            CurrencyId::ERC20(contract) => {
+                Self::ensure_erc20_registered(contract)?;
+                let spender_address =
+                    T::AddressMapping::get_evm_address(spender).ok_or(Error::<T>::EvmAccountNotFound)?;
+                let from_address = T::AddressMapping::get_evm_address(from).ok_or(Error::<T>::EvmAccountNotFound)?;
+                let to_address = T::AddressMapping::get_or_create_evm_address(to);
+                T::EVMBridge::transfer_from(
+                    InvokeContext {
+                        contract,
+                        sender: spender_address,
+                        origin: spender_address,
+                    },
+                    from_address,
+                    to_address,
+                    amount,
+                )?;
+            }
+            _ => {
+                let allowance = Self::allowances((currency_id, from.clone()), spender)
+                    .checked_sub(&amount)
+                    .ok_or(Error::<T>::AllowanceTooLow)?;
+                match currency_id {
+                    CurrencyId::Token(TokenSymbol::OVR) => T::NativeCurrency::transfer(from, to, amount)?,
+                    _ => T::MultiCurrency::transfer(currency_id, from, to, amount)?,
+                }
+                Self::set_allowance(currency_id, from, spender, allowance);
+            }
+        }
+
+        Self::deposit_event(Event::Transferred(currency_id, from.clone(), to.clone(), amount));
+        Ok(())
+    }
+}
+
+impl<T: Config> MultiCurrencyAllowance<T::AccountId> for Pallet<T> {
+    type CurrencyId = CurrencyIdOf<T>;
+    type Balance = BalanceOf<T>;
+
+    fn allowance(currency_id: Self::CurrencyId, owner: &T::AccountId, spender: &T::AccountId) -> Self::Balance {
+        Pallet::<T>::allowance(currency_id, owner, spender)
+    }
+
+    fn approve(
+        currency_id: Self::CurrencyId,
+        owner: &T::AccountId,
+        spender: &T::AccountId,
+        amount: Self::Balance,
+    ) -> DispatchResult {
+        Pallet::<T>::approve(currency_id, owner, spender, amount)
+    }
+
+    fn transfer_from(
+        currency_id: Self::CurrencyId,
+        spender: &T::AccountId,
+        from: &T::AccountId,
+        to: &T::AccountId,
+        amount: Self::Balance,
+    ) -> DispatchResult {
+        Pallet::<T>::transfer_from(currency_id, spender, from, to, amount)
+    }
+}
+
 impl<T: Config> MultiCurrency<T::AccountId> for Pallet<T> {
     type CurrencyId = CurrencyIdOf<T>;
     type Balance = BalanceOf<T>;
@@ -185,6 +412,7 @@ impl<T: Config> MultiCurrency<T::AccountId> for Pallet<T> {
 
     fn total_issuance(currency_id: Self::CurrencyId) -> Self::Balance {
         match currency_id {
+            CurrencyId::ERC20(contract) if !T::Erc20Registry::is_registered(contract) => Default::default(),
             CurrencyId::ERC20(contract) => T::EVMBridge::total_supply(InvokeContext {
                 contract,
                 sender: Default::default(),
@@ -199,15 +427,7 @@ impl<T: Config> MultiCurrency<T::AccountId> for Pallet<T> {
     fn total_balance(currency_id: Self::CurrencyId, who: &T::AccountId) -> Self::Balance {
         match currency_id {
             CurrencyId::ERC20(contract) => {
-                if let Some(address) = T::AddressMapping::get_evm_address(who) {
-                    let context = InvokeContext {
-                        contract,
-                        sender: Default::default(),
-                        origin: Default::default(),
-                    };
-                    return T::EVMBridge::balance_of(context, address).unwrap_or_default();
-                }
-                Default::default()
+                Self::free_balance(currency_id, who).saturating_add(Self::erc20_reserved_balances(contract, who))
             }
             CurrencyId::Token(TokenSymbol::OVR) => T::NativeCurrency::total_balance(who),
             _ => T::MultiCurrency::total_balance(currency_id, who),
@@ -216,16 +436,10 @@ impl<T: Config> MultiCurrency<T::AccountId> for Pallet<T> {
 
     fn free_balance(currency_id: Self::CurrencyId, who: &T::AccountId) -> Self::Balance {
         match currency_id {
+            CurrencyId::ERC20(contract) if !T::Erc20Registry::is_registered(contract) => Default::default(),
+            // like orml_tokens, the locked balance is part of the free balance
             CurrencyId::ERC20(contract) => {
-                if let Some(address) = T::AddressMapping::get_evm_address(who) {
-                    let context = InvokeContext {
-                        contract,
-                        sender: Default::default(),
-                        origin: Default::default(),
-                    };
-                    return T::EVMBridge::balance_of(context, address).unwrap_or_default();
-                }
-                Default::default()
+                Self::erc20_balance_of(contract, who).saturating_add(Self::erc20_locked_balances(contract, who))
             }
             CurrencyId::Token(TokenSymbol::OVR) => T::NativeCurrency::free_balance(who),
             _ => T::MultiCurrency::free_balance(currency_id, who),
@@ -235,17 +449,18 @@ impl<T: Config> MultiCurrency<T::AccountId> for Pallet<T> {
     fn ensure_can_withdraw(currency_id: Self::CurrencyId, who: &T::AccountId, amount: Self::Balance) -> DispatchResult {
         match currency_id {
             CurrencyId::ERC20(contract) => {
-                let address = T::AddressMapping::get_evm_address(who).ok_or(Error::<T>::EvmAccountNotFound)?;
-                let balance = T::EVMBridge::balance_of(
-                    InvokeContext {
-                        contract,
-                        sender: Default::default(),
-                        origin: Default::default(),
-                    },
-                    address,
-                )
-                .unwrap_or_default();
-                ensure!(balance >= amount, Error::<T>::BalanceTooLow);
+                Self::ensure_erc20_registered(contract)?;
+                ensure!(
+                    T::AddressMapping::get_evm_address(who).is_some(),
+                    Error::<T>::EvmAccountNotFound
+                );
+                // the held part of the locks is already out of the balance
+                let unheld_frozen = Self::erc20_frozen_balance(contract, who)
+                    .saturating_sub(Self::erc20_locked_balances(contract, who));
+                ensure!(
+                    Self::erc20_balance_of(contract, who).saturating_sub(unheld_frozen) >= amount,
+                    Error::<T>::BalanceTooLow
+                );
                 Ok(())
             }
             CurrencyId::Token(TokenSymbol::OVR) => T::NativeCurrency::ensure_can_withdraw(who, amount),
@@ -265,6 +480,7 @@ impl<T: Config> MultiCurrency<T::AccountId> for Pallet<T> {
 
         match currency_id {
             CurrencyId::ERC20(contract) => {
+                Self::ensure_erc20_registered(contract)?;
                 let sender = T::AddressMapping::get_evm_address(from).ok_or(Error::<T>::EvmAccountNotFound)?;
                 let origin = T::EVMBridge::get_origin().unwrap_or_default();
                 let origin_address = T::AddressMapping::get_or_create_evm_address(&origin);
@@ -354,7 +570,15 @@ impl<T: Config> MultiLockableCurrency<T::AccountId> for Pallet<T> {
         amount: Self::Balance,
     ) -> DispatchResult {
         match currency_id {
-            CurrencyId::ERC20(_) => Err(Error::<T>::ERC20InvalidOperation.into()),
+            CurrencyId::ERC20(contract) => {
+                if amount.is_zero() {
+                    return Ok(());
+                }
+                Self::ensure_erc20_registered(contract)?;
+                let mut locks = Self::erc20_locks(contract, who);
+                locks.insert(lock_id, amount);
+                Self::update_erc20_locks(contract, who, locks)
+            }
             CurrencyId::Token(TokenSymbol::OVR) => T::NativeCurrency::set_lock(lock_id, who, amount),
             _ => T::MultiCurrency::set_lock(lock_id, currency_id, who, amount),
         }
@@ -367,7 +591,16 @@ impl<T: Config> MultiLockableCurrency<T::AccountId> for Pallet<T> {
         amount: Self::Balance,
     ) -> DispatchResult {
         match currency_id {
-            CurrencyId::ERC20(_) => Err(Error::<T>::ERC20InvalidOperation.into()),
+            CurrencyId::ERC20(contract) => {
+                if amount.is_zero() {
+                    return Ok(());
+                }
+                Self::ensure_erc20_registered(contract)?;
+                let mut locks = Self::erc20_locks(contract, who);
+                let lock = locks.entry(lock_id).or_default();
+                *lock = (*lock).max(amount);
+                Self::update_erc20_locks(contract, who, locks)
+            }
             CurrencyId::Token(TokenSymbol::OVR) => T::NativeCurrency::extend_lock(lock_id, who, amount),
             _ => T::MultiCurrency::extend_lock(lock_id, currency_id, who, amount),
         }
@@ -375,7 +608,11 @@ impl<T: Config> MultiLockableCurrency<T::AccountId> for Pallet<T> {
 
     fn remove_lock(lock_id: LockIdentifier, currency_id: Self::CurrencyId, who: &T::AccountId) -> DispatchResult {
         match currency_id {
-            CurrencyId::ERC20(_) => Err(Error::<T>::ERC20InvalidOperation.into()),
+            CurrencyId::ERC20(contract) => {
+                let mut locks = Self::erc20_locks(contract, who);
+                locks.remove(&lock_id);
+                Self::update_erc20_locks(contract, who, locks)
+            }
             CurrencyId::Token(TokenSymbol::OVR) => T::NativeCurrency::remove_lock(lock_id, who),
             _ => T::MultiCurrency::remove_lock(lock_id, currency_id, who),
         }
@@ -394,23 +631,19 @@ impl<T: Config> MultiReservableCurrency<T::AccountId> for Pallet<T> {
     fn slash_reserved(currency_id: Self::CurrencyId, who: &T::AccountId, value: Self::Balance) -> Self::Balance {
         match currency_id {
             CurrencyId::ERC20(contract) => {
-                if let Some(address) = T::AddressMapping::get_evm_address(who) {
-                    let account_balance = T::EVMBridge::balance_of(
-                        InvokeContext {
-                            contract,
-                            sender: Default::default(),
-                            origin: Default::default(),
-                        },
-                        address,
-                    )
-                    .unwrap_or_default();
-                    return if value < account_balance {
-                        value
-                    } else {
-                        account_balance
-                    };
+                if value.is_zero() {
+                    return value;
+                }
+                // ERC20 tokens can't be burned, the slashed tokens go to `Erc20SlashBeneficiary`
+                let reserved_balance = Self::erc20_reserved_balances(contract, who);
+                let actual = reserved_balance.min(value);
+                match Self::erc20_release(contract, who, &T::Erc20SlashBeneficiary::get(), actual) {
+                    Ok(_) => {
+                        Self::set_erc20_reserved_balance(contract, who, reserved_balance - actual);
+                        value - actual
+                    }
+                    Err(_) => value,
                 }
-                value
             }
             CurrencyId::Token(TokenSymbol::OVR) => T::NativeCurrency::slash_reserved(who, value),
             _ => T::MultiCurrency::slash_reserved(currency_id, who, value),
@@ -419,20 +652,7 @@ impl<T: Config> MultiReservableCurrency<T::AccountId> for Pallet<T> {
 
     fn reserved_balance(currency_id: Self::CurrencyId, who: &T::AccountId) -> Self::Balance {
         match currency_id {
-            CurrencyId::ERC20(contract) => {
-                if let Some(address) = T::AddressMapping::get_evm_address(who) {
-                    return T::EVMBridge::balance_of(
-                        InvokeContext {
-                            contract,
-                            sender: Default::default(),
-                            origin: Default::default(),
-                        },
-                        reserve_address(address),
-                    )
-                    .unwrap_or_default();
-                }
-                Default::default()
-            }
+            CurrencyId::ERC20(contract) => Self::erc20_reserved_balances(contract, who),
             CurrencyId::Token(TokenSymbol::OVR) => T::NativeCurrency::reserved_balance(who),
             _ => T::MultiCurrency::reserved_balance(currency_id, who),
         }
@@ -444,6 +664,7 @@ impl<T: Config> MultiReservableCurrency<T::AccountId> for Pallet<T> {
                 if value.is_zero() {
                     return Ok(());
                 }
+                Self::ensure_erc20_registered(contract)?;
                 let address = T::AddressMapping::get_evm_address(who).ok_or(Error::<T>::EvmAccountNotFound)?;
                 T::EVMBridge::transfer(
                     InvokeContext {
@@ -451,9 +672,13 @@ impl<T: Config> MultiReservableCurrency<T::AccountId> for Pallet<T> {
                         sender: address,
                         origin: address,
                     },
-                    reserve_address(address),
+                    T::Erc20HoldingAccount::get(),
                     value,
-                )
+                )?;
+                Erc20ReservedBalances::<T>::mutate(contract, who, |reserved| {
+                    *reserved = reserved.saturating_add(value)
+                });
+                Ok(())
             }
             CurrencyId::Token(TokenSymbol::OVR) => T::NativeCurrency::reserve(who, value),
             _ => T::MultiCurrency::reserve(currency_id, who, value),
@@ -466,32 +691,15 @@ impl<T: Config> MultiReservableCurrency<T::AccountId> for Pallet<T> {
                 if value.is_zero() {
                     return value;
                 }
-                if let Some(address) = T::AddressMapping::get_evm_address(who) {
-                    let sender = reserve_address(address);
-                    let reserved_balance = T::EVMBridge::balance_of(
-                        InvokeContext {
-                            contract,
-                            sender: Default::default(),
-                            origin: Default::default(),
-                        },
-                        sender,
-                    )
-                    .unwrap_or_default();
-                    let actual = reserved_balance.min(value);
-                    return match T::EVMBridge::transfer(
-                        InvokeContext {
-                            contract,
-                            sender,
-                            origin: address,
-                        },
-                        address,
-                        actual,
-                    ) {
-                        Ok(_) => value - actual,
-                        Err(_) => value,
-                    };
+                let reserved_balance = Self::erc20_reserved_balances(contract, who);
+                let actual = reserved_balance.min(value);
+                match Self::erc20_release(contract, who, who, actual) {
+                    Ok(_) => {
+                        Self::set_erc20_reserved_balance(contract, who, reserved_balance - actual);
+                        value - actual
+                    }
+                    Err(_) => value,
                 }
-                value
             }
             CurrencyId::Token(TokenSymbol::OVR) => T::NativeCurrency::unreserve(who, value),
             _ => T::MultiCurrency::unreserve(currency_id, who, value),
@@ -519,44 +727,17 @@ impl<T: Config> MultiReservableCurrency<T::AccountId> for Pallet<T> {
                     };
                 }
 
-                let slashed_address =
-                    T::AddressMapping::get_evm_address(slashed).ok_or(Error::<T>::EvmAccountNotFound)?;
-                let beneficiary_address = T::AddressMapping::get_or_create_evm_address(beneficiary);
-
-                let slashed_reserve_address = reserve_address(slashed_address);
-                let beneficiary_reserve_address = reserve_address(beneficiary_address);
-
-                let slashed_reserved_balance = T::EVMBridge::balance_of(
-                    InvokeContext {
-                        contract,
-                        sender: Default::default(),
-                        origin: Default::default(),
-                    },
-                    slashed_reserve_address,
-                )
-                .unwrap_or_default();
+                let slashed_reserved_balance = Self::erc20_reserved_balances(contract, slashed);
                 let actual = slashed_reserved_balance.min(value);
                 match status {
-                    BalanceStatus::Free => T::EVMBridge::transfer(
-                        InvokeContext {
-                            contract,
-                            sender: slashed_reserve_address,
-                            origin: slashed_address,
-                        },
-                        beneficiary_address,
-                        actual,
-                    ),
-                    BalanceStatus::Reserved => T::EVMBridge::transfer(
-                        InvokeContext {
-                            contract,
-                            sender: slashed_reserve_address,
-                            origin: slashed_address,
-                        },
-                        beneficiary_reserve_address,
-                        actual,
-                    ),
+                    BalanceStatus::Free => Self::erc20_release(contract, slashed, beneficiary, actual)?,
+                    // the tokens stay in `Erc20HoldingAccount`, only the owner changes
+                    BalanceStatus::Reserved => Erc20ReservedBalances::<T>::mutate(contract, beneficiary, |reserved| {
+                        *reserved = reserved.saturating_add(actual)
+                    }),
                 }
-                .map(|_| value - actual)
+                Self::set_erc20_reserved_balance(contract, slashed, slashed_reserved_balance - actual);
+                Ok(value - actual)
             }
             CurrencyId::Token(TokenSymbol::OVR) => {
                 T::NativeCurrency::repatriate_reserved(slashed, beneficiary, value, status)
@@ -566,6 +747,124 @@ impl<T: Config> MultiReservableCurrency<T::AccountId> for Pallet<T> {
     }
 }
 
+impl<T: Config> Pallet<T> {
+    fn set_allowance(currency_id: CurrencyIdOf<T>, owner: &T::AccountId, spender: &T::AccountId, amount: BalanceOf<T>) {
+        if amount.is_zero() {
+            Allowances::<T>::remove((currency_id, owner.clone()), spender);
+        } else {
+            Allowances::<T>::insert((currency_id, owner.clone()), spender, amount);
+        }
+    }
+
+    /// Unregistered ERC20 tokens can't be transferred, reserved or locked.
+    /// Their reserved and locked balances can still be released.
+    fn ensure_erc20_registered(contract: EvmAddress) -> DispatchResult {
+        ensure!(
+            T::Erc20Registry::is_registered(contract),
+            Error::<T>::Erc20NotRegistered
+        );
+        Ok(())
+    }
+
+    /// The ERC20 balance of `who`'s EVM address, which excludes the reserved
+    /// and locked balances.
+    fn erc20_balance_of(contract: EvmAddress, who: &T::AccountId) -> BalanceOf<T> {
+        if let Some(address) = T::AddressMapping::get_evm_address(who) {
+            let context = InvokeContext {
+                contract,
+                sender: Default::default(),
+                origin: Default::default(),
+            };
+            return T::EVMBridge::balance_of(context, address).unwrap_or_default();
+        }
+        Default::default()
+    }
+
+    /// The largest lock of `who`.
+    fn erc20_frozen_balance(contract: EvmAddress, who: &T::AccountId) -> BalanceOf<T> {
+        Self::erc20_locks(contract, who)
+            .values()
+            .copied()
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn set_erc20_reserved_balance(contract: EvmAddress, who: &T::AccountId, value: BalanceOf<T>) {
+        if value.is_zero() {
+            Erc20ReservedBalances::<T>::remove(contract, who);
+        } else {
+            Erc20ReservedBalances::<T>::insert(contract, who, value);
+        }
+    }
+
+    /// Move `value` of `who`'s tokens from `Erc20HoldingAccount` to
+    /// `beneficiary`. `who` pays the storage of the transfer.
+    fn erc20_release(
+        contract: EvmAddress,
+        who: &T::AccountId,
+        beneficiary: &T::AccountId,
+        value: BalanceOf<T>,
+    ) -> DispatchResult {
+        if value.is_zero() {
+            return Ok(());
+        }
+        let origin = T::AddressMapping::get_evm_address(who).ok_or(Error::<T>::EvmAccountNotFound)?;
+        T::EVMBridge::transfer(
+            InvokeContext {
+                contract,
+                sender: T::Erc20HoldingAccount::get(),
+                origin,
+            },
+            T::AddressMapping::get_or_create_evm_address(beneficiary),
+            value,
+        )
+    }
+
+    /// Store the new locks of `who`, moving the largest lock into
+    /// `Erc20HoldingAccount` as far as the balance of `who` allows, or
+    /// releasing what it no longer covers.
+    fn update_erc20_locks(
+        contract: EvmAddress,
+        who: &T::AccountId,
+        locks: BTreeMap<LockIdentifier, BalanceOf<T>>,
+    ) -> DispatchResult {
+        let locked = Self::erc20_locked_balances(contract, who);
+        let frozen = locks.values().copied().max().unwrap_or_default();
+
+        let new_locked = if frozen > locked {
+            let amount = (frozen - locked).min(Self::erc20_balance_of(contract, who));
+            if !amount.is_zero() {
+                let address = T::AddressMapping::get_evm_address(who).ok_or(Error::<T>::EvmAccountNotFound)?;
+                T::EVMBridge::transfer(
+                    InvokeContext {
+                        contract,
+                        sender: address,
+                        origin: address,
+                    },
+                    T::Erc20HoldingAccount::get(),
+                    amount,
+                )?;
+            }
+            locked + amount
+        } else {
+            Self::erc20_release(contract, who, who, locked - frozen)?;
+            frozen
+        };
+
+        if new_locked.is_zero() {
+            Erc20LockedBalances::<T>::remove(contract, who);
+        } else {
+            Erc20LockedBalances::<T>::insert(contract, who, new_locked);
+        }
+        if locks.is_empty() {
+            Erc20Locks::<T>::remove(contract, who);
+        } else {
+            Erc20Locks::<T>::insert(contract, who, locks);
+        }
+        Ok(())
+    }
+}
+
 pub struct Currency<T, GetCurrencyId>(marker::PhantomData<T>, marker::PhantomData<GetCurrencyId>);
 
 impl<T, GetCurrencyId> BasicCurrency<T::AccountId> for Currency<T, GetCurrencyId>
@@ -858,8 +1157,3 @@ impl<T: Config> TransferAll<T::AccountId> for Pallet<T> {
         })
     }
 }
-
-fn reserve_address(address: EvmAddress) -> EvmAddress {
-    let payload = (b"erc20:", address);
-    EvmAddress::from_slice(&payload.using_encoded(blake2_256)[0..20])
-}
@@ -0,0 +1,131 @@
+//! Storage migrations of the currencies module.
+
+use crate::*;
+use frame_support::traits::OnRuntimeUpgrade;
+use sp_std::vec::Vec;
+
+/// Move the ERC20 reserves from the per-account reserve addresses to
+/// `Erc20HoldingAccount` and `Erc20ReservedBalances`.
+///
+/// The reserves are moved in `on_idle`, a page of accounts at a time, for
+/// the registered ERC20 tokens. Until its page is migrated, the reserves of
+/// an account stay at its reserve address.
+pub mod v1 {
+    use super::*;
+    use codec::Encode;
+    use sp_io::hashing::blake2_256;
+
+    /// The EVM address holding the reserved ERC20 tokens of `address` before
+    /// `v1`.
+    pub fn reserve_address(address: EvmAddress) -> EvmAddress {
+        let payload = (b"erc20:", address);
+        EvmAddress::from_slice(&payload.using_encoded(blake2_256)[0..20])
+    }
+
+    /// The accounts which could reserve ERC20 tokens before `v1`, the ones
+    /// with an EVM address.
+    pub trait Erc20ReserveAccounts<AccountId> {
+        /// Up to `limit` accounts and their EVM address, following `start`
+        /// or from the first one.
+        fn accounts(start: Option<AccountId>, limit: u32) -> Vec<(AccountId, EvmAddress)>;
+        /// The weight of moving the reserve of an account in an ERC20 token:
+        /// reading its balance and transferring it.
+        fn reserve_weight() -> Weight;
+    }
+
+    impl<AccountId> Erc20ReserveAccounts<AccountId> for () {
+        fn accounts(_start: Option<AccountId>, _limit: u32) -> Vec<(AccountId, EvmAddress)> {
+            Vec::new()
+        }
+
+        fn reserve_weight() -> Weight {
+            0
+        }
+    }
+
+    /// Start moving the reserves, see `migrate_erc20_reserves`.
+    pub struct MigrateErc20Reserves<T>(marker::PhantomData<T>);
+
+    impl<T: Config> OnRuntimeUpgrade for MigrateErc20Reserves<T> {
+        fn on_runtime_upgrade() -> Weight {
+            let weight = T::DbWeight::get().reads(1);
+            if StorageVersion::get::<Pallet<T>>() >= 1 {
+                return weight;
+            }
+
+            Erc20ReservesMigration::<T>::put(None::<T::AccountId>);
+            StorageVersion::new(1).put::<Pallet<T>>();
+            weight.saturating_add(T::DbWeight::get().writes(2))
+        }
+    }
+
+    /// Move the reserves of the next page of `Erc20ReserveAccounts` fitting
+    /// in `limit`, the reserves which can't be moved are left at their
+    /// reserve address.
+    pub fn migrate_erc20_reserves<T: Config>(limit: Weight) -> Weight {
+        let mut weight = T::DbWeight::get().reads(1);
+        let start = match Erc20ReservesMigration::<T>::get() {
+            Some(start) => start,
+            None => return weight,
+        };
+
+        let contracts = T::Erc20Registry::contracts();
+        weight = weight.saturating_add(T::DbWeight::get().reads_writes(contracts.len() as Weight, 1));
+
+        // reading the account, then moving its reserve in every contract
+        let account_weight = T::DbWeight::get().reads(1).saturating_add(
+            T::Erc20ReserveAccounts::reserve_weight()
+                .saturating_add(T::DbWeight::get().reads_writes(1, 1))
+                .saturating_mul(contracts.len() as Weight),
+        );
+        let max_accounts = limit.saturating_sub(weight) / account_weight.max(1);
+        if max_accounts.is_zero() {
+            return weight;
+        }
+        let max_accounts = max_accounts.min(u32::MAX.into()) as u32;
+
+        let accounts = T::Erc20ReserveAccounts::accounts(start, max_accounts);
+        weight = weight.saturating_add(account_weight.saturating_mul(accounts.len() as Weight));
+        for (who, address) in &accounts {
+            for contract in &contracts {
+                migrate_reserve::<T>(*contract, who, *address);
+            }
+        }
+
+        if accounts.len() < max_accounts as usize {
+            Erc20ReservesMigration::<T>::kill();
+        } else {
+            Erc20ReservesMigration::<T>::put(accounts.last().map(|(who, _)| who.clone()));
+        }
+        weight
+    }
+
+    fn migrate_reserve<T: Config>(contract: EvmAddress, who: &T::AccountId, address: EvmAddress) {
+        let sender = reserve_address(address);
+        let reserved = T::EVMBridge::balance_of(
+            InvokeContext {
+                contract,
+                sender: Default::default(),
+                origin: Default::default(),
+            },
+            sender,
+        )
+        .unwrap_or_default();
+        if reserved.is_zero() {
+            return;
+        }
+
+        let moved = T::EVMBridge::transfer(
+            InvokeContext {
+                contract,
+                sender,
+                origin: address,
+            },
+            T::Erc20HoldingAccount::get(),
+            reserved,
+        );
+        if moved.is_ok() {
+            Erc20ReservedBalances::<T>::mutate(contract, who, |balance| *balance = balance.saturating_add(reserved));
+        }
+    }
+}
@@ -4,16 +4,20 @@
 
 use frame_support::{ord_parameter_types, parameter_types, traits::GenesisBuild, PalletId};
 use orml_traits::parameter_type_with_key;
-use primitives::{evm::AddressMapping, mocks::MockAddressMapping, CurrencyId, TokenSymbol};
+use primitives::{
+    evm::{AddressMapping, ERC20_HOLDING_ACCOUNT},
+    mocks::{MockAddressMapping, MockRandomness},
+    CurrencyId, TokenSymbol,
+};
 use sp_core::H256;
 use sp_runtime::{
     testing::Header,
-    traits::{AccountIdConversion, IdentityLookup},
+    traits::{AccountIdConversion, Identity, IdentityLookup},
     AccountId32, Perbill,
 };
 
 use super::*;
-use frame_system::EnsureSignedBy;
+use frame_system::{EnsureRoot, EnsureSignedBy};
 use module_evm::GenesisAccount;
 use sp_core::{bytes::from_hex, H160};
 use sp_std::collections::btree_map::BTreeMap;
@@ -126,6 +130,7 @@ ord_parameter_types! {
     pub const NetworkContractAccount: AccountId32 = AccountId32::from([0u8; 32]);
     pub const StorageDepositPerByte: u128 = 10;
     pub const MaxCodeSize: u32 = 60 * 1024;
+    pub const MaxCodeHistory: u32 = 10;
     pub const DeveloperDeposit: u64 = 1000;
     pub const DeploymentFee: u64 = 200;
 }
@@ -137,11 +142,18 @@ impl module_evm::Config for Runtime {
     type NewContractExtraBytes = NewContractExtraBytes;
     type StorageDepositPerByte = StorageDepositPerByte;
     type MaxCodeSize = MaxCodeSize;
+    type MaxCodeHistory = MaxCodeHistory;
 
     type Event = Event;
     type Precompiles = ();
     type ChainId = ();
+    type MinimumGasPrice = ();
+    type TransactionByteFee = ();
     type GasToWeight = ();
+    type BaseFeeUpdate = Identity;
+    type FindAuthor = ();
+    type BlockGasLimit = ();
+    type Randomness = MockRandomness;
     type ChargeTransactionPayment = ();
     type NetworkContractOrigin = EnsureSignedBy<NetworkContractAccount, AccountId>;
     type NetworkContractSource = NetworkContractSource;
@@ -149,6 +161,7 @@ impl module_evm::Config for Runtime {
     type DeveloperDeposit = DeveloperDeposit;
     type DeploymentFee = DeploymentFee;
     type FreeDeploymentOrigin = EnsureSignedBy<CouncilAccount, AccountId32>;
+    type PrecompileOrigin = EnsureRoot<AccountId32>;
 
     type WeightInfo = ();
 }
@@ -157,6 +170,54 @@ impl module_evm_bridge::Config for Runtime {
     type EVM = EVM;
 }
 
+parameter_types! {
+    pub const Erc20HoldingAccount: H160 = ERC20_HOLDING_ACCOUNT;
+    pub const Erc20SlashBeneficiary: AccountId = TREASURY;
+}
+
+/// Only `ERC20_ADDRESS` is registered.
+pub struct MockErc20Registry;
+impl support::Erc20Registry for MockErc20Registry {
+    fn is_registered(contract: H160) -> bool {
+        contract == ERC20_ADDRESS
+    }
+
+    fn metadata(_contract: H160) -> Option<primitives::evm::Erc20Metadata> {
+        None
+    }
+
+    fn contracts() -> Vec<H160> {
+        vec![ERC20_ADDRESS]
+    }
+}
+
+/// The accounts of `alice` and `bob`, moving a reserve weighs 1_000.
+pub struct MockErc20ReserveAccounts;
+impl migrations::v1::Erc20ReserveAccounts<AccountId> for MockErc20ReserveAccounts {
+    fn accounts(start: Option<AccountId>, limit: u32) -> Vec<(AccountId, H160)> {
+        let accounts = vec![alice(), bob()];
+        let skip = start.map_or(0, |start| {
+            accounts
+                .iter()
+                .position(|who| *who == start)
+                .map_or(accounts.len(), |index| index + 1)
+        });
+        accounts
+            .into_iter()
+            .skip(skip)
+            .take(limit as usize)
+            .map(|who| {
+                let address = MockAddressMapping::get_evm_address(&who).unwrap();
+                (who, address)
+            })
+            .collect()
+    }
+
+    fn reserve_weight() -> Weight {
+        1_000
+    }
+}
+
 impl Config for Runtime {
     type Event = Event;
     type MultiCurrency = Tokens;
@@ -164,6 +225,10 @@ impl Config for Runtime {
     type WeightInfo = ();
     type AddressMapping = MockAddressMapping;
     type EVMBridge = EVMBridge;
+    type Erc20HoldingAccount = Erc20HoldingAccount;
+    type Erc20SlashBeneficiary = Erc20SlashBeneficiary;
+    type Erc20Registry = MockErc20Registry;
+    type Erc20ReserveAccounts = MockErc20ReserveAccounts;
 }
 
 pub type NativeCurrency = Currency<Runtime, GetNativeCurrencyId>;
@@ -183,7 +248,7 @@ frame_support::construct_runtime!(
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
         Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
         Tokens: tokens::{Pallet, Storage, Event<T>, Config<T>},
-        Currencies: currencies::{Pallet, Call, Event<T>},
+        Currencies: currencies::{Pallet, Call, Storage, Event<T>},
         EVM: module_evm::{Pallet, Config<T>, Call, Storage, Event<T>},
         EVMBridge: module_evm_bridge::{Pallet},
     }
@@ -204,11 +269,27 @@ pub fn bob() -> AccountId {
 pub const ALICE: AccountId = AccountId::new([1u8; 32]);
 pub const BOB: AccountId = AccountId::new([2u8; 32]);
 pub const EVA: AccountId = AccountId::new([5u8; 32]);
+pub const TREASURY: AccountId = AccountId::new([9u8; 32]);
 
 pub const ID_1: LockIdentifier = *b"1       ";
+pub const ID_2: LockIdentifier = *b"2       ";
 
 pub const ERC20_ADDRESS: H160 = H160([32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1]);
 pub const ERC20: CurrencyId = CurrencyId::ERC20(ERC20_ADDRESS);
+pub const UNREGISTERED_ERC20: CurrencyId =
+    CurrencyId::ERC20(H160([32, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2]));
+
+pub fn erc20_balance_of(address: H160) -> Balance {
+    <EVMBridge as support::EVMBridge<AccountId, Balance>>::balance_of(
+        support::InvokeContext {
+            contract: ERC20_ADDRESS,
+            sender: Default::default(),
+            origin: Default::default(),
+        },
+        address,
+    )
+    .unwrap()
+}
 
 pub struct ExtBuilder {
     endowed_accounts: Vec<(AccountId, CurrencyId, Balance)>,
@@ -3,11 +3,13 @@
 #![cfg(test)]
 
 use super::*;
-use frame_support::{assert_noop, assert_ok};
+use frame_support::{assert_noop, assert_ok, traits::OnRuntimeUpgrade};
 use mock::{
-    alice, bob, AccountId, AdaptedBasicCurrency, Currencies, Event, ExtBuilder, NativeCurrency, Origin, PalletBalances,
-    Runtime, System, Tokens, ALICE, BOB, ERC20, EVA, EVM, ID_1, NATIVE_CURRENCY_ID, X_TOKEN_ID,
+    alice, bob, erc20_balance_of, AccountId, AdaptedBasicCurrency, Currencies, EVMBridge, Event, ExtBuilder,
+    NativeCurrency, Origin, PalletBalances, Runtime, System, Tokens, ALICE, BOB, ERC20, ERC20_ADDRESS, EVA, EVM, ID_1,
+    ID_2, NATIVE_CURRENCY_ID, TREASURY, UNREGISTERED_ERC20, X_TOKEN_ID,
 };
+use primitives::evm::ERC20_HOLDING_ACCOUNT;
 use sp_core::H160;
 use sp_runtime::traits::BadOrigin;
 use support::EVM as EVMTrait;
@@ -397,6 +399,113 @@ fn erc20_transfer_should_fail() {
         });
 }
 
+#[test]
+fn unregistered_erc20_should_be_rejected() {
+    ExtBuilder::default()
+        .balances(vec![(alice(), NATIVE_CURRENCY_ID, 100000)])
+        .build()
+        .execute_with(|| {
+            <EVM as EVMTrait<AccountId>>::set_origin(alice());
+            assert_eq!(Currencies::total_issuance(UNREGISTERED_ERC20), 0);
+            assert_eq!(Currencies::free_balance(UNREGISTERED_ERC20, &alice()), 0);
+            assert_eq!(Currencies::allowance(UNREGISTERED_ERC20, &alice(), &bob()), 0);
+            assert_eq!(Currencies::can_reserve(UNREGISTERED_ERC20, &alice(), 1), false);
+
+            assert_noop!(
+                Currencies::transfer(Origin::signed(alice()), bob(), UNREGISTERED_ERC20, 100),
+                Error::<Runtime>::Erc20NotRegistered
+            );
+            assert_noop!(
+                Currencies::approve(UNREGISTERED_ERC20, &alice(), &bob(), 100),
+                Error::<Runtime>::Erc20NotRegistered
+            );
+            assert_noop!(
+                Currencies::transfer_from(UNREGISTERED_ERC20, &bob(), &alice(), &bob(), 100),
+                Error::<Runtime>::Erc20NotRegistered
+            );
+            assert_noop!(
+                Currencies::reserve(UNREGISTERED_ERC20, &alice(), 100),
+                Error::<Runtime>::Erc20NotRegistered
+            );
+            assert_noop!(
+                Currencies::set_lock(ID_1, UNREGISTERED_ERC20, &alice(), 100),
+                Error::<Runtime>::Erc20NotRegistered
+            );
+        });
+}
+
+#[test]
+fn erc20_transfer_from_should_work() {
+    ExtBuilder::default()
+        .balances(vec![
+            (alice(), NATIVE_CURRENCY_ID, 100000),
+            (bob(), NATIVE_CURRENCY_ID, 100000),
+        ])
+        .build()
+        .execute_with(|| {
+            assert_eq!(Currencies::allowance(ERC20, &alice(), &bob()), 0);
+            assert!(Currencies::transfer_from(ERC20, &bob(), &alice(), &bob(), 100).is_err());
+
+            assert_ok!(Currencies::approve(ERC20, &alice(), &bob(), 100));
+            let approved_event = Event::Currencies(crate::Event::Approved(ERC20, alice(), bob(), 100));
+            assert!(System::events().iter().any(|record| record.event == approved_event));
+            assert_eq!(Currencies::allowance(ERC20, &alice(), &bob()), 100);
+
+            assert_ok!(Currencies::transfer_from(ERC20, &bob(), &alice(), &bob(), 60));
+            let transferred_event = Event::Currencies(crate::Event::Transferred(ERC20, alice(), bob(), 60));
+            assert!(System::events().iter().any(|record| record.event == transferred_event));
+
+            assert_eq!(Currencies::allowance(ERC20, &alice(), &bob()), 40);
+            assert_eq!(Currencies::free_balance(ERC20, &bob()), 60);
+            assert_eq!(Currencies::free_balance(ERC20, &alice()), u128::max_value() - 60);
+
+            assert!(Currencies::transfer_from(ERC20, &bob(), &alice(), &bob(), 41).is_err());
+        });
+}
+
+#[test]
+fn transfer_from_should_work() {
+    ExtBuilder::default()
+        .one_hundred_for_alice_n_bob()
+        .build()
+        .execute_with(|| {
+            for currency_id in [X_TOKEN_ID, NATIVE_CURRENCY_ID] {
+                assert_eq!(Currencies::allowance(currency_id, &ALICE, &BOB), 0);
+                assert_noop!(
+                    Currencies::transfer_from(currency_id, &BOB, &ALICE, &EVA, 50),
+                    Error::<Runtime>::AllowanceTooLow
+                );
+
+                assert_ok!(Currencies::approve(currency_id, &ALICE, &BOB, 50));
+                let approved_event = Event::Currencies(crate::Event::Approved(currency_id, ALICE, BOB, 50));
+                assert!(System::events().iter().any(|record| record.event == approved_event));
+                assert_eq!(Currencies::allowance(currency_id, &ALICE, &BOB), 50);
+                assert_eq!(Currencies::allowance(currency_id, &BOB, &ALICE), 0);
+
+                assert_ok!(Currencies::transfer_from(currency_id, &BOB, &ALICE, &EVA, 30));
+                let transferred_event = Event::Currencies(crate::Event::Transferred(currency_id, ALICE, EVA, 30));
+                assert!(System::events().iter().any(|record| record.event == transferred_event));
+                assert_eq!(Currencies::free_balance(currency_id, &ALICE), 70);
+                assert_eq!(Currencies::free_balance(currency_id, &EVA), 30);
+                assert_eq!(Currencies::allowance(currency_id, &ALICE, &BOB), 20);
+
+                assert_noop!(
+                    Currencies::transfer_from(currency_id, &BOB, &ALICE, &EVA, 21),
+                    Error::<Runtime>::AllowanceTooLow
+                );
+
+                // the allowance is only consumed by successful transfers
+                assert_ok!(Currencies::approve(currency_id, &ALICE, &BOB, 100));
+                assert!(Currencies::transfer_from(currency_id, &BOB, &ALICE, &EVA, 71).is_err());
+                assert_eq!(Currencies::allowance(currency_id, &ALICE, &BOB), 100);
+
+                assert_ok!(Currencies::approve(currency_id, &ALICE, &BOB, 0));
+                assert_eq!(Currencies::allowance(currency_id, &ALICE, &BOB), 0);
+                assert!(!Allowances::<Runtime>::contains_key((currency_id, ALICE), BOB));
+            }
+        });
+}
+
 #[test]
 fn erc20_can_reserve_should_work() {
     ExtBuilder::default().build().execute_with(|| {
@@ -412,7 +521,16 @@ fn erc20_slash_reserve_should_work() {
         .execute_with(|| {
             assert_eq!(Currencies::slash_reserved(ERC20, &alice(), 1), 1);
             assert_ok!(Currencies::reserve(ERC20, &alice(), 100));
-            assert_eq!(Currencies::slash_reserved(ERC20, &alice(), 10), 10);
+            assert_eq!(Currencies::slash_reserved(ERC20, &alice(), 10), 0);
+            assert_eq!(Currencies::reserved_balance(ERC20, &alice()), 90);
+            assert_eq!(Currencies::slash_reserved(ERC20, &alice(), 100), 10);
+            assert_eq!(Currencies::reserved_balance(ERC20, &alice()), 0);
+            assert_eq!(Currencies::free_balance(ERC20, &alice()), u128::max_value() - 100);
+
+            // the slashed tokens leave the holding account
+            assert_eq!(erc20_balance_of(ERC20_HOLDING_ACCOUNT), 0);
+            let treasury_address = <Runtime as Config>::AddressMapping::get_or_create_evm_address(&TREASURY);
+            assert_eq!(erc20_balance_of(treasury_address), 100);
         });
 }
 
@@ -429,6 +547,14 @@ fn erc20_reserve_should_work() {
 
             assert_eq!(Currencies::reserved_balance(ERC20, &alice()), 100);
             assert_eq!(Currencies::free_balance(ERC20, &alice()), u128::max_value() - 100);
+            assert_eq!(Currencies::total_balance(ERC20, &alice()), u128::max_value());
+            assert_eq!(Currencies::erc20_reserved_balances(ERC20_ADDRESS, alice()), 100);
+            assert_eq!(erc20_balance_of(ERC20_HOLDING_ACCOUNT), 100);
+
+            assert_eq!(
+                Currencies::reserve(ERC20, &bob(), 1),
+                Err(module_evm_bridge::Error::<Runtime>::ExecutionRevert.into())
+            );
         });
 }
 
@@ -464,21 +590,168 @@ fn erc20_should_not_slash() {
 }
 
 #[test]
-fn erc20_should_not_be_lockable() {
-    ExtBuilder::default().build().execute_with(|| {
-        assert_noop!(
-            Currencies::set_lock(ID_1, ERC20, &alice(), 1),
-            Error::<Runtime>::ERC20InvalidOperation
-        );
-        assert_noop!(
-            Currencies::extend_lock(ID_1, ERC20, &alice(), 1),
-            Error::<Runtime>::ERC20InvalidOperation
-        );
-        assert_noop!(
-            Currencies::remove_lock(ID_1, ERC20, &alice()),
-            Error::<Runtime>::ERC20InvalidOperation
-        );
-    });
+fn erc20_lock_should_work() {
+    ExtBuilder::default()
+        .balances(vec![(alice(), NATIVE_CURRENCY_ID, 100000)])
+        .build()
+        .execute_with(|| {
+            let alice_balance = u128::max_value();
+
+            assert_ok!(Currencies::set_lock(ID_1, ERC20, &alice(), 100));
+            assert_eq!(Currencies::erc20_locks(ERC20_ADDRESS, alice()).get(&ID_1), Some(&100));
+            assert_eq!(erc20_balance_of(ERC20_HOLDING_ACCOUNT), 100);
+            // locked balance is still free, but can't be withdrawn
+            assert_eq!(Currencies::free_balance(ERC20, &alice()), alice_balance);
+            assert_ok!(Currencies::ensure_can_withdraw(ERC20, &alice(), alice_balance - 100));
+            assert_noop!(
+                Currencies::ensure_can_withdraw(ERC20, &alice(), alice_balance - 99),
+                Error::<Runtime>::BalanceTooLow
+            );
+
+            // the largest lock is held
+            assert_ok!(Currencies::set_lock(ID_2, ERC20, &alice(), 50));
+            assert_eq!(erc20_balance_of(ERC20_HOLDING_ACCOUNT), 100);
+            assert_ok!(Currencies::extend_lock(ID_2, ERC20, &alice(), 150));
+            assert_eq!(erc20_balance_of(ERC20_HOLDING_ACCOUNT), 150);
+            assert_ok!(Currencies::extend_lock(ID_2, ERC20, &alice(), 120));
+            assert_eq!(Currencies::erc20_locks(ERC20_ADDRESS, alice()).get(&ID_2), Some(&150));
+
+            // shrinking a lock releases the difference
+            assert_ok!(Currencies::set_lock(ID_2, ERC20, &alice(), 20));
+            assert_eq!(erc20_balance_of(ERC20_HOLDING_ACCOUNT), 100);
+            assert_ok!(Currencies::remove_lock(ID_1, ERC20, &alice()));
+            assert_eq!(erc20_balance_of(ERC20_HOLDING_ACCOUNT), 20);
+            assert_ok!(Currencies::remove_lock(ID_2, ERC20, &alice()));
+            assert_eq!(erc20_balance_of(ERC20_HOLDING_ACCOUNT), 0);
+            assert!(!Erc20Locks::<Runtime>::contains_key(ERC20_ADDRESS, alice()));
+            assert_eq!(Currencies::free_balance(ERC20, &alice()), alice_balance);
+
+            // bob has no tokens to hold
+            assert_ok!(Currencies::set_lock(ID_1, ERC20, &bob(), 1));
+            assert_eq!(Currencies::erc20_locks(ERC20_ADDRESS, bob()).get(&ID_1), Some(&1));
+            assert_eq!(Currencies::erc20_locked_balances(ERC20_ADDRESS, bob()), 0);
+        });
+}
+
+#[test]
+fn erc20_lock_should_not_need_the_balance() {
+    ExtBuilder::default()
+        .balances(vec![
+            (alice(), NATIVE_CURRENCY_ID, 100000),
+            (bob(), NATIVE_CURRENCY_ID, 100000),
+        ])
+        .build()
+        .execute_with(|| {
+            <EVM as EVMTrait<AccountId>>::set_origin(alice());
+            assert_ok!(Currencies::transfer(Origin::signed(alice()), bob(), ERC20, 100));
+
+            // the balance is held, the rest of the lock restricts the withdrawals
+            assert_ok!(Currencies::set_lock(ID_1, ERC20, &bob(), 150));
+            assert_eq!(Currencies::erc20_locked_balances(ERC20_ADDRESS, bob()), 100);
+            assert_eq!(erc20_balance_of(ERC20_HOLDING_ACCOUNT), 100);
+            assert_eq!(Currencies::free_balance(ERC20, &bob()), 100);
+
+            assert_ok!(Currencies::transfer(Origin::signed(alice()), bob(), ERC20, 80));
+            assert_ok!(Currencies::ensure_can_withdraw(ERC20, &bob(), 30));
+            assert_noop!(
+                Currencies::ensure_can_withdraw(ERC20, &bob(), 31),
+                Error::<Runtime>::BalanceTooLow
+            );
+
+            // the next update holds the rest of the lock
+            assert_ok!(Currencies::extend_lock(ID_1, ERC20, &bob(), 160));
+            assert_eq!(Currencies::erc20_locked_balances(ERC20_ADDRESS, bob()), 160);
+            assert_eq!(erc20_balance_of(ERC20_HOLDING_ACCOUNT), 160);
+
+            assert_ok!(Currencies::remove_lock(ID_1, ERC20, &bob()));
+            assert!(!Erc20LockedBalances::<Runtime>::contains_key(ERC20_ADDRESS, bob()));
+            assert_eq!(Currencies::free_balance(ERC20, &bob()), 180);
+        });
+}
+
+#[test]
+fn erc20_reserves_migration_should_work() {
+    ExtBuilder::default()
+        .balances(vec![(alice(), NATIVE_CURRENCY_ID, 100000)])
+        .build()
+        .execute_with(|| {
+            let alice_address = <Runtime as Config>::AddressMapping::get_evm_address(&alice()).unwrap();
+            let bob_address = <Runtime as Config>::AddressMapping::get_evm_address(&bob()).unwrap();
+            let reserve = |address: H160, amount: u128| {
+                assert_ok!(<EVMBridge as support::EVMBridge<AccountId, u128>>::transfer(
+                    support::InvokeContext {
+                        contract: ERC20_ADDRESS,
+                        sender: alice_address,
+                        origin: alice_address,
+                    },
+                    migrations::v1::reserve_address(address),
+                    amount,
+                ));
+            };
+            reserve(alice_address, 100);
+            reserve(bob_address, 50);
+            StorageVersion::new(0).put::<Currencies>();
+
+            // the migration only starts on the upgrade
+            migrations::v1::MigrateErc20Reserves::<Runtime>::on_runtime_upgrade();
+            assert_eq!(StorageVersion::get::<Currencies>(), 1);
+            assert_eq!(Currencies::reserved_balance(ERC20, &alice()), 0);
+
+            // not enough weight for an account
+            Currencies::on_idle(1, 999);
+            assert_eq!(Currencies::reserved_balance(ERC20, &alice()), 0);
+
+            // an account per block
+            assert_eq!(Currencies::on_idle(1, 1_500), 1_000);
+            assert_eq!(erc20_balance_of(migrations::v1::reserve_address(alice_address)), 0);
+            assert_eq!(erc20_balance_of(ERC20_HOLDING_ACCOUNT), 100);
+            assert_eq!(Currencies::reserved_balance(ERC20, &alice()), 100);
+            assert_eq!(Currencies::reserved_balance(ERC20, &bob()), 0);
+            assert_eq!(Erc20ReservesMigration::<Runtime>::get(), Some(Some(alice())));
+
+            assert_eq!(Currencies::on_idle(1, 1_500), 1_000);
+            assert_eq!(erc20_balance_of(ERC20_HOLDING_ACCOUNT), 150);
+            assert_eq!(Currencies::reserved_balance(ERC20, &bob()), 50);
+
+            // the last page is shorter, the migration is done
+            Currencies::on_idle(1, 1_500);
+            assert!(!Erc20ReservesMigration::<Runtime>::exists());
+
+            // only migrated once
+            reserve(alice_address, 100);
+            migrations::v1::MigrateErc20Reserves::<Runtime>::on_runtime_upgrade();
+            assert_eq!(Currencies::on_idle(1, 1_500), 0);
+            assert_eq!(Currencies::reserved_balance(ERC20, &alice()), 100);
+        });
+}
+
+#[test]
+fn erc20_reserve_and_lock_should_work_together() {
+    ExtBuilder::default()
+        .balances(vec![
+            (alice(), NATIVE_CURRENCY_ID, 100000),
+            (bob(), NATIVE_CURRENCY_ID, 100000),
+        ])
+        .build()
+        .execute_with(|| {
+            <EVM as EVMTrait<AccountId>>::set_origin(alice());
+            assert_ok!(Currencies::transfer(Origin::signed(alice()), bob(), ERC20, 100));
+
+            assert_ok!(Currencies::set_lock(ID_1, ERC20, &bob(), 60));
+            assert!(!Currencies::can_reserve(ERC20, &bob(), 41));
+            assert_ok!(Currencies::reserve(ERC20, &bob(), 40));
+            assert_eq!(erc20_balance_of(ERC20_HOLDING_ACCOUNT), 100);
+
+            assert_eq!(Currencies::free_balance(ERC20, &bob()), 60);
+            assert_eq!(Currencies::reserved_balance(ERC20, &bob()), 40);
+            assert_eq!(Currencies::total_balance(ERC20, &bob()), 100);
+
+            assert_eq!(Currencies::unreserve(ERC20, &bob(), 40), 0);
+            assert_ok!(Currencies::remove_lock(ID_1, ERC20, &bob()));
+            assert_eq!(erc20_balance_of(ERC20_HOLDING_ACCOUNT), 0);
+            let bob_address = <Runtime as Config>::AddressMapping::get_evm_address(&bob()).unwrap();
+            assert_eq!(erc20_balance_of(bob_address), 100);
+        });
 }
 
 #[test]
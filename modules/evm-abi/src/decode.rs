@@ -0,0 +1,108 @@
+use crate::{Error, ParamType, Token, WORD_SIZE};
+use ethereum_types::{H160, U256};
+use sp_std::{iter, vec::Vec};
+
+/// Decode a sequence of `types`, e.g. the parameters of a function call.
+pub fn decode(types: &[ParamType], data: &[u8]) -> Result<Vec<Token>, Error> {
+    decode_sequence(types.iter(), data, 0)
+}
+
+/// Decode a sequence whose heads start at `base`. Offsets of dynamic
+/// elements are relative to `base`.
+fn decode_sequence<'a>(
+    types: impl Iterator<Item = &'a ParamType>,
+    data: &[u8],
+    base: usize,
+) -> Result<Vec<Token>, Error> {
+    let mut tokens = Vec::new();
+    let mut position = base;
+    for param_type in types {
+        let token = if param_type.is_dynamic() {
+            let offset = read_usize(data, position)?;
+            decode_token(param_type, data, base.checked_add(offset).ok_or(Error::InvalidData)?)?
+        } else {
+            decode_token(param_type, data, position)?
+        };
+        tokens.push(token);
+        position = position.checked_add(param_type.head_size()).ok_or(Error::InvalidData)?;
+    }
+    Ok(tokens)
+}
+
+fn decode_token(param_type: &ParamType, data: &[u8], at: usize) -> Result<Token, Error> {
+    match param_type {
+        ParamType::Address => {
+            let bytes = read_word(data, at)?;
+            if bytes[..12].iter().any(|b| *b != 0) {
+                return Err(Error::InvalidData);
+            }
+            Ok(Token::Address(H160::from_slice(&bytes[12..])))
+        }
+        ParamType::Uint(bits) => {
+            let value = U256::from_big_endian(read_word(data, at)?);
+            if *bits < 256 && !(value >> *bits).is_zero() {
+                return Err(Error::InvalidData);
+            }
+            Ok(Token::Uint(value))
+        }
+        ParamType::Int(bits) => {
+            let value = U256::from_big_endian(read_word(data, at)?);
+            if *bits > 0 && *bits < 256 {
+                // the bits above the sign bit must all equal the sign bit
+                let high = value >> (*bits - 1);
+                if !high.is_zero() && high != U256::MAX >> (*bits - 1) {
+                    return Err(Error::InvalidData);
+                }
+            }
+            Ok(Token::Int(value))
+        }
+        ParamType::Bool => match U256::from_big_endian(read_word(data, at)?) {
+            value if value.is_zero() => Ok(Token::Bool(false)),
+            value if value == U256::one() => Ok(Token::Bool(true)),
+            _ => Err(Error::InvalidData),
+        },
+        ParamType::FixedBytes(len) => {
+            if *len > WORD_SIZE {
+                return Err(Error::InvalidData);
+            }
+            Ok(Token::FixedBytes(read_word(data, at)?[..*len].to_vec()))
+        }
+        ParamType::Bytes => Ok(Token::Bytes(read_bytes(data, at)?)),
+        ParamType::String => Ok(Token::String(read_bytes(data, at)?)),
+        ParamType::Array(inner) => {
+            let len = read_usize(data, at)?;
+            // every element takes at least one word, reject lengths the data can't hold
+            if len > data.len() / WORD_SIZE {
+                return Err(Error::InvalidData);
+            }
+            let base = at.checked_add(WORD_SIZE).ok_or(Error::InvalidData)?;
+            decode_sequence(iter::repeat(inner.as_ref()).take(len), data, base).map(Token::Array)
+        }
+        ParamType::FixedArray(inner, len) => {
+            decode_sequence(iter::repeat(inner.as_ref()).take(*len), data, at).map(Token::FixedArray)
+        }
+        ParamType::Tuple(types) => decode_sequence(types.iter(), data, at).map(Token::Tuple),
+    }
+}
+
+fn read_word(data: &[u8], at: usize) -> Result<&[u8], Error> {
+    let end = at.checked_add(WORD_SIZE).ok_or(Error::InvalidData)?;
+    data.get(at..end).ok_or(Error::InvalidData)
+}
+
+fn read_usize(data: &[u8], at: usize) -> Result<usize, Error> {
+    let value = U256::from_big_endian(read_word(data, at)?);
+    if value > U256::from(usize::MAX) {
+        return Err(Error::InvalidData);
+    }
+    Ok(value.as_usize())
+}
+
+fn read_bytes(data: &[u8], at: usize) -> Result<Vec<u8>, Error> {
+    let len = read_usize(data, at)?;
+    let start = at.checked_add(WORD_SIZE).ok_or(Error::InvalidData)?;
+    let end = start.checked_add(len).ok_or(Error::InvalidData)?;
+    data.get(start..end)
+        .map(|bytes| bytes.to_vec())
+        .ok_or(Error::InvalidData)
+}
@@ -0,0 +1,74 @@
+use crate::{Token, WORD_SIZE};
+use ethereum_types::U256;
+use sp_std::vec::Vec;
+
+/// Encode `tokens` as a sequence, e.g. the parameters of a function call.
+pub fn encode(tokens: &[Token]) -> Vec<u8> {
+    encode_sequence(tokens)
+}
+
+fn encode_sequence(tokens: &[Token]) -> Vec<u8> {
+    let heads_size: usize = tokens.iter().map(head_size).sum();
+
+    let mut head = Vec::with_capacity(heads_size);
+    let mut tail = Vec::new();
+    for token in tokens {
+        if token.is_dynamic() {
+            // offset from the start of the sequence
+            head.extend_from_slice(&word(U256::from(heads_size + tail.len())));
+            tail.extend(encode_token(token));
+        } else {
+            head.extend(encode_token(token));
+        }
+    }
+
+    head.extend(tail);
+    head
+}
+
+fn head_size(token: &Token) -> usize {
+    if token.is_dynamic() {
+        return WORD_SIZE;
+    }
+    match token {
+        Token::FixedArray(inner) | Token::Tuple(inner) => inner.iter().map(head_size).sum(),
+        _ => WORD_SIZE,
+    }
+}
+
+fn encode_token(token: &Token) -> Vec<u8> {
+    match token {
+        Token::Address(address) => {
+            let mut bytes = [0u8; WORD_SIZE];
+            bytes[12..].copy_from_slice(address.as_bytes());
+            bytes.to_vec()
+        }
+        Token::Uint(value) | Token::Int(value) => word(*value).to_vec(),
+        Token::Bool(value) => word(U256::from(*value as u8)).to_vec(),
+        Token::FixedBytes(bytes) => pad_right(bytes),
+        Token::Bytes(bytes) | Token::String(bytes) => {
+            let mut data = word(U256::from(bytes.len())).to_vec();
+            data.extend(pad_right(bytes));
+            data
+        }
+        Token::Array(tokens) => {
+            let mut data = word(U256::from(tokens.len())).to_vec();
+            data.extend(encode_sequence(tokens));
+            data
+        }
+        Token::FixedArray(tokens) | Token::Tuple(tokens) => encode_sequence(tokens),
+    }
+}
+
+fn word(value: U256) -> [u8; WORD_SIZE] {
+    let mut bytes = [0u8; WORD_SIZE];
+    value.to_big_endian(&mut bytes);
+    bytes
+}
+
+fn pad_right(bytes: &[u8]) -> Vec<u8> {
+    let len = (bytes.len() + WORD_SIZE - 1) / WORD_SIZE * WORD_SIZE;
+    let mut data = bytes.to_vec();
+    data.resize(len, 0);
+    data
+}
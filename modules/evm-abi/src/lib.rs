@@ -0,0 +1,193 @@
+//! # EVM ABI
+//!
+//! A `no_std` encoder and decoder for the Solidity contract ABI.
+//!
+//! Values are described by [`ParamType`] and carried as [`Token`]s. The
+//! [`Tokenizable`] trait maps Rust types onto tokens, so that typed call data
+//! and return values can be built and read with [`encode_function_call`] and
+//! [`decode_params`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use ethereum_types::{H160, H256, U256};
+use sha3::{Digest, Keccak256};
+use sp_std::{boxed::Box, vec::Vec};
+
+mod decode;
+mod encode;
+mod tests;
+mod tokenizable;
+
+pub use decode::decode;
+pub use encode::encode;
+pub use tokenizable::{Bytes, SolString, Tokenizable};
+
+/// Size in bytes of an ABI word.
+pub const WORD_SIZE: usize = 32;
+
+/// Selector of the `Error(string)` revert reason.
+pub const ERROR_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// Selector of the `Panic(uint256)` revert reason.
+pub const PANIC_SELECTOR: [u8; 4] = [0x4e, 0x48, 0x7b, 0x71];
+
+/// Solidity ABI types.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParamType {
+    /// `address`
+    Address,
+    /// `uint<M>`, `M` in bits.
+    Uint(usize),
+    /// `int<M>`, `M` in bits.
+    Int(usize),
+    /// `bool`
+    Bool,
+    /// `bytes<M>`, `M` in bytes.
+    FixedBytes(usize),
+    /// `bytes`
+    Bytes,
+    /// `string`
+    String,
+    /// `T[]`
+    Array(Box<ParamType>),
+    /// `T[k]`
+    FixedArray(Box<ParamType>, usize),
+    /// `(T1, T2, ..., Tn)`, also used for structs.
+    Tuple(Vec<ParamType>),
+}
+
+impl ParamType {
+    /// Whether the type is encoded in the tail, behind an offset.
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            ParamType::Bytes | ParamType::String | ParamType::Array(_) => true,
+            ParamType::FixedArray(inner, len) => *len > 0 && inner.is_dynamic(),
+            ParamType::Tuple(inner) => inner.iter().any(ParamType::is_dynamic),
+            _ => false,
+        }
+    }
+
+    /// Number of bytes the type takes in the head of its enclosing sequence.
+    pub fn head_size(&self) -> usize {
+        if self.is_dynamic() {
+            return WORD_SIZE;
+        }
+        match self {
+            ParamType::FixedArray(inner, len) => inner.head_size().saturating_mul(*len),
+            ParamType::Tuple(inner) => inner.iter().map(ParamType::head_size).sum(),
+            _ => WORD_SIZE,
+        }
+    }
+}
+
+/// An ABI encoded value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Token {
+    Address(H160),
+    Uint(U256),
+    /// Two's complement representation of a signed integer.
+    Int(U256),
+    Bool(bool),
+    FixedBytes(Vec<u8>),
+    Bytes(Vec<u8>),
+    String(Vec<u8>),
+    Array(Vec<Token>),
+    FixedArray(Vec<Token>),
+    Tuple(Vec<Token>),
+}
+
+impl Token {
+    /// Whether the token is encoded in the tail, behind an offset.
+    pub fn is_dynamic(&self) -> bool {
+        match self {
+            Token::Bytes(_) | Token::String(_) | Token::Array(_) => true,
+            Token::FixedArray(inner) | Token::Tuple(inner) => inner.iter().any(Token::is_dynamic),
+            _ => false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Error {
+    /// The data is too short, or an offset, length or value is malformed.
+    InvalidData,
+    /// The token does not match the requested type.
+    InvalidToken,
+    /// The value does not fit into the requested type.
+    Overflow,
+}
+
+impl From<Error> for &'static str {
+    fn from(err: Error) -> &'static str {
+        match err {
+            Error::InvalidData => "invalid abi data",
+            Error::InvalidToken => "invalid abi token",
+            Error::Overflow => "abi value overflow",
+        }
+    }
+}
+
+/// Decoded reason of a reverted call.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RevertReason {
+    /// `revert("...")` or `require(false, "...")`
+    Error(Vec<u8>),
+    /// Compiler inserted checks, e.g. overflow or division by zero.
+    Panic(U256),
+}
+
+/// The first 4 bytes of the keccak256 hash of a function signature, e.g.
+/// `transfer(address,uint256)`.
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = Keccak256::digest(signature.as_bytes());
+    let mut selector = [0u8; 4];
+    selector.copy_from_slice(&hash[..4]);
+    selector
+}
+
+/// The keccak256 hash of an event signature, e.g.
+/// `Transfer(address,address,uint256)`, the first topic of its logs.
+pub fn event_topic(signature: &str) -> H256 {
+    H256::from_slice(&Keccak256::digest(signature.as_bytes()))
+}
+
+/// Encode `params` as a list of function parameters. A tuple is flattened
+/// into its elements, so a single struct argument needs to be wrapped in a
+/// 1-tuple.
+pub fn encode_params<T: Tokenizable>(params: T) -> Vec<u8> {
+    match params.into_token() {
+        Token::Tuple(tokens) => encode(&tokens),
+        token => encode(&[token]),
+    }
+}
+
+/// Decode a list of function parameters or return values, the inverse of
+/// `encode_params`.
+pub fn decode_params<T: Tokenizable>(data: &[u8]) -> Result<T, Error> {
+    match T::param_type() {
+        ParamType::Tuple(types) => T::from_token(Token::Tuple(decode(&types, data)?)),
+        param_type => T::from_token(decode(&[param_type], data)?.pop().ok_or(Error::InvalidData)?),
+    }
+}
+
+/// Build the call data of `signature` called with `params`.
+pub fn encode_function_call<T: Tokenizable>(signature: &str, params: T) -> Vec<u8> {
+    let mut data = selector(signature).to_vec();
+    data.extend(encode_params(params));
+    data
+}
+
+/// Decode the output of a reverted call, `None` if it isn't a known reason.
+pub fn decode_revert_reason(output: &[u8]) -> Option<RevertReason> {
+    if output.len() < 4 {
+        return None;
+    }
+    let (selector, data) = output.split_at(4);
+    if selector == ERROR_SELECTOR {
+        decode_params::<SolString>(data).ok().map(|s| RevertReason::Error(s.0))
+    } else if selector == PANIC_SELECTOR {
+        decode_params::<U256>(data).ok().map(RevertReason::Panic)
+    } else {
+        None
+    }
+}
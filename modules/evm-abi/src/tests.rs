@@ -0,0 +1,264 @@
+//! Unit tests for the evm-abi module.
+
+#![cfg(test)]
+
+use super::*;
+use core::str::FromStr;
+use ethereum_types::H256;
+use hex_literal::hex;
+
+#[test]
+fn selector_works() {
+    assert_eq!(selector("transfer(address,uint256)"), hex!("a9059cbb"));
+    assert_eq!(selector("baz(uint32,bool)"), hex!("cdcd77c0"));
+    assert_eq!(selector("Error(string)"), ERROR_SELECTOR);
+    assert_eq!(selector("Panic(uint256)"), PANIC_SELECTOR);
+}
+
+#[test]
+fn event_topic_works() {
+    assert_eq!(
+        event_topic("Transfer(address,address,uint256)"),
+        H256(hex!("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef"))
+    );
+    assert_eq!(
+        event_topic("Approval(address,address,uint256)"),
+        H256(hex!("8c5be1e5ebec7d5bd14f71427d1e84f3dd0314c0f7b2291e5b200ac8c7c3b925"))
+    );
+}
+
+#[test]
+fn encode_static_params_works() {
+    assert_eq!(
+        encode_function_call("baz(uint32,bool)", (69u32, true)),
+        hex!(
+            "cdcd77c0"
+            "0000000000000000000000000000000000000000000000000000000000000045"
+            "0000000000000000000000000000000000000000000000000000000000000001"
+        )
+        .to_vec()
+    );
+}
+
+#[test]
+fn encode_dynamic_params_works() {
+    let expected = hex!(
+        "a5643bf2"
+        "0000000000000000000000000000000000000000000000000000000000000060"
+        "0000000000000000000000000000000000000000000000000000000000000001"
+        "00000000000000000000000000000000000000000000000000000000000000a0"
+        "0000000000000000000000000000000000000000000000000000000000000004"
+        "6461766500000000000000000000000000000000000000000000000000000000"
+        "0000000000000000000000000000000000000000000000000000000000000003"
+        "0000000000000000000000000000000000000000000000000000000000000001"
+        "0000000000000000000000000000000000000000000000000000000000000002"
+        "0000000000000000000000000000000000000000000000000000000000000003"
+    )
+    .to_vec();
+    let params = (
+        Bytes(b"dave".to_vec()),
+        true,
+        vec![U256::from(1), U256::from(2), U256::from(3)],
+    );
+    assert_eq!(
+        encode_function_call("sam(bytes,bool,uint256[])", params.clone()),
+        expected
+    );
+
+    assert_eq!(decode_params::<(Bytes, bool, Vec<U256>)>(&expected[4..]), Ok(params));
+}
+
+#[test]
+fn encode_mixed_params_works() {
+    let expected = hex!(
+        "8be65246"
+        "0000000000000000000000000000000000000000000000000000000000000123"
+        "0000000000000000000000000000000000000000000000000000000000000080"
+        "3132333435363738393000000000000000000000000000000000000000000000"
+        "00000000000000000000000000000000000000000000000000000000000000e0"
+        "0000000000000000000000000000000000000000000000000000000000000002"
+        "0000000000000000000000000000000000000000000000000000000000000456"
+        "0000000000000000000000000000000000000000000000000000000000000789"
+        "000000000000000000000000000000000000000000000000000000000000000d"
+        "48656c6c6f2c20776f726c642100000000000000000000000000000000000000"
+    )
+    .to_vec();
+    let tokens = vec![
+        Token::Uint(0x123.into()),
+        Token::Array(vec![Token::Uint(0x456.into()), Token::Uint(0x789.into())]),
+        Token::FixedBytes(b"1234567890".to_vec()),
+        Token::Bytes(b"Hello, world!".to_vec()),
+    ];
+    let types = vec![
+        ParamType::Uint(256),
+        ParamType::Array(Box::new(ParamType::Uint(32))),
+        ParamType::FixedBytes(10),
+        ParamType::Bytes,
+    ];
+
+    assert_eq!(selector("f(uint256,uint32[],bytes10,bytes)"), expected[..4]);
+    assert_eq!(encode(&tokens), expected[4..].to_vec());
+    assert_eq!(decode(&types, &expected[4..]), Ok(tokens));
+}
+
+#[test]
+fn nested_dynamic_arrays_works() {
+    let expected = hex!(
+        "2289b18c"
+        "0000000000000000000000000000000000000000000000000000000000000040"
+        "0000000000000000000000000000000000000000000000000000000000000140"
+        "0000000000000000000000000000000000000000000000000000000000000002"
+        "0000000000000000000000000000000000000000000000000000000000000040"
+        "00000000000000000000000000000000000000000000000000000000000000a0"
+        "0000000000000000000000000000000000000000000000000000000000000002"
+        "0000000000000000000000000000000000000000000000000000000000000001"
+        "0000000000000000000000000000000000000000000000000000000000000002"
+        "0000000000000000000000000000000000000000000000000000000000000001"
+        "0000000000000000000000000000000000000000000000000000000000000003"
+        "0000000000000000000000000000000000000000000000000000000000000003"
+        "0000000000000000000000000000000000000000000000000000000000000060"
+        "00000000000000000000000000000000000000000000000000000000000000a0"
+        "00000000000000000000000000000000000000000000000000000000000000e0"
+        "0000000000000000000000000000000000000000000000000000000000000003"
+        "6f6e650000000000000000000000000000000000000000000000000000000000"
+        "0000000000000000000000000000000000000000000000000000000000000003"
+        "74776f0000000000000000000000000000000000000000000000000000000000"
+        "0000000000000000000000000000000000000000000000000000000000000005"
+        "7468726565000000000000000000000000000000000000000000000000000000"
+    )
+    .to_vec();
+    let params = (
+        vec![vec![U256::from(1), U256::from(2)], vec![U256::from(3)]],
+        vec![
+            SolString(b"one".to_vec()),
+            SolString(b"two".to_vec()),
+            SolString(b"three".to_vec()),
+        ],
+    );
+
+    assert_eq!(
+        encode_function_call("g(uint256[][],string[])", params.clone()),
+        expected
+    );
+    assert_eq!(
+        decode_params::<(Vec<Vec<U256>>, Vec<SolString>)>(&expected[4..]),
+        Ok(params)
+    );
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Transfer {
+    to: H160,
+    amount: U256,
+    memo: SolString,
+}
+
+impl Tokenizable for Transfer {
+    fn param_type() -> ParamType {
+        <(H160, U256, SolString)>::param_type()
+    }
+
+    fn into_token(self) -> Token {
+        (self.to, self.amount, self.memo).into_token()
+    }
+
+    fn from_token(token: Token) -> Result<Self, Error> {
+        let (to, amount, memo) = <(H160, U256, SolString)>::from_token(token)?;
+        Ok(Transfer { to, amount, memo })
+    }
+}
+
+#[test]
+fn nested_struct_works() {
+    let transfer = Transfer {
+        to: H160::from_str("1000000000000000000000000000000000000001").unwrap(),
+        amount: U256::from(100),
+        memo: SolString(b"memo".to_vec()),
+    };
+    let transfers = vec![transfer.clone(), transfer.clone()];
+
+    // f((address,uint256,string),uint8)
+    let data = encode_params((transfer.clone(), 7u8));
+    assert_eq!(
+        data[..32],
+        hex!("0000000000000000000000000000000000000000000000000000000000000040")
+    );
+    assert_eq!(decode_params::<(Transfer, u8)>(&data), Ok((transfer, 7u8)));
+
+    // f((address,uint256,string)[])
+    let data = encode_params((transfers.clone(),));
+    assert_eq!(decode_params::<(Vec<Transfer>,)>(&data), Ok((transfers,)));
+}
+
+#[test]
+fn integers_works() {
+    assert_eq!(decode_params::<i128>(&encode_params(-1i128)), Ok(-1i128));
+    assert_eq!(encode_params(-1i128), [0xffu8; 32].to_vec());
+    assert_eq!(decode_params::<i128>(&encode_params(i128::MIN)), Ok(i128::MIN));
+    assert_eq!(decode_params::<i128>(&encode_params(i128::MAX)), Ok(i128::MAX));
+    assert_eq!(decode_params::<u128>(&encode_params(u128::MAX)), Ok(u128::MAX));
+    assert_eq!(
+        decode_params::<H256>(&encode_params(H256::repeat_byte(1))),
+        Ok(H256::repeat_byte(1))
+    );
+
+    // dirty high bits
+    assert_eq!(
+        decode_params::<u32>(&encode_params(U256::from(u64::MAX))),
+        Err(Error::InvalidData)
+    );
+    assert_eq!(u32::from_token(Token::Uint(U256::from(u64::MAX))), Err(Error::Overflow));
+    assert_eq!(decode_params::<bool>(&encode_params(2u8)), Err(Error::InvalidData));
+    assert_eq!(
+        decode_params::<H160>(&encode_params(U256::MAX)),
+        Err(Error::InvalidData)
+    );
+}
+
+#[test]
+fn decode_invalid_data_fails() {
+    assert_eq!(decode_params::<U256>(&[0u8; 31]), Err(Error::InvalidData));
+    assert_eq!(decode_params::<Bytes>(&[]), Err(Error::InvalidData));
+
+    // offset points outside the data
+    let mut data = encode_params(Bytes(b"dave".to_vec()));
+    data[31] = 0xff;
+    assert_eq!(decode_params::<Bytes>(&data), Err(Error::InvalidData));
+
+    // length exceeds the data
+    let mut data = encode_params(Bytes(b"dave".to_vec()));
+    data[63] = 0xff;
+    assert_eq!(decode_params::<Bytes>(&data), Err(Error::InvalidData));
+
+    // array length exceeds the data
+    let mut data = encode_params(vec![U256::one()]);
+    data[62] = 0xff;
+    assert_eq!(decode_params::<Vec<U256>>(&data), Err(Error::InvalidData));
+}
+
+#[test]
+fn decode_revert_reason_works() {
+    let output = hex!(
+        "08c379a0"
+        "0000000000000000000000000000000000000000000000000000000000000020"
+        "000000000000000000000000000000000000000000000000000000000000001a"
+        "4e6f7420656e6f7567682045746865722070726f76696465642e000000000000"
+    );
+    assert_eq!(
+        decode_revert_reason(&output),
+        Some(RevertReason::Error(b"Not enough Ether provided.".to_vec()))
+    );
+
+    let output = hex!(
+        "4e487b71"
+        "0000000000000000000000000000000000000000000000000000000000000011"
+    );
+    assert_eq!(
+        decode_revert_reason(&output),
+        Some(RevertReason::Panic(U256::from(0x11)))
+    );
+
+    assert_eq!(decode_revert_reason(&[]), None);
+    assert_eq!(decode_revert_reason(&hex!("a9059cbb")), None);
+    assert_eq!(decode_revert_reason(&output[..20]), None);
+}
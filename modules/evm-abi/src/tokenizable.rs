@@ -0,0 +1,264 @@
+use crate::{Error, ParamType, Token};
+use ethereum_types::{H160, H256, U256};
+use sp_std::{boxed::Box, convert::TryFrom, vec, vec::Vec};
+
+/// A Rust type with a Solidity ABI representation.
+///
+/// Structs are represented as tuples:
+///
+/// ```ignore
+/// impl Tokenizable for Transfer {
+///     fn param_type() -> ParamType {
+///         <(H160, U256)>::param_type()
+///     }
+///     fn into_token(self) -> Token {
+///         (self.to, self.amount).into_token()
+///     }
+///     fn from_token(token: Token) -> Result<Self, Error> {
+///         let (to, amount) = <(H160, U256)>::from_token(token)?;
+///         Ok(Transfer { to, amount })
+///     }
+/// }
+/// ```
+pub trait Tokenizable: Sized {
+    fn param_type() -> ParamType;
+    fn into_token(self) -> Token;
+    fn from_token(token: Token) -> Result<Self, Error>;
+}
+
+/// Solidity `bytes`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bytes(pub Vec<u8>);
+
+/// Solidity `string`, kept as raw bytes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SolString(pub Vec<u8>);
+
+impl Tokenizable for H160 {
+    fn param_type() -> ParamType {
+        ParamType::Address
+    }
+
+    fn into_token(self) -> Token {
+        Token::Address(self)
+    }
+
+    fn from_token(token: Token) -> Result<Self, Error> {
+        match token {
+            Token::Address(address) => Ok(address),
+            _ => Err(Error::InvalidToken),
+        }
+    }
+}
+
+impl Tokenizable for H256 {
+    fn param_type() -> ParamType {
+        ParamType::FixedBytes(32)
+    }
+
+    fn into_token(self) -> Token {
+        Token::FixedBytes(self.as_bytes().to_vec())
+    }
+
+    fn from_token(token: Token) -> Result<Self, Error> {
+        match token {
+            Token::FixedBytes(bytes) if bytes.len() == 32 => Ok(H256::from_slice(&bytes)),
+            _ => Err(Error::InvalidToken),
+        }
+    }
+}
+
+impl Tokenizable for U256 {
+    fn param_type() -> ParamType {
+        ParamType::Uint(256)
+    }
+
+    fn into_token(self) -> Token {
+        Token::Uint(self)
+    }
+
+    fn from_token(token: Token) -> Result<Self, Error> {
+        match token {
+            Token::Uint(value) => Ok(value),
+            _ => Err(Error::InvalidToken),
+        }
+    }
+}
+
+impl Tokenizable for bool {
+    fn param_type() -> ParamType {
+        ParamType::Bool
+    }
+
+    fn into_token(self) -> Token {
+        Token::Bool(self)
+    }
+
+    fn from_token(token: Token) -> Result<Self, Error> {
+        match token {
+            Token::Bool(value) => Ok(value),
+            _ => Err(Error::InvalidToken),
+        }
+    }
+}
+
+macro_rules! impl_uint {
+    ($($t:ty => $bits:expr),+) => {
+        $(
+            impl Tokenizable for $t {
+                fn param_type() -> ParamType {
+                    ParamType::Uint($bits)
+                }
+
+                fn into_token(self) -> Token {
+                    Token::Uint(U256::from(self))
+                }
+
+                fn from_token(token: Token) -> Result<Self, Error> {
+                    match token {
+                        Token::Uint(value) => <$t>::try_from(value).map_err(|_| Error::Overflow),
+                        _ => Err(Error::InvalidToken),
+                    }
+                }
+            }
+        )+
+    };
+}
+
+impl_uint!(u8 => 8, u16 => 16, u32 => 32, u64 => 64, u128 => 128);
+
+impl Tokenizable for i128 {
+    fn param_type() -> ParamType {
+        ParamType::Int(128)
+    }
+
+    fn into_token(self) -> Token {
+        if self >= 0 {
+            Token::Int(U256::from(self as u128))
+        } else {
+            // !x == -x - 1, so !U256(!x) is the two's complement of x
+            Token::Int(!U256::from((!self) as u128))
+        }
+    }
+
+    fn from_token(token: Token) -> Result<Self, Error> {
+        match token {
+            Token::Int(value) => {
+                let (negative, magnitude) = if value.bit(255) { (true, !value) } else { (false, value) };
+                if magnitude > U256::from(i128::MAX as u128) {
+                    return Err(Error::Overflow);
+                }
+                let magnitude = magnitude.low_u128() as i128;
+                Ok(if negative { !magnitude } else { magnitude })
+            }
+            _ => Err(Error::InvalidToken),
+        }
+    }
+}
+
+impl Tokenizable for Bytes {
+    fn param_type() -> ParamType {
+        ParamType::Bytes
+    }
+
+    fn into_token(self) -> Token {
+        Token::Bytes(self.0)
+    }
+
+    fn from_token(token: Token) -> Result<Self, Error> {
+        match token {
+            Token::Bytes(bytes) => Ok(Bytes(bytes)),
+            _ => Err(Error::InvalidToken),
+        }
+    }
+}
+
+impl Tokenizable for SolString {
+    fn param_type() -> ParamType {
+        ParamType::String
+    }
+
+    fn into_token(self) -> Token {
+        Token::String(self.0)
+    }
+
+    fn from_token(token: Token) -> Result<Self, Error> {
+        match token {
+            Token::String(bytes) => Ok(SolString(bytes)),
+            _ => Err(Error::InvalidToken),
+        }
+    }
+}
+
+impl<T: Tokenizable> Tokenizable for Vec<T> {
+    fn param_type() -> ParamType {
+        ParamType::Array(Box::new(T::param_type()))
+    }
+
+    fn into_token(self) -> Token {
+        Token::Array(self.into_iter().map(Tokenizable::into_token).collect())
+    }
+
+    fn from_token(token: Token) -> Result<Self, Error> {
+        match token {
+            Token::Array(tokens) => tokens.into_iter().map(T::from_token).collect(),
+            _ => Err(Error::InvalidToken),
+        }
+    }
+}
+
+impl Tokenizable for () {
+    fn param_type() -> ParamType {
+        ParamType::Tuple(vec![])
+    }
+
+    fn into_token(self) -> Token {
+        Token::Tuple(vec![])
+    }
+
+    fn from_token(token: Token) -> Result<Self, Error> {
+        match token {
+            Token::Tuple(tokens) if tokens.is_empty() => Ok(()),
+            _ => Err(Error::InvalidToken),
+        }
+    }
+}
+
+macro_rules! impl_tuple {
+    ($($name:ident),+) => {
+        #[allow(non_snake_case)]
+        impl<$($name: Tokenizable),+> Tokenizable for ($($name,)+) {
+            fn param_type() -> ParamType {
+                ParamType::Tuple(vec![$($name::param_type()),+])
+            }
+
+            fn into_token(self) -> Token {
+                let ($($name,)+) = self;
+                Token::Tuple(vec![$($name.into_token()),+])
+            }
+
+            fn from_token(token: Token) -> Result<Self, Error> {
+                match token {
+                    Token::Tuple(tokens) => {
+                        let mut tokens = tokens.into_iter();
+                        let value = ($($name::from_token(tokens.next().ok_or(Error::InvalidToken)?)?,)+);
+                        if tokens.next().is_some() {
+                            return Err(Error::InvalidToken);
+                        }
+                        Ok(value)
+                    }
+                    _ => Err(Error::InvalidToken),
+                }
+            }
+        }
+    };
+}
+
+impl_tuple!(A);
+impl_tuple!(A, B);
+impl_tuple!(A, B, C);
+impl_tuple!(A, B, C, D);
+impl_tuple!(A, B, C, D, E);
+impl_tuple!(A, B, C, D, E, F);
+impl_tuple!(A, B, C, D, E, F, G);
+impl_tuple!(A, B, C, D, E, F, G, H);
@@ -3,19 +3,22 @@
 
 use sp_std::vec::Vec;
 
-use ethereum_types::{BigEndianHash, H160, H256, U256};
+use ethereum_types::{H160, U256};
 use frame_support::{
     dispatch::{DispatchError, DispatchResult},
     pallet_prelude::*,
 };
-use hex_literal::hex;
 use module_evm::{ExitReason, ExitSucceed};
+use module_evm_abi::{decode_params, decode_revert_reason, encode_function_call, SolString, Tokenizable};
 use sp_runtime::SaturatedConversion;
 use support::{EVMBridge as EVMBridgeTrait, ExecutionMode, InvokeContext, EVM};
 
 type AccountIdOf<T> = <T as frame_system::Config>::AccountId;
 type BalanceOf<T> = <<T as Config>::EVM as EVM<AccountIdOf<T>>>::Balance;
 
+/// The gas limit of each call to an ERC20 contract.
+pub const GAS_LIMIT: u64 = 2_100_000;
+
 mod mock;
 mod tests;
 
@@ -52,96 +55,92 @@ pub mod module {
 
 impl<T: Config> EVMBridgeTrait<AccountIdOf<T>, BalanceOf<T>> for Pallet<T> {
     fn name(context: InvokeContext) -> Result<Vec<u8>, DispatchError> {
-        // ERC20.name method hash
-        let input = hex!["06fdde03"].to_vec();
+        let input = encode_function_call("name()", ());
 
-        let info = T::EVM::execute(context, input, Default::default(), 2_100_000, 0, ExecutionMode::View)?;
+        let info = T::EVM::execute(context, input, Default::default(), GAS_LIMIT, 0, ExecutionMode::View)?;
 
-        Self::handle_exit_reason(info.exit_reason)?;
-        Self::decode_string(info.output.as_slice().to_vec())
+        Self::handle_exit_reason(info.exit_reason, &info.output)?;
+        Self::decode_output::<SolString>(&info.output).map(|name| name.0)
     }
 
     fn symbol(context: InvokeContext) -> Result<Vec<u8>, DispatchError> {
-        // ERC20.symbol method hash
-        let input = hex!["95d89b41"].to_vec();
+        let input = encode_function_call("symbol()", ());
 
-        let info = T::EVM::execute(context, input, Default::default(), 2_100_000, 0, ExecutionMode::View)?;
+        let info = T::EVM::execute(context, input, Default::default(), GAS_LIMIT, 0, ExecutionMode::View)?;
 
-        Self::handle_exit_reason(info.exit_reason)?;
-        Self::decode_string(info.output.as_slice().to_vec())
+        Self::handle_exit_reason(info.exit_reason, &info.output)?;
+        Self::decode_output::<SolString>(&info.output).map(|symbol| symbol.0)
     }
 
     fn decimals(context: InvokeContext) -> Result<u8, DispatchError> {
-        // ERC20.decimals method hash
-        let input = hex!["313ce567"].to_vec();
-
-        let info = T::EVM::execute(context, input, Default::default(), 2_100_000, 0, ExecutionMode::View)?;
+        let input = encode_function_call("decimals()", ());
 
-        Self::handle_exit_reason(info.exit_reason)?;
+        let info = T::EVM::execute(context, input, Default::default(), GAS_LIMIT, 0, ExecutionMode::View)?;
 
-        ensure!(info.output.len() == 32, Error::<T>::InvalidReturnValue);
-        let value = U256::from(info.output.as_slice()).saturated_into::<u8>();
-        Ok(value)
+        Self::handle_exit_reason(info.exit_reason, &info.output)?;
+        Self::decode_output::<u8>(&info.output)
     }
 
     fn total_supply(context: InvokeContext) -> Result<BalanceOf<T>, DispatchError> {
-        // ERC20.totalSupply method hash
-        let input = hex!("18160ddd").to_vec();
+        let input = encode_function_call("totalSupply()", ());
 
-        let info = T::EVM::execute(context, input, Default::default(), 2_100_000, 0, ExecutionMode::View)?;
+        let info = T::EVM::execute(context, input, Default::default(), GAS_LIMIT, 0, ExecutionMode::View)?;
 
-        Self::handle_exit_reason(info.exit_reason)?;
+        Self::handle_exit_reason(info.exit_reason, &info.output)?;
 
-        let value = U256::from(info.output.as_slice()).saturated_into::<u128>();
+        let value = Self::decode_output::<U256>(&info.output)?.saturated_into::<u128>();
         Ok(value.saturated_into::<BalanceOf<T>>())
     }
 
     fn balance_of(context: InvokeContext, address: H160) -> Result<BalanceOf<T>, DispatchError> {
-        // ERC20.balanceOf method hash
-        let mut input = hex!("70a08231").to_vec();
-        // append address
-        input.extend_from_slice(H256::from(address).as_bytes());
+        let input = encode_function_call("balanceOf(address)", (address,));
 
-        let info = T::EVM::execute(context, input, Default::default(), 2_100_000, 0, ExecutionMode::View)?;
+        let info = T::EVM::execute(context, input, Default::default(), GAS_LIMIT, 0, ExecutionMode::View)?;
 
-        Self::handle_exit_reason(info.exit_reason)?;
+        Self::handle_exit_reason(info.exit_reason, &info.output)?;
 
-        Ok(U256::from(info.output.as_slice())
+        Ok(Self::decode_output::<U256>(&info.output)?
             .saturated_into::<u128>()
             .saturated_into::<BalanceOf<T>>())
     }
 
     fn transfer(context: InvokeContext, to: H160, value: BalanceOf<T>) -> DispatchResult {
-        // ERC20.transfer method hash
-        let mut input = hex!("a9059cbb").to_vec();
-        // append receiver address
-        input.extend_from_slice(H256::from(to).as_bytes());
-        // append amount to be transferred
-        input.extend_from_slice(H256::from_uint(&U256::from(value.saturated_into::<u128>())).as_bytes());
+        let input = encode_function_call(
+            "transfer(address,uint256)",
+            (to, U256::from(value.saturated_into::<u128>())),
+        );
 
-        let storage_limit = if context.origin == Default::default() { 0 } else { 1_000 };
+        Self::execute_and_ensure_true(context, input)
+    }
 
-        let info = T::EVM::execute(
-            context,
-            input,
-            Default::default(),
-            2_100_000,
-            storage_limit,
-            ExecutionMode::Execute,
-        )?;
+    fn approve(context: InvokeContext, spender: H160, value: BalanceOf<T>) -> DispatchResult {
+        let input = encode_function_call(
+            "approve(address,uint256)",
+            (spender, U256::from(value.saturated_into::<u128>())),
+        );
 
-        Self::handle_exit_reason(info.exit_reason)?;
+        Self::execute_and_ensure_true(context, input)
+    }
 
-        // return value is true.
-        let mut bytes = [0u8; 32];
-        U256::from(1).to_big_endian(&mut bytes);
+    fn allowance(context: InvokeContext, owner: H160, spender: H160) -> Result<BalanceOf<T>, DispatchError> {
+        let input = encode_function_call("allowance(address,address)", (owner, spender));
 
-        // Check return value to make sure not calling on empty contracts.
-        ensure!(
-            !info.output.is_empty() && info.output == bytes,
-            Error::<T>::InvalidReturnValue
+        let info = T::EVM::execute(context, input, Default::default(), GAS_LIMIT, 0, ExecutionMode::View)?;
+
+        Self::handle_exit_reason(info.exit_reason, &info.output)?;
+
+        Ok(Self::decode_output::<U256>(&info.output)?
+            .saturated_into::<u128>()
+            .saturated_into::<BalanceOf<T>>())
+    }
+
+    fn transfer_from(context: InvokeContext, from: H160, to: H160, value: BalanceOf<T>) -> DispatchResult {
+        let input = encode_function_call(
+            "transferFrom(address,address,uint256)",
+            (from, to, U256::from(value.saturated_into::<u128>())),
         );
-        Ok(())
+
+        Self::execute_and_ensure_true(context, input)
     }
 
     fn get_origin() -> Option<AccountIdOf<T>> {
@@ -154,62 +153,44 @@ impl<T: Config> EVMBridgeTrait<AccountIdOf<T>, BalanceOf<T>> for Pallet<T> {
 }
 
 impl<T: Config> Pallet<T> {
-    fn handle_exit_reason(exit_reason: ExitReason) -> Result<(), DispatchError> {
+    fn handle_exit_reason(exit_reason: ExitReason, output: &[u8]) -> Result<(), DispatchError> {
         match exit_reason {
             ExitReason::Succeed(ExitSucceed::Returned) => Ok(()),
             ExitReason::Succeed(ExitSucceed::Stopped) => Ok(()),
             ExitReason::Succeed(_) => Err(Error::<T>::ExecutionFail.into()),
-            ExitReason::Revert(_) => Err(Error::<T>::ExecutionRevert.into()),
+            ExitReason::Revert(_) => {
+                log::debug!(target: "evm-bridge", "execution reverted: {:?}", decode_revert_reason(output));
+                Err(Error::<T>::ExecutionRevert.into())
+            }
             ExitReason::Fatal(_) => Err(Error::<T>::ExecutionFatal.into()),
             ExitReason::Error(_) => Err(Error::<T>::ExecutionError.into()),
         }
     }
 
-    fn decode_string(output: Vec<u8>) -> Result<Vec<u8>, DispatchError> {
-        // output is 32-byte aligned and consists of 3 parts:
-        // - part 1: 32 byte, the offset of its description is passed in the position of
-        // the corresponding parameter or return value.
-        // - part 2: 32 byte, string length
-        // - part 3: string data
-        ensure!(
-            output.len() >= 64 && output.len() % 32 == 0,
-            Error::<T>::InvalidReturnValue
-        );
+    fn decode_output<V: Tokenizable>(output: &[u8]) -> Result<V, DispatchError> {
+        decode_params::<V>(output).map_err(|_| Error::<T>::InvalidReturnValue.into())
+    }
 
-        let offset = U256::from_big_endian(&output[0..32]);
-        ensure!(offset <= U256::from(usize::MAX), Error::<T>::InvalidReturnValue);
-        let length = U256::from_big_endian(
-            &output[offset.as_usize()
-                ..offset
-                    .as_usize()
-                    .checked_add(32)
-                    .ok_or(Error::<T>::InvalidReturnValue)?],
-        );
-        ensure!(length <= U256::from(usize::MAX), Error::<T>::InvalidReturnValue);
+    /// Execute a state changing ERC20 method, which must return `true`.
+    fn execute_and_ensure_true(context: InvokeContext, input: Vec<u8>) -> DispatchResult {
+        let storage_limit = if context.origin == Default::default() { 0 } else { 1_000 };
+
+        let info = T::EVM::execute(
+            context,
+            input,
+            Default::default(),
+            GAS_LIMIT,
+            storage_limit,
+            ExecutionMode::Execute,
+        )?;
+
+        Self::handle_exit_reason(info.exit_reason, &info.output)?;
+
+        // Check return value to make sure not calling on empty contracts.
         ensure!(
-            // output is 32-byte aligned. ensure total_length >= offset + string length + string data length.
-            output.len()
-                >= offset
-                    .as_usize()
-                    .checked_add(32)
-                    .and_then(|sum| sum.checked_add(length.as_usize()))
-                    .ok_or(Error::<T>::InvalidReturnValue)?,
+            Self::decode_output::<bool>(&info.output)?,
             Error::<T>::InvalidReturnValue
         );
-
-        let mut data = Vec::new();
-        data.extend_from_slice(
-            &output[offset
-                .as_usize()
-                .checked_add(32)
-                .ok_or(Error::<T>::InvalidReturnValue)?
-                ..offset
-                    .as_usize()
-                    .checked_add(32)
-                    .and_then(|sum| sum.checked_add(length.as_usize()))
-                    .ok_or(Error::<T>::InvalidReturnValue)?],
-        );
-
-        Ok(data.to_vec())
+        Ok(())
     }
 }
@@ -4,11 +4,17 @@
 
 use super::*;
 use frame_support::{construct_runtime, ord_parameter_types, parameter_types};
-use frame_system::EnsureSignedBy;
+use frame_system::{EnsureRoot, EnsureSignedBy};
 use module_evm::GenesisAccount;
-use primitives::{evm::EvmAddress, mocks::MockAddressMapping};
+use primitives::{
+    evm::EvmAddress,
+    mocks::{MockAddressMapping, MockRandomness},
+};
 use sp_core::{bytes::from_hex, crypto::AccountId32, H256};
-use sp_runtime::{testing::Header, traits::IdentityLookup};
+use sp_runtime::{
+    testing::Header,
+    traits::{Identity, IdentityLookup},
+};
 use sp_std::{collections::btree_map::BTreeMap, str::FromStr};
 
 pub type AccountId = AccountId32;
@@ -88,6 +94,7 @@ ord_parameter_types! {
     pub const NetworkContractAccount: AccountId32 = AccountId32::from([0u8; 32]);
     pub const StorageDepositPerByte: u128 = 10;
     pub const MaxCodeSize: u32 = 60 * 1024;
+    pub const MaxCodeHistory: u32 = 10;
     pub const DeveloperDeposit: u64 = 1000;
     pub const DeploymentFee: u64 = 200;
 }
@@ -99,11 +106,18 @@ impl module_evm::Config for Runtime {
     type NewContractExtraBytes = NewContractExtraBytes;
     type StorageDepositPerByte = StorageDepositPerByte;
     type MaxCodeSize = MaxCodeSize;
+    type MaxCodeHistory = MaxCodeHistory;
 
     type Event = Event;
     type Precompiles = ();
     type ChainId = ();
+    type MinimumGasPrice = ();
+    type TransactionByteFee = ();
     type GasToWeight = ();
+    type BaseFeeUpdate = Identity;
+    type FindAuthor = ();
+    type BlockGasLimit = ();
+    type Randomness = MockRandomness;
     type ChargeTransactionPayment = ();
     type NetworkContractOrigin = EnsureSignedBy<NetworkContractAccount, AccountId32>;
     type NetworkContractSource = NetworkContractSource;
@@ -111,6 +125,7 @@ impl module_evm::Config for Runtime {
     type DeveloperDeposit = DeveloperDeposit;
     type DeploymentFee = DeploymentFee;
     type FreeDeploymentOrigin = EnsureSignedBy<CouncilAccount, AccountId32>;
+    type PrecompileOrigin = EnsureRoot<AccountId32>;
 
     type WeightInfo = ();
 }
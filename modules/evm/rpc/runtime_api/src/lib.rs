@@ -1,18 +1,25 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::all)]
 
-use ethereum_types::H160;
-use primitives::evm::{CallInfo, CreateInfo, EstimateResourcesRequest};
+use ethereum_types::{H160, H256, U256};
+use primitives::evm::{
+    Account, AccountOverride, CallInfo, CreateInfo, EstimateResourcesRequest, ExtrinsicReceipt, Trace, TracerConfig,
+};
 use sp_runtime::{
     codec::Codec,
-    traits::{MaybeDisplay, MaybeFromStr},
+    traits::{Block as BlockT, MaybeDisplay, MaybeFromStr},
 };
 use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
+    /// Version 2 adds the Ethereum compatible queries, from `chain_id` on,
+    /// and the state overrides of `call` and `create`.
+    #[api_version(2)]
     pub trait EVMRuntimeRPCApi<Balance> where
         Balance: Codec + MaybeDisplay + MaybeFromStr,
     {
+        /// Calls `to` from `from`, on the state with the accounts of
+        /// `state_override` overridden. No change is kept.
         fn call(
             from: H160,
             to: H160,
@@ -21,8 +28,11 @@ sp_api::decl_runtime_apis! {
             gas_limit: u64,
             storage_limit: u32,
             estimate: bool,
+            state_override: Vec<(H160, AccountOverride)>,
         ) -> Result<CallInfo, sp_runtime::DispatchError>;
 
+        /// Creates a contract from `from`, on the state with the accounts of
+        /// `state_override` overridden. No change is kept.
         fn create(
             from: H160,
             data: Vec<u8>,
@@ -30,10 +40,61 @@ sp_api::decl_runtime_apis! {
             gas_limit: u64,
             storage_limit: u32,
             estimate: bool,
+            state_override: Vec<(H160, AccountOverride)>,
         ) -> Result<CreateInfo, sp_runtime::DispatchError>;
 
         fn get_estimate_resources_request(
             data: Vec<u8>
         ) -> Result<EstimateResourcesRequest, sp_runtime::DispatchError>;
+
+        /// Returns the EVM chain id.
+        fn chain_id() -> u64;
+
+        /// Returns the nonce and free balance of the account at `address`.
+        fn account_basic(address: H160) -> Account;
+
+        /// Returns the code deployed at `address`, empty if there is none.
+        fn get_code(address: H160) -> Vec<u8>;
+
+        /// Returns the value of the storage slot `index` of the contract at `address`.
+        fn get_storage_at(address: H160, index: H256) -> H256;
+
+        /// Returns the base fee per gas, the minimum gas price of EVM transactions.
+        fn gas_price() -> U256;
+
+        /// Returns the receipts of the EVM executions of the current block.
+        fn block_receipts() -> Vec<ExtrinsicReceipt>;
+
+        /// Returns the timestamp of the current block, in seconds.
+        fn block_timestamp() -> u64;
+
+        /// Returns the EVM address of the author of the current block.
+        fn block_coinbase() -> H160;
+
+        /// Returns the gas limit of the EVM executions of a block.
+        fn block_gas_limit() -> u64;
+
+        /// Returns the extrinsic applying the raw signed Ethereum `transaction`.
+        fn convert_transaction(transaction: Vec<u8>) -> <Block as BlockT>::Extrinsic;
+
+        /// Traces a call to `to`, or a create without `to`, from `from`.
+        fn trace_call(
+            from: H160,
+            to: Option<H160>,
+            data: Vec<u8>,
+            value: Balance,
+            gas_limit: u64,
+            storage_limit: u32,
+            tracer: TracerConfig,
+        ) -> Result<Trace, sp_runtime::DispatchError>;
+
+        /// Traces the EVM execution of the extrinsic at `index` of `extrinsics`,
+        /// after applying the extrinsics before it. Called on the parent of
+        /// the block of `extrinsics`, after `initialize_block`.
+        fn trace_extrinsic(
+            extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+            index: u32,
+            tracer: TracerConfig,
+        ) -> Result<Trace, sp_runtime::DispatchError>;
     }
 }
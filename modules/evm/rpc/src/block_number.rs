@@ -0,0 +1,89 @@
+use ethereum_types::U64;
+use serde::de::{Error, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+
+/// Block number parameter of the `eth_*` methods.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockNumber {
+    /// Latest block
+    Latest,
+    /// Earliest block (genesis)
+    Earliest,
+    /// Pending block, resolved as the latest block
+    Pending,
+    /// Block by number
+    Num(u64),
+}
+
+impl Default for BlockNumber {
+    fn default() -> Self {
+        BlockNumber::Latest
+    }
+}
+
+impl Serialize for BlockNumber {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            BlockNumber::Latest => serializer.serialize_str("latest"),
+            BlockNumber::Earliest => serializer.serialize_str("earliest"),
+            BlockNumber::Pending => serializer.serialize_str("pending"),
+            BlockNumber::Num(n) => U64::from(n).serialize(serializer),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockNumber {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<BlockNumber, D::Error> {
+        deserializer.deserialize_any(BlockNumberVisitor)
+    }
+}
+
+struct BlockNumberVisitor;
+
+impl<'de> Visitor<'de> for BlockNumberVisitor {
+    type Value = BlockNumber;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a block number or 'latest', 'earliest' or 'pending'")
+    }
+
+    fn visit_str<E: Error>(self, value: &str) -> Result<Self::Value, E> {
+        match value {
+            "latest" => Ok(BlockNumber::Latest),
+            "earliest" => Ok(BlockNumber::Earliest),
+            "pending" => Ok(BlockNumber::Pending),
+            _ if value.starts_with("0x") => u64::from_str_radix(&value[2..], 16)
+                .map(BlockNumber::Num)
+                .map_err(|e| Error::custom(format!("Invalid block number: {}", e))),
+            _ => Err(Error::custom("Invalid block number: missing 0x prefix")),
+        }
+    }
+
+    fn visit_string<E: Error>(self, value: String) -> Result<Self::Value, E> {
+        self.visit_str(value.as_ref())
+    }
+
+    fn visit_u64<E: Error>(self, value: u64) -> Result<Self::Value, E> {
+        Ok(BlockNumber::Num(value))
+    }
+}
+
+#[test]
+fn block_number_deserialize_should_work() {
+    let numbers: Vec<BlockNumber> =
+        serde_json::from_str(r#"["latest", "earliest", "pending", "0x1f", 42]"#).unwrap();
+    assert_eq!(
+        numbers,
+        vec![
+            BlockNumber::Latest,
+            BlockNumber::Earliest,
+            BlockNumber::Pending,
+            BlockNumber::Num(31),
+            BlockNumber::Num(42),
+        ]
+    );
+
+    assert!(serde_json::from_str::<BlockNumber>(r#""1f""#).is_err());
+    assert!(serde_json::from_str::<BlockNumber>(r#""0xzz""#).is_err());
+}
@@ -1,7 +1,8 @@
-use ethereum_types::{H160, U256};
+use ethereum_types::{H160, H256, U256};
 use serde::{Deserialize, Serialize};
 use sp_core::Bytes;
 use sp_rpc::number::NumberOrHex;
+use std::collections::BTreeMap;
 
 /// Call request
 #[derive(Debug, Default, PartialEq, Deserialize, Clone)]
@@ -20,6 +21,25 @@ pub struct CallRequest {
     pub value: Option<NumberOrHex>,
     /// Data
     pub data: Option<Bytes>,
+    /// Overrides of the state of the accounts, by address
+    pub state_override: Option<BTreeMap<H160, OverrideAccount>>,
+}
+
+/// Override of the state of an account
+#[derive(Debug, Default, PartialEq, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct OverrideAccount {
+    /// Balance
+    pub balance: Option<NumberOrHex>,
+    /// Nonce
+    pub nonce: Option<NumberOrHex>,
+    /// Code
+    pub code: Option<Bytes>,
+    /// Storage slots replacing the whole storage
+    pub state: Option<BTreeMap<H256, H256>>,
+    /// Storage slots set over the existing storage
+    pub state_diff: Option<BTreeMap<H256, H256>>,
 }
 
 /// EstimateResources response
@@ -33,3 +53,64 @@ pub struct EstimateResourcesResponse {
     /// Adjusted weight fee
     pub weight_fee: U256,
 }
+
+/// Call request of the `eth_call` and `eth_estimateGas` methods
+#[derive(Debug, Default, PartialEq, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EthCallRequest {
+    /// From
+    pub from: Option<H160>,
+    /// To
+    pub to: Option<H160>,
+    /// Gas
+    pub gas: Option<U256>,
+    /// Gas Price
+    pub gas_price: Option<U256>,
+    /// Value
+    pub value: Option<U256>,
+    /// Data
+    #[serde(alias = "input")]
+    pub data: Option<Bytes>,
+}
+
+#[test]
+fn call_request_state_override_should_work() {
+    let request: CallRequest = serde_json::from_str(
+        r#"{
+            "to": "0x0000000000000000000000000000000000000001",
+            "stateOverride": {
+                "0x0000000000000000000000000000000000000002": {
+                    "balance": "0x10",
+                    "code": "0x6000",
+                    "stateDiff": {
+                        "0x0000000000000000000000000000000000000000000000000000000000000001":
+                            "0x0000000000000000000000000000000000000000000000000000000000000002"
+                    }
+                }
+            }
+        }"#,
+    )
+    .unwrap();
+
+    let mut state_diff = BTreeMap::new();
+    state_diff.insert(H256::from_low_u64_be(1), H256::from_low_u64_be(2));
+    let mut state_override = BTreeMap::new();
+    state_override.insert(
+        H160::from_low_u64_be(2),
+        OverrideAccount {
+            balance: Some(NumberOrHex::Hex(U256::from(16))),
+            code: Some(Bytes(vec![0x60, 0x00])),
+            state_diff: Some(state_diff),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        request,
+        CallRequest {
+            to: Some(H160::from_low_u64_be(1)),
+            state_override: Some(state_override),
+            ..Default::default()
+        }
+    );
+}
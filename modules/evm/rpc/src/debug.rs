@@ -0,0 +1,144 @@
+//! Geth compatible debug rpc implementation.
+
+use ethereum_types::{H256, U256};
+use jsonrpc_core::Result;
+use sc_client_api::BlockBackend;
+use sc_rpc::DenyUnsafe;
+use sp_api::{Core, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_core::offchain::OffchainStorage;
+use sp_runtime::{
+    codec::Codec,
+    generic::BlockId,
+    traits::{Block as BlockT, Header as HeaderT, MaybeDisplay, MaybeFromStr, Zero},
+    SaturatedConversion,
+};
+use std::convert::TryFrom;
+use std::{marker::PhantomData, sync::Arc};
+
+use crate::{
+    block_number::BlockNumber,
+    call_request::EthCallRequest,
+    internal_err, to_balance,
+    trace::{TraceOptions, TraceResult},
+    DebugApiT, EVMRuntimeRPCApi, IndexerStorage, GAS_LIMIT, STORAGE_LIMIT,
+};
+
+pub struct DebugApi<B, C, S, Balance> {
+    client: Arc<C>,
+    indexer: Option<IndexerStorage<S>>,
+    deny_unsafe: DenyUnsafe,
+    _marker: PhantomData<(B, Balance)>,
+}
+
+impl<B, C, S, Balance> DebugApi<B, C, S, Balance> {
+    /// Create the api. Transactions are only traced if the EVM `indexer`
+    /// storage is available. Tracing is expensive, it is an unsafe call.
+    pub fn new(client: Arc<C>, indexer: Option<IndexerStorage<S>>, deny_unsafe: DenyUnsafe) -> Self {
+        Self {
+            client,
+            indexer,
+            deny_unsafe,
+            _marker: Default::default(),
+        }
+    }
+}
+
+impl<B, C, S, Balance> DebugApiT for DebugApi<B, C, S, Balance>
+where
+    B: BlockT<Hash = H256>,
+    C: ProvideRuntimeApi<B> + HeaderBackend<B> + BlockBackend<B> + Send + Sync + 'static,
+    C::Api: Core<B> + EVMRuntimeRPCApi<B, Balance>,
+    S: OffchainStorage + 'static,
+    Balance: Codec + MaybeDisplay + MaybeFromStr + Default + Send + Sync + 'static + TryFrom<u128>,
+{
+    fn trace_transaction(&self, hash: H256, options: Option<TraceOptions>) -> Result<TraceResult> {
+        self.deny_unsafe.check_if_safe()?;
+        let config = options.unwrap_or_default().tracer_config()?;
+
+        let indexer = self
+            .indexer
+            .as_ref()
+            .ok_or_else(|| internal_err("EVM indexer is not available"))?;
+        let block_hash = indexer
+            .transaction_block(&hash)
+            .ok_or_else(|| internal_err(format!("transaction {:?} not found", hash)))?;
+        let index = indexer
+            .block(&block_hash)
+            .and_then(|block| {
+                block
+                    .transactions
+                    .into_iter()
+                    .find(|transaction| transaction.hash == hash)
+            })
+            .map(|transaction| transaction.receipt.extrinsic_index)
+            .ok_or_else(|| internal_err(format!("transaction {:?} not found", hash)))?;
+
+        let block = self
+            .client
+            .block(&BlockId::Hash(block_hash))
+            .map_err(|err| internal_err(format!("{:?}", err)))?
+            .ok_or_else(|| internal_err(format!("block {:?} not found", block_hash)))?
+            .block;
+        let (header, extrinsics) = block.deconstruct();
+        let parent = BlockId::Hash(*header.parent_hash());
+
+        // replay the block on the state of its parent, up to the transaction
+        let api = self.client.runtime_api();
+        api.initialize_block(&parent, &header)
+            .map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+        let trace = api
+            .trace_extrinsic(&parent, extrinsics, index, config)
+            .map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+            .map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+        Ok(TraceResult::new(trace, config))
+    }
+
+    fn trace_call(
+        &self,
+        request: EthCallRequest,
+        number: Option<BlockNumber>,
+        options: Option<TraceOptions>,
+    ) -> Result<TraceResult> {
+        self.deny_unsafe.check_if_safe()?;
+        let config = options.unwrap_or_default().tracer_config()?;
+
+        let at = match number.unwrap_or_default() {
+            BlockNumber::Latest | BlockNumber::Pending => BlockId::Hash(self.client.info().best_hash),
+            BlockNumber::Earliest => BlockId::Number(Zero::zero()),
+            BlockNumber::Num(n) => BlockId::Number(n.saturated_into()),
+        };
+
+        let EthCallRequest {
+            from,
+            to,
+            gas,
+            gas_price: _,
+            value,
+            data,
+        } = request;
+
+        let gas_limit = gas.map_or(GAS_LIMIT, |gas| gas.min(U256::from(GAS_LIMIT)).as_u64());
+        let data = data.map(|d| d.0).unwrap_or_default();
+        let balance_value = to_balance(value)?;
+
+        let trace = self
+            .client
+            .runtime_api()
+            .trace_call(
+                &at,
+                from.unwrap_or_default(),
+                to,
+                data,
+                balance_value,
+                gas_limit,
+                STORAGE_LIMIT,
+                config,
+            )
+            .map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+            .map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+        Ok(TraceResult::new(trace, config))
+    }
+}
@@ -0,0 +1,30 @@
+//! Geth compatible debug rpc interface.
+
+use ethereum_types::H256;
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+
+pub use rpc_impl_DebugApi::gen_server::DebugApi as DebugApiServer;
+
+use crate::{
+    block_number::BlockNumber,
+    call_request::EthCallRequest,
+    trace::{TraceOptions, TraceResult},
+};
+
+/// Geth compatible debug rpc interface.
+#[rpc(server)]
+pub trait DebugApi {
+    /// Returns the trace of the EVM execution of the given transaction.
+    #[rpc(name = "debug_traceTransaction")]
+    fn trace_transaction(&self, hash: H256, options: Option<TraceOptions>) -> Result<TraceResult>;
+
+    /// Returns the trace of the EVM execution of the given call.
+    #[rpc(name = "debug_traceCall")]
+    fn trace_call(
+        &self,
+        request: EthCallRequest,
+        number: Option<BlockNumber>,
+        options: Option<TraceOptions>,
+    ) -> Result<TraceResult>;
+}
@@ -0,0 +1,467 @@
+//! Ethereum compatible rpc implementation.
+
+use ethereum_types::{Bloom, BloomInput, H160, H256, H64, U256, U64};
+use jsonrpc_core::{Error, ErrorCode, Result};
+use sc_transaction_pool_api::{TransactionPool, TransactionSource};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::{offchain::OffchainStorage, Bytes};
+use sp_io::hashing::keccak_256;
+use sp_runtime::{
+    codec::Codec,
+    generic::BlockId,
+    traits::{Block as BlockT, Header as HeaderT, MaybeDisplay, MaybeFromStr, UniqueSaturatedInto, Zero},
+    SaturatedConversion,
+};
+use std::convert::TryFrom;
+use std::{marker::PhantomData, sync::Arc};
+
+use crate::{
+    binary_search_estimate,
+    block_number::BlockNumber,
+    call_request::EthCallRequest,
+    error_on_execution_failure,
+    eth_types::{Block, BlockTransactions, Log, Receipt, Transaction},
+    filter::Filter,
+    internal_err, to_balance, EVMRuntimeRPCApi, EthApiT, IndexedBlock, IndexerStorage, GAS_LIMIT, MAX_LOGS_BLOCK_RANGE,
+    STORAGE_LIMIT,
+};
+
+/// Keccak-256 hash of the RLP encoding of an empty list, the uncles hash of a
+/// block without uncles.
+const EMPTY_UNCLES_HASH: H256 = H256([
+    0x1d, 0xcc, 0x4d, 0xe8, 0xde, 0xc7, 0x5d, 0x7a, 0xab, 0x85, 0xb5, 0x67, 0xb6, 0xcc, 0xd4, 0x1a, 0xd3, 0x12, 0x45,
+    0x1b, 0x94, 0x8a, 0x74, 0x13, 0xf0, 0xa1, 0x42, 0xfd, 0x40, 0xd4, 0x93, 0x47,
+]);
+
+pub struct EthApi<B, C, P, S, Balance> {
+    client: Arc<C>,
+    pool: Arc<P>,
+    indexer: Option<IndexerStorage<S>>,
+    _marker: PhantomData<(B, Balance)>,
+}
+
+impl<B, C, P, S, Balance> EthApi<B, C, P, S, Balance> {
+    /// Create the api. Transactions are submitted to `pool`. Receipts, logs
+    /// and blocks are only served if the EVM `indexer` storage is available.
+    pub fn new(client: Arc<C>, pool: Arc<P>, indexer: Option<IndexerStorage<S>>) -> Self {
+        Self {
+            client,
+            pool,
+            indexer,
+            _marker: Default::default(),
+        }
+    }
+}
+
+fn logs_bloom<'a>(logs: impl IntoIterator<Item = &'a module_evm::Log>) -> Bloom {
+    let mut bloom = Bloom::default();
+    for log in logs {
+        bloom.accrue(BloomInput::Raw(log.address.as_bytes()));
+        for topic in &log.topics {
+            bloom.accrue(BloomInput::Raw(topic.as_bytes()));
+        }
+    }
+    bloom
+}
+
+/// Returns the logs of an indexed block matching the filter, if any.
+fn block_logs(hash: H256, block: &IndexedBlock, filter: Option<&Filter>) -> Vec<Log> {
+    let mut logs = Vec::new();
+    let mut log_index = 0usize;
+
+    for (transaction_index, transaction) in block.transactions.iter().enumerate() {
+        for (transaction_log_index, log) in transaction.receipt.logs.iter().enumerate() {
+            if filter.map_or(true, |filter| filter.matches(log)) {
+                logs.push(Log {
+                    address: log.address,
+                    topics: log.topics.clone(),
+                    data: Bytes(log.data.clone()),
+                    block_hash: hash,
+                    block_number: U256::from(block.number),
+                    transaction_hash: transaction.hash,
+                    transaction_index: U256::from(transaction_index),
+                    log_index: U256::from(log_index),
+                    transaction_log_index: U256::from(transaction_log_index),
+                    removed: false,
+                });
+            }
+            log_index += 1;
+        }
+    }
+
+    logs
+}
+
+impl<B, C, P, S, Balance> EthApi<B, C, P, S, Balance>
+where
+    B: BlockT<Hash = H256>,
+    C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + 'static,
+    C::Api: EVMRuntimeRPCApi<B, Balance>,
+    S: OffchainStorage + 'static,
+    Balance: Codec + MaybeDisplay + MaybeFromStr + Default + Send + Sync + 'static + TryFrom<u128>,
+{
+    fn indexer(&self) -> Result<&IndexerStorage<S>> {
+        self.indexer
+            .as_ref()
+            .ok_or_else(|| internal_err("EVM indexer is not available"))
+    }
+
+    fn resolve_number(&self, number: BlockNumber) -> u64 {
+        match number {
+            BlockNumber::Latest | BlockNumber::Pending => {
+                UniqueSaturatedInto::<u64>::unique_saturated_into(self.client.info().best_number)
+            }
+            BlockNumber::Earliest => 0,
+            BlockNumber::Num(n) => n,
+        }
+    }
+
+    /// Returns the hash of the canonical block with the given number.
+    fn canonical_hash(&self, number: u64) -> Result<Option<H256>> {
+        self.client
+            .hash(number.saturated_into())
+            .map_err(|err| internal_err(format!("{:?}", err)))
+    }
+
+    fn block_id(&self, number: Option<BlockNumber>) -> BlockId<B> {
+        match number.unwrap_or_default() {
+            BlockNumber::Latest | BlockNumber::Pending => BlockId::Hash(self.client.info().best_hash),
+            BlockNumber::Earliest => BlockId::Number(Zero::zero()),
+            BlockNumber::Num(n) => BlockId::Number(n.saturated_into()),
+        }
+    }
+
+    /// Execute the request at the given block, returning the output and the used gas.
+    fn execute(&self, request: EthCallRequest, at: &BlockId<B>, estimate: bool) -> Result<(Vec<u8>, U256)> {
+        let EthCallRequest {
+            from,
+            to,
+            gas,
+            gas_price: _,
+            value,
+            data,
+        } = request;
+
+        let gas_limit = gas.map_or(GAS_LIMIT, |gas| gas.min(U256::from(GAS_LIMIT)).as_u64());
+        let data = data.map(|d| d.0).unwrap_or_default();
+
+        let balance_value = to_balance(value)?;
+
+        let api = self.client.runtime_api();
+
+        match to {
+            Some(to) => {
+                let info = api
+                    .call(
+                        at,
+                        from.unwrap_or_default(),
+                        to,
+                        data,
+                        balance_value,
+                        gas_limit,
+                        STORAGE_LIMIT,
+                        estimate,
+                        Vec::new(),
+                    )
+                    .map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+                    .map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+                error_on_execution_failure(&info.exit_reason, &info.output)?;
+
+                Ok((info.output, info.used_gas))
+            }
+            None => {
+                let info = api
+                    .create(
+                        at,
+                        from.unwrap_or_default(),
+                        data,
+                        balance_value,
+                        gas_limit,
+                        STORAGE_LIMIT,
+                        estimate,
+                        Vec::new(),
+                    )
+                    .map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
+                    .map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
+
+                error_on_execution_failure(&info.exit_reason, &[])?;
+
+                Ok((info.output, info.used_gas))
+            }
+        }
+    }
+}
+
+impl<B, C, P, S, Balance> EthApiT for EthApi<B, C, P, S, Balance>
+where
+    B: BlockT<Hash = H256>,
+    C: ProvideRuntimeApi<B> + HeaderBackend<B> + Send + Sync + 'static,
+    C::Api: EVMRuntimeRPCApi<B, Balance>,
+    P: TransactionPool<Block = B> + 'static,
+    S: OffchainStorage + 'static,
+    Balance: Codec + MaybeDisplay + MaybeFromStr + Default + Send + Sync + 'static + TryFrom<u128>,
+{
+    fn chain_id(&self) -> Result<U64> {
+        self.client
+            .runtime_api()
+            .chain_id(&self.block_id(None))
+            .map(U64::from)
+            .map_err(|err| internal_err(format!("runtime error: {:?}", err)))
+    }
+
+    fn block_number(&self) -> Result<U256> {
+        Ok(U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(
+            self.client.info().best_number,
+        )))
+    }
+
+    fn balance(&self, address: H160, number: Option<BlockNumber>) -> Result<U256> {
+        self.client
+            .runtime_api()
+            .account_basic(&self.block_id(number), address)
+            .map(|account| account.balance)
+            .map_err(|err| internal_err(format!("runtime error: {:?}", err)))
+    }
+
+    fn code_at(&self, address: H160, number: Option<BlockNumber>) -> Result<Bytes> {
+        self.client
+            .runtime_api()
+            .get_code(&self.block_id(number), address)
+            .map(Bytes)
+            .map_err(|err| internal_err(format!("runtime error: {:?}", err)))
+    }
+
+    fn storage_at(&self, address: H160, index: U256, number: Option<BlockNumber>) -> Result<H256> {
+        let mut key = [0u8; 32];
+        index.to_big_endian(&mut key);
+
+        self.client
+            .runtime_api()
+            .get_storage_at(&self.block_id(number), address, H256::from(key))
+            .map_err(|err| internal_err(format!("runtime error: {:?}", err)))
+    }
+
+    fn call(&self, request: EthCallRequest, number: Option<BlockNumber>) -> Result<Bytes> {
+        let (output, _) = self.execute(request, &self.block_id(number), false)?;
+        Ok(Bytes(output))
+    }
+
+    fn estimate_gas(&self, request: EthCallRequest, number: Option<BlockNumber>) -> Result<U256> {
+        let at = self.block_id(number);
+        let calculate_gas_used =
+            |request: EthCallRequest| self.execute(request, &at, true).map(|(_, used_gas)| used_gas);
+
+        if cfg!(feature = "rpc_binary_search_estimate") {
+            // get a good upper limit, but below U64::max to operation overflow
+            let upper = request
+                .gas
+                .map_or(U256::from(GAS_LIMIT), |gas| gas.min(U256::from(GAS_LIMIT)));
+            binary_search_estimate(upper, |gas| {
+                let mut test_request = request.clone();
+                test_request.gas = Some(gas);
+                calculate_gas_used(test_request).map(|used_gas| (used_gas, ()))
+            })
+            .map(|(used_gas, _)| used_gas)
+        } else {
+            calculate_gas_used(request)
+        }
+    }
+
+    fn transaction_count(&self, address: H160, number: Option<BlockNumber>) -> Result<U256> {
+        self.client
+            .runtime_api()
+            .account_basic(&self.block_id(number), address)
+            .map(|account| account.nonce)
+            .map_err(|err| internal_err(format!("runtime error: {:?}", err)))
+    }
+
+    fn gas_price(&self) -> Result<U256> {
+        self.client
+            .runtime_api()
+            .gas_price(&self.block_id(None))
+            .map_err(|err| internal_err(format!("runtime error: {:?}", err)))
+    }
+
+    fn transaction_receipt(&self, hash: H256) -> Result<Option<Receipt>> {
+        let indexer = self.indexer()?;
+
+        let block_hash = match indexer.transaction_block(&hash) {
+            Some(block_hash) => block_hash,
+            None => return Ok(None),
+        };
+        let block = match indexer.block(&block_hash) {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+
+        // the last block including the transaction might not be canonical anymore
+        if self.canonical_hash(block.number)? != Some(block_hash) {
+            return Ok(None);
+        }
+
+        let index = match block
+            .transactions
+            .iter()
+            .position(|transaction| transaction.hash == hash)
+        {
+            Some(index) => index,
+            None => return Ok(None),
+        };
+        let receipt = &block.transactions[index].receipt;
+
+        let cumulative_gas_used = block.transactions[..=index].iter().fold(0u64, |acc, transaction| {
+            acc.saturating_add(transaction.receipt.used_gas)
+        });
+
+        let logs = block_logs(block_hash, &block, None)
+            .into_iter()
+            .filter(|log| log.transaction_index == U256::from(index))
+            .collect();
+
+        Ok(Some(Receipt {
+            transaction_hash: hash,
+            transaction_index: U256::from(index),
+            block_hash,
+            block_number: U256::from(block.number),
+            from: receipt.from,
+            to: receipt.to,
+            cumulative_gas_used: U256::from(cumulative_gas_used),
+            gas_used: U256::from(receipt.used_gas),
+            contract_address: receipt.contract_address,
+            logs,
+            logs_bloom: logs_bloom(&receipt.logs),
+            status: U64::from(receipt.succeed as u64),
+        }))
+    }
+
+    fn logs(&self, filter: Filter) -> Result<Vec<Log>> {
+        let indexer = self.indexer()?;
+
+        let hashes = match filter.block_hash {
+            Some(hash) => vec![hash],
+            None => {
+                let from = self.resolve_number(filter.from_block.unwrap_or_default());
+                let to = self.resolve_number(filter.to_block.unwrap_or_default());
+                if to.saturating_sub(from) >= MAX_LOGS_BLOCK_RANGE {
+                    return Err(Error {
+                        code: ErrorCode::InvalidParams,
+                        message: format!("Block range too large, at most {} blocks", MAX_LOGS_BLOCK_RANGE),
+                        data: None,
+                    });
+                }
+
+                let mut hashes = Vec::new();
+                for number in from..=to {
+                    hashes.extend(self.canonical_hash(number)?);
+                }
+                hashes
+            }
+        };
+
+        let mut logs = Vec::new();
+        for hash in hashes {
+            if let Some(block) = indexer.block(&hash) {
+                logs.extend(block_logs(hash, &block, Some(&filter)));
+            }
+        }
+
+        Ok(logs)
+    }
+
+    fn block_by_number(&self, number: BlockNumber, full: bool) -> Result<Option<Block>> {
+        let indexer = self.indexer()?;
+
+        let hash = match self.canonical_hash(self.resolve_number(number))? {
+            Some(hash) => hash,
+            None => return Ok(None),
+        };
+        let header = match self
+            .client
+            .header(BlockId::Hash(hash))
+            .map_err(|err| internal_err(format!("{:?}", err)))?
+        {
+            Some(header) => header,
+            None => return Ok(None),
+        };
+        // not indexed yet
+        let block = match indexer.block(&hash) {
+            Some(block) => block,
+            None => return Ok(None),
+        };
+
+        let at = BlockId::Hash(hash);
+        let api = self.client.runtime_api();
+        let miner = api
+            .block_coinbase(&at)
+            .map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+        let gas_limit = api
+            .block_gas_limit(&at)
+            .map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+
+        let gas_used = block.transactions.iter().fold(0u64, |acc, transaction| {
+            acc.saturating_add(transaction.receipt.used_gas)
+        });
+
+        let transactions = if full {
+            BlockTransactions::Full(
+                block
+                    .transactions
+                    .iter()
+                    .enumerate()
+                    .map(|(index, transaction)| Transaction {
+                        hash: transaction.hash,
+                        block_hash: hash,
+                        block_number: U256::from(block.number),
+                        transaction_index: U256::from(index),
+                        from: transaction.receipt.from,
+                        to: transaction.receipt.to,
+                    })
+                    .collect(),
+            )
+        } else {
+            BlockTransactions::Hashes(block.transactions.iter().map(|transaction| transaction.hash).collect())
+        };
+
+        Ok(Some(Block {
+            hash,
+            parent_hash: block.parent_hash,
+            sha3_uncles: EMPTY_UNCLES_HASH,
+            miner,
+            state_root: *header.state_root(),
+            transactions_root: *header.extrinsics_root(),
+            number: U256::from(block.number),
+            gas_used: U256::from(gas_used),
+            gas_limit: U256::from(gas_limit),
+            extra_data: Bytes(Vec::new()),
+            logs_bloom: logs_bloom(
+                block
+                    .transactions
+                    .iter()
+                    .flat_map(|transaction| &transaction.receipt.logs),
+            ),
+            timestamp: U256::from(block.timestamp),
+            difficulty: U256::zero(),
+            nonce: H64::default(),
+            transactions,
+            uncles: Vec::new(),
+        }))
+    }
+
+    fn send_raw_transaction(&self, transaction: Bytes) -> Result<H256> {
+        let hash = H256::from(keccak_256(&transaction));
+        let at = BlockId::Hash(self.client.info().best_hash);
+
+        let extrinsic = self
+            .client
+            .runtime_api()
+            .convert_transaction(&at, transaction.into_inner())
+            .map_err(|err| internal_err(format!("runtime error: {:?}", err)))?;
+
+        futures::executor::block_on(self.pool.submit_one(&at, TransactionSource::Local, extrinsic))
+            .map_err(|err| internal_err(format!("submit transaction to pool failed: {}", err)))?;
+
+        Ok(hash)
+    }
+}
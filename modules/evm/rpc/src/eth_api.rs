@@ -0,0 +1,71 @@
+//! Ethereum compatible rpc interface.
+
+use ethereum_types::{H160, H256, U256, U64};
+use jsonrpc_core::Result;
+use jsonrpc_derive::rpc;
+use sp_core::Bytes;
+
+pub use rpc_impl_EthApi::gen_server::EthApi as EthApiServer;
+
+use crate::{
+    block_number::BlockNumber,
+    call_request::EthCallRequest,
+    eth_types::{Block, Log, Receipt},
+    filter::Filter,
+};
+
+/// Ethereum compatible rpc interface.
+#[rpc(server)]
+pub trait EthApi {
+    /// Returns the chain id.
+    #[rpc(name = "eth_chainId")]
+    fn chain_id(&self) -> Result<U64>;
+
+    /// Returns the number of the best block.
+    #[rpc(name = "eth_blockNumber")]
+    fn block_number(&self) -> Result<U256>;
+
+    /// Returns the balance of the given account.
+    #[rpc(name = "eth_getBalance")]
+    fn balance(&self, address: H160, number: Option<BlockNumber>) -> Result<U256>;
+
+    /// Returns the code deployed at the given address.
+    #[rpc(name = "eth_getCode")]
+    fn code_at(&self, address: H160, number: Option<BlockNumber>) -> Result<Bytes>;
+
+    /// Returns the content of the storage slot at the given address.
+    #[rpc(name = "eth_getStorageAt")]
+    fn storage_at(&self, address: H160, index: U256, number: Option<BlockNumber>) -> Result<H256>;
+
+    /// Call contract, returning the output data.
+    #[rpc(name = "eth_call")]
+    fn call(&self, request: EthCallRequest, number: Option<BlockNumber>) -> Result<Bytes>;
+
+    /// Estimate gas needed for execution of given contract.
+    #[rpc(name = "eth_estimateGas")]
+    fn estimate_gas(&self, request: EthCallRequest, number: Option<BlockNumber>) -> Result<U256>;
+
+    /// Returns the number of transactions sent from the given address.
+    #[rpc(name = "eth_getTransactionCount")]
+    fn transaction_count(&self, address: H160, number: Option<BlockNumber>) -> Result<U256>;
+
+    /// Returns the current gas price.
+    #[rpc(name = "eth_gasPrice")]
+    fn gas_price(&self) -> Result<U256>;
+
+    /// Returns the receipt of the given transaction.
+    #[rpc(name = "eth_getTransactionReceipt")]
+    fn transaction_receipt(&self, hash: H256) -> Result<Option<Receipt>>;
+
+    /// Returns the logs matching the given filter.
+    #[rpc(name = "eth_getLogs")]
+    fn logs(&self, filter: Filter) -> Result<Vec<Log>>;
+
+    /// Returns the block with the given number, with full transactions if `full` is true.
+    #[rpc(name = "eth_getBlockByNumber")]
+    fn block_by_number(&self, number: BlockNumber, full: bool) -> Result<Option<Block>>;
+
+    /// Submits a raw signed transaction, returning its hash.
+    #[rpc(name = "eth_sendRawTransaction")]
+    fn send_raw_transaction(&self, transaction: Bytes) -> Result<H256>;
+}
@@ -0,0 +1,125 @@
+use ethereum_types::{Bloom, H160, H256, H64, U256, U64};
+use serde::Serialize;
+use sp_core::Bytes;
+
+/// Transaction receipt
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Receipt {
+    /// Transaction Hash
+    pub transaction_hash: H256,
+    /// Transaction Index
+    pub transaction_index: U256,
+    /// Block Hash
+    pub block_hash: H256,
+    /// Block Number
+    pub block_number: U256,
+    /// Sender
+    pub from: H160,
+    /// Recipient, `None` for contract creation
+    pub to: Option<H160>,
+    /// Gas used by the transactions of the block up to this one
+    pub cumulative_gas_used: U256,
+    /// Gas used by this transaction
+    pub gas_used: U256,
+    /// Created contract address
+    pub contract_address: Option<H160>,
+    /// Logs
+    pub logs: Vec<Log>,
+    /// Logs Bloom
+    pub logs_bloom: Bloom,
+    /// Status, 1 for success and 0 for failure
+    pub status: U64,
+}
+
+/// Log
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Log {
+    /// Emitting contract address
+    pub address: H160,
+    /// Topics
+    pub topics: Vec<H256>,
+    /// Data
+    pub data: Bytes,
+    /// Block Hash
+    pub block_hash: H256,
+    /// Block Number
+    pub block_number: U256,
+    /// Transaction Hash
+    pub transaction_hash: H256,
+    /// Transaction Index
+    pub transaction_index: U256,
+    /// Index of the log in the block
+    pub log_index: U256,
+    /// Index of the log in the transaction
+    pub transaction_log_index: U256,
+    /// Whether the log was removed by a chain reorganization
+    pub removed: bool,
+}
+
+/// Transaction of a block
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+    /// Transaction Hash
+    pub hash: H256,
+    /// Block Hash
+    pub block_hash: H256,
+    /// Block Number
+    pub block_number: U256,
+    /// Transaction Index
+    pub transaction_index: U256,
+    /// Sender
+    pub from: H160,
+    /// Recipient, `None` for contract creation
+    pub to: Option<H160>,
+}
+
+/// Transactions of a block, either hashes or full transactions
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum BlockTransactions {
+    /// Transaction hashes
+    Hashes(Vec<H256>),
+    /// Full transactions
+    Full(Vec<Transaction>),
+}
+
+/// Block
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Block {
+    /// Block Hash
+    pub hash: H256,
+    /// Parent Hash
+    pub parent_hash: H256,
+    /// Uncles Hash, always the hash of an empty uncle list
+    pub sha3_uncles: H256,
+    /// Block Author
+    pub miner: H160,
+    /// State Root
+    pub state_root: H256,
+    /// Transactions Root
+    pub transactions_root: H256,
+    /// Block Number
+    pub number: U256,
+    /// Gas used by the transactions of the block
+    pub gas_used: U256,
+    /// Gas Limit
+    pub gas_limit: U256,
+    /// Extra Data
+    pub extra_data: Bytes,
+    /// Logs Bloom
+    pub logs_bloom: Bloom,
+    /// Timestamp, in seconds
+    pub timestamp: U256,
+    /// Difficulty
+    pub difficulty: U256,
+    /// Nonce
+    pub nonce: H64,
+    /// Transactions
+    pub transactions: BlockTransactions,
+    /// Uncles, always empty
+    pub uncles: Vec<H256>,
+}
@@ -0,0 +1,133 @@
+use ethereum_types::{H160, H256};
+use serde::Deserialize;
+
+use module_evm::Log;
+
+use crate::block_number::BlockNumber;
+
+/// A single value or an array of values.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum ValueOrArray<T> {
+    /// Single value
+    Single(T),
+    /// Array of values
+    Multiple(Vec<T>),
+}
+
+impl<T: PartialEq> ValueOrArray<T> {
+    /// Returns true if the value, or any of the array values, equals `value`.
+    pub fn contains(&self, value: &T) -> bool {
+        match self {
+            ValueOrArray::Single(v) => v == value,
+            ValueOrArray::Multiple(values) => values.contains(value),
+        }
+    }
+}
+
+/// Log filter of the `eth_getLogs` method
+#[derive(Debug, Default, PartialEq, Deserialize, Clone)]
+#[serde(deny_unknown_fields)]
+#[serde(rename_all = "camelCase")]
+pub struct Filter {
+    /// From Block
+    pub from_block: Option<BlockNumber>,
+    /// To Block
+    pub to_block: Option<BlockNumber>,
+    /// Block Hash, exclusive with the block range
+    pub block_hash: Option<H256>,
+    /// Emitting contract addresses
+    pub address: Option<ValueOrArray<H160>>,
+    /// Topics by position, `None` matches any topic
+    pub topics: Option<Vec<Option<ValueOrArray<H256>>>>,
+}
+
+impl Filter {
+    /// Returns true if the log matches the address and topics of the filter.
+    pub fn matches(&self, log: &Log) -> bool {
+        if let Some(address) = &self.address {
+            if !address.contains(&log.address) {
+                return false;
+            }
+        }
+
+        if let Some(topics) = &self.topics {
+            for (index, topic) in topics.iter().enumerate() {
+                if let Some(topic) = topic {
+                    match log.topics.get(index) {
+                        Some(log_topic) if topic.contains(log_topic) => {}
+                        _ => return false,
+                    }
+                }
+            }
+        }
+
+        true
+    }
+}
+
+#[test]
+fn filter_deserialize_should_work() {
+    let filter: Filter = serde_json::from_str(
+        r#"{
+            "fromBlock": "0x1",
+            "toBlock": "latest",
+            "address": "0x0000000000000000000000000000000000000001",
+            "topics": [null, ["0x0000000000000000000000000000000000000000000000000000000000000002"]]
+        }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        filter,
+        Filter {
+            from_block: Some(BlockNumber::Num(1)),
+            to_block: Some(BlockNumber::Latest),
+            block_hash: None,
+            address: Some(ValueOrArray::Single(H160::from_low_u64_be(1))),
+            topics: Some(vec![
+                None,
+                Some(ValueOrArray::Multiple(vec![H256::from_low_u64_be(2)]))
+            ]),
+        }
+    );
+}
+
+#[test]
+fn filter_matches_should_work() {
+    let log = Log {
+        address: H160::from_low_u64_be(1),
+        topics: vec![H256::from_low_u64_be(1), H256::from_low_u64_be(2)],
+        data: vec![],
+    };
+
+    assert!(Filter::default().matches(&log));
+
+    let filter = Filter {
+        address: Some(ValueOrArray::Multiple(vec![
+            H160::from_low_u64_be(2),
+            H160::from_low_u64_be(1),
+        ])),
+        topics: Some(vec![None, Some(ValueOrArray::Single(H256::from_low_u64_be(2)))]),
+        ..Default::default()
+    };
+    assert!(filter.matches(&log));
+
+    let filter = Filter {
+        address: Some(ValueOrArray::Single(H160::from_low_u64_be(2))),
+        ..Default::default()
+    };
+    assert!(!filter.matches(&log));
+
+    let filter = Filter {
+        topics: Some(vec![Some(ValueOrArray::Single(H256::from_low_u64_be(2)))]),
+        ..Default::default()
+    };
+    assert!(!filter.matches(&log));
+
+    let filter = Filter {
+        topics: Some(vec![None, None, Some(ValueOrArray::Single(H256::from_low_u64_be(3)))]),
+        ..Default::default()
+    };
+    assert!(!filter.matches(&log));
+}
@@ -0,0 +1,253 @@
+//! Index of the EVM receipts and logs of the imported blocks.
+//!
+//! The index lives in the offchain database of the node and is keyed by block
+//! hash, so blocks of different forks never overwrite each other.
+
+use codec::{Decode, Encode};
+use ethereum_types::H256;
+use futures::{FutureExt, StreamExt};
+use sc_client_api::{BlockBackend, BlockchainEvents};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::offchain::OffchainStorage;
+use sp_runtime::{
+    codec::Codec,
+    generic::BlockId,
+    traits::{
+        Block as BlockT, Hash as HashT, HashFor, Header as HeaderT, MaybeDisplay, MaybeFromStr, UniqueSaturatedInto,
+        Zero,
+    },
+};
+use std::{marker::PhantomData, sync::Arc};
+
+use module_evm::ExtrinsicReceipt;
+
+use crate::EVMRuntimeRPCApi;
+
+/// Prefix of the index in the offchain database.
+const INDEXER_PREFIX: &[u8] = b"evm-indexer";
+const BLOCK_KEY: &[u8] = b"block:";
+const TRANSACTION_KEY: &[u8] = b"transaction:";
+const BACKFILL_KEY: &[u8] = b"backfill";
+
+/// Maximum number of blocks indexed in one go, before checking for newly
+/// imported blocks.
+const INDEX_BATCH_SIZE: usize = 256;
+
+/// EVM data of an indexed block.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct IndexedBlock {
+    /// Block number
+    pub number: u64,
+    /// Parent block hash
+    pub parent_hash: H256,
+    /// Block timestamp, in seconds
+    pub timestamp: u64,
+    /// Extrinsics of the block with EVM executions
+    pub transactions: Vec<IndexedTransaction>,
+}
+
+/// EVM executions of an extrinsic of an indexed block.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct IndexedTransaction {
+    /// Ethereum transaction hash, or the extrinsic hash
+    pub hash: H256,
+    /// Receipt of the EVM executions
+    pub receipt: ExtrinsicReceipt,
+}
+
+/// Access to the index in the offchain database.
+#[derive(Clone)]
+pub struct IndexerStorage<S> {
+    storage: S,
+}
+
+impl<S: OffchainStorage> IndexerStorage<S> {
+    pub fn new(storage: S) -> Self {
+        Self { storage }
+    }
+
+    /// Returns the indexed block with the given hash.
+    pub fn block(&self, hash: &H256) -> Option<IndexedBlock> {
+        self.get(&Self::key(BLOCK_KEY, hash))
+    }
+
+    /// Returns the hash of the last indexed block including the given transaction.
+    pub fn transaction_block(&self, hash: &H256) -> Option<H256> {
+        self.get(&Self::key(TRANSACTION_KEY, hash))
+    }
+
+    /// Blocks whose ancestors still need to be indexed, most recent last.
+    fn backfill(&self) -> Vec<H256> {
+        self.get(BACKFILL_KEY).unwrap_or_default()
+    }
+
+    fn set_backfill(&mut self, backfill: &[H256]) {
+        self.storage.set(INDEXER_PREFIX, BACKFILL_KEY, &backfill.encode());
+    }
+
+    fn insert_block(&mut self, hash: &H256, block: &IndexedBlock) {
+        for transaction in &block.transactions {
+            self.storage.set(
+                INDEXER_PREFIX,
+                &Self::key(TRANSACTION_KEY, &transaction.hash),
+                &hash.encode(),
+            );
+        }
+        // the block is written last, an indexed block always has its transactions indexed.
+        self.storage
+            .set(INDEXER_PREFIX, &Self::key(BLOCK_KEY, hash), &block.encode());
+    }
+
+    fn get<T: Decode>(&self, key: &[u8]) -> Option<T> {
+        self.storage
+            .get(INDEXER_PREFIX, key)
+            .and_then(|value| T::decode(&mut &value[..]).ok())
+    }
+
+    fn key(kind: &[u8], hash: &H256) -> Vec<u8> {
+        [kind, hash.as_bytes()].concat()
+    }
+}
+
+/// Background task indexing the EVM receipts and logs of the imported blocks.
+pub struct EvmIndexer<B, C, S, Balance> {
+    client: Arc<C>,
+    storage: IndexerStorage<S>,
+    _marker: PhantomData<(B, Balance)>,
+}
+
+impl<B, C, S, Balance> EvmIndexer<B, C, S, Balance>
+where
+    B: BlockT<Hash = H256>,
+    C: ProvideRuntimeApi<B> + HeaderBackend<B> + BlockBackend<B> + BlockchainEvents<B> + Send + Sync + 'static,
+    C::Api: EVMRuntimeRPCApi<B, Balance>,
+    S: OffchainStorage + 'static,
+    Balance: Codec + MaybeDisplay + MaybeFromStr + Send + Sync + 'static,
+{
+    pub fn new(client: Arc<C>, storage: S) -> Self {
+        Self {
+            client,
+            storage: IndexerStorage::new(storage),
+            _marker: Default::default(),
+        }
+    }
+
+    /// Index every imported block, until the import notification stream ends.
+    ///
+    /// Missing ancestors, back to genesis on the first run, are indexed in
+    /// batches of `INDEX_BATCH_SIZE` blocks between the import notifications.
+    /// The pending backfill is kept in the index, so it resumes after a
+    /// restart.
+    pub async fn run(mut self) {
+        let mut notifications = self.client.import_notification_stream();
+        loop {
+            let mut backfill = self.storage.backfill();
+
+            let imported = if backfill.is_empty() {
+                notifications.next().await
+            } else {
+                match notifications.next().now_or_never() {
+                    Some(notification) => notification,
+                    None => {
+                        // no new block, continue the backfill
+                        let hash = backfill.pop().expect("backfill is not empty; qed");
+                        self.index_batch(hash, backfill);
+                        continue;
+                    }
+                }
+            };
+
+            match imported {
+                Some(notification) => self.index_batch(notification.hash, backfill),
+                None => return,
+            }
+        }
+    }
+
+    /// Index a batch of the block and its ancestors which are not indexed yet,
+    /// remembering where to continue if the batch ends before an indexed block.
+    fn index_batch(&mut self, hash: H256, mut backfill: Vec<H256>) {
+        match self.index_chain(hash) {
+            Ok(Some(next)) => backfill.push(next),
+            Ok(None) => {}
+            Err(err) => log::warn!(target: "evm", "failed to index block {:?}: {}", hash, err),
+        }
+        self.storage.set_backfill(&backfill);
+    }
+
+    /// Index up to `INDEX_BATCH_SIZE` blocks of the block and its ancestors
+    /// which are not indexed yet. Returns the next ancestor to index, if any.
+    fn index_chain(&mut self, mut hash: H256) -> Result<Option<H256>, String> {
+        for _ in 0..INDEX_BATCH_SIZE {
+            if self.storage.block(&hash).is_some() {
+                return Ok(None);
+            }
+
+            let header = self
+                .client
+                .header(BlockId::Hash(hash))
+                .map_err(|err| format!("{:?}", err))?
+                .ok_or_else(|| "unknown block".to_string())?;
+
+            self.index_block(&header)?;
+
+            if header.number().is_zero() {
+                return Ok(None);
+            }
+            hash = *header.parent_hash();
+        }
+
+        Ok(if self.storage.block(&hash).is_none() {
+            Some(hash)
+        } else {
+            None
+        })
+    }
+
+    fn index_block(&mut self, header: &B::Header) -> Result<(), String> {
+        let hash = header.hash();
+        let at = BlockId::Hash(hash);
+
+        let api = self.client.runtime_api();
+        let receipts = api
+            .block_receipts(&at)
+            .map_err(|err| format!("runtime error: {:?}", err))?;
+        let timestamp = api
+            .block_timestamp(&at)
+            .map_err(|err| format!("runtime error: {:?}", err))?;
+
+        let extrinsics = self
+            .client
+            .block_body(&at)
+            .map_err(|err| format!("{:?}", err))?
+            .unwrap_or_default();
+
+        let transactions = receipts
+            .into_iter()
+            .filter_map(|receipt| {
+                extrinsics
+                    .get(receipt.extrinsic_index as usize)
+                    .map(|extrinsic| IndexedTransaction {
+                        // Ethereum transactions are looked up by their own hash
+                        hash: receipt
+                            .transaction_hash
+                            .unwrap_or_else(|| HashFor::<B>::hash_of(extrinsic)),
+                        receipt,
+                    })
+            })
+            .collect();
+
+        self.storage.insert_block(
+            &hash,
+            &IndexedBlock {
+                number: UniqueSaturatedInto::<u64>::unique_saturated_into(*header.number()),
+                parent_hash: *header.parent_hash(),
+                timestamp,
+                transactions,
+            },
+        );
+
+        Ok(())
+    }
+}
@@ -14,18 +14,34 @@ use sp_runtime::{
     SaturatedConversion,
 };
 use std::convert::{TryFrom, TryInto};
-use std::{marker::PhantomData, sync::Arc};
+use std::{collections::BTreeMap, marker::PhantomData, sync::Arc};
 
-use call_request::{CallRequest, EstimateResourcesResponse};
-pub use module_evm::{AddressMapping, ExitError, ExitReason};
+use call_request::{CallRequest, EstimateResourcesResponse, OverrideAccount};
+pub use module_evm::{AccountOverride, AddressMapping, ExitError, ExitReason};
 pub use module_evm_rpc_runtime_api::EVMRuntimeRPCApi;
 
 use pallet_transaction_payment_rpc_runtime_api::TransactionPaymentApi;
 
+pub use crate::debug::DebugApi;
+pub use crate::debug_api::{DebugApi as DebugApiT, DebugApiServer};
+pub use crate::eth::EthApi;
+pub use crate::eth_api::{EthApi as EthApiT, EthApiServer};
 pub use crate::evm_api::{EVMApi as EVMApiT, EVMApiServer};
+pub use block_number::BlockNumber;
+pub use indexer::{EvmIndexer, IndexedBlock, IndexedTransaction, IndexerStorage};
+pub use trace::{TraceOptions, TraceResult};
 
+mod block_number;
 mod call_request;
+mod debug;
+mod debug_api;
+mod eth;
+mod eth_api;
+mod eth_types;
 mod evm_api;
+mod filter;
+mod indexer;
+mod trace;
 
 // default gas and storage limits:
 // limits only apply to call() API
@@ -33,6 +49,9 @@ mod evm_api;
 pub const GAS_LIMIT: u64 = 100_000_000;
 pub const STORAGE_LIMIT: u32 = 1_000_000;
 
+// maximum number of blocks scanned by a single eth_getLogs request
+pub const MAX_LOGS_BLOCK_RANGE: u64 = 1024;
+
 fn internal_err<T: ToString>(message: T) -> Error {
     Error {
         code: ErrorCode::InternalError,
@@ -74,6 +93,59 @@ fn error_on_execution_failure(reason: &ExitReason, data: &[u8]) -> Result<()> {
     }
 }
 
+/// Binary searches the gas limit a request needs, between 21000 and `upper`.
+/// `calculate` runs the request with a gas limit, returning the used gas and
+/// any other result of the run. Out of gas or fund errors ask for more gas,
+/// other errors are returned. The search stops once the used gas changes by
+/// less than 10%.
+fn binary_search_estimate<T>(upper: U256, mut calculate: impl FnMut(U256) -> Result<(U256, T)>) -> Result<(U256, T)> {
+    let threshold_pct = U256::from(10);
+    let mut lower = U256::from(21_000).min(upper);
+    let mut upper = upper;
+    let mut mid = upper;
+    let mut old_best = upper;
+    let mut best = None;
+
+    // invariant: lower <= mid <= upper
+    loop {
+        match calculate(mid) {
+            // if Ok -- try to reduce the gas used
+            Ok((used_gas, result)) => {
+                let change = if old_best > used_gas {
+                    old_best - used_gas
+                } else {
+                    used_gas - old_best
+                };
+                let change_pct = (U256::from(100).saturating_mul(change))
+                    .checked_div(old_best)
+                    .unwrap_or_default();
+                if change_pct <= threshold_pct {
+                    return Ok((used_gas, result));
+                }
+
+                old_best = used_gas;
+                best = Some((used_gas, result));
+                upper = mid;
+                mid = (lower + upper + 1) / 2;
+            }
+
+            // if Err == OutofGas or OutofFund, we need more gas
+            Err(err) if err.code == ErrorCode::ServerError(0) => {
+                lower = mid;
+                mid = (lower + upper + 1) / 2;
+
+                // exit the loop
+                if mid == lower {
+                    return best.ok_or(err);
+                }
+            }
+
+            // Other errors, return directly
+            Err(err) => return Err(err),
+        }
+    }
+}
+
 fn decode_revert_message(data: &[u8]) -> Option<String> {
     // A minimum size of error function selector (4) + offset (32) + string length
     // (32) should contain a utf-8 encoded revert reason.
@@ -110,6 +182,72 @@ fn to_u128(val: NumberOrHex) -> std::result::Result<u128, ()> {
     val.into_u256().try_into().map_err(|_| ())
 }
 
+/// Converts the `stateOverride` of a call request.
+fn to_state_override(state_override: Option<BTreeMap<H160, OverrideAccount>>) -> Result<Vec<(H160, AccountOverride)>> {
+    let invalid_params = |message: String| Error {
+        code: ErrorCode::InvalidParams,
+        message,
+        data: None,
+    };
+
+    state_override
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(address, account)| {
+            if account.state.is_some() && account.state_diff.is_some() {
+                return Err(invalid_params(format!(
+                    "Invalid parameter stateOverride: both state and stateDiff of {:?}",
+                    address
+                )));
+            }
+
+            let balance = account
+                .balance
+                .map(|balance| {
+                    to_u128(balance).map_err(|_| {
+                        invalid_params(format!("Invalid parameter stateOverride: balance of {:?}", address))
+                    })
+                })
+                .transpose()?;
+            let nonce = account
+                .nonce
+                .map(|nonce| {
+                    nonce
+                        .into_u256()
+                        .try_into()
+                        .map_err(|_| invalid_params(format!("Invalid parameter stateOverride: nonce of {:?}", address)))
+                })
+                .transpose()?;
+
+            Ok((
+                address,
+                AccountOverride {
+                    balance,
+                    nonce,
+                    code: account.code.map(|code| code.0),
+                    state: account.state.map(|state| state.into_iter().collect()),
+                    state_diff: account.state_diff.unwrap_or_default().into_iter().collect(),
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Converts the value of an Ethereum call request, zero if none.
+fn to_balance<Balance: Default + TryFrom<u128>>(value: Option<U256>) -> Result<Balance> {
+    match value {
+        Some(value) => TryInto::<u128>::try_into(value)
+            .ok()
+            .and_then(|v| TryInto::<Balance>::try_into(v).ok())
+            .ok_or(Error {
+                code: ErrorCode::InvalidParams,
+                message: format!("Invalid parameter value: {:?}", value),
+                data: None,
+            }),
+        None => Ok(Default::default()),
+    }
+}
+
 impl<B, C, Balance> EVMApiT<B> for EVMApi<B, C, Balance>
 where
     B: BlockT,
@@ -131,11 +269,13 @@ where
             storage_limit,
             value,
             data,
+            state_override,
         } = request;
 
         let gas_limit = gas_limit.unwrap_or(GAS_LIMIT).min(GAS_LIMIT);
         let storage_limit = storage_limit.unwrap_or(STORAGE_LIMIT).min(STORAGE_LIMIT);
         let data = data.map(|d| d.0).unwrap_or_default();
+        let state_override = to_state_override(state_override)?;
 
         let api = self.client.runtime_api();
 
@@ -163,6 +303,7 @@ where
                         gas_limit,
                         storage_limit,
                         false,
+                        state_override,
                     )
                     .map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
                     .map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
@@ -181,6 +322,7 @@ where
                         gas_limit,
                         storage_limit,
                         false,
+                        state_override,
                     )
                     .map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
                     .map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
@@ -206,11 +348,13 @@ where
                 storage_limit,
                 value,
                 data,
+                state_override,
             } = request;
 
             let gas_limit = gas_limit.unwrap_or(GAS_LIMIT).min(GAS_LIMIT);
             let storage_limit = storage_limit.unwrap_or(STORAGE_LIMIT).min(STORAGE_LIMIT);
             let data = data.map(|d| d.0).unwrap_or_default();
+            let state_override = to_state_override(state_override)?;
 
             let balance_value = if let Some(value) = value {
                 to_u128(value).and_then(|v| TryInto::<Balance>::try_into(v).map_err(|_| ()))
@@ -238,6 +382,7 @@ where
                             gas_limit,
                             storage_limit,
                             true,
+                            state_override,
                         )
                         .map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
                         .map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
@@ -258,6 +403,7 @@ where
                             gas_limit,
                             storage_limit,
                             true,
+                            state_override,
                         )
                         .map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
                         .map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
@@ -272,48 +418,12 @@ where
         };
 
         if cfg!(feature = "rpc_binary_search_estimate") {
-            let mut lower = U256::from(21_000);
-            // get a good upper limit, but below U64::max to operation overflow
-            let mut upper = U256::from(GAS_LIMIT);
-            let mut mid = upper;
-            let mut best = mid;
-            let mut old_best: U256;
-
-            // if the gas estimation depends on the gas limit, then we want to binary
-            // search until the change is under some threshold. but if not dependent,
-            // we want to stop immediately.
-            let mut change_pct = U256::from(100);
-            let threshold_pct = U256::from(10);
-
-            // invariant: lower <= mid <= upper
-            while change_pct > threshold_pct {
+            binary_search_estimate(U256::from(GAS_LIMIT), |gas_limit| {
                 let mut test_request = request.clone();
-                test_request.gas_limit = Some(mid.as_u64());
-                match calculate_gas_used(test_request) {
-                    // if Ok -- try to reduce the gas used
-                    Ok(used_gas) => {
-                        old_best = best;
-                        best = used_gas;
-                        change_pct = (U256::from(100) * (old_best - best))
-                            .checked_div(old_best)
-                            .unwrap_or_default();
-                        upper = mid;
-                        mid = (lower + upper + 1) / 2;
-                    }
-
-                    // if Err -- we need more gas
-                    Err(_) => {
-                        lower = mid;
-                        mid = (lower + upper + 1) / 2;
-
-                        // exit the loop
-                        if mid == lower {
-                            break;
-                        }
-                    }
-                }
-            }
-            Ok(best)
+                test_request.gas_limit = Some(gas_limit.as_u64());
+                calculate_gas_used(test_request).map(|used_gas| (used_gas, ()))
+            })
+            .map(|(used_gas, _)| used_gas)
         } else {
             calculate_gas_used(request)
         }
@@ -343,6 +453,7 @@ where
             storage_limit: request.storage_limit,
             value: request.value.map(|v| NumberOrHex::Hex(U256::from(v))),
             data: request.data.map(Bytes),
+            state_override: None,
         };
 
         let calculate_gas_used = |request| -> Result<(U256, i32)> {
@@ -353,11 +464,13 @@ where
                 storage_limit,
                 value,
                 data,
+                state_override,
             } = request;
 
             let gas_limit = gas_limit.unwrap_or(GAS_LIMIT).min(GAS_LIMIT);
             let storage_limit = storage_limit.unwrap_or(STORAGE_LIMIT).min(STORAGE_LIMIT);
             let data = data.map(|d| d.0).unwrap_or_default();
+            let state_override = to_state_override(state_override)?;
 
             let balance_value = if let Some(value) = value {
                 to_u128(value).and_then(|v| TryInto::<Balance>::try_into(v).map_err(|_| ()))
@@ -385,6 +498,7 @@ where
                             gas_limit,
                             storage_limit,
                             true,
+                            state_override,
                         )
                         .map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
                         .map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
@@ -405,6 +519,7 @@ where
                             gas_limit,
                             storage_limit,
                             true,
+                            state_override,
                         )
                         .map_err(|err| internal_err(format!("runtime error: {:?}", err)))?
                         .map_err(|err| internal_err(format!("execution fatal: {:?}", err)))?;
@@ -419,64 +534,13 @@ where
         };
 
         if cfg!(feature = "rpc_binary_search_estimate") {
-            let mut lower = U256::from(21_000);
-            // get a good upper limit, but below U64::max to operation overflow
-            let mut upper = U256::from(GAS_LIMIT);
-            let mut mid = upper;
-            let mut best = mid;
-            let mut old_best: U256;
-            let mut storage: i32 = Default::default();
-
-            // if the gas estimation depends on the gas limit, then we want to binary
-            // search until the change is under some threshold. but if not dependent,
-            // we want to stop immediately.
-            let mut change_pct = U256::from(100);
-            let threshold_pct = U256::from(10);
-
-            // invariant: lower <= mid <= upper
-            while change_pct > threshold_pct {
+            let (best, storage) = binary_search_estimate(U256::from(GAS_LIMIT), |gas_limit| {
                 let mut test_request = request.clone();
-                test_request.gas_limit = Some(mid.as_u64());
-                match calculate_gas_used(test_request) {
-                    // if Ok -- try to reduce the gas used
-                    Ok((used_gas, used_storage)) => {
-                        log::debug!(
-                            target: "evm",
-                            "calculate_gas_used ok, used_gas: {:?}, used_storage: {:?}",
-                            used_gas, used_storage,
-                        );
-
-                        old_best = best;
-                        best = used_gas;
-                        change_pct = (U256::from(100) * (old_best - best))
-                            .checked_div(old_best)
-                            .unwrap_or_default();
-                        upper = mid;
-                        mid = (lower + upper + 1) / 2;
-                        storage = used_storage;
-                    }
-
-                    Err(err) => {
-                        log::debug!(
-                            target: "evm",
-                            "calculate_gas_used err, lower: {:?}, upper: {:?}, mid: {:?}",
-                            lower, upper, mid
-                        );
-
-                        // if Err == OutofGas or OutofFund, we need more gas
-                        if err.code == ErrorCode::ServerError(0) {
-                            lower = mid;
-                            mid = (lower + upper + 1) / 2;
-                            if mid == lower {
-                                break;
-                            }
-                        }
-
-                        // Other errors, return directly
-                        return Err(err);
-                    }
-                }
-            }
+                test_request.gas_limit = Some(gas_limit.as_u64());
+                let result = calculate_gas_used(test_request);
+                log::debug!(target: "evm", "calculate_gas_used with gas_limit {:?}: {:?}", gas_limit, result);
+                result
+            })?;
 
             let uxt: <B as BlockT>::Extrinsic = Decode::decode(&mut &*unsigned_extrinsic).map_err(|e| Error {
                 code: ErrorCode::InternalError,
@@ -549,3 +613,47 @@ fn decode_revert_message_should_work() {
     let data = from_hex("0x8c379a00000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000000d6572726f72206d65737361676500000000000000000000000000000000000000").unwrap();
     assert_eq!(decode_revert_message(&data), Some("error message".into()));
 }
+
+#[test]
+fn binary_search_estimate_should_work() {
+    let out_of_gas = || Error {
+        code: ErrorCode::ServerError(0),
+        message: "out of gas".into(),
+        data: None,
+    };
+
+    // used gas grows with the gas limit, the search must not underflow
+    let (used_gas, _) = binary_search_estimate(U256::from(GAS_LIMIT), |gas| {
+        if gas < U256::from(50_000) {
+            Err(out_of_gas())
+        } else {
+            Ok((U256::from(50_000) + gas / 1_000, ()))
+        }
+    })
+    .unwrap();
+    assert!(used_gas >= U256::from(50_000));
+
+    // never enough gas
+    assert_eq!(
+        binary_search_estimate(U256::from(GAS_LIMIT), |_| -> Result<(U256, ())> { Err(out_of_gas()) })
+            .unwrap_err()
+            .code,
+        ErrorCode::ServerError(0)
+    );
+
+    // other errors are returned directly
+    assert_eq!(
+        binary_search_estimate(U256::from(GAS_LIMIT), |_| -> Result<(U256, ())> {
+            Err(Error::invalid_request())
+        })
+        .unwrap_err()
+        .code,
+        ErrorCode::InvalidRequest
+    );
+
+    // the extra result of the best run is kept
+    assert_eq!(
+        binary_search_estimate(U256::from(GAS_LIMIT), |_| Ok((U256::from(21_000), 42i32))).unwrap(),
+        (U256::from(21_000), 42)
+    );
+}
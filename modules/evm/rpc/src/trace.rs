@@ -0,0 +1,495 @@
+use ethereum_types::{H160, H256, U256};
+use jsonrpc_core::{Error, ErrorCode, Result};
+use rustc_hex::ToHex;
+use serde::{Deserialize, Serialize};
+use sp_core::Bytes;
+use std::collections::BTreeMap;
+
+use module_evm::{CallFrame, CallType, ExitError, ExitReason, StructLog, Trace, TracerConfig};
+
+/// Tracer of the `callTracer` option
+pub const CALL_TRACER: &str = "callTracer";
+
+/// Options of the `debug_traceTransaction` and `debug_traceCall` methods
+#[derive(Debug, Default, PartialEq, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceOptions {
+    /// Tracer, the struct logger if none
+    pub tracer: Option<String>,
+    /// Disable the stack of the struct logs
+    #[serde(default)]
+    pub disable_stack: bool,
+    /// Disable the memory of the struct logs
+    #[serde(default)]
+    pub disable_memory: bool,
+    /// Disable the storage of the struct logs
+    #[serde(default)]
+    pub disable_storage: bool,
+    /// Maximum number of struct logs, 0 for no limit
+    #[serde(default)]
+    pub limit: u32,
+}
+
+impl TraceOptions {
+    /// Returns the config of the runtime tracer.
+    pub fn tracer_config(&self) -> Result<TracerConfig> {
+        match self.tracer.as_deref() {
+            None => Ok(TracerConfig::StructLogger {
+                disable_stack: self.disable_stack,
+                disable_memory: self.disable_memory,
+                disable_storage: self.disable_storage,
+                limit: self.limit,
+            }),
+            Some(CALL_TRACER) => Ok(TracerConfig::CallTracer),
+            Some(tracer) => Err(Error {
+                code: ErrorCode::InvalidParams,
+                message: format!("Unsupported tracer: {}", tracer),
+                data: None,
+            }),
+        }
+    }
+}
+
+/// Opcode executed by the struct logger
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLogResult {
+    /// Program Counter
+    pub pc: u32,
+    /// Opcode name
+    pub op: String,
+    /// Gas left before the opcode
+    pub gas: u64,
+    /// Gas cost of the opcode
+    pub gas_cost: u64,
+    /// Call depth, 1 for the transaction
+    pub depth: u32,
+    /// Stack, the top last
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack: Option<Vec<U256>>,
+    /// Memory, by 32 bytes words
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<Vec<String>>,
+    /// Storage slots accessed by the contract so far
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub storage: Option<BTreeMap<String, String>>,
+}
+
+/// Result of the struct logger
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLoggerResult {
+    /// Used gas
+    pub gas: u64,
+    /// Whether the execution failed
+    pub failed: bool,
+    /// Output
+    pub return_value: String,
+    /// Executed opcodes
+    pub struct_logs: Vec<StructLogResult>,
+}
+
+/// Call frame of the call tracer
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallTracerResult {
+    /// Call type
+    #[serde(rename = "type")]
+    pub call_type: String,
+    /// Caller
+    pub from: H160,
+    /// Callee, or created contract
+    pub to: H160,
+    /// Value
+    pub value: U256,
+    /// Gas Limit
+    pub gas: U256,
+    /// Used gas
+    pub gas_used: U256,
+    /// Input
+    pub input: Bytes,
+    /// Output
+    pub output: Bytes,
+    /// Error of a failed call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Sub calls
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub calls: Vec<CallTracerResult>,
+}
+
+/// Result of a trace, in the format of the tracer
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum TraceResult {
+    /// Struct logger result
+    StructLogger(StructLoggerResult),
+    /// Call tracer result
+    CallTracer(CallTracerResult),
+}
+
+impl TraceResult {
+    pub fn new(trace: Trace, config: TracerConfig) -> Self {
+        match config {
+            TracerConfig::StructLogger { .. } => TraceResult::StructLogger(StructLoggerResult {
+                gas: trace.call.gas_used,
+                failed: !matches!(trace.call.exit_reason, Some(ExitReason::Succeed(_))),
+                return_value: trace.call.output.to_hex::<String>(),
+                struct_logs: trace.struct_logs.into_iter().map(struct_log).collect(),
+            }),
+            TracerConfig::CallTracer => TraceResult::CallTracer(call_frame(trace.call)),
+        }
+    }
+}
+
+fn struct_log(log: StructLog) -> StructLogResult {
+    StructLogResult {
+        pc: log.pc,
+        op: opcode_name(log.op).to_string(),
+        gas: log.gas,
+        gas_cost: log.gas_cost,
+        depth: log.depth,
+        stack: log
+            .stack
+            .map(|stack| stack.iter().map(|value| U256::from(value.as_bytes())).collect()),
+        memory: log
+            .memory
+            .map(|memory| memory.chunks(32).map(|word| word.to_hex::<String>()).collect()),
+        storage: log
+            .storage
+            .map(|storage| storage.iter().map(|(index, value)| (hex(index), hex(value))).collect()),
+    }
+}
+
+fn call_frame(frame: CallFrame) -> CallTracerResult {
+    let call_type = match frame.call_type {
+        CallType::Call => "CALL",
+        CallType::CallCode => "CALLCODE",
+        CallType::DelegateCall => "DELEGATECALL",
+        CallType::StaticCall => "STATICCALL",
+        CallType::Create => "CREATE",
+        CallType::Create2 => "CREATE2",
+    };
+
+    let error = match frame.exit_reason {
+        None => Some("execution aborted".to_string()),
+        Some(ExitReason::Succeed(_)) => None,
+        Some(ExitReason::Revert(_)) => Some("execution reverted".to_string()),
+        Some(ExitReason::Error(ExitError::OutOfGas)) => Some("out of gas".to_string()),
+        Some(ExitReason::Error(e)) => Some(format!("{:?}", e)),
+        Some(ExitReason::Fatal(e)) => Some(format!("{:?}", e)),
+    };
+
+    CallTracerResult {
+        call_type: call_type.to_string(),
+        from: frame.from,
+        to: frame.to,
+        value: frame.value,
+        gas: U256::from(frame.gas),
+        gas_used: U256::from(frame.gas_used),
+        input: Bytes(frame.input),
+        output: Bytes(frame.output),
+        error,
+        calls: frame.calls.into_iter().map(call_frame).collect(),
+    }
+}
+
+fn hex(value: &H256) -> String {
+    value.as_bytes().to_hex::<String>()
+}
+
+/// Returns the name of the opcode, as printed by geth.
+pub fn opcode_name(opcode: u8) -> &'static str {
+    match opcode {
+        0x00 => "STOP",
+        0x01 => "ADD",
+        0x02 => "MUL",
+        0x03 => "SUB",
+        0x04 => "DIV",
+        0x05 => "SDIV",
+        0x06 => "MOD",
+        0x07 => "SMOD",
+        0x08 => "ADDMOD",
+        0x09 => "MULMOD",
+        0x0a => "EXP",
+        0x0b => "SIGNEXTEND",
+        0x10 => "LT",
+        0x11 => "GT",
+        0x12 => "SLT",
+        0x13 => "SGT",
+        0x14 => "EQ",
+        0x15 => "ISZERO",
+        0x16 => "AND",
+        0x17 => "OR",
+        0x18 => "XOR",
+        0x19 => "NOT",
+        0x1a => "BYTE",
+        0x1b => "SHL",
+        0x1c => "SHR",
+        0x1d => "SAR",
+        0x20 => "SHA3",
+        0x30 => "ADDRESS",
+        0x31 => "BALANCE",
+        0x32 => "ORIGIN",
+        0x33 => "CALLER",
+        0x34 => "CALLVALUE",
+        0x35 => "CALLDATALOAD",
+        0x36 => "CALLDATASIZE",
+        0x37 => "CALLDATACOPY",
+        0x38 => "CODESIZE",
+        0x39 => "CODECOPY",
+        0x3a => "GASPRICE",
+        0x3b => "EXTCODESIZE",
+        0x3c => "EXTCODECOPY",
+        0x3d => "RETURNDATASIZE",
+        0x3e => "RETURNDATACOPY",
+        0x3f => "EXTCODEHASH",
+        0x40 => "BLOCKHASH",
+        0x41 => "COINBASE",
+        0x42 => "TIMESTAMP",
+        0x43 => "NUMBER",
+        0x44 => "DIFFICULTY",
+        0x45 => "GASLIMIT",
+        0x46 => "CHAINID",
+        0x47 => "SELFBALANCE",
+        0x48 => "BASEFEE",
+        0x50 => "POP",
+        0x51 => "MLOAD",
+        0x52 => "MSTORE",
+        0x53 => "MSTORE8",
+        0x54 => "SLOAD",
+        0x55 => "SSTORE",
+        0x56 => "JUMP",
+        0x57 => "JUMPI",
+        0x58 => "PC",
+        0x59 => "MSIZE",
+        0x5a => "GAS",
+        0x5b => "JUMPDEST",
+        0x60 => "PUSH1",
+        0x61 => "PUSH2",
+        0x62 => "PUSH3",
+        0x63 => "PUSH4",
+        0x64 => "PUSH5",
+        0x65 => "PUSH6",
+        0x66 => "PUSH7",
+        0x67 => "PUSH8",
+        0x68 => "PUSH9",
+        0x69 => "PUSH10",
+        0x6a => "PUSH11",
+        0x6b => "PUSH12",
+        0x6c => "PUSH13",
+        0x6d => "PUSH14",
+        0x6e => "PUSH15",
+        0x6f => "PUSH16",
+        0x70 => "PUSH17",
+        0x71 => "PUSH18",
+        0x72 => "PUSH19",
+        0x73 => "PUSH20",
+        0x74 => "PUSH21",
+        0x75 => "PUSH22",
+        0x76 => "PUSH23",
+        0x77 => "PUSH24",
+        0x78 => "PUSH25",
+        0x79 => "PUSH26",
+        0x7a => "PUSH27",
+        0x7b => "PUSH28",
+        0x7c => "PUSH29",
+        0x7d => "PUSH30",
+        0x7e => "PUSH31",
+        0x7f => "PUSH32",
+        0x80 => "DUP1",
+        0x81 => "DUP2",
+        0x82 => "DUP3",
+        0x83 => "DUP4",
+        0x84 => "DUP5",
+        0x85 => "DUP6",
+        0x86 => "DUP7",
+        0x87 => "DUP8",
+        0x88 => "DUP9",
+        0x89 => "DUP10",
+        0x8a => "DUP11",
+        0x8b => "DUP12",
+        0x8c => "DUP13",
+        0x8d => "DUP14",
+        0x8e => "DUP15",
+        0x8f => "DUP16",
+        0x90 => "SWAP1",
+        0x91 => "SWAP2",
+        0x92 => "SWAP3",
+        0x93 => "SWAP4",
+        0x94 => "SWAP5",
+        0x95 => "SWAP6",
+        0x96 => "SWAP7",
+        0x97 => "SWAP8",
+        0x98 => "SWAP9",
+        0x99 => "SWAP10",
+        0x9a => "SWAP11",
+        0x9b => "SWAP12",
+        0x9c => "SWAP13",
+        0x9d => "SWAP14",
+        0x9e => "SWAP15",
+        0x9f => "SWAP16",
+        0xa0 => "LOG0",
+        0xa1 => "LOG1",
+        0xa2 => "LOG2",
+        0xa3 => "LOG3",
+        0xa4 => "LOG4",
+        0xf0 => "CREATE",
+        0xf1 => "CALL",
+        0xf2 => "CALLCODE",
+        0xf3 => "RETURN",
+        0xf4 => "DELEGATECALL",
+        0xf5 => "CREATE2",
+        0xfa => "STATICCALL",
+        0xfd => "REVERT",
+        0xfe => "INVALID",
+        0xff => "SELFDESTRUCT",
+        _ => "UNKNOWN",
+    }
+}
+
+#[test]
+fn trace_options_should_work() {
+    let options: TraceOptions = serde_json::from_str(r#"{"disableStack": true, "timeout": "10s"}"#).unwrap();
+    assert_eq!(
+        options.tracer_config().unwrap(),
+        TracerConfig::StructLogger {
+            disable_stack: true,
+            disable_memory: false,
+            disable_storage: false,
+            limit: 0,
+        }
+    );
+
+    let options: TraceOptions = serde_json::from_str(r#"{"disableMemory": true, "limit": 100}"#).unwrap();
+    assert_eq!(
+        options.tracer_config().unwrap(),
+        TracerConfig::StructLogger {
+            disable_stack: false,
+            disable_memory: true,
+            disable_storage: false,
+            limit: 100,
+        }
+    );
+
+    let options: TraceOptions = serde_json::from_str(r#"{"tracer": "callTracer"}"#).unwrap();
+    assert_eq!(options.tracer_config().unwrap(), TracerConfig::CallTracer);
+
+    let options: TraceOptions = serde_json::from_str(r#"{"tracer": "prestateTracer"}"#).unwrap();
+    assert!(options.tracer_config().is_err());
+}
+
+#[test]
+fn struct_logger_result_should_work() {
+    let trace = Trace {
+        call: CallFrame {
+            call_type: CallType::Call,
+            from: H160::from_low_u64_be(1),
+            to: H160::from_low_u64_be(2),
+            input: vec![],
+            output: vec![0x01],
+            value: U256::zero(),
+            gas: 100_000,
+            gas_used: 21_006,
+            exit_reason: Some(ExitReason::Succeed(module_evm::ExitSucceed::Returned)),
+            calls: vec![],
+        },
+        struct_logs: vec![StructLog {
+            pc: 2,
+            op: 0x55,
+            gas: 78_994,
+            gas_cost: 20_000,
+            depth: 1,
+            stack: Some(vec![H256::from_low_u64_be(0x2a), H256::zero()]),
+            memory: Some(vec![0u8; 32]),
+            storage: Some(vec![(H256::zero(), H256::from_low_u64_be(0x2a))]),
+        }],
+    };
+
+    let result = TraceResult::new(
+        trace,
+        TracerConfig::StructLogger {
+            disable_stack: false,
+            disable_memory: false,
+            disable_storage: false,
+            limit: 0,
+        },
+    );
+
+    assert_eq!(
+        serde_json::to_value(result).unwrap(),
+        serde_json::json!({
+            "gas": 21006,
+            "failed": false,
+            "returnValue": "01",
+            "structLogs": [{
+                "pc": 2,
+                "op": "SSTORE",
+                "gas": 78994,
+                "gasCost": 20000,
+                "depth": 1,
+                "stack": ["0x2a", "0x0"],
+                "memory": ["0000000000000000000000000000000000000000000000000000000000000000"],
+                "storage": {
+                    "0000000000000000000000000000000000000000000000000000000000000000":
+                        "000000000000000000000000000000000000000000000000000000000000002a"
+                }
+            }]
+        })
+    );
+}
+
+#[test]
+fn call_tracer_result_should_work() {
+    let trace = Trace {
+        call: CallFrame {
+            call_type: CallType::Call,
+            from: H160::from_low_u64_be(1),
+            to: H160::from_low_u64_be(2),
+            input: vec![0x01],
+            output: vec![],
+            value: U256::from(10),
+            gas: 100_000,
+            gas_used: 30_000,
+            exit_reason: Some(ExitReason::Revert(module_evm::ExitRevert::Reverted)),
+            calls: vec![CallFrame {
+                call_type: CallType::StaticCall,
+                from: H160::from_low_u64_be(2),
+                to: H160::from_low_u64_be(3),
+                input: vec![],
+                output: vec![0x02],
+                value: U256::zero(),
+                gas: 50_000,
+                gas_used: 100,
+                exit_reason: Some(ExitReason::Succeed(module_evm::ExitSucceed::Returned)),
+                calls: vec![],
+            }],
+        },
+        struct_logs: vec![],
+    };
+
+    assert_eq!(
+        serde_json::to_value(TraceResult::new(trace, TracerConfig::CallTracer)).unwrap(),
+        serde_json::json!({
+            "type": "CALL",
+            "from": "0x0000000000000000000000000000000000000001",
+            "to": "0x0000000000000000000000000000000000000002",
+            "value": "0xa",
+            "gas": "0x186a0",
+            "gasUsed": "0x7530",
+            "input": "0x01",
+            "output": "0x",
+            "error": "execution reverted",
+            "calls": [{
+                "type": "STATICCALL",
+                "from": "0x0000000000000000000000000000000000000002",
+                "to": "0x0000000000000000000000000000000000000003",
+                "value": "0x0",
+                "gas": "0xc350",
+                "gasUsed": "0x64",
+                "input": "0x",
+                "output": "0x02"
+            }]
+        })
+    );
+}
@@ -11,32 +11,47 @@ use frame_support::{
     ensure,
     error::BadOrigin,
     pallet_prelude::*,
-    traits::{Currency, EnsureOrigin, ExistenceRequirement, Get, OnKilledAccount, ReservableCurrency, WithdrawReasons},
+    traits::{
+        Currency, EnsureOrigin, ExistenceRequirement, FindAuthor, Get, Imbalance, OnKilledAccount, Randomness,
+        ReservableCurrency, WithdrawReasons,
+    },
     transactional,
-    weights::{Pays, PostDispatchInfo, Weight},
+    weights::{Pays, PostDispatchInfo, RuntimeDbWeight, Weight},
     RuntimeDebug,
 };
-use frame_system::{ensure_root, ensure_signed, pallet_prelude::*, EnsureOneOf, EnsureRoot, EnsureSigned};
+use frame_system::{ensure_none, ensure_root, ensure_signed, pallet_prelude::*, EnsureOneOf, EnsureRoot, EnsureSigned};
 use primitive_types::{H256, U256};
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
 use sha3::{Digest, Keccak256};
 use sp_runtime::{
-    traits::{Convert, DispatchInfoOf, One, PostDispatchInfoOf, SignedExtension, UniqueSaturatedInto},
-    transaction_validity::TransactionValidityError,
-    Either, TransactionOutcome,
+    traits::{
+        CheckedAdd, CheckedSub, Convert, DispatchInfoOf, One, PostDispatchInfoOf, Saturating, SignedExtension,
+        UniqueSaturatedInto, Zero,
+    },
+    transaction_validity::{
+        InvalidTransaction, TransactionSource, TransactionValidity, TransactionValidityError, ValidTransaction,
+    },
+    Either, FixedPointNumber, FixedU128, TransactionOutcome,
 };
-use sp_std::{marker::PhantomData, vec::Vec};
-use support::{EVMStateRentTrait, ExecutionMode, InvokeContext, TransactionPayment, EVM as EVMTrait};
+use sp_std::{borrow::Cow, cell::RefCell, marker::PhantomData, rc::Rc, vec::Vec};
+use support::{EVMLog, EVMStateRentTrait, ExecutionMode, InvokeContext, TransactionPayment, EVM as EVMTrait};
+
+use crate::runner::handler::{Handler, STORAGE_SIZE};
 
 pub use crate::precompiles::{Precompile, Precompiles};
-pub use crate::runner::Runner;
+pub use crate::runner::{tracer::Tracer, Runner};
+pub use crate::transaction::{LegacyTransaction, TransactionAction};
 pub use evm::{Context, ExitError, ExitFatal, ExitReason, ExitRevert, ExitSucceed};
 pub use orml_traits::currency::TransferAll;
-pub use primitives::evm::{Account, AddressMapping, CallInfo, CreateInfo, EvmAddress, Log, Vicinity};
+pub use primitives::evm::{
+    AccessList, Account, AccountOverride, AddressMapping, CallFrame, CallInfo, CallType, CreateInfo, EvmAddress,
+    ExtrinsicReceipt, Log, StructLog, Trace, TracerConfig, Vicinity,
+};
 
 pub mod precompiles;
 pub mod runner;
+pub mod transaction;
 
 mod default_weight;
 mod mock;
@@ -56,7 +71,9 @@ pub trait WeightInfo {
     fn enable_contract_development() -> Weight;
     fn disable_contract_development() -> Weight;
     fn set_code() -> Weight;
+    fn rollback_code() -> Weight;
     fn selfdestruct() -> Weight;
+    fn set_precompile_enabled() -> Weight;
 }
 
 // Initially based on Istanbul hard fork configuration.
@@ -65,9 +82,11 @@ static EVM_CONFIG: EvmConfig = EvmConfig {
     gas_ext_code_hash: 700,
     gas_balance: 700,
     gas_sload: 800,
+    gas_sload_cold: 0,
     gas_sstore_set: 20000,
     gas_sstore_reset: 5000,
     refund_sstore_clears: 0, // no gas refund
+    max_refund_quotient: 2,
     gas_suicide: 5000,
     gas_suicide_new_account: 25000,
     gas_call: 700,
@@ -76,8 +95,15 @@ static EVM_CONFIG: EvmConfig = EvmConfig {
     gas_transaction_call: 21000,
     gas_transaction_zero_data: 4,
     gas_transaction_non_zero_data: 16,
+    gas_access_list_address: 0,
+    gas_access_list_storage_key: 0,
+    gas_account_access_cold: 0,
+    gas_storage_read_warm: 0,
     sstore_gas_metering: false,         // no gas refund
     sstore_revert_under_stipend: false, // ignored
+    increase_state_access_gas: false,
+    decrease_clears_refund: false,
+    disallow_executable_format: false,
     err_on_call_with_more_gas: false,
     empty_considered_exists: false,
     create_increase_nonce: true,
@@ -95,9 +121,24 @@ static EVM_CONFIG: EvmConfig = EvmConfig {
     has_chain_id: true,
     has_self_balance: true,
     has_ext_code_hash: true,
+    has_base_fee: false,
     estimate: false,
 };
 
+/// Berlin hard fork configuration, with the EIP-2929 access lists and the
+/// EIP-2200 storage gas metering.
+pub static BERLIN_CONFIG: EvmConfig = EvmConfig {
+    create_contract_limit: None, // ignored
+    ..EvmConfig::berlin()
+};
+
+/// London hard fork configuration, the Berlin one with the EIP-3529 reduced
+/// refunds and the `BASEFEE` opcode.
+pub static LONDON_CONFIG: EvmConfig = EvmConfig {
+    create_contract_limit: None, // ignored
+    ..EvmConfig::london()
+};
+
 #[frame_support::pallet]
 pub mod module {
     use super::*;
@@ -127,6 +168,10 @@ pub mod module {
         #[pallet::constant]
         type MaxCodeSize: Get<u32>;
 
+        /// Max number of previous codes kept per contract to roll back to.
+        #[pallet::constant]
+        type MaxCodeHistory: Get<u32>;
+
         /// The overarching event type.
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
 
@@ -136,9 +181,32 @@ pub mod module {
         /// Chain ID of EVM.
         type ChainId: Get<u64>;
 
+        /// Minimum gas price of the Ethereum transactions.
+        #[pallet::constant]
+        type MinimumGasPrice: Get<BalanceOf<Self>>;
+
+        /// Fee per byte of the Ethereum transactions, paid for their length
+        /// on top of the gas.
+        #[pallet::constant]
+        type TransactionByteFee: Get<BalanceOf<Self>>;
+
         /// Convert gas to weight.
         type GasToWeight: Convert<u64, Weight>;
 
+        /// Update of the base fee multiplier at the end of a block, following
+        /// its fullness.
+        type BaseFeeUpdate: Convert<FixedU128, FixedU128>;
+
+        /// Find the author of the block, the `COINBASE` of the EVM.
+        type FindAuthor: FindAuthor<Self::AccountId>;
+
+        /// The gas limit of a block, the `GASLIMIT` of the EVM.
+        #[pallet::constant]
+        type BlockGasLimit: Get<u64>;
+
+        /// Randomness source of the `DIFFICULTY` of the EVM.
+        type Randomness: Randomness<Self::Hash, Self::BlockNumber>;
+
         /// ChargeTransactionPayment convert weight to fee.
         type ChargeTransactionPayment: TransactionPayment<Self::AccountId, BalanceOf<Self>, NegativeImbalanceOf<Self>>;
 
@@ -164,6 +232,9 @@ pub mod module {
 
         type FreeDeploymentOrigin: EnsureOrigin<Self::Origin>;
 
+        /// Required origin for enabling or disabling precompiles.
+        type PrecompileOrigin: EnsureOrigin<Self::Origin>;
+
         /// Weight information for the extrinsics in this module.
         type WeightInfo: WeightInfo;
     }
@@ -198,6 +269,18 @@ pub mod module {
         pub ref_count: u32,
     }
 
+    /// A previous code of a contract, referenced in `CodeInfos` until it
+    /// leaves the history.
+    #[derive(Clone, Eq, PartialEq, RuntimeDebug, Encode, Decode)]
+    pub struct CodeHistoryEntry<AccountId, BlockNumber> {
+        /// Hash of the code.
+        pub code_hash: H256,
+        /// Block in which the code was set, `None` for the deployed code.
+        pub block_number: Option<BlockNumber>,
+        /// Who set the code, `None` for root and the deployed code.
+        pub who: Option<AccountId>,
+    }
+
     #[cfg(feature = "std")]
     #[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, Serialize, Deserialize)]
     /// Account definition used for genesis block construction.
@@ -230,6 +313,42 @@ pub mod module {
     #[pallet::getter(fn code_infos)]
     pub type CodeInfos<T: Config> = StorageMap<_, Identity, H256, CodeInfo>;
 
+    #[pallet::type_value]
+    pub fn DefaultBaseFeeMultiplier() -> FixedU128 {
+        FixedU128::one()
+    }
+
+    /// Multiplier of the minimum gas price giving the base fee per gas.
+    #[pallet::storage]
+    #[pallet::getter(fn base_fee_multiplier)]
+    pub type BaseFeeMultiplier<T: Config> = StorageValue<_, FixedU128, ValueQuery, DefaultBaseFeeMultiplier>;
+
+    /// Previous codes of the contracts, the most recent last.
+    #[pallet::storage]
+    #[pallet::getter(fn code_history)]
+    pub type CodeHistory<T: Config> =
+        StorageMap<_, Twox64Concat, EvmAddress, Vec<CodeHistoryEntry<T::AccountId, T::BlockNumber>>, ValueQuery>;
+
+    /// Who set the current code of the contracts and in which block, `None`
+    /// for root. Only present for the codes set by `set_code` or
+    /// `rollback_code`.
+    #[pallet::storage]
+    #[pallet::getter(fn code_set_by)]
+    pub type CodeSetBy<T: Config> = StorageMap<_, Twox64Concat, EvmAddress, (Option<T::AccountId>, T::BlockNumber)>;
+
+    /// Removed contracts whose storage is being collected, with the
+    /// maintainer their storage deposit is released to.
+    #[pallet::storage]
+    #[pallet::getter(fn contracts_to_remove)]
+    pub type ContractsToRemove<T: Config> = StorageMap<_, Twox64Concat, EvmAddress, EvmAddress>;
+
+    /// Precompiles disabled by governance, calls to them fail.
+    ///
+    /// DisabledPrecompiles: map EvmAddress => bool
+    #[pallet::storage]
+    #[pallet::getter(fn disabled_precompiles)]
+    pub type DisabledPrecompiles<T: Config> = StorageMap<_, Twox64Concat, EvmAddress, bool, ValueQuery>;
+
     /// Next available system contract address.
     #[pallet::storage]
     #[pallet::getter(fn network_contract_index)]
@@ -283,21 +402,29 @@ pub mod module {
     }
 
     /// EVM events
+    ///
+    /// Note for clients decoding the events: `Created`, `CreatedFailed`,
+    /// `Executed` and `ExecutedFailed` used to carry only the contract (and
+    /// the exit reason and output on failure). They now start with the caller
+    /// and end with the used gas, which the receipts of the EVM RPC are built
+    /// from.
     #[pallet::event]
     #[pallet::generate_deposit(pub(crate) fn deposit_event)]
     pub enum Event<T: Config> {
         /// Ethereum events from contracts.
         Log(Log),
-        /// A contract has been created at given \[address\].
-        Created(EvmAddress),
+        /// A contract has been created at given address.
+        /// \[from, contract, used_gas\]
+        Created(EvmAddress, EvmAddress, u64),
         /// A contract was attempted to be created, but the execution failed.
-        /// \[contract, exit_reason, output\]
-        CreatedFailed(EvmAddress, ExitReason, Vec<u8>),
-        /// A \[contract\] has been executed successfully with states applied.
-        Executed(EvmAddress),
+        /// \[from, contract, exit_reason, output, used_gas\]
+        CreatedFailed(EvmAddress, EvmAddress, ExitReason, Vec<u8>, u64),
+        /// A contract has been executed successfully with states applied.
+        /// \[from, contract, used_gas\]
+        Executed(EvmAddress, EvmAddress, u64),
         /// A contract has been executed with errors. States are reverted with
-        /// only gas fees applied. \[contract, exit_reason, output\]
-        ExecutedFailed(EvmAddress, ExitReason, Vec<u8>),
+        /// only gas fees applied. \[from, contract, exit_reason, output, used_gas\]
+        ExecutedFailed(EvmAddress, EvmAddress, ExitReason, Vec<u8>, u64),
         /// A deposit has been made at a given address. \[sender, address,
         /// value\]
         BalanceDeposit(T::AccountId, EvmAddress, U256),
@@ -322,10 +449,22 @@ pub mod module {
         ContractDevelopmentDisabled(T::AccountId),
         /// Deployed contract. \[contract\]
         ContractDeployed(EvmAddress),
-        /// Set contract code. \[contract\]
-        ContractSetCode(EvmAddress),
+        /// Set contract code. \[contract, old_code_hash, new_code_hash\]
+        ContractSetCode(EvmAddress, H256, H256),
+        /// Rolled back contract code to a previous code. \[contract,
+        /// old_code_hash, new_code_hash\]
+        ContractCodeRolledBack(EvmAddress, H256, H256),
         /// Selfdestructed contract code. \[contract\]
         ContractSelfdestructed(EvmAddress),
+        /// An Ethereum transaction has been applied. \[hash, from\]
+        EthTransactionApplied(H256, EvmAddress),
+        /// The storage of a removed contract has been collected and its
+        /// storage deposit released. \[contract, maintainer\]
+        ContractStorageRemoved(EvmAddress, EvmAddress),
+        /// Enabled a precompile. \[address\]
+        PrecompileEnabled(EvmAddress),
+        /// Disabled a precompile. \[address\]
+        PrecompileDisabled(EvmAddress),
     }
 
     #[pallet::error]
@@ -354,13 +493,76 @@ pub mod module {
         ChargeFeeFailed,
         /// Contract address conflicts with the system contract
         ConflictContractAddress,
+        /// Ethereum transaction can't be decoded
+        InvalidEthTransaction,
+        /// Ethereum transaction is signed for another chain
+        InvalidChainId,
+        /// Ethereum transaction signature is invalid
+        InvalidSignature,
+        /// Ethereum transaction nonce is not the sender nonce
+        InvalidNonce,
+        /// Gas price is below the base fee per gas
+        GasPriceTooLow,
+        /// Max priority fee per gas is above the max fee per gas
+        PriorityFeeTooHigh,
+        /// Code is not in the code history of the contract
+        CodeNotInHistory,
+        /// The address is not a precompile
+        PrecompileNotFound,
     }
 
     #[pallet::pallet]
     pub struct Pallet<T>(PhantomData<T>);
 
     #[pallet::hooks]
-    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {}
+    impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
+        fn on_initialize(_: T::BlockNumber) -> Weight {
+            // the base fee multiplier is updated in `on_finalize`
+            T::DbWeight::get().reads_writes(1, 1)
+        }
+
+        fn on_finalize(_: T::BlockNumber) {
+            BaseFeeMultiplier::<T>::mutate(|multiplier| *multiplier = T::BaseFeeUpdate::convert(*multiplier));
+        }
+
+        fn on_idle(_: T::BlockNumber, remaining_weight: Weight) -> Weight {
+            Self::remove_contracts_storage(remaining_weight, T::DbWeight::get())
+        }
+    }
+
+    #[pallet::validate_unsigned]
+    impl<T: Config> ValidateUnsigned for Pallet<T> {
+        type Call = Call<T>;
+
+        fn validate_unsigned(_source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+            if let Call::eth_transact(transaction) = call {
+                let (tx, source, nonce) = Self::validate_eth_transaction(transaction)?;
+
+                let mut valid = ValidTransaction::with_tag_prefix("EvmEthTransaction")
+                    .priority(tx.gas_price.min(U256::from(u64::MAX)).low_u64())
+                    .and_provides((source, tx.nonce))
+                    .propagate(true);
+                if tx.nonce > nonce {
+                    valid = valid.and_requires((source, tx.nonce - 1));
+                }
+                valid.build()
+            } else {
+                InvalidTransaction::Call.into()
+            }
+        }
+
+        fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+            if let Call::eth_transact(transaction) = call {
+                let (tx, _, nonce) = Self::validate_eth_transaction(transaction)?;
+                if tx.nonce > nonce {
+                    return Err(InvalidTransaction::Future.into());
+                }
+                Ok(())
+            } else {
+                Err(InvalidTransaction::Call.into())
+            }
+        }
+    }
 
     #[pallet::call]
     impl<T: Config> Pallet<T> {
@@ -389,14 +591,20 @@ pub mod module {
                 T::config(),
             )?;
 
+            let used_gas: u64 = info.used_gas.unique_saturated_into();
+
             if info.exit_reason.is_succeed() {
-                Pallet::<T>::deposit_event(Event::<T>::Executed(target));
+                Pallet::<T>::deposit_event(Event::<T>::Executed(source, target, used_gas));
             } else {
-                Pallet::<T>::deposit_event(Event::<T>::ExecutedFailed(target, info.exit_reason, info.output));
+                Pallet::<T>::deposit_event(Event::<T>::ExecutedFailed(
+                    source,
+                    target,
+                    info.exit_reason,
+                    info.output,
+                    used_gas,
+                ));
             }
 
-            let used_gas: u64 = info.used_gas.unique_saturated_into();
-
             Ok(PostDispatchInfo {
                 actual_weight: Some(T::GasToWeight::convert(used_gas)),
                 pays_fee: Pays::Yes,
@@ -429,17 +637,22 @@ pub mod module {
 
             let info = Runner::<T>::call(from, from, target, input, value, gas_limit, storage_limit, T::config())?;
 
+            let used_gas: u64 = info.used_gas.unique_saturated_into();
+
             if info.exit_reason.is_succeed() {
-                Pallet::<T>::deposit_event(Event::<T>::Executed(target));
+                Pallet::<T>::deposit_event(Event::<T>::Executed(from, target, used_gas));
             } else {
-                Pallet::<T>::deposit_event(Event::<T>::ExecutedFailed(target, info.exit_reason, info.output));
+                Pallet::<T>::deposit_event(Event::<T>::ExecutedFailed(
+                    from,
+                    target,
+                    info.exit_reason,
+                    info.output,
+                    used_gas,
+                ));
             }
 
-            let used_gas: u64 = info.used_gas.unique_saturated_into();
-
             #[cfg(not(feature = "with-ethereum-compatibility"))]
             {
-                use sp_runtime::traits::Zero;
                 let refund_gas = gas_limit.saturating_sub(used_gas);
                 if !refund_gas.is_zero() {
                     // ignore the result to continue. if it fails, just the user will not
@@ -473,14 +686,20 @@ pub mod module {
 
             let info = Runner::<T>::create(source, init, value, gas_limit, storage_limit, T::config())?;
 
+            let used_gas: u64 = info.used_gas.unique_saturated_into();
+
             if info.exit_reason.is_succeed() {
-                Pallet::<T>::deposit_event(Event::<T>::Created(info.address));
+                Pallet::<T>::deposit_event(Event::<T>::Created(source, info.address, used_gas));
             } else {
-                Pallet::<T>::deposit_event(Event::<T>::CreatedFailed(info.address, info.exit_reason, info.output));
+                Pallet::<T>::deposit_event(Event::<T>::CreatedFailed(
+                    source,
+                    info.address,
+                    info.exit_reason,
+                    info.output,
+                    used_gas,
+                ));
             }
 
-            let used_gas: u64 = info.used_gas.unique_saturated_into();
-
             Ok(PostDispatchInfo {
                 actual_weight: Some(T::GasToWeight::convert(used_gas)),
                 pays_fee: Pays::Yes,
@@ -502,17 +721,166 @@ pub mod module {
 
             let info = Runner::<T>::create2(source, init, salt, value, gas_limit, storage_limit, T::config())?;
 
+            let used_gas: u64 = info.used_gas.unique_saturated_into();
+
             if info.exit_reason.is_succeed() {
-                Pallet::<T>::deposit_event(Event::<T>::Created(info.address));
+                Pallet::<T>::deposit_event(Event::<T>::Created(source, info.address, used_gas));
             } else {
-                Pallet::<T>::deposit_event(Event::<T>::CreatedFailed(info.address, info.exit_reason, info.output));
+                Pallet::<T>::deposit_event(Event::<T>::CreatedFailed(
+                    source,
+                    info.address,
+                    info.exit_reason,
+                    info.output,
+                    used_gas,
+                ));
             }
 
+            Ok(PostDispatchInfo {
+                actual_weight: Some(T::GasToWeight::convert(used_gas)),
+                pays_fee: Pays::Yes,
+            })
+        }
+
+        /// Issue an EVM call operation paying the gas at the base fee plus
+        /// the priority fee, up to `max_fee_per_gas`, with the addresses and
+        /// storage slots of `access_list` warmed up. This is similar to an
+        /// EIP-1559 message call transaction in Ethereum.
+        ///
+        /// The fee of the used gas is paid instead of the transaction fee. The
+        /// base fee is burned and the priority fee goes to the block author.
+        #[pallet::weight(T::GasToWeight::convert(*gas_limit))]
+        #[transactional]
+        pub fn dynamic_fee_call(
+            origin: OriginFor<T>,
+            target: EvmAddress,
+            input: Vec<u8>,
+            value: BalanceOf<T>,
+            gas_limit: u64,
+            storage_limit: u32,
+            max_fee_per_gas: BalanceOf<T>,
+            max_priority_fee_per_gas: BalanceOf<T>,
+            access_list: AccessList,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let source = T::AddressMapping::get_or_create_evm_address(&who);
+
+            let gas_price = Self::effective_gas_price(max_fee_per_gas, max_priority_fee_per_gas)?;
+            let fee = Self::withdraw_gas_fee(&who, gas_price, gas_limit)?;
+
+            let info = Runner::<T>::traced_call(
+                source,
+                source,
+                target,
+                input,
+                value,
+                gas_limit,
+                storage_limit,
+                gas_price,
+                access_list,
+                T::config(),
+                None,
+            )?;
+
             let used_gas: u64 = info.used_gas.unique_saturated_into();
 
+            if info.exit_reason.is_succeed() {
+                Pallet::<T>::deposit_event(Event::<T>::Executed(source, target, used_gas));
+            } else {
+                Pallet::<T>::deposit_event(Event::<T>::ExecutedFailed(
+                    source,
+                    target,
+                    info.exit_reason,
+                    info.output,
+                    used_gas,
+                ));
+            }
+
+            Self::settle_gas_fee(&who, fee, gas_price, gas_limit, used_gas);
+
             Ok(PostDispatchInfo {
                 actual_weight: Some(T::GasToWeight::convert(used_gas)),
-                pays_fee: Pays::Yes,
+                pays_fee: Pays::No,
+            })
+        }
+
+        /// Issue an EVM create operation paying the gas at the base fee plus
+        /// the priority fee, up to `max_fee_per_gas`, with the addresses and
+        /// storage slots of `access_list` warmed up. This is similar to an
+        /// EIP-1559 contract creation transaction in Ethereum.
+        ///
+        /// The fee of the used gas is paid instead of the transaction fee. The
+        /// base fee is burned and the priority fee goes to the block author.
+        #[pallet::weight(T::GasToWeight::convert(*gas_limit))]
+        #[transactional]
+        pub fn dynamic_fee_create(
+            origin: OriginFor<T>,
+            init: Vec<u8>,
+            value: BalanceOf<T>,
+            gas_limit: u64,
+            storage_limit: u32,
+            max_fee_per_gas: BalanceOf<T>,
+            max_priority_fee_per_gas: BalanceOf<T>,
+            access_list: AccessList,
+        ) -> DispatchResultWithPostInfo {
+            let who = ensure_signed(origin)?;
+            let source = T::AddressMapping::get_or_create_evm_address(&who);
+
+            let gas_price = Self::effective_gas_price(max_fee_per_gas, max_priority_fee_per_gas)?;
+            let fee = Self::withdraw_gas_fee(&who, gas_price, gas_limit)?;
+
+            let info = Runner::<T>::traced_create(
+                source,
+                init,
+                None,
+                value,
+                gas_limit,
+                storage_limit,
+                gas_price,
+                access_list,
+                T::config(),
+                None,
+            )?;
+
+            let used_gas: u64 = info.used_gas.unique_saturated_into();
+
+            if info.exit_reason.is_succeed() {
+                Pallet::<T>::deposit_event(Event::<T>::Created(source, info.address, used_gas));
+            } else {
+                Pallet::<T>::deposit_event(Event::<T>::CreatedFailed(
+                    source,
+                    info.address,
+                    info.exit_reason,
+                    info.output,
+                    used_gas,
+                ));
+            }
+
+            Self::settle_gas_fee(&who, fee, gas_price, gas_limit, used_gas);
+
+            Ok(PostDispatchInfo {
+                actual_weight: Some(T::GasToWeight::convert(used_gas)),
+                pays_fee: Pays::No,
+            })
+        }
+
+        /// Apply a RLP encoded, signed legacy Ethereum transaction.
+        ///
+        /// The sender is recovered from the signature, and pays
+        /// `gas_limit * gas_price` and the length fee upfront, with the unused
+        /// gas refunded. The gas price above the base fee goes to the block
+        /// author. The storage limit is the storage the sender can afford. An
+        /// execution failing with an error still consumes the nonce and pays
+        /// the whole gas limit.
+        #[pallet::weight(Pallet::<T>::eth_transaction_weight(transaction))]
+        #[transactional]
+        pub fn eth_transact(origin: OriginFor<T>, transaction: Vec<u8>) -> DispatchResultWithPostInfo {
+            ensure_none(origin)?;
+
+            let used_gas = Self::apply_eth_transaction(&transaction, None)?;
+
+            Ok(PostDispatchInfo {
+                actual_weight: Some(T::GasToWeight::convert(used_gas)),
+                pays_fee: Pays::No,
             })
         }
 
@@ -535,14 +903,20 @@ pub mod module {
 
             NetworkContractIndex::<T>::mutate(|v| *v = v.saturating_add(One::one()));
 
+            let used_gas: u64 = info.used_gas.unique_saturated_into();
+
             if info.exit_reason.is_succeed() {
-                Pallet::<T>::deposit_event(Event::<T>::Created(info.address));
+                Pallet::<T>::deposit_event(Event::<T>::Created(source, info.address, used_gas));
             } else {
-                Pallet::<T>::deposit_event(Event::<T>::CreatedFailed(info.address, info.exit_reason, info.output));
+                Pallet::<T>::deposit_event(Event::<T>::CreatedFailed(
+                    source,
+                    info.address,
+                    info.exit_reason,
+                    info.output,
+                    used_gas,
+                ));
             }
 
-            let used_gas: u64 = info.used_gas.unique_saturated_into();
-
             Ok(PostDispatchInfo {
                 actual_weight: Some(T::GasToWeight::convert(used_gas)),
                 pays_fee: Pays::Yes,
@@ -636,9 +1010,26 @@ pub mod module {
         #[transactional]
         pub fn set_code(origin: OriginFor<T>, contract: EvmAddress, code: Vec<u8>) -> DispatchResultWithPostInfo {
             let root_or_signed = Self::ensure_root_or_signed(origin)?;
-            Self::do_set_code(root_or_signed, contract, code)?;
+            let (old_code_hash, new_code_hash) = Self::do_set_code(root_or_signed, contract, code)?;
 
-            Pallet::<T>::deposit_event(Event::<T>::ContractSetCode(contract));
+            Pallet::<T>::deposit_event(Event::<T>::ContractSetCode(contract, old_code_hash, new_code_hash));
+
+            Ok(().into())
+        }
+
+        /// Restore the code `code_hash` from the code history of `contract`,
+        /// the current code takes its place in the history.
+        #[pallet::weight(<T as Config>::WeightInfo::rollback_code())]
+        #[transactional]
+        pub fn rollback_code(
+            origin: OriginFor<T>,
+            contract: EvmAddress,
+            code_hash: H256,
+        ) -> DispatchResultWithPostInfo {
+            let root_or_signed = Self::ensure_root_or_signed(origin)?;
+            let old_code_hash = Self::do_rollback_code(root_or_signed, contract, code_hash)?;
+
+            Pallet::<T>::deposit_event(Event::<T>::ContractCodeRolledBack(contract, old_code_hash, code_hash));
 
             Ok(().into())
         }
@@ -654,6 +1045,29 @@ pub mod module {
 
             Ok(().into())
         }
+
+        /// Enable or disable the precompile at `address`. Calls to a disabled
+        /// precompile fail.
+        #[pallet::weight(<T as Config>::WeightInfo::set_precompile_enabled())]
+        #[transactional]
+        pub fn set_precompile_enabled(
+            origin: OriginFor<T>,
+            address: EvmAddress,
+            enabled: bool,
+        ) -> DispatchResultWithPostInfo {
+            T::PrecompileOrigin::ensure_origin(origin)?;
+            ensure!(T::Precompiles::is_precompile(address), Error::<T>::PrecompileNotFound);
+
+            if enabled {
+                DisabledPrecompiles::<T>::remove(address);
+                Pallet::<T>::deposit_event(Event::<T>::PrecompileEnabled(address));
+            } else {
+                DisabledPrecompiles::<T>::insert(address, true);
+                Pallet::<T>::deposit_event(Event::<T>::PrecompileDisabled(address));
+            }
+
+            Ok(().into())
+        }
     }
 }
 
@@ -668,24 +1082,69 @@ impl<T: Config> Pallet<T> {
             ..
         }) = Self::accounts(address)
         {
-            CodeInfos::<T>::mutate_exists(&contract_info.code_hash, |maybe_code_info| {
-                if let Some(code_info) = maybe_code_info.as_mut() {
-                    size = code_info.code_size;
-                    code_info.ref_count = code_info.ref_count.saturating_sub(1);
-                    if code_info.ref_count == 0 {
-                        Codes::<T>::remove(&contract_info.code_hash);
-                        *maybe_code_info = None;
-                    }
-                }
-            });
+            size = Self::dec_code_ref(&contract_info.code_hash);
+            Self::remove_code_history(address);
+
+            // the storage is removed in `on_idle`
+            ContractsToRemove::<T>::insert(address, contract_info.maintainer);
         }
 
         Accounts::<T>::remove(address);
-        AccountStorages::<T>::remove_prefix(address, None);
 
         Ok(size)
     }
 
+    /// Remove the storage of the removed contracts within `limit` weight,
+    /// the storage deposit still reserved by the removed entries is released
+    /// to the maintainer of the contract, and the remaining deposit once all
+    /// its storage is removed. Returns the used weight.
+    fn remove_contracts_storage(limit: Weight, db_weight: RuntimeDbWeight) -> Weight {
+        // removing the queued contract and releasing its deposit
+        let contract_weight = db_weight.reads_writes(4, 4);
+        let entry_weight = db_weight.writes(1);
+
+        let mut weight = db_weight.reads(1);
+        for (contract, maintainer) in ContractsToRemove::<T>::iter() {
+            let available = limit.saturating_sub(weight).saturating_sub(contract_weight);
+            let max_entries = available / entry_weight.max(1);
+            if max_entries.is_zero() {
+                break;
+            }
+
+            let (removed, all_removed) =
+                match AccountStorages::<T>::remove_prefix(contract, Some(max_entries.unique_saturated_into())) {
+                    sp_io::KillStorageResult::AllRemoved(removed) => (removed, true),
+                    sp_io::KillStorageResult::SomeRemaining(removed) => (removed, false),
+                };
+            weight = weight
+                .saturating_add(contract_weight)
+                .saturating_add(entry_weight.saturating_mul(removed.into()));
+
+            let contract_account_id = T::AddressMapping::get_account_id(&contract);
+            let deposit = if all_removed {
+                T::Currency::reserved_balance(&contract_account_id)
+            } else {
+                T::StorageDepositPerByte::get().saturating_mul(removed.saturating_mul(STORAGE_SIZE).into())
+            };
+            // the maintainer could be a new account so we need to do unreserve/transfer
+            let released = deposit.saturating_sub(T::Currency::unreserve(&contract_account_id, deposit));
+            let _ = T::Currency::transfer(
+                &contract_account_id,
+                &T::AddressMapping::get_account_id(&maintainer),
+                released,
+                ExistenceRequirement::AllowDeath,
+            );
+
+            if !all_removed {
+                break;
+            }
+            ContractsToRemove::<T>::remove(contract);
+            Pallet::<T>::deposit_event(Event::<T>::ContractStorageRemoved(contract, maintainer));
+        }
+
+        weight
+    }
+
     /// Get the account basic in EVM format.
     pub fn account_basic(address: &EvmAddress) -> Account {
         let account_id = T::AddressMapping::get_account_id(address);
@@ -717,6 +1176,504 @@ impl<T: Config> Pallet<T> {
         Self::codes(&Self::code_hash_at_address(address))
     }
 
+    /// Run `f` on the state with the accounts of `state_override`
+    /// overridden, reverting every change after.
+    pub fn with_state_override<R>(
+        state_override: Vec<(EvmAddress, AccountOverride)>,
+        f: impl FnOnce() -> Result<R, DispatchError>,
+    ) -> Result<R, DispatchError> {
+        frame_support::storage::with_transaction(|| {
+            for (address, account) in state_override {
+                if let Err(e) = Self::override_account(address, account) {
+                    return TransactionOutcome::Rollback(Err(e));
+                }
+            }
+            TransactionOutcome::Rollback(f())
+        })
+    }
+
+    fn override_account(address: EvmAddress, account: AccountOverride) -> DispatchResult {
+        if let Some(balance) = account.balance {
+            let balance = BalanceOf::<T>::try_from(balance).map_err(|_| Error::<T>::NumOutOfBound)?;
+            T::Currency::make_free_balance_be(&T::AddressMapping::get_account_id(&address), balance);
+        }
+
+        if let Some(nonce) = account.nonce {
+            let nonce = T::Index::from(nonce);
+            Accounts::<T>::mutate(address, |maybe_account_info| match maybe_account_info.as_mut() {
+                Some(account_info) => account_info.nonce = nonce,
+                None => *maybe_account_info = Some(EvmAccountInfo::<T>::new(nonce, None)),
+            });
+        }
+
+        if let Some(code) = account.code {
+            let maintainer = match Self::accounts(address) {
+                Some(EvmAccountInfo {
+                    contract_info: Some(contract_info),
+                    ..
+                }) => contract_info.maintainer,
+                _ => address,
+            };
+            Self::on_contract_initialization(&address, &maintainer, code).map_err(exit_error_to_dispatch_error)?;
+            // the overridden contract is callable by anyone
+            Accounts::<T>::mutate(address, |maybe_account_info| {
+                if let Some(EvmAccountInfo {
+                    contract_info: Some(contract_info),
+                    ..
+                }) = maybe_account_info.as_mut()
+                {
+                    contract_info.deployed = true;
+                }
+            });
+        }
+
+        if let Some(state) = account.state {
+            AccountStorages::<T>::remove_prefix(address, None);
+            for (index, value) in state.into_iter().filter(|(_, value)| *value != H256::default()) {
+                AccountStorages::<T>::insert(address, index, value);
+            }
+        }
+
+        for (index, value) in account.state_diff {
+            if value == H256::default() {
+                AccountStorages::<T>::remove(address, index);
+            } else {
+                AccountStorages::<T>::insert(address, index, value);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Decode a raw Ethereum transaction and recover its sender.
+    pub fn check_eth_transaction(transaction: &[u8]) -> Result<(LegacyTransaction, EvmAddress), Error<T>> {
+        let tx = LegacyTransaction::decode(transaction).map_err(|_| Error::<T>::InvalidEthTransaction)?;
+        if let Some(chain_id) = tx.chain_id() {
+            ensure!(chain_id == T::ChainId::get(), Error::<T>::InvalidChainId);
+        }
+        ensure!(tx.gas_price >= Self::base_fee_per_gas(), Error::<T>::GasPriceTooLow);
+        let source = tx.recover_signer().ok_or(Error::<T>::InvalidSignature)?;
+        Ok((tx, source))
+    }
+
+    /// Dispatch weight of a raw Ethereum transaction, the weight of its gas
+    /// limit.
+    pub fn eth_transaction_weight(transaction: &[u8]) -> Weight {
+        LegacyTransaction::decode(transaction).map_or(0, |tx| {
+            T::GasToWeight::convert(tx.gas_limit.min(U256::from(u64::MAX)).low_u64())
+        })
+    }
+
+    /// Check that the sender of a raw Ethereum transaction can pay for it,
+    /// with the same rules as its dispatch: the gas limit and value are in
+    /// range, the fee of the gas limit and of the length of the transaction
+    /// can be withdrawn keeping the sender alive, and the sender can afford
+    /// the fee and the value. Returns the sender account, the gas limit, the
+    /// value and the fee.
+    fn check_eth_transaction_payment(
+        transaction: &[u8],
+        tx: &LegacyTransaction,
+        source: &EvmAddress,
+    ) -> Result<(T::AccountId, u64, BalanceOf<T>, BalanceOf<T>), Error<T>> {
+        let gas_limit: u64 = tx.gas_limit.try_into().map_err(|_| Error::<T>::NumOutOfBound)?;
+        let value = u128::try_from(tx.value)
+            .ok()
+            .and_then(|value| BalanceOf::<T>::try_from(value).ok())
+            .ok_or(Error::<T>::NumOutOfBound)?;
+        let length_fee = T::TransactionByteFee::get().saturating_mul((transaction.len() as u32).into());
+        let fee = Self::eth_transaction_fee(tx.gas_price, gas_limit)
+            .and_then(|fee| fee.checked_add(&length_fee))
+            .ok_or(Error::<T>::NumOutOfBound)?;
+
+        let who = T::AddressMapping::get_account_id(source);
+        let free_balance = T::Currency::free_balance(&who);
+        let new_balance = free_balance.checked_sub(&fee).ok_or(Error::<T>::ChargeFeeFailed)?;
+        ensure!(
+            new_balance >= T::Currency::minimum_balance() && new_balance >= value,
+            Error::<T>::ChargeFeeFailed
+        );
+        T::Currency::ensure_can_withdraw(&who, fee, WithdrawReasons::FEE, new_balance)
+            .map_err(|_| Error::<T>::ChargeFeeFailed)?;
+
+        Ok((who, gas_limit, value, fee))
+    }
+
+    /// Apply a raw Ethereum transaction, reporting its execution to
+    /// `tracer`. Returns the used gas.
+    ///
+    /// Once the transaction passed the checks of its validation, the nonce
+    /// of the sender is consumed and the fee is charged: an execution that
+    /// fails with an error is reverted and charged its whole gas limit.
+    fn apply_eth_transaction(transaction: &[u8], tracer: Option<Rc<RefCell<Tracer>>>) -> Result<u64, DispatchError> {
+        let (tx, source) = Self::check_eth_transaction(transaction)?;
+        ensure!(tx.nonce == Self::account_basic(&source).nonce, Error::<T>::InvalidNonce);
+        let (who, gas_limit, value, fee) = Self::check_eth_transaction_payment(transaction, &tx, &source)?;
+
+        let fee = T::Currency::withdraw(&who, fee, WithdrawReasons::FEE, ExistenceRequirement::KeepAlive)
+            .map_err(|_| Error::<T>::ChargeFeeFailed)?;
+        let storage_limit = Self::eth_storage_limit(&who, value);
+
+        Pallet::<T>::deposit_event(Event::<T>::EthTransactionApplied(
+            H256::from(sp_io::hashing::keccak_256(transaction)),
+            source,
+        ));
+
+        let result = frame_support::storage::with_transaction(|| {
+            match Self::execute_eth_transaction(tx.clone(), source, value, gas_limit, storage_limit, tracer) {
+                Ok(used_gas) => TransactionOutcome::Commit(Ok(used_gas)),
+                Err(e) => TransactionOutcome::Rollback(Err(e)),
+            }
+        });
+
+        let used_gas = result.unwrap_or_else(|e| {
+            log::debug!(
+                target: "evm",
+                "eth transaction of {:?} failed: {:?}",
+                source,
+                e
+            );
+
+            Handler::<T>::inc_nonce(source);
+
+            let exit_reason = ExitReason::Error(ExitError::Other(Into::<&'static str>::into(e).into()));
+            match tx.action {
+                TransactionAction::Call(target) => Pallet::<T>::deposit_event(Event::<T>::ExecutedFailed(
+                    source,
+                    target,
+                    exit_reason,
+                    Vec::new(),
+                    gas_limit,
+                )),
+                TransactionAction::Create => Pallet::<T>::deposit_event(Event::<T>::CreatedFailed(
+                    source,
+                    Handler::<T>::create_address(evm::CreateScheme::Legacy { caller: source }).unwrap_or_default(),
+                    exit_reason,
+                    Vec::new(),
+                    gas_limit,
+                )),
+            }
+            gas_limit
+        });
+
+        Self::settle_gas_fee(&who, fee, tx.gas_price, gas_limit, used_gas);
+
+        Ok(used_gas)
+    }
+
+    /// Execute an Ethereum transaction whose fee is paid, returns the used
+    /// gas.
+    fn execute_eth_transaction(
+        tx: LegacyTransaction,
+        source: EvmAddress,
+        value: BalanceOf<T>,
+        gas_limit: u64,
+        storage_limit: u32,
+        tracer: Option<Rc<RefCell<Tracer>>>,
+    ) -> Result<u64, DispatchError> {
+        let used_gas: u64 = match tx.action {
+            TransactionAction::Call(target) => {
+                let info = Runner::<T>::traced_call(
+                    source,
+                    source,
+                    target,
+                    tx.input,
+                    value,
+                    gas_limit,
+                    storage_limit,
+                    tx.gas_price,
+                    Vec::new(),
+                    T::config(),
+                    tracer,
+                )?;
+
+                let used_gas: u64 = info.used_gas.unique_saturated_into();
+
+                if info.exit_reason.is_succeed() {
+                    Pallet::<T>::deposit_event(Event::<T>::Executed(source, target, used_gas));
+                } else {
+                    Pallet::<T>::deposit_event(Event::<T>::ExecutedFailed(
+                        source,
+                        target,
+                        info.exit_reason,
+                        info.output,
+                        used_gas,
+                    ));
+                }
+                used_gas
+            }
+            TransactionAction::Create => {
+                let info = Runner::<T>::traced_create(
+                    source,
+                    tx.input,
+                    None,
+                    value,
+                    gas_limit,
+                    storage_limit,
+                    tx.gas_price,
+                    Vec::new(),
+                    T::config(),
+                    tracer,
+                )?;
+
+                let used_gas: u64 = info.used_gas.unique_saturated_into();
+
+                if info.exit_reason.is_succeed() {
+                    Pallet::<T>::deposit_event(Event::<T>::Created(source, info.address, used_gas));
+                } else {
+                    Pallet::<T>::deposit_event(Event::<T>::CreatedFailed(
+                        source,
+                        info.address,
+                        info.exit_reason,
+                        info.output,
+                        used_gas,
+                    ));
+                }
+                used_gas
+            }
+        };
+
+        Ok(used_gas)
+    }
+
+    /// Trace a call to `target`, or a create without `target`, from
+    /// `source`.
+    pub fn trace(
+        source: EvmAddress,
+        target: Option<EvmAddress>,
+        input: Vec<u8>,
+        value: BalanceOf<T>,
+        gas_limit: u64,
+        storage_limit: u32,
+        config: TracerConfig,
+    ) -> Result<Trace, DispatchError> {
+        Self::with_tracer(config, |tracer| match target {
+            Some(target) => Runner::<T>::traced_call(
+                source,
+                source,
+                target,
+                input,
+                value,
+                gas_limit,
+                storage_limit,
+                Self::base_fee_per_gas(),
+                Vec::new(),
+                T::config(),
+                Some(tracer),
+            )
+            .map(|_| ()),
+            None => Runner::<T>::traced_create(
+                source,
+                input,
+                None,
+                value,
+                gas_limit,
+                storage_limit,
+                Self::base_fee_per_gas(),
+                Vec::new(),
+                T::config(),
+                Some(tracer),
+            )
+            .map(|_| ()),
+        })
+    }
+
+    /// Trace the EVM executions of `f`, including the executions nested in
+    /// other dispatches, like the precompiles and the bridge, which report to
+    /// the tracer of the executions they are nested in, or to this one.
+    pub fn trace_executions(
+        config: TracerConfig,
+        f: impl FnOnce() -> Result<(), DispatchError>,
+    ) -> Result<Trace, DispatchError> {
+        Self::with_tracer(config, |_| f())
+    }
+
+    fn with_tracer(
+        config: TracerConfig,
+        f: impl FnOnce(Rc<RefCell<Tracer>>) -> Result<(), DispatchError>,
+    ) -> Result<Trace, DispatchError> {
+        let tracer = Rc::new(RefCell::new(Tracer::new(config)));
+        runner::tracer::using(&tracer, || f(tracer.clone()))?;
+        Rc::try_unwrap(tracer)
+            .ok()
+            .and_then(|tracer| tracer.into_inner().finish())
+            .ok_or(DispatchError::Other("No EVM execution"))
+    }
+
+    /// Validate a raw Ethereum transaction for the transaction pool, returns
+    /// the transaction, its sender and the current nonce of the sender.
+    fn validate_eth_transaction(
+        transaction: &[u8],
+    ) -> Result<(LegacyTransaction, EvmAddress, U256), TransactionValidityError> {
+        let (tx, source) = Self::check_eth_transaction(transaction).map_err(|e| match e {
+            Error::<T>::InvalidChainId | Error::<T>::InvalidSignature => InvalidTransaction::BadProof,
+            Error::<T>::GasPriceTooLow => InvalidTransaction::Payment,
+            _ => InvalidTransaction::Call,
+        })?;
+
+        let nonce = Self::account_basic(&source).nonce;
+        if tx.nonce < nonce {
+            return Err(InvalidTransaction::Stale.into());
+        }
+
+        Self::check_eth_transaction_payment(transaction, &tx, &source).map_err(|_| InvalidTransaction::Payment)?;
+
+        Ok((tx, source, nonce))
+    }
+
+    /// The base fee per gas of the current block, the minimum gas price
+    /// times the base fee multiplier.
+    pub fn base_fee_per_gas() -> U256 {
+        let minimum_gas_price = UniqueSaturatedInto::<u128>::unique_saturated_into(T::MinimumGasPrice::get());
+        U256::from(Self::base_fee_multiplier().saturating_mul_int(minimum_gas_price))
+    }
+
+    /// The author of the current block, if known.
+    pub fn block_author() -> Option<T::AccountId> {
+        let digest = frame_system::Pallet::<T>::digest();
+        let pre_runtime_digests = digest.logs.iter().filter_map(|d| d.as_pre_runtime());
+        T::FindAuthor::find_author(pre_runtime_digests)
+    }
+
+    /// The EVM address of the author of the current block, without claiming a
+    /// default address for the author.
+    pub fn block_coinbase() -> EvmAddress {
+        Self::block_author()
+            .map(|author| {
+                T::AddressMapping::get_evm_address(&author)
+                    .unwrap_or_else(|| T::AddressMapping::get_default_evm_address(&author))
+            })
+            .unwrap_or_default()
+    }
+
+    /// Gas price paid by a transaction with `max_fee_per_gas` and
+    /// `max_priority_fee_per_gas`: the base fee plus the priority fee, up to
+    /// the max fee (EIP-1559).
+    fn effective_gas_price(
+        max_fee_per_gas: BalanceOf<T>,
+        max_priority_fee_per_gas: BalanceOf<T>,
+    ) -> Result<U256, Error<T>> {
+        let max_fee_per_gas = U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(max_fee_per_gas));
+        let max_priority_fee_per_gas = U256::from(UniqueSaturatedInto::<u128>::unique_saturated_into(
+            max_priority_fee_per_gas,
+        ));
+        let base_fee_per_gas = Self::base_fee_per_gas();
+
+        ensure!(max_fee_per_gas >= base_fee_per_gas, Error::<T>::GasPriceTooLow);
+        ensure!(
+            max_priority_fee_per_gas <= max_fee_per_gas,
+            Error::<T>::PriorityFeeTooHigh
+        );
+        Ok(max_fee_per_gas.min(base_fee_per_gas.saturating_add(max_priority_fee_per_gas)))
+    }
+
+    /// Withdraw the fee of `gas_limit` at `gas_price` from `who`.
+    fn withdraw_gas_fee(
+        who: &T::AccountId,
+        gas_price: U256,
+        gas_limit: u64,
+    ) -> Result<NegativeImbalanceOf<T>, DispatchError> {
+        let max_fee = Self::eth_transaction_fee(gas_price, gas_limit).ok_or(Error::<T>::NumOutOfBound)?;
+        T::Currency::withdraw(who, max_fee, WithdrawReasons::FEE, ExistenceRequirement::KeepAlive)
+            .map_err(|_| Error::<T>::ChargeFeeFailed.into())
+    }
+
+    /// Settle the `fee` withdrawn for `gas_limit` at `gas_price`: the unused
+    /// gas is refunded to `who`, the tip above the base fee of the used gas
+    /// goes to the block author, and the base fee is burned.
+    fn settle_gas_fee(who: &T::AccountId, fee: NegativeImbalanceOf<T>, gas_price: U256, gas_limit: u64, used_gas: u64) {
+        let refund = Self::eth_transaction_fee(gas_price, gas_limit.saturating_sub(used_gas))
+            .expect("refund is lower than the withdrawn fee; qed");
+        let (refund, fee) = fee.split(refund);
+        T::Currency::resolve_creating(who, refund);
+
+        if let Some(author) = Self::block_author() {
+            let tip = Self::eth_transaction_fee(gas_price.saturating_sub(Self::base_fee_per_gas()), used_gas)
+                .expect("tip is lower than the withdrawn fee; qed");
+            let (tip, _burned) = fee.split(tip);
+            T::Currency::resolve_creating(&author, tip);
+        }
+    }
+
+    /// Fee of `gas` at `gas_price`, `None` on overflow.
+    fn eth_transaction_fee(gas_price: U256, gas: u64) -> Option<BalanceOf<T>> {
+        let fee = gas_price.checked_mul(U256::from(gas))?;
+        BalanceOf::<T>::try_from(u128::try_from(fee).ok()?).ok()
+    }
+
+    /// Storage limit of an Ethereum transaction, which has none: the storage
+    /// `who` can afford after the transfer of `value`.
+    fn eth_storage_limit(who: &T::AccountId, value: BalanceOf<T>) -> u32 {
+        let deposit_per_byte = T::StorageDepositPerByte::get();
+        if deposit_per_byte.is_zero() {
+            return u32::MAX;
+        }
+        let free = T::Currency::free_balance(who).saturating_sub(value);
+        (free / deposit_per_byte).unique_saturated_into()
+    }
+
+    /// Collect the receipts of the extrinsics from the EVM events of a block.
+    ///
+    /// `events` are the EVM events in block order, with the index of the
+    /// extrinsic that emitted them. Logs belong to the extrinsic that emitted
+    /// them, the sender, target and created contract are taken from its first
+    /// execution. Extrinsics applying an Ethereum transaction carry its hash.
+    pub fn extrinsic_receipts(events: impl IntoIterator<Item = (u32, Event<T>)>) -> Vec<ExtrinsicReceipt> {
+        let mut receipts: Vec<ExtrinsicReceipt> = Vec::new();
+
+        for (index, event) in events {
+            let transaction_hash = match &event {
+                Event::EthTransactionApplied(hash, _) => Some(*hash),
+                _ => None,
+            };
+            let (execution, used_gas, succeed, log) = match event {
+                Event::Log(log) => (None, 0, true, Some(log)),
+                Event::EthTransactionApplied(..) => (None, 0, true, None),
+                Event::Created(from, contract, used_gas) => (Some((from, None, Some(contract))), used_gas, true, None),
+                Event::CreatedFailed(from, contract, _, _, used_gas) => {
+                    (Some((from, None, Some(contract))), used_gas, false, None)
+                }
+                Event::Executed(from, contract, used_gas) => (Some((from, Some(contract), None)), used_gas, true, None),
+                Event::ExecutedFailed(from, contract, _, _, used_gas) => {
+                    (Some((from, Some(contract), None)), used_gas, false, None)
+                }
+                _ => continue,
+            };
+
+            if receipts.last().map_or(true, |receipt| receipt.extrinsic_index != index) {
+                receipts.push(ExtrinsicReceipt {
+                    extrinsic_index: index,
+                    transaction_hash: None,
+                    from: Default::default(),
+                    to: None,
+                    contract_address: None,
+                    used_gas: 0,
+                    succeed: true,
+                    logs: Vec::new(),
+                });
+            }
+            let receipt = receipts
+                .last_mut()
+                .expect("receipt of the extrinsic is pushed above; qed");
+
+            if let Some((from, to, contract_address)) = execution {
+                // every execution has either a target or a created contract
+                if receipt.to.is_none() && receipt.contract_address.is_none() {
+                    receipt.from = from;
+                    receipt.to = to;
+                    receipt.contract_address = contract_address;
+                }
+            }
+            if transaction_hash.is_some() {
+                receipt.transaction_hash = transaction_hash;
+            }
+            receipt.used_gas = receipt.used_gas.saturating_add(used_gas);
+            receipt.succeed = receipt.succeed && succeed;
+            receipt.logs.extend(log);
+        }
+
+        receipts
+    }
+
     /// Handler on new contract initialization.
     ///
     /// - Create new account for the contract.
@@ -794,7 +1751,7 @@ impl<T: Config> Pallet<T> {
     /// Mark contract as deployed
     ///
     /// If maintainer is provider then it will check maintainer
-    fn mark_deployed(contract: EvmAddress, maintainer: Option<EvmAddress>) -> DispatchResult {
+    pub fn mark_deployed(contract: EvmAddress, maintainer: Option<EvmAddress>) -> DispatchResult {
         Accounts::<T>::mutate(contract, |maybe_account_info| -> DispatchResult {
             if let Some(EvmAccountInfo {
                 contract_info: Some(contract_info),
@@ -813,24 +1770,26 @@ impl<T: Config> Pallet<T> {
         })
     }
 
-    fn do_set_code(root_or_signed: Either<(), T::AccountId>, contract: EvmAddress, code: Vec<u8>) -> DispatchResult {
-        Accounts::<T>::mutate(contract, |maybe_account_info| -> DispatchResult {
+    /// Set the code of `contract`, returns the old and the new code hashes.
+    fn do_set_code(
+        root_or_signed: Either<(), T::AccountId>,
+        contract: EvmAddress,
+        code: Vec<u8>,
+    ) -> Result<(H256, H256), DispatchError> {
+        Accounts::<T>::try_mutate(contract, |maybe_account_info| -> Result<(H256, H256), DispatchError> {
             let account_info = maybe_account_info.as_mut().ok_or(Error::<T>::ContractNotFound)?;
             let contract_info = account_info
                 .contract_info
-                .as_ref()
+                .as_mut()
                 .ok_or(Error::<T>::ContractNotFound)?;
 
-            if let Either::Right(signer) = root_or_signed {
-                let maintainer = T::AddressMapping::get_evm_address(&signer).ok_or(Error::<T>::AddressNotMapped)?;
-                ensure!(contract_info.maintainer == maintainer, Error::<T>::NoPermission);
-                ensure!(!contract_info.deployed, Error::<T>::ContractAlreadyDeployed);
-            }
+            let who = Self::ensure_maintainer(root_or_signed, contract_info)?;
 
             let code_size = code.len() as u32;
             let code_hash = code_hash(code.as_slice());
-            if code_hash == contract_info.code_hash {
-                return Ok(());
+            let old_code_hash = contract_info.code_hash;
+            if code_hash == old_code_hash {
+                return Ok((old_code_hash, code_hash));
             }
 
             ensure!(
@@ -852,10 +1811,116 @@ impl<T: Config> Pallet<T> {
                 }
             });
 
-            Ok(())
-        })?;
+            // the reference of the old code moves to the history
+            contract_info.code_hash = code_hash;
+            let old_entry = Self::replace_code_set_by(&contract, old_code_hash, who);
+            CodeHistory::<T>::mutate(contract, |history| {
+                history.push(old_entry);
 
-        Ok(())
+                let max_history = T::MaxCodeHistory::get() as usize;
+                if history.len() > max_history {
+                    for entry in history.drain(..history.len() - max_history) {
+                        Self::dec_code_ref(&entry.code_hash);
+                    }
+                }
+            });
+
+            Ok((old_code_hash, code_hash))
+        })
+    }
+
+    /// Restore the code `code_hash` from the code history of `contract`,
+    /// returns the replaced code hash.
+    fn do_rollback_code(
+        root_or_signed: Either<(), T::AccountId>,
+        contract: EvmAddress,
+        code_hash: H256,
+    ) -> Result<H256, DispatchError> {
+        Accounts::<T>::try_mutate(contract, |maybe_account_info| -> Result<H256, DispatchError> {
+            let account_info = maybe_account_info.as_mut().ok_or(Error::<T>::ContractNotFound)?;
+            let contract_info = account_info
+                .contract_info
+                .as_mut()
+                .ok_or(Error::<T>::ContractNotFound)?;
+
+            let who = Self::ensure_maintainer(root_or_signed, contract_info)?;
+
+            // the references of the two codes are swapped between the contract
+            // and the history, the ref counts stay the same
+            CodeHistory::<T>::try_mutate(contract, |history| -> Result<H256, DispatchError> {
+                let index = history
+                    .iter()
+                    .rposition(|entry| entry.code_hash == code_hash)
+                    .ok_or(Error::<T>::CodeNotInHistory)?;
+                history.remove(index);
+
+                let old_code_hash = contract_info.code_hash;
+                contract_info.code_hash = code_hash;
+                history.push(Self::replace_code_set_by(&contract, old_code_hash, who));
+
+                Ok(old_code_hash)
+            })
+        })
+    }
+
+    /// Record that `who` sets the code of `contract` in the current block,
+    /// returns the history entry of the replaced code `old_code_hash`.
+    fn replace_code_set_by(
+        contract: &EvmAddress,
+        old_code_hash: H256,
+        who: Option<T::AccountId>,
+    ) -> CodeHistoryEntry<T::AccountId, T::BlockNumber> {
+        let old_set_by = CodeSetBy::<T>::mutate(contract, |set_by| {
+            set_by.replace((who, frame_system::Pallet::<T>::block_number()))
+        });
+        let (who, block_number) = old_set_by.map_or((None, None), |(who, block_number)| (who, Some(block_number)));
+
+        CodeHistoryEntry {
+            code_hash: old_code_hash,
+            block_number,
+            who,
+        }
+    }
+
+    /// Ensure `root_or_signed` can change the code of the contract, returns
+    /// the signer.
+    fn ensure_maintainer(
+        root_or_signed: Either<(), T::AccountId>,
+        contract_info: &ContractInfo,
+    ) -> Result<Option<T::AccountId>, DispatchError> {
+        match root_or_signed {
+            Either::Left(_) => Ok(None),
+            Either::Right(signer) => {
+                let maintainer = T::AddressMapping::get_evm_address(&signer).ok_or(Error::<T>::AddressNotMapped)?;
+                ensure!(contract_info.maintainer == maintainer, Error::<T>::NoPermission);
+                ensure!(!contract_info.deployed, Error::<T>::ContractAlreadyDeployed);
+                Ok(Some(signer))
+            }
+        }
+    }
+
+    /// Deref code, and remove it if ref count is zero. Returns the code size.
+    fn dec_code_ref(code_hash: &H256) -> u32 {
+        let mut size = 0u32;
+        CodeInfos::<T>::mutate_exists(code_hash, |maybe_code_info| {
+            if let Some(code_info) = maybe_code_info.as_mut() {
+                size = code_info.code_size;
+                code_info.ref_count = code_info.ref_count.saturating_sub(1);
+                if code_info.ref_count == 0 {
+                    Codes::<T>::remove(code_hash);
+                    *maybe_code_info = None;
+                }
+            }
+        });
+        size
+    }
+
+    /// Deref the codes in the code history of `contract` and clear it.
+    fn remove_code_history(contract: &EvmAddress) {
+        CodeSetBy::<T>::remove(contract);
+        for entry in CodeHistory::<T>::take(contract) {
+            Self::dec_code_ref(&entry.code_hash);
+        }
     }
 
     fn do_selfdestruct(who: T::AccountId, maintainer: &EvmAddress, contract: EvmAddress) -> DispatchResult {
@@ -869,17 +1934,11 @@ impl<T: Config> Pallet<T> {
             ensure!(contract_info.maintainer == *maintainer, Error::<T>::NoPermission);
             ensure!(!contract_info.deployed, Error::<T>::ContractAlreadyDeployed);
 
-            AccountStorages::<T>::remove_prefix(contract, None);
+            // the storage is removed in `on_idle`
+            ContractsToRemove::<T>::insert(contract, contract_info.maintainer);
 
-            CodeInfos::<T>::mutate_exists(&contract_info.code_hash, |maybe_code_info| {
-                if let Some(code_info) = maybe_code_info.as_mut() {
-                    code_info.ref_count = code_info.ref_count.saturating_sub(1);
-                    if code_info.ref_count == 0 {
-                        Codes::<T>::remove(&contract_info.code_hash);
-                        *maybe_code_info = None;
-                    }
-                }
-            });
+            Self::dec_code_ref(&contract_info.code_hash);
+            Self::remove_code_history(&contract);
 
             let contract_account_id = T::AddressMapping::get_account_id(&contract);
             T::Currency::unreserve(
@@ -930,14 +1989,21 @@ impl<T: Config> EVMTrait<T::AccountId> for Pallet<T> {
             match result {
                 Ok(info) => match mode {
                     ExecutionMode::Execute => {
+                        let used_gas: u64 = info.used_gas.unique_saturated_into();
                         if info.exit_reason.is_succeed() {
-                            Pallet::<T>::deposit_event(Event::<T>::Executed(context.contract));
+                            Pallet::<T>::deposit_event(Event::<T>::Executed(
+                                context.sender,
+                                context.contract,
+                                used_gas,
+                            ));
                             TransactionOutcome::Commit(Ok(info))
                         } else {
                             Pallet::<T>::deposit_event(Event::<T>::ExecutedFailed(
+                                context.sender,
                                 context.contract,
                                 info.exit_reason.clone(),
                                 info.output.clone(),
+                                used_gas,
                             ));
                             TransactionOutcome::Rollback(Ok(info))
                         }
@@ -960,6 +2026,14 @@ impl<T: Config> EVMTrait<T::AccountId> for Pallet<T> {
     }
 }
 
+/// The logs of the `LOG` opcodes and of the precompiles are deposited as
+/// `Event::Log`, from which the receipts are built.
+impl<T: Config> EVMLog for Pallet<T> {
+    fn log(address: EvmAddress, topics: Vec<H256>, data: Vec<u8>) {
+        Pallet::<T>::deposit_event(Event::<T>::Log(Log { address, topics, data }));
+    }
+}
+
 impl<T: Config> EVMStateRentTrait<T::AccountId, BalanceOf<T>> for Pallet<T> {
     fn query_new_contract_extra_bytes() -> u32 {
         T::NewContractExtraBytes::get()
@@ -1005,6 +2079,28 @@ pub fn code_hash(code: &[u8]) -> H256 {
     H256::from_slice(Keccak256::digest(code).as_slice())
 }
 
+/// Convert an EVM exit error to a dispatch error, keeping its reason.
+fn exit_error_to_dispatch_error(e: ExitError) -> DispatchError {
+    DispatchError::Other(match e {
+        ExitError::StackUnderflow => "StackUnderflow",
+        ExitError::StackOverflow => "StackOverflow",
+        ExitError::InvalidJump => "InvalidJump",
+        ExitError::InvalidRange => "InvalidRange",
+        ExitError::DesignatedInvalid => "DesignatedInvalid",
+        ExitError::CallTooDeep => "CallTooDeep",
+        ExitError::CreateCollision => "CreateCollision",
+        ExitError::CreateContractLimit => "CreateContractLimit",
+        ExitError::InvalidCode => "InvalidCode",
+        ExitError::OutOfOffset => "OutOfOffset",
+        ExitError::OutOfGas => "OutOfGas",
+        ExitError::OutOfFund => "OutOfFund",
+        ExitError::PCUnderflow => "PCUnderflow",
+        ExitError::CreateEmpty => "CreateEmpty",
+        ExitError::Other(Cow::Borrowed(reason)) => reason,
+        ExitError::Other(Cow::Owned(_)) => "Other",
+    })
+}
+
 #[derive(Encode, Decode, Clone, Eq, PartialEq)]
 pub struct SetEvmOrigin<T: Config + Send + Sync>(PhantomData<T>);
 
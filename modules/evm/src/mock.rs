@@ -3,9 +3,9 @@
 use super::*;
 
 use frame_support::{construct_runtime, ord_parameter_types, parameter_types};
-use frame_system::EnsureSignedBy;
+use frame_system::{EnsureRoot, EnsureSignedBy};
 use orml_traits::parameter_type_with_key;
-use primitives::mocks::MockAddressMapping;
+use primitives::mocks::{MockAddressMapping, MockRandomness};
 use primitives::{Amount, BlockNumber, CurrencyId, TokenSymbol};
 use sp_core::{H160, H256};
 use sp_runtime::{
@@ -115,8 +115,28 @@ impl Convert<u64, u64> for GasToWeight {
     }
 }
 
+pub struct BaseFeeUpdate;
+
+impl Convert<FixedU128, FixedU128> for BaseFeeUpdate {
+    fn convert(multiplier: FixedU128) -> FixedU128 {
+        multiplier.saturating_mul(FixedU128::saturating_from_integer(2))
+    }
+}
+
+pub struct AuthorGiven;
+
+impl FindAuthor<AccountId32> for AuthorGiven {
+    fn find_author<'a, I>(_digests: I) -> Option<AccountId32>
+    where
+        I: 'a + IntoIterator<Item = (frame_support::ConsensusEngineId, &'a [u8])>,
+    {
+        Some(MockAddressMapping::get_account_id(&bob()))
+    }
+}
+
 parameter_types! {
     pub NetworkContractSource: H160 = alice();
+    pub const BlockGasLimit: u64 = 10_000_000;
 }
 
 ord_parameter_types! {
@@ -127,7 +147,10 @@ ord_parameter_types! {
     pub const DeveloperDeposit: u64 = 1000;
     pub const DeploymentFee: u64 = 200;
     pub const MaxCodeSize: u32 = 1000;
+    pub const MaxCodeHistory: u32 = 2;
     pub const ChainId: u64 = 1;
+    pub const MinimumGasPrice: u64 = 1;
+    pub const TransactionByteFee: u64 = 10;
 }
 
 impl Config for Test {
@@ -137,11 +160,18 @@ impl Config for Test {
     type NewContractExtraBytes = NewContractExtraBytes;
     type StorageDepositPerByte = StorageDepositPerByte;
     type MaxCodeSize = MaxCodeSize;
+    type MaxCodeHistory = MaxCodeHistory;
 
     type Event = Event;
     type Precompiles = ();
     type ChainId = ChainId;
+    type MinimumGasPrice = MinimumGasPrice;
+    type TransactionByteFee = TransactionByteFee;
     type GasToWeight = GasToWeight;
+    type BaseFeeUpdate = BaseFeeUpdate;
+    type FindAuthor = AuthorGiven;
+    type BlockGasLimit = BlockGasLimit;
+    type Randomness = MockRandomness;
     type ChargeTransactionPayment = ();
 
     type NetworkContractOrigin = EnsureSignedBy<NetworkContractAccount, AccountId32>;
@@ -149,6 +179,7 @@ impl Config for Test {
     type DeveloperDeposit = DeveloperDeposit;
     type DeploymentFee = DeploymentFee;
     type FreeDeploymentOrigin = EnsureSignedBy<CouncilAccount, AccountId32>;
+    type PrecompileOrigin = EnsureRoot<AccountId32>;
 
     type WeightInfo = ();
 }
@@ -163,7 +194,7 @@ construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic,
     {
         System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
-        EVM: evm_mod::{Pallet, Config<T>, Call, Storage, Event<T>},
+        EVM: evm_mod::{Pallet, Config<T>, Call, Storage, Event<T>, ValidateUnsigned},
         Tokens: orml_tokens::{Pallet, Storage, Event<T>},
         Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
         Currencies: orml_currencies::{Pallet, Call, Event<T>},
@@ -264,3 +295,165 @@ pub fn reserved_balance(address: H160) -> u64 {
 pub fn deploy_free(contract: H160) {
     let _ = EVM::deploy_free(Origin::signed(CouncilAccount::get()), contract);
 }
+
+/// A runtime like `Test`, with the standard Ethereum precompiles.
+pub mod with_precompiles {
+    use super::*;
+    use crate::precompiles::{
+        Blake2F, Bn128Add, Bn128Mul, Bn128Pairing, ECRecover, ECRecoverPublicKey, EvmPrecompiles, Identity, Modexp,
+        Ripemd160, Sha256, Sha3FIPS256, Sha3FIPS512,
+    };
+
+    impl frame_system::Config for Test {
+        type BaseCallFilter = frame_support::traits::Everything;
+        type BlockWeights = ();
+        type BlockLength = ();
+        type Origin = Origin;
+        type Call = Call;
+        type Index = u64;
+        type BlockNumber = u64;
+        type Hash = H256;
+        type Hashing = BlakeTwo256;
+        type AccountId = AccountId32;
+        type Lookup = IdentityLookup<Self::AccountId>;
+        type Header = Header;
+        type Event = Event;
+        type BlockHashCount = BlockHashCount;
+        type DbWeight = ();
+        type Version = ();
+        type PalletInfo = PalletInfo;
+        type AccountData = pallet_balances::AccountData<u64>;
+        type OnNewAccount = ();
+        type OnKilledAccount = ();
+        type SystemWeightInfo = ();
+        type SS58Prefix = ();
+        type OnSetCode = ();
+    }
+
+    impl pallet_balances::Config for Test {
+        type Balance = u64;
+        type DustRemoval = ();
+        type Event = Event;
+        type ExistentialDeposit = ExistentialDeposit;
+        type AccountStore = System;
+        type WeightInfo = ();
+        type MaxLocks = MaxLocks;
+        type MaxReserves = MaxReserves;
+        type ReserveIdentifier = [u8; 8];
+    }
+
+    impl pallet_timestamp::Config for Test {
+        type Moment = u64;
+        type OnTimestampSet = ();
+        type MinimumPeriod = MinimumPeriod;
+        type WeightInfo = ();
+    }
+
+    impl orml_tokens::Config for Test {
+        type Event = Event;
+        type Balance = u64;
+        type Amount = Amount;
+        type CurrencyId = CurrencyId;
+        type WeightInfo = ();
+        type ExistentialDeposits = ExistentialDeposits;
+        type OnDust = ();
+        type DustRemovalWhitelist = ();
+        type MaxLocks = MaxLocks;
+    }
+
+    impl orml_currencies::Config for Test {
+        type Event = Event;
+        type MultiCurrency = Tokens;
+        type NativeCurrency = AdaptedBasicCurrency;
+        type GetNativeCurrencyId = GetNativeCurrencyId;
+        type WeightInfo = ();
+    }
+    pub type AdaptedBasicCurrency = orml_currencies::BasicCurrencyAdapter<Test, Balances, Amount, BlockNumber>;
+
+    impl Config for Test {
+        type AddressMapping = MockAddressMapping;
+        type Currency = Balances;
+        type TransferAll = Currencies;
+        type NewContractExtraBytes = NewContractExtraBytes;
+        type StorageDepositPerByte = StorageDepositPerByte;
+        type MaxCodeSize = MaxCodeSize;
+        type MaxCodeHistory = MaxCodeHistory;
+
+        type Event = Event;
+        type Precompiles = EvmPrecompiles<
+            ECRecover,
+            Sha256,
+            Ripemd160,
+            Identity,
+            Modexp,
+            Bn128Add,
+            Bn128Mul,
+            Bn128Pairing,
+            Blake2F,
+            ECRecoverPublicKey,
+            Sha3FIPS256,
+            Sha3FIPS512,
+        >;
+        type ChainId = ChainId;
+        type MinimumGasPrice = MinimumGasPrice;
+        type TransactionByteFee = TransactionByteFee;
+        type GasToWeight = GasToWeight;
+        type BaseFeeUpdate = BaseFeeUpdate;
+        type FindAuthor = AuthorGiven;
+        type BlockGasLimit = BlockGasLimit;
+        type Randomness = MockRandomness;
+        type ChargeTransactionPayment = ();
+
+        type NetworkContractOrigin = EnsureSignedBy<NetworkContractAccount, AccountId32>;
+        type NetworkContractSource = NetworkContractSource;
+        type DeveloperDeposit = DeveloperDeposit;
+        type DeploymentFee = DeploymentFee;
+        type FreeDeploymentOrigin = EnsureSignedBy<CouncilAccount, AccountId32>;
+        type PrecompileOrigin = EnsureRoot<AccountId32>;
+
+        type WeightInfo = ();
+    }
+
+    type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+    type Block = frame_system::mocking::MockBlock<Test>;
+
+    construct_runtime!(
+        pub enum Test where
+            Block = Block,
+            NodeBlock = Block,
+            UncheckedExtrinsic = UncheckedExtrinsic,
+        {
+            System: frame_system::{Pallet, Call, Storage, Config, Event<T>},
+            EVM: evm_mod::{Pallet, Config<T>, Call, Storage, Event<T>, ValidateUnsigned},
+            Tokens: orml_tokens::{Pallet, Storage, Event<T>},
+            Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+            Currencies: orml_currencies::{Pallet, Call, Event<T>},
+        }
+    );
+
+    pub fn new_test_ext() -> sp_io::TestExternalities {
+        let mut t = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+
+        let mut accounts = BTreeMap::new();
+        accounts.insert(
+            alice(),
+            GenesisAccount {
+                nonce: 1,
+                balance: INITIAL_BALANCE,
+                storage: Default::default(),
+                code: Default::default(),
+            },
+        );
+
+        pallet_balances::GenesisConfig::<Test>::default()
+            .assimilate_storage(&mut t)
+            .unwrap();
+        evm_mod::GenesisConfig::<Test> { accounts }
+            .assimilate_storage(&mut t)
+            .unwrap();
+
+        let mut ext = sp_io::TestExternalities::new(t);
+        ext.execute_with(|| System::set_block_number(1));
+        ext
+    }
+}
@@ -1,13 +1,23 @@
 //! Builtin precompiles.
 
 use evm::{Context, ExitError, ExitSucceed};
+use frame_support::traits::Get;
 use impl_trait_for_tuples::impl_for_tuples;
 use primitive_types::H160;
 use ripemd160::Digest;
 use sp_runtime::SaturatedConversion;
 use sp_std::{cmp::min, marker::PhantomData, vec::Vec};
+use support::PrecompileCallerFilter;
 use tiny_keccak::Hasher;
 
+mod blake2f;
+mod bn128;
+mod modexp;
+
+pub use blake2f::Blake2F;
+pub use bn128::{Bn128Add, Bn128Mul, Bn128Pairing};
+pub use modexp::Modexp;
+
 /// Custom precompiles to be used by EVM engine.
 pub trait Precompiles {
     #![allow(clippy::type_complexity)]
@@ -21,7 +31,11 @@ pub trait Precompiles {
         input: &[u8],
         target_gas: Option<u64>,
         context: &Context,
+        is_static: bool,
     ) -> Option<core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError>>;
+
+    /// Whether `address` is one of the precompiles.
+    fn is_precompile(address: H160) -> bool;
 }
 
 /// One single precompile used by EVM engine.
@@ -33,52 +47,182 @@ pub trait Precompile {
         input: &[u8],
         target_gas: Option<u64>,
         context: &Context,
+        is_static: bool,
     ) -> core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError>;
 }
 
+/// A registry of precompile sets, e.g. `RegisteredPrecompile`s, each
+/// dispatching the addresses it declares.
 #[impl_for_tuples(16)]
 #[tuple_types_no_default_trait_bound]
 impl Precompiles for Tuple {
-    for_tuples!( where #( Tuple: Precompile )* );
+    for_tuples!( where #( Tuple: Precompiles )* );
     #[allow(clippy::type_complexity)]
     fn execute(
         address: H160,
         input: &[u8],
         target_gas: Option<u64>,
         context: &Context,
+        is_static: bool,
     ) -> Option<core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError>> {
-        let mut index = 0;
-
         for_tuples!( #(
-			index += 1;
-			if address == H160::from_low_u64_be(index) {
-				return Some(Tuple::execute(input, target_gas, context))
+			if let Some(result) = Tuple::execute(address, input, target_gas, context, is_static) {
+				return Some(result)
 			}
 		)* );
 
         None
     }
+
+    fn is_precompile(address: H160) -> bool {
+        for_tuples!( #(
+			if Tuple::is_precompile(address) {
+				return true
+			}
+		)* );
+
+        false
+    }
+}
+
+/// The gas charged for a precompile call, on top of the gas the precompile
+/// itself reports.
+pub trait PrecompileGasCost {
+    /// The gas cost of calling the precompile with `input`.
+    fn gas_cost(input: &[u8]) -> u64;
+}
+
+/// No extra gas, the precompile meters itself.
+impl PrecompileGasCost for () {
+    fn gas_cost(_input: &[u8]) -> u64 {
+        0
+    }
+}
+
+/// `Base` gas plus `Word` gas per 32 bytes word of the input.
+pub struct LinearGasCost<Base, Word>(PhantomData<(Base, Word)>);
+
+impl<Base: Get<u64>, Word: Get<u64>> PrecompileGasCost for LinearGasCost<Base, Word> {
+    fn gas_cost(input: &[u8]) -> u64 {
+        let words = (input.len() as u64).saturating_add(31) / 32;
+        Base::get().saturating_add(Word::get().saturating_mul(words))
+    }
+}
+
+/// The precompile `P` registered at `Address`. Only the callers allowed by
+/// `CallerFilter` can call it, and each call is charged `GasCost`.
+pub struct RegisteredPrecompile<Address, P, CallerFilter, GasCost>(PhantomData<(Address, P, CallerFilter, GasCost)>);
+
+impl<Address, P, CallerFilter, GasCost> Precompiles for RegisteredPrecompile<Address, P, CallerFilter, GasCost>
+where
+    Address: Get<H160>,
+    P: Precompile,
+    CallerFilter: PrecompileCallerFilter,
+    GasCost: PrecompileGasCost,
+{
+    #[allow(clippy::type_complexity)]
+    fn execute(
+        address: H160,
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+        is_static: bool,
+    ) -> Option<core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError>> {
+        if address != Address::get() {
+            return None;
+        }
+
+        if !CallerFilter::is_allowed(context.caller) {
+            log::debug!(target: "evm", "Precompile no permission");
+            return Some(Err(ExitError::Other("no permission".into())));
+        }
+
+        let cost = GasCost::gas_cost(input);
+        let target_gas = match target_gas {
+            Some(target_gas) if cost > target_gas => return Some(Err(ExitError::OutOfGas)),
+            Some(target_gas) => Some(target_gas - cost),
+            None => None,
+        };
+
+        Some(
+            P::execute(input, target_gas, context, is_static)
+                .map(|(exit_status, output, used_gas)| (exit_status, output, used_gas.saturating_add(cost))),
+        )
+    }
+
+    fn is_precompile(address: H160) -> bool {
+        address == Address::get()
+    }
 }
 
-pub struct EvmPrecompiles<ECRecover, Sha256, Ripemd160, Identity, ECRecoverPublicKey, Sha3FIPS256, Sha3FIPS512>(
+pub struct EvmPrecompiles<
+    ECRecover,
+    Sha256,
+    Ripemd160,
+    Identity,
+    Modexp,
+    Bn128Add,
+    Bn128Mul,
+    Bn128Pairing,
+    Blake2F,
+    ECRecoverPublicKey,
+    Sha3FIPS256,
+    Sha3FIPS512,
+>(
     PhantomData<(
         ECRecover,
         Sha256,
         Ripemd160,
         Identity,
+        Modexp,
+        Bn128Add,
+        Bn128Mul,
+        Bn128Pairing,
+        Blake2F,
         ECRecoverPublicKey,
         Sha3FIPS256,
         Sha3FIPS512,
     )>,
 );
 
-impl<ECRecover, Sha256, Ripemd160, Identity, ECRecoverPublicKey, Sha3FIPS256, Sha3FIPS512> Precompiles
-    for EvmPrecompiles<ECRecover, Sha256, Ripemd160, Identity, ECRecoverPublicKey, Sha3FIPS256, Sha3FIPS512>
+impl<
+        ECRecover,
+        Sha256,
+        Ripemd160,
+        Identity,
+        Modexp,
+        Bn128Add,
+        Bn128Mul,
+        Bn128Pairing,
+        Blake2F,
+        ECRecoverPublicKey,
+        Sha3FIPS256,
+        Sha3FIPS512,
+    > Precompiles
+    for EvmPrecompiles<
+        ECRecover,
+        Sha256,
+        Ripemd160,
+        Identity,
+        Modexp,
+        Bn128Add,
+        Bn128Mul,
+        Bn128Pairing,
+        Blake2F,
+        ECRecoverPublicKey,
+        Sha3FIPS256,
+        Sha3FIPS512,
+    >
 where
     ECRecover: Precompile,
     Sha256: Precompile,
     Ripemd160: Precompile,
     Identity: Precompile,
+    Modexp: Precompile,
+    Bn128Add: Precompile,
+    Bn128Mul: Precompile,
+    Bn128Pairing: Precompile,
+    Blake2F: Precompile,
     ECRecoverPublicKey: Precompile,
     Sha3FIPS256: Precompile,
     Sha3FIPS512: Precompile,
@@ -89,28 +233,45 @@ where
         input: &[u8],
         target_gas: Option<u64>,
         context: &Context,
+        is_static: bool,
     ) -> Option<core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError>> {
         // https://github.com/ethereum/go-ethereum/blob/9357280fce5c5d57111d690a336cca5f89e34da6/core/vm/contracts.go#L83
         if address == H160::from_low_u64_be(1) {
-            Some(ECRecover::execute(input, target_gas, context))
+            Some(ECRecover::execute(input, target_gas, context, is_static))
         } else if address == H160::from_low_u64_be(2) {
-            Some(Sha256::execute(input, target_gas, context))
+            Some(Sha256::execute(input, target_gas, context, is_static))
         } else if address == H160::from_low_u64_be(3) {
-            Some(Ripemd160::execute(input, target_gas, context))
+            Some(Ripemd160::execute(input, target_gas, context, is_static))
         } else if address == H160::from_low_u64_be(4) {
-            Some(Identity::execute(input, target_gas, context))
+            Some(Identity::execute(input, target_gas, context, is_static))
+        } else if address == H160::from_low_u64_be(5) {
+            Some(Modexp::execute(input, target_gas, context, is_static))
+        } else if address == H160::from_low_u64_be(6) {
+            Some(Bn128Add::execute(input, target_gas, context, is_static))
+        } else if address == H160::from_low_u64_be(7) {
+            Some(Bn128Mul::execute(input, target_gas, context, is_static))
+        } else if address == H160::from_low_u64_be(8) {
+            Some(Bn128Pairing::execute(input, target_gas, context, is_static))
+        } else if address == H160::from_low_u64_be(9) {
+            Some(Blake2F::execute(input, target_gas, context, is_static))
         }
         // Non-standard precompile starts with 128
         else if address == H160::from_low_u64_be(128) {
-            Some(ECRecoverPublicKey::execute(input, target_gas, context))
+            Some(ECRecoverPublicKey::execute(input, target_gas, context, is_static))
         } else if address == H160::from_low_u64_be(129) {
-            Some(Sha3FIPS256::execute(input, target_gas, context))
+            Some(Sha3FIPS256::execute(input, target_gas, context, is_static))
         } else if address == H160::from_low_u64_be(130) {
-            Some(Sha3FIPS512::execute(input, target_gas, context))
+            Some(Sha3FIPS512::execute(input, target_gas, context, is_static))
         } else {
             None
         }
     }
+
+    fn is_precompile(address: H160) -> bool {
+        [1, 2, 3, 4, 5, 6, 7, 8, 9, 128, 129, 130]
+            .iter()
+            .any(|index| address == H160::from_low_u64_be(*index))
+    }
 }
 
 /// Linear gas cost
@@ -140,6 +301,7 @@ impl Precompile for Identity {
         input: &[u8],
         target_gas: Option<u64>,
         _context: &Context,
+        _is_static: bool,
     ) -> core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
         let cost = ensure_linear_cost(target_gas, input.len(), 15, 3)?;
 
@@ -155,6 +317,7 @@ impl Precompile for ECRecover {
         i: &[u8],
         target_gas: Option<u64>,
         _context: &Context,
+        _is_static: bool,
     ) -> core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
         let cost = ensure_linear_cost(target_gas, i.len(), 3000, 0)?;
 
@@ -186,6 +349,7 @@ impl Precompile for Ripemd160 {
         input: &[u8],
         target_gas: Option<u64>,
         _context: &Context,
+        _is_static: bool,
     ) -> core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
         let cost = ensure_linear_cost(target_gas, input.len(), 600, 120)?;
 
@@ -203,6 +367,7 @@ impl Precompile for Sha256 {
         input: &[u8],
         target_gas: Option<u64>,
         _context: &Context,
+        _is_static: bool,
     ) -> core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
         let cost = ensure_linear_cost(target_gas, input.len(), 60, 12)?;
 
@@ -219,6 +384,7 @@ impl Precompile for ECRecoverPublicKey {
         i: &[u8],
         target_gas: Option<u64>,
         _context: &Context,
+        _is_static: bool,
     ) -> core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
         let cost = ensure_linear_cost(target_gas, i.len(), 3000, 0)?;
 
@@ -248,6 +414,7 @@ impl Precompile for Sha3FIPS256 {
         input: &[u8],
         target_gas: Option<u64>,
         _context: &Context,
+        _is_static: bool,
     ) -> core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
         let cost = ensure_linear_cost(target_gas, input.len(), 60, 12)?;
 
@@ -267,6 +434,7 @@ impl Precompile for Sha3FIPS512 {
         input: &[u8],
         target_gas: Option<u64>,
         _context: &Context,
+        _is_static: bool,
     ) -> core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
         let cost = ensure_linear_cost(target_gas, input.len(), 60, 12)?;
 
@@ -298,6 +466,7 @@ mod tests {
                 caller: Default::default(),
                 apparent_value: Default::default(),
             },
+            false,
         ) {
             Ok((_, out, _)) => {
                 assert_eq!(out, expected);
@@ -327,6 +496,7 @@ mod tests {
                 caller: Default::default(),
                 apparent_value: Default::default(),
             },
+            false,
         ) {
             Ok((_, out, _)) => {
                 assert_eq!(out, expected);
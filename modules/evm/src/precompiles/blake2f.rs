@@ -0,0 +1,212 @@
+//! The BLAKE2b compression function precompile of EIP-152.
+
+use super::Precompile;
+use evm::{Context, ExitError, ExitSucceed};
+use sp_std::{convert::TryInto, vec::Vec};
+
+/// The gas cost of each round.
+const GAS_COST_PER_ROUND: u64 = 1;
+
+/// The length of the input: rounds, state, message, offset counters and
+/// final block flag.
+const INPUT_LENGTH: usize = 213;
+
+/// The BLAKE2b initialization vector.
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179,
+];
+
+/// The message word permutations.
+const SIGMA: [[usize; 16]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15],
+    [14, 10, 4, 8, 9, 15, 13, 6, 1, 12, 0, 2, 11, 7, 5, 3],
+    [11, 8, 12, 0, 5, 2, 15, 13, 10, 14, 3, 6, 7, 1, 9, 4],
+    [7, 9, 3, 1, 13, 12, 11, 14, 2, 6, 5, 10, 4, 0, 15, 8],
+    [9, 0, 5, 7, 2, 4, 10, 15, 14, 1, 11, 12, 6, 8, 3, 13],
+    [2, 12, 6, 10, 0, 11, 8, 3, 4, 13, 7, 5, 15, 14, 1, 9],
+    [12, 5, 1, 15, 14, 13, 4, 10, 0, 7, 6, 3, 9, 2, 8, 11],
+    [13, 11, 7, 14, 12, 1, 3, 9, 5, 0, 15, 4, 8, 6, 2, 10],
+    [6, 15, 14, 9, 11, 3, 0, 8, 12, 2, 13, 7, 1, 4, 10, 5],
+    [10, 2, 8, 4, 7, 6, 1, 5, 15, 11, 9, 14, 3, 12, 13, 0],
+];
+
+/// The mixing function.
+#[allow(clippy::many_single_char_names)]
+fn g(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+/// The compression function F of RFC 7693, with `rounds` rounds.
+fn compress(h: &mut [u64; 8], m: [u64; 16], t: [u64; 2], f: bool, rounds: u32) {
+    let mut v = [0u64; 16];
+    v[..8].copy_from_slice(h);
+    v[8..].copy_from_slice(&IV);
+    v[12] ^= t[0];
+    v[13] ^= t[1];
+    if f {
+        v[14] = !v[14];
+    }
+
+    for i in 0..rounds as usize {
+        let s = &SIGMA[i % 10];
+        g(&mut v, 0, 4, 8, 12, m[s[0]], m[s[1]]);
+        g(&mut v, 1, 5, 9, 13, m[s[2]], m[s[3]]);
+        g(&mut v, 2, 6, 10, 14, m[s[4]], m[s[5]]);
+        g(&mut v, 3, 7, 11, 15, m[s[6]], m[s[7]]);
+        g(&mut v, 0, 5, 10, 15, m[s[8]], m[s[9]]);
+        g(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+        g(&mut v, 2, 7, 8, 13, m[s[12]], m[s[13]]);
+        g(&mut v, 3, 4, 9, 14, m[s[14]], m[s[15]]);
+    }
+
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+}
+
+/// The little endian 64 bits word at `start` of `input`.
+fn read_u64(input: &[u8], start: usize) -> u64 {
+    u64::from_le_bytes(input[start..start + 8].try_into().expect("slice of 8 bytes; qed"))
+}
+
+/// The BLAKE2b compression function precompile.
+pub struct Blake2F;
+
+impl Precompile for Blake2F {
+    fn execute(
+        input: &[u8],
+        target_gas: Option<u64>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
+        if input.len() != INPUT_LENGTH {
+            return Err(ExitError::Other("invalid input length".into()));
+        }
+
+        let rounds = u32::from_be_bytes(input[0..4].try_into().expect("slice of 4 bytes; qed"));
+        let cost = GAS_COST_PER_ROUND * rounds as u64;
+        if let Some(target_gas) = target_gas {
+            if cost > target_gas {
+                return Err(ExitError::OutOfGas);
+            }
+        }
+
+        let f = match input[212] {
+            0 => false,
+            1 => true,
+            _ => return Err(ExitError::Other("invalid final block flag".into())),
+        };
+
+        let mut h = [0u64; 8];
+        for (i, word) in h.iter_mut().enumerate() {
+            *word = read_u64(input, 4 + i * 8);
+        }
+        let mut m = [0u64; 16];
+        for (i, word) in m.iter_mut().enumerate() {
+            *word = read_u64(input, 68 + i * 8);
+        }
+        let t = [read_u64(input, 196), read_u64(input, 204)];
+
+        compress(&mut h, m, t, f, rounds);
+
+        let output = h.iter().flat_map(|word| word.to_le_bytes()).collect();
+        Ok((ExitSucceed::Returned, output, cost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::bytes::from_hex;
+
+    // the state, message and offset counters of the test vectors, hashing "abc"
+    const STATE_MESSAGE_COUNTERS: &str = "48c9bdf267e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d182e6ad7f520e511f6c3e2b8c68059b6bbd41fbabd9831f79217e1319cde05b\
+		61626300000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000\
+		00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000\
+		03000000000000000000000000000000";
+
+    fn input(rounds: &str, final_block: &str) -> Vec<u8> {
+        from_hex(&[rounds, STATE_MESSAGE_COUNTERS, final_block].concat()).unwrap()
+    }
+
+    fn context() -> Context {
+        Context {
+            address: Default::default(),
+            caller: Default::default(),
+            apparent_value: Default::default(),
+        }
+    }
+
+    // https://eips.ethereum.org/EIPS/eip-152#test-cases
+    #[test]
+    fn blake2f_should_work() {
+        assert_eq!(
+            Blake2F::execute(&input("0000000c", "01"), None, &context(), false),
+            Ok((
+                ExitSucceed::Returned,
+                from_hex("ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923").unwrap(),
+                12
+            ))
+        );
+        assert_eq!(
+            Blake2F::execute(&input("00000000", "01"), None, &context(), false),
+            Ok((
+                ExitSucceed::Returned,
+                from_hex("08c9bcf367e6096a3ba7ca8485ae67bb2bf894fe72f36e3cf1361d5f3af54fa5d282e6ad7f520e511f6c3e2b8c68059b9442be0454267ce079217e1319cde05b").unwrap(),
+                0
+            ))
+        );
+        assert_eq!(
+            Blake2F::execute(&input("0000000c", "00"), None, &context(), false),
+            Ok((
+                ExitSucceed::Returned,
+                from_hex("75ab69d3190a562c51aef8d88f1c2775876944407270c42c9844252c26d2875298743e7f6d5ea2f2d3e8d226039cd31b4e426ac4f2d3d666a610c2116fde4735").unwrap(),
+                12
+            ))
+        );
+        assert_eq!(
+            Blake2F::execute(&input("00000001", "01"), None, &context(), false),
+            Ok((
+                ExitSucceed::Returned,
+                from_hex("b63a380cb2897d521994a85234ee2c181b5f844d2c624c002677e9703449d2fba551b3a8333bcdf5f2f7e08993d53923de3d64fcc68c034e717b9293fed7a421").unwrap(),
+                1
+            ))
+        );
+    }
+
+    #[test]
+    fn blake2f_should_reject_invalid_input() {
+        assert_eq!(
+            Blake2F::execute(&[], None, &context(), false),
+            Err(ExitError::Other("invalid input length".into()))
+        );
+        let mut too_long = input("0000000c", "01");
+        too_long.push(0);
+        assert_eq!(
+            Blake2F::execute(&too_long, None, &context(), false),
+            Err(ExitError::Other("invalid input length".into()))
+        );
+        assert_eq!(
+            Blake2F::execute(&input("0000000c", "02"), None, &context(), false),
+            Err(ExitError::Other("invalid final block flag".into()))
+        );
+        assert_eq!(
+            Blake2F::execute(&input("ffffffff", "01"), Some(1_000_000), &context(), false),
+            Err(ExitError::OutOfGas)
+        );
+    }
+}
@@ -0,0 +1,311 @@
+//! The BN254 (alt_bn128) precompiles of EIP-196 and EIP-197, with the gas
+//! costs of EIP-1108.
+
+use super::Precompile;
+use bn::{arith, pairing_batch, AffineG1, AffineG2, Fq, Fq2, Fr, Group, Gt, G1, G2};
+use evm::{Context, ExitError, ExitSucceed};
+use sp_std::vec::Vec;
+
+/// The gas cost of an addition.
+const ADD_GAS_COST: u64 = 150;
+/// The gas cost of a scalar multiplication.
+const MUL_GAS_COST: u64 = 6_000;
+/// The base gas cost of a pairing check.
+const PAIRING_BASE_GAS_COST: u64 = 45_000;
+/// The gas cost of each pair of a pairing check.
+const PAIRING_PER_PAIR_GAS_COST: u64 = 34_000;
+
+/// The length of a pair of a pairing check: a G1 point and a G2 point.
+const PAIR_LENGTH: usize = 192;
+
+/// Read the 32 bytes at `start` of `input`, right padded with zeros.
+fn read_word(input: &[u8], start: usize) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    if start < input.len() {
+        let available = (input.len() - start).min(32);
+        word[..available].copy_from_slice(&input[start..start + available]);
+    }
+    word
+}
+
+fn read_fq(input: &[u8], start: usize) -> Result<Fq, ExitError> {
+    Fq::from_slice(&read_word(input, start)).map_err(|_| ExitError::Other("invalid field element".into()))
+}
+
+/// Read the scalar at `start` of `input`, any 256 bits number.
+fn read_scalar(input: &[u8], start: usize) -> Result<Fr, ExitError> {
+    let scalar =
+        arith::U256::from_slice(&read_word(input, start)).map_err(|_| ExitError::Other("invalid scalar".into()))?;
+    Ok(Fr::new_mul_factor(scalar))
+}
+
+/// Read the G1 point at `start` of `input`, `(0, 0)` being the point at
+/// infinity.
+fn read_g1(input: &[u8], start: usize) -> Result<G1, ExitError> {
+    let x = read_fq(input, start)?;
+    let y = read_fq(input, start + 32)?;
+
+    if x.is_zero() && y.is_zero() {
+        Ok(G1::zero())
+    } else {
+        AffineG1::new(x, y)
+            .map(Into::into)
+            .map_err(|_| ExitError::Other("invalid G1 point".into()))
+    }
+}
+
+/// Read the G2 point at `start` of `input`, whose coordinates are encoded
+/// with their imaginary part first, `(0, 0)` being the point at infinity.
+fn read_g2(input: &[u8], start: usize) -> Result<G2, ExitError> {
+    let x = Fq2::new(read_fq(input, start + 32)?, read_fq(input, start)?);
+    let y = Fq2::new(read_fq(input, start + 96)?, read_fq(input, start + 64)?);
+
+    if x.is_zero() && y.is_zero() {
+        Ok(G2::zero())
+    } else {
+        AffineG2::new(x, y)
+            .map(Into::into)
+            .map_err(|_| ExitError::Other("invalid G2 point".into()))
+    }
+}
+
+/// Encode the G1 `point`, the point at infinity as `(0, 0)`.
+fn encode_g1(point: G1) -> Vec<u8> {
+    let mut output = [0u8; 64];
+    if let Some(point) = AffineG1::from_jacobian(point) {
+        // the coordinates are field elements, which are 32 bytes
+        let _ = point.x().to_big_endian(&mut output[0..32]);
+        let _ = point.y().to_big_endian(&mut output[32..64]);
+    }
+    output.to_vec()
+}
+
+fn ensure_gas(cost: u64, target_gas: Option<u64>) -> Result<(), ExitError> {
+    match target_gas {
+        Some(target_gas) if cost > target_gas => Err(ExitError::OutOfGas),
+        _ => Ok(()),
+    }
+}
+
+/// The BN254 point addition precompile.
+pub struct Bn128Add;
+
+impl Precompile for Bn128Add {
+    fn execute(
+        input: &[u8],
+        target_gas: Option<u64>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
+        ensure_gas(ADD_GAS_COST, target_gas)?;
+
+        let p1 = read_g1(input, 0)?;
+        let p2 = read_g1(input, 64)?;
+
+        Ok((ExitSucceed::Returned, encode_g1(p1 + p2), ADD_GAS_COST))
+    }
+}
+
+/// The BN254 scalar multiplication precompile.
+pub struct Bn128Mul;
+
+impl Precompile for Bn128Mul {
+    fn execute(
+        input: &[u8],
+        target_gas: Option<u64>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
+        ensure_gas(MUL_GAS_COST, target_gas)?;
+
+        let p = read_g1(input, 0)?;
+        let scalar = read_scalar(input, 64)?;
+
+        Ok((ExitSucceed::Returned, encode_g1(p * scalar), MUL_GAS_COST))
+    }
+}
+
+/// The BN254 pairing check precompile.
+pub struct Bn128Pairing;
+
+impl Precompile for Bn128Pairing {
+    fn execute(
+        input: &[u8],
+        target_gas: Option<u64>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
+        if input.len() % PAIR_LENGTH != 0 {
+            return Err(ExitError::Other("invalid input length".into()));
+        }
+
+        let pairs = input.len() / PAIR_LENGTH;
+        let cost = PAIRING_BASE_GAS_COST.saturating_add(PAIRING_PER_PAIR_GAS_COST.saturating_mul(pairs as u64));
+        ensure_gas(cost, target_gas)?;
+
+        let pairs = (0..pairs)
+            .map(|i| {
+                let start = i * PAIR_LENGTH;
+                Ok((read_g1(input, start)?, read_g2(input, start + 64)?))
+            })
+            .collect::<Result<Vec<_>, ExitError>>()?;
+
+        let mut output = [0u8; 32];
+        if pairing_batch(&pairs) == Gt::one() {
+            output[31] = 1;
+        }
+
+        Ok((ExitSucceed::Returned, output.to_vec(), cost))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::bytes::from_hex;
+
+    // the generators of G1 and G2, and the negation of the G1 generator
+    const G1_GENERATOR: &str = "0000000000000000000000000000000000000000000000000000000000000001\
+		0000000000000000000000000000000000000000000000000000000000000002";
+    const G1_GENERATOR_NEG: &str = "0000000000000000000000000000000000000000000000000000000000000001\
+		30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd45";
+    const G2_GENERATOR: &str = "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+		1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed\
+		090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+		12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa";
+
+    fn context() -> Context {
+        Context {
+            address: Default::default(),
+            caller: Default::default(),
+            apparent_value: Default::default(),
+        }
+    }
+
+    // https://github.com/ethereum/go-ethereum/blob/master/core/vm/testdata/precompiles/bn256Add.json
+    #[test]
+    fn bn128_add_should_work() {
+        let input = from_hex(
+            "18b18acfb4c2c30276db5411368e7185b311dd124691610c5d3b74034e093dc9\
+			063c909c4720840cb5134cb9f59fa749755796819658d32efc0d288198f37266\
+			07c2b7f58a84bd6145f00c9c2bc0bb1a187f20ff2c92963a88019e7c6a014eed\
+			06614e20c147e940f2d70da3f74c9a17df361706a4485c742bd6788478fa17d7",
+        )
+        .unwrap();
+        let expected = from_hex(
+            "2243525c5efd4b9c3d3c45ac0ca3fe4dd85e830a4ce6b65fa1eeaee202839703\
+			301d1d33be6da8e509df21cc35964723180eed7532537db9ae5e7d48f195c915",
+        )
+        .unwrap();
+        assert_eq!(
+            Bn128Add::execute(&input, None, &context(), false),
+            Ok((ExitSucceed::Returned, expected, 150))
+        );
+
+        // the point at infinity, the missing input bytes are zeros
+        assert_eq!(
+            Bn128Add::execute(&[], None, &context(), false),
+            Ok((ExitSucceed::Returned, vec![0u8; 64], 150))
+        );
+        let input = from_hex(&[G1_GENERATOR, G1_GENERATOR_NEG].concat()).unwrap();
+        assert_eq!(
+            Bn128Add::execute(&input, None, &context(), false),
+            Ok((ExitSucceed::Returned, vec![0u8; 64], 150))
+        );
+
+        // not on the curve
+        let input = from_hex(
+            "0000000000000000000000000000000000000000000000000000000000000001\
+			0000000000000000000000000000000000000000000000000000000000000003",
+        )
+        .unwrap();
+        assert_eq!(
+            Bn128Add::execute(&input, None, &context(), false),
+            Err(ExitError::Other("invalid G1 point".into()))
+        );
+
+        assert_eq!(
+            Bn128Add::execute(&[], Some(149), &context(), false),
+            Err(ExitError::OutOfGas)
+        );
+    }
+
+    // https://github.com/ethereum/go-ethereum/blob/master/core/vm/testdata/precompiles/bn256ScalarMul.json
+    #[test]
+    fn bn128_mul_should_work() {
+        let input = from_hex(
+            "2bd3e6d0f3b142924f5ca7b49ce5b9d54c4703d7ae5648e61d02268b1a0a9fb7\
+			21611ce0a6af85915e2f1d70300909ce2e49dfad4a4619c8390cae66cefdb204\
+			00000000000000000000000000000000000000000000000011138ce750fa15c2",
+        )
+        .unwrap();
+        let expected = from_hex(
+            "070a8d6a982153cae4be29d434e8faef8a47b274a053f5a4ee2a6c9c13c31e5c\
+			031b8ce914eba3a9ffb989f9cdd5b0f01943074bf4f0f315690ec3cec6981afc",
+        )
+        .unwrap();
+        assert_eq!(
+            Bn128Mul::execute(&input, None, &context(), false),
+            Ok((ExitSucceed::Returned, expected, 6_000))
+        );
+
+        // scalars above the group order are reduced
+        let input = from_hex(
+            &[
+                G1_GENERATOR,
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            ]
+            .concat(),
+        )
+        .unwrap();
+        let expected = from_hex(
+            "2f588cffe99db877a4434b598ab28f81e0522910ea52b45f0adaa772b2d5d352\
+			12f42fa8fd34fb1b33d8c6a718b6590198389b26fc9d8808d971f8b009777a97",
+        )
+        .unwrap();
+        assert_eq!(
+            Bn128Mul::execute(&input, None, &context(), false),
+            Ok((ExitSucceed::Returned, expected, 6_000))
+        );
+
+        assert_eq!(
+            Bn128Mul::execute(&input, Some(5_999), &context(), false),
+            Err(ExitError::OutOfGas)
+        );
+    }
+
+    // https://github.com/ethereum/go-ethereum/blob/master/core/vm/testdata/precompiles/bn256Pairing.json
+    #[test]
+    fn bn128_pairing_should_work() {
+        let mut one = vec![0u8; 32];
+        one[31] = 1;
+
+        assert_eq!(
+            Bn128Pairing::execute(&[], None, &context(), false),
+            Ok((ExitSucceed::Returned, one.clone(), 45_000))
+        );
+
+        // e(g1, g2) * e(-g1, g2) == 1
+        let input = from_hex(&[G1_GENERATOR, G2_GENERATOR, G1_GENERATOR_NEG, G2_GENERATOR].concat()).unwrap();
+        assert_eq!(
+            Bn128Pairing::execute(&input, None, &context(), false),
+            Ok((ExitSucceed::Returned, one, 113_000))
+        );
+
+        // e(g1, g2) != 1
+        let input = from_hex(&[G1_GENERATOR, G2_GENERATOR].concat()).unwrap();
+        assert_eq!(
+            Bn128Pairing::execute(&input, None, &context(), false),
+            Ok((ExitSucceed::Returned, vec![0u8; 32], 79_000))
+        );
+
+        assert_eq!(
+            Bn128Pairing::execute(&input[1..], None, &context(), false),
+            Err(ExitError::Other("invalid input length".into()))
+        );
+        assert_eq!(
+            Bn128Pairing::execute(&input, Some(78_999), &context(), false),
+            Err(ExitError::OutOfGas)
+        );
+    }
+}
@@ -0,0 +1,199 @@
+//! The modexp precompile of EIP-198, with the gas cost of EIP-2565.
+
+use super::Precompile;
+use evm::{Context, ExitError, ExitSucceed};
+use num::{BigUint, Zero};
+use primitive_types::U256;
+use sp_std::{cmp::max, vec, vec::Vec};
+
+/// The minimum gas cost of a call.
+const MIN_GAS_COST: u64 = 200;
+
+/// The maximum length of the base, exponent and modulus.
+const MAX_LENGTH: u64 = 1024;
+
+/// The modexp precompile.
+pub struct Modexp;
+
+impl Precompile for Modexp {
+    fn execute(
+        input: &[u8],
+        target_gas: Option<u64>,
+        _context: &Context,
+        _is_static: bool,
+    ) -> core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
+        let base_length = read_length(input, 0)?;
+        let exp_length = read_length(input, 32)?;
+        let mod_length = read_length(input, 64)?;
+
+        let base_start = 96;
+        let exp_start = base_start + base_length;
+        let mod_start = exp_start + exp_length;
+
+        let exponent = BigUint::from_bytes_be(&read_bytes(input, exp_start, exp_length));
+        let cost = gas_cost(base_length as u64, exp_length as u64, mod_length as u64, &exponent);
+        if let Some(target_gas) = target_gas {
+            if cost > target_gas {
+                return Err(ExitError::OutOfGas);
+            }
+        }
+
+        if mod_length == 0 {
+            return Ok((ExitSucceed::Returned, Vec::new(), cost));
+        }
+
+        let base = BigUint::from_bytes_be(&read_bytes(input, base_start, base_length));
+        let modulus = BigUint::from_bytes_be(&read_bytes(input, mod_start, mod_length));
+
+        let mut output = vec![0u8; mod_length];
+        if !modulus.is_zero() {
+            let result = base.modpow(&exponent, &modulus).to_bytes_be();
+            // the result is less than the modulus, so it fits in `mod_length` bytes
+            output[mod_length - result.len()..].copy_from_slice(&result);
+        }
+
+        Ok((ExitSucceed::Returned, output, cost))
+    }
+}
+
+/// Read the length at `start` of `input`.
+fn read_length(input: &[u8], start: usize) -> Result<usize, ExitError> {
+    let length = U256::from_big_endian(&read_bytes(input, start, 32));
+    if length > U256::from(MAX_LENGTH) {
+        return Err(ExitError::Other("unreasonably large length".into()));
+    }
+
+    Ok(length.low_u64() as usize)
+}
+
+/// Read `length` bytes at `start` of `input`, right padded with zeros.
+fn read_bytes(input: &[u8], start: usize, length: usize) -> Vec<u8> {
+    let mut bytes = vec![0u8; length];
+    if start < input.len() {
+        let available = (input.len() - start).min(length);
+        bytes[..available].copy_from_slice(&input[start..start + available]);
+    }
+    bytes
+}
+
+/// The gas cost of EIP-2565.
+fn gas_cost(base_length: u64, exp_length: u64, mod_length: u64, exponent: &BigUint) -> u64 {
+    let words = (max(base_length, mod_length) + 7) / 8;
+    let multiplication_complexity = words * words;
+
+    let iteration_count = if exp_length <= 32 {
+        exponent.bits().saturating_sub(1)
+    } else {
+        // the bit length of the first 32 bytes of the exponent
+        let head = exponent >> (8 * (exp_length - 32));
+        8 * (exp_length - 32) + head.bits().saturating_sub(1)
+    };
+
+    max(MIN_GAS_COST, multiplication_complexity * max(iteration_count, 1) / 3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sp_core::bytes::from_hex;
+
+    fn context() -> Context {
+        Context {
+            address: Default::default(),
+            caller: Default::default(),
+            apparent_value: Default::default(),
+        }
+    }
+
+    // https://github.com/ethereum/go-ethereum/blob/master/core/vm/testdata/precompiles/modexp_eip2565.json
+    #[test]
+    fn modexp_should_work() {
+        // 3 ^ (p - 1) mod p, p the secp256k1 field prime
+        let input = from_hex(
+            "0000000000000000000000000000000000000000000000000000000000000001\
+			0000000000000000000000000000000000000000000000000000000000000020\
+			0000000000000000000000000000000000000000000000000000000000000020\
+			03\
+			fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e\
+			fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+        )
+        .unwrap();
+        assert_eq!(
+            Modexp::execute(&input, None, &context(), false),
+            Ok((
+                ExitSucceed::Returned,
+                from_hex("0000000000000000000000000000000000000000000000000000000000000001").unwrap(),
+                1360
+            ))
+        );
+
+        // 0 ^ (p - 1) mod p
+        let input = from_hex(
+            "0000000000000000000000000000000000000000000000000000000000000000\
+			0000000000000000000000000000000000000000000000000000000000000020\
+			0000000000000000000000000000000000000000000000000000000000000020\
+			fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2e\
+			fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f",
+        )
+        .unwrap();
+        assert_eq!(
+            Modexp::execute(&input, None, &context(), false),
+            Ok((
+                ExitSucceed::Returned,
+                from_hex("0000000000000000000000000000000000000000000000000000000000000000").unwrap(),
+                1360
+            ))
+        );
+
+        assert_eq!(
+            Modexp::execute(&input, Some(1359), &context(), false),
+            Err(ExitError::OutOfGas)
+        );
+    }
+
+    #[test]
+    fn modexp_should_handle_edge_cases() {
+        // empty input
+        assert_eq!(
+            Modexp::execute(&[], None, &context(), false),
+            Ok((ExitSucceed::Returned, vec![], 200))
+        );
+
+        // zero modulus, the missing input bytes are zeros
+        let input = from_hex(
+            "0000000000000000000000000000000000000000000000000000000000000001\
+			0000000000000000000000000000000000000000000000000000000000000001\
+			0000000000000000000000000000000000000000000000000000000000000002\
+			0203",
+        )
+        .unwrap();
+        assert_eq!(
+            Modexp::execute(&input, None, &context(), false),
+            Ok((ExitSucceed::Returned, vec![0, 0], 200))
+        );
+
+        // 2 ^ 3 mod 5
+        let input = from_hex(
+            "0000000000000000000000000000000000000000000000000000000000000001\
+			0000000000000000000000000000000000000000000000000000000000000001\
+			0000000000000000000000000000000000000000000000000000000000000002\
+			02030005",
+        )
+        .unwrap();
+        assert_eq!(
+            Modexp::execute(&input, None, &context(), false),
+            Ok((ExitSucceed::Returned, vec![0, 3], 200))
+        );
+
+        let input = from_hex(
+            "0000000000000000000000000000000000000000000000000000000000000401\
+			0000000000000000000000000000000000000000000000000000000000000001\
+			0000000000000000000000000000000000000000000000000000000000000001",
+        )
+        .unwrap();
+        assert_eq!(
+            Modexp::execute(&input, None, &context(), false),
+            Err(ExitError::Other("unreasonably large length".into()))
+        );
+    }
+}
@@ -2,16 +2,19 @@
 
 use crate::{
     precompiles::Precompiles,
-    runner::storage_meter::{StorageMeter, StorageMeterHandler},
-    AccountStorages, Accounts, AddressMapping, Codes, Config, ContractInfo, Error, Event, EvmAccountInfo, Log, Pallet,
-    TransferAll, Vicinity,
+    runner::{
+        storage_meter::{StorageMeter, StorageMeterHandler},
+        tracer::Tracer,
+    },
+    AccountStorages, Accounts, AddressMapping, CallType, Codes, Config, ContractInfo, ContractsToRemove, Error,
+    EvmAccountInfo, Pallet, TransferAll, Vicinity,
 };
 use evm::{Capture, Context, CreateScheme, ExitError, ExitReason, Opcode, Runtime, Stack, Transfer};
-use evm_gasometer::{self as gasometer, Gasometer};
+use evm_gasometer::{self as gasometer, Gasometer, StorageTarget};
 use evm_runtime::{Config as EvmRuntimeConfig, Handler as HandlerT};
 use frame_support::{
     require_transactional,
-    traits::{BalanceStatus, Currency, ExistenceRequirement, Get, ReservableCurrency},
+    traits::{BalanceStatus, Currency, ExistenceRequirement, Get, Randomness, ReservableCurrency},
 };
 use primitive_types::{H160, H256, U256};
 use sha3::{Digest, Keccak256};
@@ -19,19 +22,43 @@ use sp_runtime::{
     traits::{One, Saturating, UniqueSaturatedInto, Zero},
     DispatchError, DispatchResult, SaturatedConversion, TransactionOutcome,
 };
-use sp_std::{cmp::min, convert::Infallible, marker::PhantomData, prelude::*, rc::Rc};
+use sp_std::{
+    cell::RefCell,
+    cmp::min,
+    collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+    convert::Infallible,
+    marker::PhantomData,
+    mem,
+    prelude::*,
+    rc::Rc,
+};
 
 use primitives::SYSTEM_CONTRACT_ADDRESS_PREFIX;
+use support::EVMLog;
 
 /// Storage key size and storage value size.
 pub const STORAGE_SIZE: u32 = 64;
 
+/// Addresses and storage slots accessed by a transaction (EIP-2929).
+#[derive(Clone, Default)]
+pub struct AccessedSet {
+    pub addresses: BTreeSet<H160>,
+    pub storage: BTreeSet<(H160, H256)>,
+}
+
 pub struct Handler<'vicinity, 'config, 'meter, T: Config> {
     pub vicinity: &'vicinity Vicinity,
     pub config: &'config EvmRuntimeConfig,
     pub gasometer: Gasometer<'config>,
     pub storage_meter: StorageMeter<'meter>,
     pub is_static: bool,
+    pub tracer: Option<Rc<RefCell<Tracer>>>,
+    /// Values at the start of the transaction of the storage slots written
+    /// since, shared by the handlers of the sub-transactions.
+    pub original_storage: Rc<RefCell<BTreeMap<(H160, H256), H256>>>,
+    /// Accessed addresses and storage slots, discarded with a reverted
+    /// sub-transaction.
+    pub accessed: AccessedSet,
     _marker: PhantomData<T>,
 }
 
@@ -47,6 +74,7 @@ impl<'vicinity, 'config, T: Config> Handler<'vicinity, 'config, '_, T> {
         contract: H160,
         is_static: bool,
         config: &'config EvmRuntimeConfig,
+        tracer: Option<Rc<RefCell<Tracer>>>,
         f: F,
     ) -> Result<R, DispatchError> {
         frame_support::storage::with_transaction(|| {
@@ -59,12 +87,20 @@ impl<'vicinity, 'config, T: Config> Handler<'vicinity, 'config, '_, T> {
                 Err(e) => return TransactionOutcome::Rollback(Err(e)),
             };
 
+            // the origin and the contract are warm from the start
+            let mut accessed = AccessedSet::default();
+            accessed.addresses.insert(vicinity.origin);
+            accessed.addresses.insert(contract);
+
             let mut substate = Handler::<'vicinity, 'config, '_, T> {
                 vicinity,
                 config,
                 is_static,
                 gasometer: Gasometer::new(gas_limit, config),
                 storage_meter,
+                tracer,
+                original_storage: Default::default(),
+                accessed,
                 _marker: PhantomData,
             };
 
@@ -103,12 +139,18 @@ impl<'vicinity, 'config, T: Config> Handler<'vicinity, 'config, '_, T> {
                 is_static,
                 gasometer: Gasometer::new(gas_limit, config),
                 storage_meter,
+                tracer: self.tracer.clone(),
+                original_storage: self.original_storage.clone(),
+                accessed: self.accessed.clone(),
                 _marker: PhantomData,
             };
 
             match f(&mut substate, &mut self.gasometer) {
                 TransactionOutcome::Commit(r) => match substate.storage_meter.finish() {
-                    Ok(_) => TransactionOutcome::Commit(Ok(r)),
+                    Ok(_) => {
+                        self.accessed = mem::take(&mut substate.accessed);
+                        TransactionOutcome::Commit(Ok(r))
+                    }
                     Err(e) => TransactionOutcome::Rollback(Err(e)),
                 },
                 TransactionOutcome::Rollback(e) => TransactionOutcome::Rollback(Ok(e)),
@@ -120,7 +162,7 @@ impl<'vicinity, 'config, T: Config> Handler<'vicinity, 'config, '_, T> {
     pub fn used_gas(&self) -> u64 {
         self.gasometer.total_used_gas()
             - min(
-                self.gasometer.total_used_gas() / 2,
+                self.gasometer.total_used_gas() / self.config.max_refund_quotient,
                 self.gasometer.refunded_gas() as u64,
             )
     }
@@ -129,6 +171,16 @@ impl<'vicinity, 'config, T: Config> Handler<'vicinity, 'config, '_, T> {
         self.storage_meter.used_storage()
     }
 
+    /// Warm the addresses and storage slots of `access_list` (EIP-2930).
+    pub fn warm_access_list(&mut self, access_list: &[(H160, Vec<H256>)]) {
+        for (address, indexes) in access_list {
+            self.accessed.addresses.insert(*address);
+            self.accessed
+                .storage
+                .extend(indexes.iter().map(|index| (*address, *index)));
+        }
+    }
+
     pub fn execute(
         &mut self,
         caller: H160,
@@ -143,11 +195,42 @@ impl<'vicinity, 'config, T: Config> Handler<'vicinity, 'config, '_, T> {
             apparent_value: value,
         };
 
-        let mut runtime = Runtime::new(Rc::new(code), Rc::new(input), context, self.config);
+        let code = Rc::new(code);
+        let mut runtime = Runtime::new(code.clone(), Rc::new(input), context, self.config);
+
+        let reason = match self.tracer.clone() {
+            // step through the code to record the state of the machine before each opcode
+            Some(tracer) => loop {
+                if let Ok(pc) = runtime.machine().position() {
+                    if let Some(opcode) = code.get(*pc).map(|opcode| Opcode(*opcode)) {
+                        let stack = runtime.machine().stack();
+                        let storage = match opcode {
+                            Opcode::SLOAD => stack.peek(0).ok().map(|index| (index, self.storage(address, index))),
+                            Opcode::SSTORE => stack.peek(0).ok().zip(stack.peek(1).ok()),
+                            _ => None,
+                        };
+                        tracer.borrow_mut().step(
+                            address,
+                            *pc,
+                            opcode,
+                            self.gasometer.gas(),
+                            stack.data(),
+                            runtime.machine().memory().data(),
+                            storage,
+                        );
+                    }
+                }
 
-        let reason = match runtime.run(self) {
-            Capture::Exit(s) => s,
-            Capture::Trap(_) => unreachable!("Trap is Infallible"),
+                match runtime.step(self) {
+                    Ok(()) => {}
+                    Err(Capture::Exit(s)) => break s,
+                    Err(Capture::Trap(_)) => unreachable!("Trap is Infallible"),
+                }
+            },
+            None => match runtime.run(self) {
+                Capture::Exit(s) => s,
+                Capture::Trap(_) => unreachable!("Trap is Infallible"),
+            },
         };
 
         match reason {
@@ -219,6 +302,9 @@ impl<'vicinity, 'config, T: Config> Handler<'vicinity, 'config, '_, T> {
             Err(ExitError::Other(
                 Into::<&str>::into(Error::<T>::ConflictContractAddress).into(),
             ))
+        } else if ContractsToRemove::<T>::contains_key(address) {
+            // the storage of the removed contract is still being collected
+            Err(ExitError::CreateCollision)
         } else {
             Ok(address)
         }
@@ -303,11 +389,12 @@ impl<'vicinity, 'config, 'meter, T: Config> HandlerT for Handler<'vicinity, 'con
         AccountStorages::<T>::get(address, index)
     }
 
-    fn original_storage(&self, _address: H160, _index: H256) -> H256 {
-        // We do not have the concept of original storage in the native runner, so we
-        // always return empty value. This only affects gas calculation in the current
-        // EVM specification.
-        H256::default()
+    fn original_storage(&self, address: H160, index: H256) -> H256 {
+        // a slot not written yet still has its value at the start of the transaction
+        match self.original_storage.borrow().get(&(address, index)) {
+            Some(value) => *value,
+            None => self.storage(address, index),
+        }
     }
 
     fn gas_left(&self) -> U256 {
@@ -337,7 +424,8 @@ impl<'vicinity, 'config, 'meter, T: Config> HandlerT for Handler<'vicinity, 'con
     }
 
     fn block_coinbase(&self) -> H160 {
-        H160::default()
+        // read only, the execution must not claim an address for the author
+        Pallet::<T>::block_coinbase()
     }
 
     fn block_timestamp(&self) -> U256 {
@@ -346,11 +434,16 @@ impl<'vicinity, 'config, 'meter, T: Config> HandlerT for Handler<'vicinity, 'con
     }
 
     fn block_difficulty(&self) -> U256 {
-        U256::zero()
+        let (seed, _) = T::Randomness::random_seed();
+        U256::from_big_endian(seed.as_ref())
     }
 
     fn block_gas_limit(&self) -> U256 {
-        U256::zero()
+        U256::from(T::BlockGasLimit::get())
+    }
+
+    fn block_base_fee_per_gas(&self) -> U256 {
+        Pallet::<T>::base_fee_per_gas()
     }
 
     fn chain_id(&self) -> U256 {
@@ -367,6 +460,14 @@ impl<'vicinity, 'config, 'meter, T: Config> HandlerT for Handler<'vicinity, 'con
         true
     }
 
+    fn is_cold(&self, address: H160, index: Option<H256>) -> bool {
+        match index {
+            Some(index) => !self.accessed.storage.contains(&(address, index)),
+            // precompiles are always warm
+            None => !T::Precompiles::is_precompile(address) && !self.accessed.addresses.contains(&address),
+        }
+    }
+
     fn set_storage(&mut self, address: H160, index: H256, value: H256) -> Result<(), ExitError> {
         if self.is_static {
             return Err(ExitError::OutOfGas);
@@ -380,7 +481,13 @@ impl<'vicinity, 'config, 'meter, T: Config> HandlerT for Handler<'vicinity, 'con
         let mut storage_change = StorageChange::None;
 
         let default_value = H256::default();
-        let is_prev_value_default = Pallet::<T>::account_storages(address, index) == default_value;
+        let prev_value = Pallet::<T>::account_storages(address, index);
+        let is_prev_value_default = prev_value == default_value;
+
+        self.original_storage
+            .borrow_mut()
+            .entry((address, index))
+            .or_insert(prev_value);
 
         if value == default_value {
             if !is_prev_value_default {
@@ -405,7 +512,7 @@ impl<'vicinity, 'config, 'meter, T: Config> HandlerT for Handler<'vicinity, 'con
     }
 
     fn log(&mut self, address: H160, topics: Vec<H256>, data: Vec<u8>) -> Result<(), ExitError> {
-        Pallet::<T>::deposit_event(Event::<T>::Log(Log { address, topics, data }));
+        <Pallet<T> as EVMLog>::log(address, topics, data);
 
         Ok(())
     }
@@ -447,7 +554,8 @@ impl<'vicinity, 'config, 'meter, T: Config> HandlerT for Handler<'vicinity, 'con
             return Capture::Exit((ExitError::OutOfGas.into(), None, Vec::new()));
         }
 
-        let mut after_gas = self.gasometer.gas();
+        let gas_before = self.gasometer.gas();
+        let mut after_gas = gas_before;
         if self.config.call_l64_after_gas {
             after_gas = l64(after_gas);
         }
@@ -463,9 +571,19 @@ impl<'vicinity, 'config, 'meter, T: Config> HandlerT for Handler<'vicinity, 'con
         };
         Self::inc_nonce(caller);
 
+        if let Some(tracer) = &self.tracer {
+            let call_type = match tracer.borrow().opcode() {
+                Some(Opcode::CREATE2) => CallType::Create2,
+                _ => CallType::Create,
+            };
+            tracer
+                .borrow_mut()
+                .enter(call_type, caller, address, init_code.clone(), value, target_gas);
+        }
+
         let origin = &self.vicinity.origin;
 
-        self.run_sub_transaction(
+        let result = self.run_sub_transaction(
             self.vicinity,
             target_gas,
             address,
@@ -508,14 +626,25 @@ impl<'vicinity, 'config, 'meter, T: Config> HandlerT for Handler<'vicinity, 'con
                     }
                 }
             },
-        )
-        .unwrap_or_else(|x| {
+        );
+
+        let result = result.unwrap_or_else(|x| {
             Capture::Exit((
                 ExitReason::Error(ExitError::Other(Into::<&'static str>::into(x).into())),
                 None,
                 Vec::new(),
             ))
-        })
+        });
+
+        if let (Some(tracer), Capture::Exit((reason, _, output))) = (&self.tracer, &result) {
+            tracer.borrow_mut().exit(
+                reason.clone(),
+                output.clone(),
+                gas_before.saturating_sub(self.gasometer.gas()),
+            );
+        }
+
+        result
     }
 
     fn call(
@@ -558,7 +687,26 @@ impl<'vicinity, 'config, 'meter, T: Config> HandlerT for Handler<'vicinity, 'con
 
         let code = self.code(code_address);
 
-        self.run_sub_transaction(
+        if let Some(tracer) = &self.tracer {
+            let call_type = match tracer.borrow().opcode() {
+                Some(Opcode::CALLCODE) => CallType::CallCode,
+                Some(Opcode::DELEGATECALL) => CallType::DelegateCall,
+                Some(Opcode::STATICCALL) => CallType::StaticCall,
+                _ => CallType::Call,
+            };
+            tracer.borrow_mut().enter(
+                call_type,
+                context.caller,
+                code_address,
+                input.clone(),
+                context.apparent_value,
+                target_gas,
+            );
+        }
+
+        let gas_before = self.gasometer.gas();
+
+        let result = self.run_sub_transaction(
             self.vicinity,
             target_gas,
             context.address,
@@ -571,7 +719,21 @@ impl<'vicinity, 'config, 'meter, T: Config> HandlerT for Handler<'vicinity, 'con
 
                 try_or_rollback!(gasometer.record_cost(target_gas));
 
-                if let Some(ret) = T::Precompiles::execute(code_address, &input, Some(target_gas), &context) {
+                if T::Precompiles::is_precompile(code_address) && Pallet::<T>::disabled_precompiles(code_address) {
+                    log::debug!(target: "evm", "handler: call-result: precompile {:?} disabled", code_address);
+                    return TransactionOutcome::Rollback(Capture::Exit((
+                        ExitError::Other("precompile disabled".into()).into(),
+                        Vec::new(),
+                    )));
+                }
+
+                if let Some(ret) = T::Precompiles::execute(
+                    code_address,
+                    &input,
+                    Some(target_gas),
+                    &context,
+                    self.is_static || is_static,
+                ) {
                     log::debug!(
                         target: "evm",
                         "handler: call-result: precompile result {:?}",
@@ -620,23 +782,52 @@ impl<'vicinity, 'config, 'meter, T: Config> HandlerT for Handler<'vicinity, 'con
                     }
                 }
             },
-        )
-        .unwrap_or_else(|x| {
+        );
+
+        let result = result.unwrap_or_else(|x| {
             Capture::Exit((
                 ExitReason::Error(ExitError::Other(Into::<&'static str>::into(x).into())),
                 Vec::new(),
             ))
-        })
+        });
+
+        if let (Some(tracer), Capture::Exit((reason, output))) = (&self.tracer, &result) {
+            tracer.borrow_mut().exit(
+                reason.clone(),
+                output.clone(),
+                gas_before.saturating_sub(self.gasometer.gas()),
+            );
+        }
+
+        result
     }
 
     fn pre_validate(&mut self, context: &Context, opcode: Opcode, stack: &Stack) -> Result<(), ExitError> {
+        let gas_before = self.gasometer.gas();
+
         if let Some(cost) = gasometer::static_opcode_cost(opcode) {
             self.gasometer.record_cost(cost)?;
         } else {
-            let (gas_cost, memory_cost) =
+            let (gas_cost, target, memory_cost) =
                 gasometer::dynamic_opcode_cost(context.address, opcode, stack, self.is_static, self.config, self)?;
 
             self.gasometer.record_dynamic_cost(gas_cost, memory_cost)?;
+
+            match target {
+                StorageTarget::Address(address) => {
+                    self.accessed.addresses.insert(address);
+                }
+                StorageTarget::Slot(address, index) => {
+                    self.accessed.storage.insert((address, index));
+                }
+                StorageTarget::None => {}
+            }
+        }
+
+        if let Some(tracer) = &self.tracer {
+            tracer
+                .borrow_mut()
+                .step_cost(gas_before.saturating_sub(self.gasometer.gas()));
         }
         Ok(())
     }
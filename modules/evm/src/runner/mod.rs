@@ -1,7 +1,8 @@
 pub mod handler;
 pub mod storage_meter;
+pub mod tracer;
 
-use crate::{AddressMapping, BalanceOf, CallInfo, Config, CreateInfo, Error, Pallet, Vicinity};
+use crate::{AccessList, AddressMapping, BalanceOf, CallInfo, CallType, Config, CreateInfo, Error, Pallet, Vicinity};
 use evm::{CreateScheme, ExitError, ExitReason};
 use evm_gasometer::{self as gasometer};
 use evm_runtime::Handler as HandlerT;
@@ -10,7 +11,8 @@ use handler::Handler;
 use primitive_types::{H160, H256, U256};
 use sha3::{Digest, Keccak256};
 use sp_runtime::{traits::Zero, DispatchError, DispatchResult, SaturatedConversion, TransactionOutcome};
-use sp_std::{marker::PhantomData, vec::Vec};
+use sp_std::{cell::RefCell, marker::PhantomData, rc::Rc, vec::Vec};
+use tracer::Tracer;
 
 #[derive(Default)]
 pub struct Runner<T: Config> {
@@ -27,8 +29,13 @@ impl<T: Config> Runner<T> {
         assigned_address: Option<H160>,
         salt: Option<H256>,
         tag: &'static str,
+        gas_price: U256,
+        access_list: AccessList,
         config: &evm::Config,
+        tracer: Option<Rc<RefCell<Tracer>>>,
     ) -> Result<CreateInfo, DispatchError> {
+        let tracer = tracer.or_else(tracer::current);
+
         log::debug!(
             target: "evm",
             "{:?}: source {:?}, gas_limit: {:?}, storage_limit: {:?}",
@@ -39,7 +46,7 @@ impl<T: Config> Runner<T> {
         );
 
         let vicinity = Vicinity {
-            gas_price: U256::one(),
+            gas_price,
             origin: source,
         };
 
@@ -61,19 +68,37 @@ impl<T: Config> Runner<T> {
 
         Handler::<T>::inc_nonce(source);
 
-        Handler::<T>::run_transaction(
+        if let Some(tracer) = &tracer {
+            let call_type = if salt.is_some() {
+                CallType::Create2
+            } else {
+                CallType::Create
+            };
+            tracer.borrow_mut().enter(
+                call_type,
+                source,
+                address,
+                init.clone(),
+                U256::from(value.saturated_into::<u128>()),
+                gas_limit,
+            );
+        }
+
+        let result = Handler::<T>::run_transaction(
             &vicinity,
             gas_limit,
             storage_limit,
             address,
             false,
             config,
+            tracer.clone(),
             |substate| {
                 if let Err(e) = Self::transfer(source, address, value) {
                     return TransactionOutcome::Rollback(Err(e));
                 }
 
-                let transaction_cost = gasometer::call_transaction_cost(&init);
+                substate.warm_access_list(&access_list);
+                let transaction_cost = gasometer::call_transaction_cost(&init, &access_list);
                 if substate.gasometer.record_transaction(transaction_cost).is_err() {
                     return TransactionOutcome::Rollback(Err(DispatchError::Other("OutOfGas")));
                 }
@@ -133,7 +158,15 @@ impl<T: Config> Runner<T> {
 
                 TransactionOutcome::Commit(Ok(create_info))
             },
-        )?
+        )?;
+
+        if let (Some(tracer), Ok(info)) = (&tracer, &result) {
+            tracer
+                .borrow_mut()
+                .exit(info.exit_reason.clone(), info.output.clone(), info.used_gas.low_u64());
+        }
+
+        result
     }
 
     fn transfer(source: H160, target: H160, value: BalanceOf<T>) -> DispatchResult {
@@ -158,6 +191,39 @@ impl<T: Config> Runner<T> {
         storage_limit: u32,
         config: &evm::Config,
     ) -> Result<CallInfo, DispatchError> {
+        Self::traced_call(
+            sender,
+            origin,
+            target,
+            input,
+            value,
+            gas_limit,
+            storage_limit,
+            Pallet::<T>::base_fee_per_gas(),
+            Vec::new(),
+            config,
+            None,
+        )
+    }
+
+    /// Issue a call at `gas_price`, warming the addresses and storage slots
+    /// of `access_list`, and reporting the execution to `tracer`, or to the
+    /// current tracer if none.
+    pub fn traced_call(
+        sender: H160,
+        origin: H160,
+        target: H160,
+        input: Vec<u8>,
+        value: BalanceOf<T>,
+        gas_limit: u64,
+        storage_limit: u32,
+        gas_price: U256,
+        access_list: AccessList,
+        config: &evm::Config,
+        tracer: Option<Rc<RefCell<Tracer>>>,
+    ) -> Result<CallInfo, DispatchError> {
+        let tracer = tracer.or_else(tracer::current);
+
         log::debug!(
             target: "evm",
             "call: sender:{:?}, origin: {:?}, target: {:?}, input: {:?}, gas_limit: {:?}, storage_limit: {:?}",
@@ -169,10 +235,7 @@ impl<T: Config> Runner<T> {
             storage_limit,
         );
 
-        let vicinity = Vicinity {
-            gas_price: U256::one(),
-            origin,
-        };
+        let vicinity = Vicinity { gas_price, origin };
 
         // if the contract not deployed, the caller must be developer or contract.
         // if the contract not exists, let evm try to execute it and handle the error.
@@ -182,39 +245,68 @@ impl<T: Config> Runner<T> {
 
         Handler::<T>::inc_nonce(sender);
 
-        Handler::<T>::run_transaction(&vicinity, gas_limit, storage_limit, target, false, config, |substate| {
-            if let Err(e) = Self::transfer(sender, target, value) {
-                return TransactionOutcome::Rollback(Err(e));
-            }
-
-            let code = substate.code(target);
-            let transaction_cost = gasometer::call_transaction_cost(&code);
-            if substate.gasometer.record_transaction(transaction_cost).is_err() {
-                return TransactionOutcome::Rollback(Err(DispatchError::Other("OutOfGas")));
-            }
-
-            let (reason, out) =
-                substate.execute(sender, target, U256::from(value.saturated_into::<u128>()), code, input);
-
-            let call_info = CallInfo {
-                exit_reason: reason.clone(),
-                output: out,
-                used_gas: U256::from(substate.used_gas()),
-                used_storage: substate.used_storage(),
-            };
-
-            log::debug!(
-                target: "evm",
-                "call-result: call_info {:?}",
-                call_info
+        if let Some(tracer) = &tracer {
+            tracer.borrow_mut().enter(
+                CallType::Call,
+                sender,
+                target,
+                input.clone(),
+                U256::from(value.saturated_into::<u128>()),
+                gas_limit,
             );
+        }
 
-            if !reason.is_succeed() {
-                return TransactionOutcome::Rollback(Ok(call_info));
-            }
+        let result = Handler::<T>::run_transaction(
+            &vicinity,
+            gas_limit,
+            storage_limit,
+            target,
+            false,
+            config,
+            tracer.clone(),
+            |substate| {
+                if let Err(e) = Self::transfer(sender, target, value) {
+                    return TransactionOutcome::Rollback(Err(e));
+                }
+
+                let code = substate.code(target);
+                substate.warm_access_list(&access_list);
+                let transaction_cost = gasometer::call_transaction_cost(&code, &access_list);
+                if substate.gasometer.record_transaction(transaction_cost).is_err() {
+                    return TransactionOutcome::Rollback(Err(DispatchError::Other("OutOfGas")));
+                }
+
+                let (reason, out) =
+                    substate.execute(sender, target, U256::from(value.saturated_into::<u128>()), code, input);
+
+                let call_info = CallInfo {
+                    exit_reason: reason.clone(),
+                    output: out,
+                    used_gas: U256::from(substate.used_gas()),
+                    used_storage: substate.used_storage(),
+                };
+
+                log::debug!(
+                    target: "evm",
+                    "call-result: call_info {:?}",
+                    call_info
+                );
+
+                if !reason.is_succeed() {
+                    return TransactionOutcome::Rollback(Ok(call_info));
+                }
+
+                TransactionOutcome::Commit(Ok(call_info))
+            },
+        )?;
+
+        if let (Some(tracer), Ok(info)) = (&tracer, &result) {
+            tracer
+                .borrow_mut()
+                .exit(info.exit_reason.clone(), info.output.clone(), info.used_gas.low_u64());
+        }
 
-            TransactionOutcome::Commit(Ok(call_info))
-        })?
+        result
     }
 
     pub fn create(
@@ -234,7 +326,41 @@ impl<T: Config> Runner<T> {
             None,
             None,
             "create",
+            Pallet::<T>::base_fee_per_gas(),
+            Vec::new(),
             config,
+            None,
+        )
+    }
+
+    /// Issue a create, or a create2 with `salt`, at `gas_price`, warming the
+    /// addresses and storage slots of `access_list`, and reporting the
+    /// execution to `tracer`, or to the current tracer if none.
+    pub fn traced_create(
+        source: H160,
+        init: Vec<u8>,
+        salt: Option<H256>,
+        value: BalanceOf<T>,
+        gas_limit: u64,
+        storage_limit: u32,
+        gas_price: U256,
+        access_list: AccessList,
+        config: &evm::Config,
+        tracer: Option<Rc<RefCell<Tracer>>>,
+    ) -> Result<CreateInfo, DispatchError> {
+        Self::inner_create(
+            source,
+            init,
+            value,
+            gas_limit,
+            storage_limit,
+            None,
+            salt,
+            if salt.is_some() { "create2" } else { "create" },
+            gas_price,
+            access_list,
+            config,
+            tracer,
         )
     }
 
@@ -256,7 +382,10 @@ impl<T: Config> Runner<T> {
             None,
             Some(salt),
             "create2",
+            Pallet::<T>::base_fee_per_gas(),
+            Vec::new(),
             config,
+            None,
         )
     }
 
@@ -278,7 +407,10 @@ impl<T: Config> Runner<T> {
             Some(assigned_address),
             None,
             "create-system-contract",
+            Pallet::<T>::base_fee_per_gas(),
+            Vec::new(),
             config,
+            None,
         )
     }
 }
@@ -0,0 +1,183 @@
+//! Tracer of EVM executions.
+//!
+//! The tracer is shared by the handlers of all the call frames of an
+//! execution. The handlers report the call frames they enter and exit, and the
+//! opcodes they execute.
+//!
+//! Executions which are not given a tracer report to the tracer set by
+//! `using`, so the executions nested in other dispatches are traced too.
+
+use crate::{CallFrame, CallType, StructLog, Trace, TracerConfig};
+use evm::{ExitReason, Opcode};
+use primitive_types::{H160, H256, U256};
+use sp_std::{cell::RefCell, collections::btree_map::BTreeMap, rc::Rc, vec::Vec};
+
+/// Maximum size of the struct logs of a trace, in bytes of stack, memory and
+/// storage. The following steps are not recorded.
+pub const MAX_STRUCT_LOGS_SIZE: usize = 64 * 1024 * 1024;
+
+environmental::environmental!(current_tracer: Rc<RefCell<Tracer>>);
+
+/// Run `f`, the executions without a tracer report to `tracer`.
+pub fn using<R>(tracer: &Rc<RefCell<Tracer>>, f: impl FnOnce() -> R) -> R {
+    let mut tracer = tracer.clone();
+    current_tracer::using(&mut tracer, f)
+}
+
+/// The tracer set by `using`, if any.
+pub fn current() -> Option<Rc<RefCell<Tracer>>> {
+    current_tracer::with(|tracer| tracer.clone())
+}
+
+pub struct Tracer {
+    config: TracerConfig,
+    /// Entered call frames, the innermost last.
+    frames: Vec<CallFrame>,
+    /// Exited top level call frame.
+    root: Option<CallFrame>,
+    struct_logs: Vec<StructLog>,
+    /// Size of the recorded struct logs, in bytes of stack, memory and
+    /// storage.
+    struct_logs_size: usize,
+    /// Storage slots accessed so far, by contract.
+    storage: BTreeMap<H160, BTreeMap<H256, H256>>,
+    /// Opcode of the last step.
+    opcode: Option<Opcode>,
+}
+
+impl Tracer {
+    pub fn new(config: TracerConfig) -> Self {
+        Self {
+            config,
+            frames: Vec::new(),
+            root: None,
+            struct_logs: Vec::new(),
+            struct_logs_size: 0,
+            storage: BTreeMap::new(),
+            opcode: None,
+        }
+    }
+
+    /// Opcode of the last step, the opcode which entered a new call frame.
+    pub fn opcode(&self) -> Option<Opcode> {
+        self.opcode
+    }
+
+    /// Record a step of the contract at `address`, before the execution of
+    /// `opcode`. `storage` is the slot accessed by `SLOAD` and `SSTORE`, with
+    /// its value after the step.
+    pub fn step(
+        &mut self,
+        address: H160,
+        pc: usize,
+        opcode: Opcode,
+        gas: u64,
+        stack: &[H256],
+        memory: &[u8],
+        storage: Option<(H256, H256)>,
+    ) {
+        self.opcode = Some(opcode);
+
+        if let TracerConfig::StructLogger {
+            disable_stack,
+            disable_memory,
+            disable_storage,
+            limit,
+        } = self.config
+        {
+            if (limit != 0 && self.struct_logs.len() >= limit as usize) || self.struct_logs_size > MAX_STRUCT_LOGS_SIZE
+            {
+                return;
+            }
+
+            let storage: Option<Vec<(H256, H256)>> = match storage {
+                Some((index, value)) if !disable_storage => {
+                    let slots = self.storage.entry(address).or_default();
+                    slots.insert(index, value);
+                    Some(slots.iter().map(|(index, value)| (*index, *value)).collect())
+                }
+                _ => None,
+            };
+
+            if !disable_stack {
+                self.struct_logs_size += stack.len() * 32;
+            }
+            if !disable_memory {
+                self.struct_logs_size += memory.len();
+            }
+            self.struct_logs_size += storage.as_ref().map_or(0, |storage| storage.len() * 64);
+
+            self.struct_logs.push(StructLog {
+                pc: pc as u32,
+                op: opcode.as_u8(),
+                gas,
+                gas_cost: 0,
+                depth: self.frames.len() as u32,
+                stack: if disable_stack { None } else { Some(stack.to_vec()) },
+                memory: if disable_memory { None } else { Some(memory.to_vec()) },
+                storage,
+            });
+        }
+    }
+
+    /// Record the gas cost of the opcode of the last step.
+    pub fn step_cost(&mut self, gas_cost: u64) {
+        if let Some(log) = self.struct_logs.last_mut() {
+            log.gas_cost = gas_cost;
+        }
+    }
+
+    /// Enter a call frame.
+    pub fn enter(&mut self, call_type: CallType, from: H160, to: H160, input: Vec<u8>, value: U256, gas: u64) {
+        self.frames.push(CallFrame {
+            call_type,
+            from,
+            to,
+            input,
+            output: Vec::new(),
+            value,
+            gas,
+            gas_used: 0,
+            exit_reason: None,
+            calls: Vec::new(),
+        });
+    }
+
+    /// Exit the innermost call frame.
+    pub fn exit(&mut self, exit_reason: ExitReason, output: Vec<u8>, gas_used: u64) {
+        if let Some(mut frame) = self.frames.pop() {
+            frame.exit_reason = Some(exit_reason);
+            frame.output = output;
+            frame.gas_used = gas_used;
+
+            match self.frames.last_mut() {
+                Some(parent) => parent.calls.push(frame),
+                // the first top level execution is the traced one
+                None if self.root.is_none() => self.root = Some(frame),
+                None => {}
+            }
+        }
+    }
+
+    /// Returns the trace, `None` if no call frame was entered.
+    pub fn finish(mut self) -> Option<Trace> {
+        // frames of an aborted execution are never exited
+        while self.frames.len() > 1 {
+            if let Some(frame) = self.frames.pop() {
+                if let Some(parent) = self.frames.last_mut() {
+                    parent.calls.push(frame);
+                }
+            }
+        }
+
+        let call = match self.root.take() {
+            Some(root) => root,
+            None => self.frames.pop()?,
+        };
+
+        Some(Trace {
+            call,
+            struct_logs: self.struct_logs,
+        })
+    }
+}
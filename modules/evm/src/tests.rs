@@ -4,7 +4,8 @@ use super::*;
 use mock::{Event, *};
 
 use crate::runner::handler::Handler;
-use frame_support::{assert_noop, assert_ok};
+use evm::Opcode;
+use frame_support::{assert_noop, assert_ok, traits::LockableCurrency, weights::constants::RocksDbWeight};
 use sp_core::{
     bytes::{from_hex, to_hex},
     H160,
@@ -530,10 +531,12 @@ fn create_network_contract_works() {
             U256::from_str("02").unwrap()
         );
 
-        let created_event = Event::EVM(crate::Event::Created(H160::from_low_u64_be(
-            primitives::NETWORK_CONTRACT_START,
+        let contract_address = H160::from_low_u64_be(primitives::NETWORK_CONTRACT_START);
+        assert!(System::events().iter().any(|record| matches!(
+            record.event,
+            Event::EVM(crate::Event::Created(from, contract, _))
+                if from == NetworkContractSource::get() && contract == contract_address
         )));
-        assert!(System::events().iter().any(|record| record.event == created_event));
 
         assert_eq!(EVM::network_contract_index(), primitives::NETWORK_CONTRACT_START + 1);
     });
@@ -884,6 +887,133 @@ fn should_set_code() {
     });
 }
 
+#[test]
+fn set_code_should_replace_contract_code() {
+    new_test_ext().execute_with(|| {
+        let alice_account_id = <Test as Config>::AddressMapping::get_account_id(&alice());
+        let contract = H160::from_str("2000000000000000000000000000000000000003").unwrap();
+        let code = vec![0x01, 0x00];
+
+        assert_ok!(EVM::on_contract_initialization(&contract, &alice(), vec![0x00]));
+        assert_ok!(EVM::set_code(Origin::signed(alice_account_id), contract, code.clone()));
+
+        assert_eq!(
+            EVM::accounts(contract).unwrap().contract_info.unwrap().code_hash,
+            code_hash(&code)
+        );
+        assert_eq!(EVM::code_at_address(&contract), code);
+    });
+}
+
+#[test]
+fn should_rollback_code() {
+    new_test_ext().execute_with(|| {
+        let alice_account_id = <Test as Config>::AddressMapping::get_account_id(&alice());
+        let bob_account_id = <Test as Config>::AddressMapping::get_account_id(&bob());
+        let contract = H160::from_str("2000000000000000000000000000000000000003").unwrap();
+        let codes = [vec![0x00], vec![0x01, 0x00], vec![0x02, 0x00], vec![0x03, 0x00]];
+        let hashes: Vec<H256> = codes.iter().map(|code| code_hash(code)).collect();
+        let ref_count = |index: usize| EVM::code_infos(hashes[index]).map(|code_info| code_info.ref_count);
+
+        assert_ok!(EVM::on_contract_initialization(&contract, &alice(), codes[0].clone()));
+
+        assert_ok!(EVM::set_code(
+            Origin::signed(alice_account_id.clone()),
+            contract,
+            codes[1].clone()
+        ));
+        let event = Event::EVM(crate::Event::ContractSetCode(contract, hashes[0], hashes[1]));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(EVM::code_at_address(&contract), codes[1]);
+        assert_eq!(
+            EVM::code_history(contract),
+            vec![CodeHistoryEntry {
+                code_hash: hashes[0],
+                block_number: None,
+                who: None,
+            }]
+        );
+        assert_eq!(EVM::code_set_by(contract), Some((Some(alice_account_id.clone()), 1)));
+        assert_eq!(ref_count(0), Some(1));
+        assert_eq!(ref_count(1), Some(1));
+
+        System::set_block_number(2);
+        assert_ok!(EVM::set_code(Origin::root(), contract, codes[2].clone()));
+        assert_eq!(
+            EVM::code_history(contract)[1],
+            CodeHistoryEntry {
+                code_hash: hashes[1],
+                block_number: Some(1),
+                who: Some(alice_account_id.clone()),
+            }
+        );
+        assert_eq!(EVM::code_set_by(contract), Some((None, 2)));
+
+        // the oldest code leaves the history
+        System::set_block_number(3);
+        assert_ok!(EVM::set_code(
+            Origin::signed(alice_account_id.clone()),
+            contract,
+            codes[3].clone()
+        ));
+        assert_eq!(
+            EVM::code_history(contract)
+                .iter()
+                .map(|entry| entry.code_hash)
+                .collect::<Vec<_>>(),
+            vec![hashes[1], hashes[2]]
+        );
+        assert_eq!(ref_count(0), None);
+        assert!(!Codes::<Test>::contains_key(hashes[0]));
+
+        assert_noop!(
+            EVM::rollback_code(Origin::signed(bob_account_id), contract, hashes[1]),
+            Error::<Test>::NoPermission
+        );
+        assert_noop!(
+            EVM::rollback_code(Origin::signed(alice_account_id.clone()), contract, hashes[0]),
+            Error::<Test>::CodeNotInHistory
+        );
+
+        System::set_block_number(4);
+        assert_ok!(EVM::rollback_code(
+            Origin::signed(alice_account_id.clone()),
+            contract,
+            hashes[1]
+        ));
+        let event = Event::EVM(crate::Event::ContractCodeRolledBack(contract, hashes[3], hashes[1]));
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(EVM::code_at_address(&contract), codes[1]);
+        assert_eq!(
+            EVM::code_history(contract)
+                .iter()
+                .map(|entry| entry.code_hash)
+                .collect::<Vec<_>>(),
+            vec![hashes[2], hashes[3]]
+        );
+        assert_eq!(
+            EVM::code_history(contract)[1],
+            CodeHistoryEntry {
+                code_hash: hashes[3],
+                block_number: Some(3),
+                who: Some(alice_account_id.clone()),
+            }
+        );
+        assert_eq!(EVM::code_set_by(contract), Some((Some(alice_account_id.clone()), 4)));
+        assert_eq!(ref_count(1), Some(1));
+        assert_eq!(ref_count(2), Some(1));
+        assert_eq!(ref_count(3), Some(1));
+
+        // the codes of the history are released with the contract
+        assert_ok!(EVM::selfdestruct(Origin::signed(alice_account_id), contract));
+        assert_eq!(EVM::code_history(contract), vec![]);
+        assert_eq!(EVM::code_set_by(contract), None);
+        assert_eq!(ref_count(1), None);
+        assert_eq!(ref_count(2), None);
+        assert_eq!(ref_count(3), None);
+    });
+}
+
 #[test]
 fn should_selfdestruct() {
     // pragma solidity ^0.5.0;
@@ -923,6 +1053,72 @@ fn should_selfdestruct() {
     });
 }
 
+#[test]
+fn should_remove_contract_storage_on_idle() {
+    // store 1 in the slots 1, 2 and 3
+    let init = from_hex("0x60016001556001600255600160035500").unwrap();
+
+    let mut ext = new_test_ext();
+    let (contract, alice_balance) = ext.execute_with(|| {
+        let result = Runner::<Test>::create(alice(), init, 0, 1000000, 1000000, <Test as Config>::config()).unwrap();
+        assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Stopped));
+        let contract = result.address;
+        assert_eq!(result.used_storage, 3 * 64 + 100);
+        assert_eq!(reserved_balance(contract), 292 * 10);
+
+        let alice_balance = balance(alice());
+        assert_ok!(EVM::selfdestruct(
+            Origin::signed(<Test as Config>::AddressMapping::get_account_id(&alice())),
+            contract
+        ));
+        assert_eq!(EVM::accounts(contract), None);
+        assert_eq!(EVM::contracts_to_remove(contract), Some(alice()));
+        assert_eq!(AccountStorages::<Test>::iter_prefix(contract).count(), 3);
+        // the storage deposit is paid out with the balance of the contract
+        assert_eq!(reserved_balance(contract), 0);
+        assert_eq!(balance(alice()), alice_balance + 292 * 10);
+
+        // the address can't be reused before the storage is removed
+        assert_eq!(
+            Handler::<Test>::create_address(evm::CreateScheme::Fixed(contract)),
+            Err(ExitError::CreateCollision)
+        );
+
+        (contract, balance(alice()))
+    });
+    // limited removals only apply to the committed storage
+    ext.commit_all().unwrap();
+
+    ext.execute_with(|| {
+        let db_weight = RocksDbWeight::get();
+        let contract_weight = db_weight.reads(1) + db_weight.reads_writes(4, 4);
+
+        // one entry at a time
+        let weight = contract_weight + db_weight.writes(1);
+        assert_eq!(EVM::remove_contracts_storage(weight, db_weight), weight);
+        assert_eq!(AccountStorages::<Test>::iter_prefix(contract).count(), 2);
+        assert_eq!(EVM::contracts_to_remove(contract), Some(alice()));
+
+        assert_eq!(
+            EVM::remove_contracts_storage(Weight::max_value(), db_weight),
+            contract_weight + db_weight.writes(2)
+        );
+        assert_eq!(AccountStorages::<Test>::iter_prefix(contract).count(), 0);
+        assert_eq!(EVM::contracts_to_remove(contract), None);
+        assert_eq!(balance(alice()), alice_balance);
+
+        let event = Event::EVM(crate::Event::ContractStorageRemoved(contract, alice()));
+        assert!(System::events().iter().any(|record| record.event == event));
+
+        // nothing left to remove
+        assert_eq!(
+            EVM::remove_contracts_storage(Weight::max_value(), db_weight),
+            db_weight.reads(1)
+        );
+        assert_eq!(EVM::on_idle(1, Weight::max_value()), 0);
+    });
+}
+
 #[test]
 fn storage_limit_should_work() {
     // pragma solidity ^0.5.0;
@@ -1197,3 +1393,740 @@ fn evm_execute_mode_should_work() {
         assert_eq!(balance(alice()), alice_balance);
     });
 }
+
+#[test]
+fn extrinsic_receipts_should_work() {
+    let log = |address: H160| Log {
+        address,
+        topics: vec![H256::repeat_byte(1)],
+        data: vec![1, 2, 3],
+    };
+
+    let events = vec![
+        (0, crate::Event::<Test>::ContractDeployed(contract_a())),
+        (1, crate::Event::<Test>::Log(log(contract_a()))),
+        (1, crate::Event::<Test>::Executed(alice(), contract_a(), 100)),
+        (1, crate::Event::<Test>::Log(log(contract_b()))),
+        (
+            1,
+            crate::Event::<Test>::ExecutedFailed(
+                contract_a(),
+                contract_b(),
+                ExitReason::Revert(ExitRevert::Reverted),
+                vec![],
+                50,
+            ),
+        ),
+        (
+            3,
+            crate::Event::<Test>::EthTransactionApplied(H256::repeat_byte(2), bob()),
+        ),
+        (3, crate::Event::<Test>::Created(bob(), contract_b(), 200)),
+    ];
+
+    assert_eq!(
+        EVM::extrinsic_receipts(events),
+        vec![
+            ExtrinsicReceipt {
+                extrinsic_index: 1,
+                transaction_hash: None,
+                from: alice(),
+                to: Some(contract_a()),
+                contract_address: None,
+                used_gas: 150,
+                succeed: false,
+                logs: vec![log(contract_a()), log(contract_b())],
+            },
+            ExtrinsicReceipt {
+                extrinsic_index: 3,
+                transaction_hash: Some(H256::repeat_byte(2)),
+                from: bob(),
+                to: None,
+                contract_address: Some(contract_b()),
+                used_gas: 200,
+                succeed: true,
+                logs: vec![],
+            },
+        ]
+    );
+}
+
+// EIP-155 example transaction, signed for chain id 1 by
+// 0x9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f
+const EIP155_TRANSACTION: &str = "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83";
+
+#[test]
+fn legacy_transaction_should_work() {
+    let raw = from_hex(EIP155_TRANSACTION).unwrap();
+    let tx = LegacyTransaction::decode(&raw).unwrap();
+
+    assert_eq!(tx.nonce, U256::from(9));
+    assert_eq!(tx.gas_price, U256::from(20_000_000_000u64));
+    assert_eq!(tx.gas_limit, U256::from(21_000));
+    assert_eq!(
+        tx.action,
+        TransactionAction::Call(H160::from_str("3535353535353535353535353535353535353535").unwrap())
+    );
+    assert_eq!(tx.value, U256::from(1_000_000_000_000_000_000u64));
+    assert_eq!(tx.chain_id(), Some(1));
+    assert_eq!(
+        tx.signing_hash(),
+        H256::from_str("daf5a779ae972f972197303d7b574746c7ef83eadac0f2791ad23db92e4c8e53").unwrap()
+    );
+    assert_eq!(
+        tx.recover_signer(),
+        Some(H160::from_str("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").unwrap())
+    );
+    assert_eq!(tx.encode(), raw);
+
+    // trailing bytes
+    assert!(LegacyTransaction::decode(&[raw.clone(), vec![0]].concat()).is_err());
+
+    // signed for another chain
+    let mut other_chain = tx.clone();
+    other_chain.v += 2;
+    assert_ne!(
+        other_chain.recover_signer(),
+        Some(H160::from_str("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").unwrap())
+    );
+
+    // high s value
+    let mut high_s = tx;
+    high_s.s = U256::MAX - high_s.s;
+    assert_eq!(high_s.recover_signer(), None);
+}
+
+#[test]
+fn eth_transact_should_work() {
+    new_test_ext().execute_with(|| {
+        let raw = from_hex(EIP155_TRANSACTION).unwrap();
+        let sender = H160::from_str("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").unwrap();
+        let target = H160::from_str("3535353535353535353535353535353535353535").unwrap();
+        let call = crate::Call::<Test>::eth_transact(raw.clone());
+
+        // not funded
+        assert_eq!(
+            EVM::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::Payment.into()
+        );
+
+        let _ = Balances::deposit_creating(
+            &<Test as Config>::AddressMapping::get_account_id(&sender),
+            2_000_000_000_000_000_000,
+        );
+
+        // future nonce
+        assert_eq!(
+            EVM::validate_unsigned(TransactionSource::External, &call),
+            ValidTransaction::with_tag_prefix("EvmEthTransaction")
+                .priority(20_000_000_000)
+                .and_provides((sender, U256::from(9)))
+                .and_requires((sender, U256::from(8)))
+                .propagate(true)
+                .build()
+        );
+        assert_eq!(EVM::pre_dispatch(&call), Err(InvalidTransaction::Future.into()));
+        assert_noop!(
+            EVM::eth_transact(Origin::none(), raw.clone()),
+            Error::<Test>::InvalidNonce
+        );
+
+        Accounts::<Test>::insert(sender, EvmAccountInfo::<Test>::new(9, None));
+        assert_eq!(
+            EVM::validate_unsigned(TransactionSource::External, &call),
+            ValidTransaction::with_tag_prefix("EvmEthTransaction")
+                .priority(20_000_000_000)
+                .and_provides((sender, U256::from(9)))
+                .propagate(true)
+                .build()
+        );
+        assert_ok!(EVM::pre_dispatch(&call));
+
+        assert_ok!(EVM::eth_transact(Origin::none(), raw.clone()));
+
+        let hash = H256::from(sp_io::hashing::keccak_256(&raw));
+        let event = Event::EVM(crate::Event::EthTransactionApplied(hash, sender));
+        assert!(System::events().iter().any(|record| record.event == event));
+        let event = Event::EVM(crate::Event::Executed(sender, target, 21_000));
+        assert!(System::events().iter().any(|record| record.event == event));
+
+        // the value, the fee of the used gas and the length fee are paid
+        assert_eq!(
+            balance(sender),
+            2_000_000_000_000_000_000
+                - 1_000_000_000_000_000_000
+                - 21_000 * 20_000_000_000
+                - raw.len() as u64 * TransactionByteFee::get()
+        );
+        assert_eq!(reserved_balance(sender), 0);
+        assert_eq!(balance(target), 1_000_000_000_000_000_000);
+        assert_eq!(EVM::account_basic(&sender).nonce, U256::from(10));
+
+        // replay
+        assert_eq!(
+            EVM::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::Stale.into()
+        );
+        assert_noop!(
+            EVM::eth_transact(Origin::none(), raw.clone()),
+            Error::<Test>::InvalidNonce
+        );
+        assert_noop!(
+            EVM::eth_transact(Origin::signed(AccountId32::from([1u8; 32])), raw),
+            BadOrigin
+        );
+    });
+}
+
+#[test]
+fn eth_transact_validation_should_follow_dispatch() {
+    new_test_ext().execute_with(|| {
+        let raw = from_hex(EIP155_TRANSACTION).unwrap();
+        let sender = H160::from_str("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").unwrap();
+        let who = <Test as Config>::AddressMapping::get_account_id(&sender);
+        let call = crate::Call::<Test>::eth_transact(raw.clone());
+
+        Accounts::<Test>::insert(sender, EvmAccountInfo::<Test>::new(9, None));
+
+        // the length fee is not covered
+        let _ = Balances::deposit_creating(&who, 1_000_000_000_000_000_000 + 21_000 * 20_000_000_000);
+        assert_eq!(
+            EVM::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::Payment.into()
+        );
+        assert_noop!(
+            EVM::eth_transact(Origin::none(), raw.clone()),
+            Error::<Test>::ChargeFeeFailed
+        );
+
+        // the fee is locked
+        let _ = Balances::deposit_creating(&who, 1_000_000_000_000_000_000);
+        Balances::set_lock(*b"testlock", &who, 2_000_000_000_000_000_000, WithdrawReasons::FEE);
+        assert_eq!(
+            EVM::validate_unsigned(TransactionSource::External, &call),
+            InvalidTransaction::Payment.into()
+        );
+        assert_noop!(
+            EVM::eth_transact(Origin::none(), raw.clone()),
+            Error::<Test>::ChargeFeeFailed
+        );
+
+        Balances::remove_lock(*b"testlock", &who);
+        assert_ok!(EVM::pre_dispatch(&call));
+        assert_ok!(EVM::eth_transact(Origin::none(), raw));
+    });
+}
+
+#[test]
+fn eth_transact_failing_execution_should_pay() {
+    new_test_ext().execute_with(|| {
+        let raw = from_hex(EIP155_TRANSACTION).unwrap();
+        let sender = H160::from_str("9d8a62f656a8d1615c1294fd71e9cfb3e4855a4f").unwrap();
+        let target = H160::from_str("3535353535353535353535353535353535353535").unwrap();
+        let who = <Test as Config>::AddressMapping::get_account_id(&sender);
+
+        Accounts::<Test>::insert(sender, EvmAccountInfo::<Test>::new(9, None));
+        let _ = Balances::deposit_creating(&who, 2_000_000_000_000_000_000);
+        // the value can not be transferred
+        Balances::set_lock(*b"testlock", &who, 2_000_000_000_000_000_000, WithdrawReasons::TRANSFER);
+        assert_ok!(EVM::pre_dispatch(&crate::Call::<Test>::eth_transact(raw.clone())));
+
+        assert_ok!(EVM::eth_transact(Origin::none(), raw.clone()));
+
+        assert!(System::events().iter().any(|record| matches!(
+            record.event,
+            Event::EVM(crate::Event::ExecutedFailed(from, to, ExitReason::Error(_), _, 21_000)) if from == sender && to == target
+        )));
+
+        // the nonce is consumed and the whole gas limit is charged
+        assert_eq!(EVM::account_basic(&sender).nonce, U256::from(10));
+        assert_eq!(
+            balance(sender),
+            2_000_000_000_000_000_000 - 21_000 * 20_000_000_000 - raw.len() as u64 * TransactionByteFee::get()
+        );
+        assert_eq!(balance(target), 0);
+
+        // no free replay
+        assert_eq!(
+            EVM::validate_unsigned(
+                TransactionSource::External,
+                &crate::Call::<Test>::eth_transact(raw.clone())
+            ),
+            InvalidTransaction::Stale.into()
+        );
+    });
+}
+
+#[test]
+fn trace_should_work() {
+    // Factory of `contract_should_deploy_contracts`
+    let contract = from_hex("0x608060405234801561001057600080fd5b5061016f806100206000396000f3fe608060405260043610610041576000357c0100000000000000000000000000000000000000000000000000000000900463ffffffff168063412a5a6d14610046575b600080fd5b61004e610050565b005b600061005a6100e2565b604051809103906000f080158015610076573d6000803e3d6000fd5b50905060008190806001815401808255809150509060018203906000526020600020016000909192909190916101000a81548173ffffffffffffffffffffffffffffffffffffffff021916908373ffffffffffffffffffffffffffffffffffffffff1602179055505050565b6040516052806100f28339019056fe6080604052348015600f57600080fd5b50603580601d6000396000f3fe6080604052600080fdfea165627a7a7230582092dc1966a8880ddf11e067f9dd56a632c11a78a4afd4a9f05924d427367958cc0029a165627a7a723058202b2cc7384e11c452cdbf39b68dada2d5e10a632cc0174a354b8b8c83237e28a40029").unwrap();
+    new_test_ext().execute_with(|| {
+        let trace = EVM::trace(
+            alice(),
+            None,
+            contract,
+            0,
+            1000000000,
+            1000000000,
+            TracerConfig::CallTracer,
+        )
+        .unwrap();
+        assert_eq!(trace.call.call_type, CallType::Create);
+        assert_eq!(trace.call.from, alice());
+        assert_eq!(trace.call.exit_reason, Some(ExitReason::Succeed(ExitSucceed::Returned)));
+        assert!(trace.call.calls.is_empty());
+        assert!(trace.struct_logs.is_empty());
+        let factory = trace.call.to;
+
+        #[cfg(not(feature = "with-ethereum-compatibility"))]
+        deploy_free(factory);
+
+        // Factory.createContract
+        let create_contract = from_hex("0x412a5a6d").unwrap();
+        let trace = EVM::trace(
+            alice(),
+            Some(factory),
+            create_contract.clone(),
+            0,
+            1000000000,
+            1000000000,
+            TracerConfig::CallTracer,
+        )
+        .unwrap();
+        assert_eq!(trace.call.call_type, CallType::Call);
+        assert_eq!(trace.call.to, factory);
+        assert_eq!(trace.call.input, create_contract);
+        assert_eq!(trace.call.exit_reason, Some(ExitReason::Succeed(ExitSucceed::Stopped)));
+        assert_eq!(trace.call.calls.len(), 1);
+        let created = &trace.call.calls[0];
+        assert_eq!(created.call_type, CallType::Create);
+        assert_eq!(created.from, factory);
+        assert_eq!(
+            created.to,
+            H160::from_str("7b8f8ca099f6e33cf1817cf67d0556429cfc54e4").unwrap()
+        );
+        assert_eq!(created.exit_reason, Some(ExitReason::Succeed(ExitSucceed::Returned)));
+        assert!(created.gas_used > 0 && created.gas_used < trace.call.gas_used);
+
+        let trace = EVM::trace(
+            alice(),
+            Some(factory),
+            create_contract.clone(),
+            0,
+            1000000000,
+            1000000000,
+            TracerConfig::StructLogger {
+                disable_stack: false,
+                disable_memory: true,
+                disable_storage: false,
+                limit: 0,
+            },
+        )
+        .unwrap();
+        assert_eq!(trace.call.calls.len(), 1);
+        let first = &trace.struct_logs[0];
+        assert_eq!((first.pc, first.op, first.depth), (0, Opcode::PUSH1.as_u8(), 1));
+        assert!(first.gas < 1000000000 - 21_000);
+        assert_eq!(first.gas_cost, 3);
+        assert_eq!(first.stack, Some(vec![]));
+        assert_eq!(first.memory, None);
+        assert!(trace.struct_logs.iter().any(|log| log.depth == 2));
+        // the array length is stored at slot 0
+        let sstore = trace
+            .struct_logs
+            .iter()
+            .find(|log| log.op == Opcode::SSTORE.as_u8())
+            .unwrap();
+        assert!(sstore
+            .storage
+            .as_ref()
+            .unwrap()
+            .contains(&(H256::zero(), H256::from_low_u64_be(2))));
+
+        let struct_logs = trace.struct_logs.len();
+        assert!(struct_logs > 10);
+
+        // limit of the struct logs
+        let trace = EVM::trace(
+            alice(),
+            Some(factory),
+            create_contract.clone(),
+            0,
+            1000000000,
+            1000000000,
+            TracerConfig::StructLogger {
+                disable_stack: true,
+                disable_memory: true,
+                disable_storage: true,
+                limit: 10,
+            },
+        )
+        .unwrap();
+        assert_eq!(trace.struct_logs.len(), 10);
+        assert_eq!(trace.struct_logs[0].stack, None);
+
+        // executions nested in a dispatch report to the tracer
+        let trace = EVM::trace_executions(TracerConfig::CallTracer, || {
+            EVM::call(
+                Origin::signed(<Test as Config>::AddressMapping::get_account_id(&alice())),
+                factory,
+                create_contract,
+                0,
+                1000000000,
+                1000000000,
+            )
+            .map(|_| ())
+            .map_err(|e| e.error)
+        })
+        .unwrap();
+        assert_eq!(trace.call.to, factory);
+        assert_eq!(trace.call.calls.len(), 1);
+
+        assert_eq!(
+            EVM::trace_executions(TracerConfig::CallTracer, || Ok(())),
+            Err(DispatchError::Other("No EVM execution"))
+        );
+    });
+}
+
+#[test]
+fn with_state_override_should_work() {
+    new_test_ext().execute_with(|| {
+        let contract = H160::from_str("2000000000000000000000000000000000000002").unwrap();
+        // return the storage slot 1
+        let code = from_hex("0x60015460005260206000f3").unwrap();
+        let value = H256::from_low_u64_be(0x2a);
+
+        let state_override = vec![(
+            contract,
+            AccountOverride {
+                balance: Some(1000),
+                nonce: Some(5),
+                code: Some(code.clone()),
+                state: None,
+                state_diff: vec![(H256::from_low_u64_be(1), value)],
+            },
+        )];
+
+        let info = EVM::with_state_override(state_override, || {
+            assert_eq!(balance(contract), 1000);
+            assert_eq!(EVM::account_basic(&contract).nonce, U256::from(5));
+            assert_eq!(EVM::code_at_address(&contract), code);
+
+            Runner::<Test>::call(
+                alice(),
+                alice(),
+                contract,
+                vec![],
+                0,
+                1000000,
+                1000000,
+                <Test as Config>::config(),
+            )
+        })
+        .unwrap();
+        assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+        assert_eq!(info.output, value.as_bytes().to_vec());
+
+        // every change is reverted
+        assert_eq!(balance(contract), 0);
+        assert_eq!(EVM::account_basic(&contract).nonce, U256::zero());
+        assert!(EVM::code_at_address(&contract).is_empty());
+        assert_eq!(
+            EVM::account_storages(contract, H256::from_low_u64_be(1)),
+            H256::default()
+        );
+        assert_eq!(EVM::account_basic(&alice()).nonce, U256::from(1));
+        assert!(!Codes::<Test>::contains_key(&code_hash(&code)));
+
+        // the error of the contract initialization is kept
+        let state_override = vec![(
+            contract,
+            AccountOverride {
+                code: Some(vec![0u8; MaxCodeSize::get() as usize + 1]),
+                ..Default::default()
+            },
+        )];
+        assert_eq!(
+            EVM::with_state_override(state_override, || Ok(())),
+            Err(DispatchError::Other("OutOfGas"))
+        );
+    });
+}
+
+#[test]
+fn exit_error_to_dispatch_error_should_work() {
+    for (error, reason) in [
+        (ExitError::StackUnderflow, "StackUnderflow"),
+        (ExitError::StackOverflow, "StackOverflow"),
+        (ExitError::InvalidJump, "InvalidJump"),
+        (ExitError::InvalidRange, "InvalidRange"),
+        (ExitError::DesignatedInvalid, "DesignatedInvalid"),
+        (ExitError::CallTooDeep, "CallTooDeep"),
+        (ExitError::CreateCollision, "CreateCollision"),
+        (ExitError::CreateContractLimit, "CreateContractLimit"),
+        (ExitError::InvalidCode, "InvalidCode"),
+        (ExitError::OutOfOffset, "OutOfOffset"),
+        (ExitError::OutOfGas, "OutOfGas"),
+        (ExitError::OutOfFund, "OutOfFund"),
+        (ExitError::PCUnderflow, "PCUnderflow"),
+        (ExitError::CreateEmpty, "CreateEmpty"),
+        (ExitError::Other("reason".into()), "reason"),
+        (ExitError::Other(String::from("reason").into()), "Other"),
+    ] {
+        assert_eq!(exit_error_to_dispatch_error(error), DispatchError::Other(reason));
+    }
+}
+
+#[test]
+fn original_storage_should_work() {
+    // store 1 and then 2 in the slot 0
+    let code = from_hex("0x6001600055600260005500").unwrap();
+    // return the code above
+    let init = [from_hex("0x600b600c600039600b6000f3").unwrap(), code.clone()].concat();
+
+    new_test_ext().execute_with(|| {
+        let result = Runner::<Test>::create(alice(), init, 0, 1000000, 1000000, &LONDON_CONFIG).unwrap();
+        assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+        let contract = result.address;
+        assert_eq!(EVM::code_at_address(&contract), code);
+
+        #[cfg(not(feature = "with-ethereum-compatibility"))]
+        deploy_free(contract);
+
+        let call = |value: u64| {
+            AccountStorages::<Test>::insert(contract, H256::zero(), H256::from_low_u64_be(value));
+            let info =
+                Runner::<Test>::call(alice(), alice(), contract, vec![], 0, 1000000, 1000000, &LONDON_CONFIG).unwrap();
+            assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Stopped));
+            assert_eq!(EVM::account_storages(contract, H256::zero()), H256::from_low_u64_be(2));
+            info.used_gas.as_u64()
+        };
+
+        // cold sstore of a new value (2100 + 20000), then a dirty one (100)
+        let set_cost = call(0);
+        // cold sstore of a changed value (2100 + 2900), then a dirty one (100)
+        let reset_cost = call(5);
+        assert_eq!(set_cost - reset_cost, 17100);
+    });
+}
+
+#[test]
+fn block_context_should_work() {
+    new_test_ext().execute_with(|| {
+        let contract = H160::from_str("2000000000000000000000000000000000000003").unwrap();
+        // return COINBASE, GASLIMIT and DIFFICULTY
+        let code = from_hex("0x41600052456020524460405260606000f3").unwrap();
+        assert_ok!(EVM::on_contract_initialization(&contract, &alice(), code));
+        #[cfg(not(feature = "with-ethereum-compatibility"))]
+        assert_ok!(EVM::mark_deployed(contract, None));
+
+        let info = Runner::<Test>::call(
+            alice(),
+            alice(),
+            contract,
+            vec![],
+            0,
+            1000000,
+            1000000,
+            <Test as Config>::config(),
+        )
+        .unwrap();
+        assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+
+        assert_eq!(info.output.len(), 96);
+        assert_eq!(&info.output[12..32], bob().as_bytes());
+        assert_eq!(U256::from(&info.output[32..64]), U256::from(10_000_000));
+        let (seed, _) = <Test as Config>::Randomness::random_seed();
+        assert_eq!(&info.output[64..96], seed.as_bytes());
+    });
+}
+
+#[test]
+fn base_fee_should_work() {
+    new_test_ext().execute_with(|| {
+        assert_eq!(EVM::base_fee_per_gas(), U256::from(1));
+
+        EVM::on_finalize(1);
+        assert_eq!(EVM::base_fee_multiplier(), FixedU128::saturating_from_integer(2));
+        assert_eq!(EVM::base_fee_per_gas(), U256::from(2));
+
+        let origin = Origin::signed(<Test as Config>::AddressMapping::get_account_id(&alice()));
+        assert_noop!(
+            EVM::dynamic_fee_call(origin.clone(), contract_a(), vec![], 0, 100000, 0, 1, 0, vec![]),
+            Error::<Test>::GasPriceTooLow
+        );
+        assert_noop!(
+            EVM::dynamic_fee_call(origin, contract_a(), vec![], 0, 100000, 0, 2, 3, vec![]),
+            Error::<Test>::PriorityFeeTooHigh
+        );
+
+        // return GASPRICE and BASEFEE
+        let contract = H160::from_str("2000000000000000000000000000000000000003").unwrap();
+        let code = from_hex("0x3a6000524860205260406000f3").unwrap();
+        assert_ok!(EVM::on_contract_initialization(&contract, &alice(), code));
+        #[cfg(not(feature = "with-ethereum-compatibility"))]
+        assert_ok!(EVM::mark_deployed(contract, None));
+
+        let info =
+            Runner::<Test>::call(alice(), alice(), contract, vec![], 0, 1000000, 1000000, &LONDON_CONFIG).unwrap();
+        assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+        assert_eq!(
+            info.output,
+            [H256::from_low_u64_be(2).as_bytes(), H256::from_low_u64_be(2).as_bytes()].concat()
+        );
+
+        let info = Runner::<Test>::traced_call(
+            alice(),
+            alice(),
+            contract,
+            vec![],
+            0,
+            1000000,
+            1000000,
+            U256::from(5),
+            vec![],
+            &LONDON_CONFIG,
+            None,
+        )
+        .unwrap();
+        assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+        assert_eq!(
+            info.output,
+            [H256::from_low_u64_be(5).as_bytes(), H256::from_low_u64_be(2).as_bytes()].concat()
+        );
+    });
+}
+
+#[test]
+fn dynamic_fee_call_should_work() {
+    new_test_ext().execute_with(|| {
+        BaseFeeMultiplier::<Test>::put(FixedU128::saturating_from_integer(2));
+        let alice_balance = balance(alice());
+        let bob_balance = balance(bob());
+
+        // pays the base fee of 2 and a tip of 3, under the max fee of 10
+        assert_ok!(EVM::dynamic_fee_call(
+            Origin::signed(<Test as Config>::AddressMapping::get_account_id(&alice())),
+            contract_a(),
+            vec![],
+            0,
+            100000,
+            0,
+            10,
+            3,
+            vec![(contract_a(), vec![H256::zero()])],
+        ));
+
+        let used_gas = System::events()
+            .iter()
+            .find_map(|record| match record.event {
+                Event::EVM(crate::Event::Executed(from, to, used_gas)) if from == alice() && to == contract_a() => {
+                    Some(used_gas)
+                }
+                _ => None,
+            })
+            .unwrap();
+        assert!(used_gas > 0);
+
+        // the base fee is burned, the tip goes to the block author
+        assert_eq!(balance(alice()), alice_balance - used_gas * 5);
+        assert_eq!(balance(bob()), bob_balance + used_gas * 3);
+    });
+}
+
+/// Tests on a runtime with the standard Ethereum precompiles.
+mod with_precompiles {
+    use super::*;
+    use crate::mock::with_precompiles::{new_test_ext, Event, Origin, System, Test, EVM};
+
+    #[test]
+    fn only_precompiles_should_be_warm() {
+        new_test_ext().execute_with(|| {
+            let contract = H160::from_str("2000000000000000000000000000000000000004").unwrap();
+            // BALANCE of the address in the input
+            let code = from_hex("0x6000353100").unwrap();
+            assert_ok!(EVM::on_contract_initialization(&contract, &alice(), code));
+            #[cfg(not(feature = "with-ethereum-compatibility"))]
+            assert_ok!(EVM::mark_deployed(contract, None));
+
+            let balance_of = |address: H160| {
+                let info = Runner::<Test>::call(
+                    alice(),
+                    alice(),
+                    contract,
+                    H256::from(address).as_bytes().to_vec(),
+                    0,
+                    1000000,
+                    1000000,
+                    &LONDON_CONFIG,
+                )
+                .unwrap();
+                assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Stopped));
+                info.used_gas.as_u64()
+            };
+
+            // ECRecover is warm (100), an address of the precompile range which is
+            // not a precompile is cold (2600)
+            assert_eq!(
+                balance_of(H160::from_low_u64_be(10)) - balance_of(H160::from_low_u64_be(1)),
+                2500
+            );
+        });
+    }
+
+    #[test]
+    fn set_precompile_enabled_should_work() {
+        // call the identity precompile and return whether the call succeeded
+        let contract = from_hex("0x601680600b6000396000f36000600060006000600060045af160005260206000f3").unwrap();
+
+        new_test_ext().execute_with(|| {
+            let identity = H160::from_low_u64_be(4);
+
+            let result =
+                Runner::<Test>::create(alice(), contract, 0, 1000000, 1000000, <Test as Config>::config()).unwrap();
+            assert_eq!(result.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+            let contract_address = result.address;
+            #[cfg(not(feature = "with-ethereum-compatibility"))]
+            assert_ok!(EVM::mark_deployed(contract_address, None));
+
+            let call = || {
+                Runner::<Test>::call(
+                    alice(),
+                    alice(),
+                    contract_address,
+                    vec![],
+                    0,
+                    1000000,
+                    1000000,
+                    <Test as Config>::config(),
+                )
+                .unwrap()
+                .output
+            };
+            assert_eq!(U256::from(call().as_slice()), U256::one());
+
+            assert_noop!(
+                EVM::set_precompile_enabled(Origin::signed(AccountId32::from([1u8; 32])), identity, false),
+                BadOrigin
+            );
+            assert_noop!(
+                EVM::set_precompile_enabled(Origin::root(), H160::from_low_u64_be(10), false),
+                Error::<Test>::PrecompileNotFound
+            );
+
+            assert_ok!(EVM::set_precompile_enabled(Origin::root(), identity, false));
+            assert!(EVM::disabled_precompiles(identity));
+            let event = Event::EVM(crate::Event::PrecompileDisabled(identity));
+            assert!(System::events().iter().any(|record| record.event == event));
+            assert_eq!(U256::from(call().as_slice()), U256::zero());
+
+            assert_ok!(EVM::set_precompile_enabled(Origin::root(), identity, true));
+            assert!(!DisabledPrecompiles::<Test>::contains_key(identity));
+            let event = Event::EVM(crate::Event::PrecompileEnabled(identity));
+            assert!(System::events().iter().any(|record| record.event == event));
+            assert_eq!(U256::from(call().as_slice()), U256::one());
+        });
+    }
+}
@@ -0,0 +1,152 @@
+//! Raw signed Ethereum transactions.
+//!
+//! Only legacy transactions are supported, with or without the EIP-155
+//! replay protection.
+
+use primitive_types::{H160, H256, U256};
+use rlp::{DecoderError, Rlp, RlpStream};
+use sha3::{Digest, Keccak256};
+use sp_std::vec::Vec;
+
+use crate::EvmAddress;
+
+/// Half of the order of the secp256k1 curve, upper bound of `s` (EIP-2).
+const SECP256K1N_HALF: U256 = U256([
+    0xdfe92f46681b20a0,
+    0x5d576e7357a4501d,
+    0xffffffffffffffff,
+    0x7fffffffffffffff,
+]);
+
+/// Action of an Ethereum transaction.
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum TransactionAction {
+    /// Message call to the address.
+    Call(EvmAddress),
+    /// Contract creation.
+    Create,
+}
+
+/// A signed legacy Ethereum transaction.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct LegacyTransaction {
+    pub nonce: U256,
+    pub gas_price: U256,
+    pub gas_limit: U256,
+    pub action: TransactionAction,
+    pub value: U256,
+    pub input: Vec<u8>,
+    pub v: u64,
+    pub r: U256,
+    pub s: U256,
+}
+
+impl LegacyTransaction {
+    /// Decode a RLP encoded signed transaction.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecoderError> {
+        let rlp = Rlp::new(bytes);
+        if !rlp.is_list() {
+            return Err(DecoderError::RlpExpectedToBeList);
+        }
+        if rlp.item_count()? != 9 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        // trailing bytes are not part of the transaction
+        if rlp.as_raw().len() != bytes.len() {
+            return Err(DecoderError::RlpInconsistentLengthAndData);
+        }
+
+        let to = rlp.at(3)?;
+        let action = if to.is_empty() {
+            TransactionAction::Create
+        } else {
+            TransactionAction::Call(to.as_val::<H160>()?)
+        };
+
+        Ok(Self {
+            nonce: rlp.val_at(0)?,
+            gas_price: rlp.val_at(1)?,
+            gas_limit: rlp.val_at(2)?,
+            action,
+            value: rlp.val_at(4)?,
+            input: rlp.val_at(5)?,
+            v: rlp.val_at(6)?,
+            r: rlp.val_at(7)?,
+            s: rlp.val_at(8)?,
+        })
+    }
+
+    /// RLP encode the signed transaction.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(9);
+        self.append_unsigned(&mut stream);
+        stream.append(&self.v);
+        stream.append(&self.r);
+        stream.append(&self.s);
+        stream.out().to_vec()
+    }
+
+    /// Chain id of the EIP-155 replay protection, `None` for a transaction
+    /// valid on any chain.
+    pub fn chain_id(&self) -> Option<u64> {
+        if self.v >= 35 {
+            Some((self.v - 35) / 2)
+        } else {
+            None
+        }
+    }
+
+    /// Hash signed by the sender.
+    pub fn signing_hash(&self) -> H256 {
+        let stream = match self.chain_id() {
+            Some(chain_id) => {
+                let mut stream = RlpStream::new_list(9);
+                self.append_unsigned(&mut stream);
+                stream.append(&chain_id);
+                stream.append(&0u8);
+                stream.append(&0u8);
+                stream
+            }
+            None => {
+                let mut stream = RlpStream::new_list(6);
+                self.append_unsigned(&mut stream);
+                stream
+            }
+        };
+        H256::from_slice(Keccak256::digest(&stream.out()).as_slice())
+    }
+
+    /// Recover the address of the sender from the signature.
+    ///
+    /// Returns `None` for an invalid signature, including a malleable one
+    /// with a high `s` value.
+    pub fn recover_signer(&self) -> Option<EvmAddress> {
+        let recovery_id = match self.chain_id() {
+            Some(chain_id) => self.v.checked_sub(chain_id.checked_mul(2)?.checked_add(35)?)?,
+            None => self.v.checked_sub(27)?,
+        };
+        if recovery_id > 1 || self.r.is_zero() || self.s.is_zero() || self.s > SECP256K1N_HALF {
+            return None;
+        }
+
+        let mut sig = [0u8; 65];
+        self.r.to_big_endian(&mut sig[0..32]);
+        self.s.to_big_endian(&mut sig[32..64]);
+        sig[64] = recovery_id as u8;
+
+        let pubkey = sp_io::crypto::secp256k1_ecdsa_recover(&sig, &self.signing_hash().0).ok()?;
+        Some(H160::from_slice(&sp_io::hashing::keccak_256(&pubkey)[12..]))
+    }
+
+    fn append_unsigned(&self, stream: &mut RlpStream) {
+        stream.append(&self.nonce);
+        stream.append(&self.gas_price);
+        stream.append(&self.gas_limit);
+        match self.action {
+            TransactionAction::Call(target) => stream.append(&target),
+            TransactionAction::Create => stream.append_empty_data(),
+        };
+        stream.append(&self.value);
+        stream.append(&self.input);
+    }
+}
@@ -0,0 +1,15 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::all)]
+
+use sp_runtime::codec::Codec;
+use sp_std::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+    pub trait PocApi<AccountId, Balance> where
+        AccountId: Codec,
+        Balance: Codec,
+    {
+        /// Returns the unclaimed voter rewards of `who`, by commitment id.
+        fn pending_rewards(who: AccountId) -> Vec<(u32, Balance)>;
+    }
+}
@@ -3,7 +3,20 @@
 use crate::*;
 use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
 use frame_system::RawOrigin;
-use primitives::{currency::OVR, time::DAYS};
+use primitives::{
+    currency::OVR,
+    time::{DAYS, MILLISECS_PER_DAY},
+};
+
+/// End `eras` eras, from the start of the current one.
+fn end_eras<T: Config>(eras: u32) {
+    let era_duration = MomentOf::<T>::unique_saturated_from(T::EraDuration::get());
+    for _ in 0..eras {
+        let now = Pallet::<T>::current_era().start.saturating_add(era_duration);
+        pallet_timestamp::Pallet::<T>::set_timestamp(now);
+        Pallet::<T>::on_initialize(frame_system::Pallet::<T>::block_number());
+    }
+}
 
 benchmarks! {
     where_clause { where BalanceOf<T>: From<u128> }
@@ -15,11 +28,12 @@ benchmarks! {
     }
 
     on_initialize_era {
-        // benchmark election worst-case (everyone votes for everyone else who is a valid candidate)
-        // TODO: commitments are weakly capped - 1k is a conservative ceiling. re-run if surpassed
+        // benchmark election worst-case (every candidate is voted for, and
+        // an era of rewards expires)
         // TODO: if MaxMembers changes (number of winners), this benchmark should re-run
-        let c in 0..1_000;
-        for i in 0..1_000 {
+        let c in 0 .. T::MaxCandidates::get();
+        end_eras::<T>(T::HistoryDepth::get().saturating_add(1));
+        for i in 0..c {
             let voter: T::AccountId = account("voter", i, 0);
             let candidate: T::AccountId = account("candidate", i, 0);
             T::Currency::deposit_creating(&voter, BalanceOf::<T>::from(100_001 * OVR));
@@ -40,7 +54,7 @@ benchmarks! {
 
     }: {
         // trigger the era change block
-        Pallet::<T>::on_initialize((7 * DAYS).into());
+        end_eras::<T>(1);
     }
 
     start_candidacy {
@@ -95,7 +109,10 @@ benchmarks! {
             bob
         );
 
-    }: _(RawOrigin::Signed(alice), amount)
+        // her rewards are pending for the whole history
+        end_eras::<T>(T::HistoryDepth::get().saturating_add(1));
+
+    }: _(RawOrigin::Signed(alice), 0, amount)
 
     unbond {
         let alice: T::AccountId = account("alice", 0, 0);
@@ -115,7 +132,10 @@ benchmarks! {
             bob
         );
 
-    }: _(RawOrigin::Signed(alice))
+        // her rewards are pending for the whole history
+        end_eras::<T>(T::HistoryDepth::get().saturating_add(1));
+
+    }: _(RawOrigin::Signed(alice), 0)
 
     withdraw {
         let alice: T::AccountId = account("alice", 0, 0);
@@ -138,12 +158,14 @@ benchmarks! {
         // she unbonds
         let _ = Pallet::<T>::unbond(
             RawOrigin::Signed(alice.clone()).into(),
+            0,
         );
 
         // skip 1 month
         frame_system::Pallet::<T>::set_block_number((31 * DAYS).into());
+        pallet_timestamp::Pallet::<T>::set_timestamp(MomentOf::<T>::unique_saturated_from(31 * MILLISECS_PER_DAY));
 
-    }: _(RawOrigin::Signed(alice))
+    }: _(RawOrigin::Signed(alice), 0)
 
     vote_candidate {
         let alice: T::AccountId = account("alice", 0, 0);
@@ -164,7 +186,64 @@ benchmarks! {
             bob
         );
 
-    }: _(RawOrigin::Signed(alice), charlie)
+    }: _(RawOrigin::Signed(alice), 0, charlie)
+
+    claim_rewards {
+        let alice: T::AccountId = account("alice", 0, 0);
+        let bob: T::AccountId = account("bob", 0, 0);
+
+        // alice needs funds
+        let deposit: BalanceOf<T> = BalanceOf::<T>::from(200_001 * OVR);
+        T::Currency::deposit_creating(&alice, deposit);
+
+        let amount: BalanceOf<T> = BalanceOf::<T>::from(100_000 * OVR);
+
+        // she makes initial commitment
+        let _ = Pallet::<T>::commit(
+            RawOrigin::Signed(alice.clone()).into(),
+            amount,
+            LockDuration::OneYear,
+            bob
+        );
+
+        // her rewards are pending for the whole history
+        end_eras::<T>(T::HistoryDepth::get().saturating_add(1));
+
+    }: _(RawOrigin::Signed(alice), 0)
+
+    slash_candidate {
+        // slash every backing commitment
+        let b in 0 .. 100;
+        let alice: T::AccountId = account("alice", 0, 0);
+
+        // alice needs funds
+        let deposit: BalanceOf<T> = BalanceOf::<T>::from(1_000_001 * OVR);
+        T::Currency::deposit_creating(&alice, deposit);
+
+        let _ = Pallet::<T>::start_candidacy(
+            RawOrigin::Signed(alice.clone()).into(),
+        );
+
+        let mut backers = Vec::new();
+        for i in 0..b {
+            let voter: T::AccountId = account("voter", i, 0);
+            T::Currency::deposit_creating(&voter, BalanceOf::<T>::from(100_001 * OVR));
+
+            let amount: BalanceOf<T> = BalanceOf::<T>::from(100_000 * OVR);
+            let _ = Pallet::<T>::commit(
+                RawOrigin::Signed(voter.clone()).into(),
+                amount,
+                LockDuration::OneYear,
+                alice.clone()
+            );
+            backers.push((voter, 0));
+        }
+
+        // alice is elected, and the rewards of the backers are pending for
+        // the whole history
+        end_eras::<T>(T::HistoryDepth::get().saturating_add(1));
+
+    }: _(RawOrigin::Root, alice, Perbill::from_percent(50), Perbill::from_percent(10), backers)
 
 }
 
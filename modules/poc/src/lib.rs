@@ -6,22 +6,28 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![allow(clippy::unused_unit)]
 
-use frame_support::sp_runtime::traits::{CheckedAdd, CheckedDiv, Saturating, Zero};
+use frame_support::sp_runtime::traits::{
+    CheckedAdd, CheckedDiv, Saturating, UniqueSaturatedFrom, UniqueSaturatedInto, Zero,
+};
 use frame_support::{
     ensure,
     pallet_prelude::*,
-    traits::{ChangeMembers, Currency, ExistenceRequirement, IsType, ReservableCurrency, WithdrawReasons},
+    traits::{
+        ChangeMembers, Currency, EnsureOrigin, ExistenceRequirement, Imbalance, IsType, OnUnbalanced,
+        ReservableCurrency, StorageVersion, WithdrawReasons,
+    },
     transactional,
     weights::Weight,
 };
 use frame_system::pallet_prelude::*;
-use sp_runtime::{PerThing, Perbill};
+use sp_runtime::{FixedPointNumber, FixedU128, PerThing, Perbill};
 use sp_std::prelude::*;
 
 #[cfg(feature = "std")]
 pub use serde::{Deserialize, Serialize};
 
 mod benchmarking;
+pub mod migrations;
 mod mock;
 mod tests;
 pub mod weights;
@@ -29,17 +35,20 @@ pub mod weights;
 pub use module::*;
 
 pub type EraIndex = u32;
+pub type CommitmentId = u32;
 pub type BalanceOf<T> = <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
-pub type CommitmentOf<T> =
-    Commitment<<T as frame_system::Config>::AccountId, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+pub type NegativeImbalanceOf<T> =
+    <<T as Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::NegativeImbalance;
+pub type MomentOf<T> = <T as pallet_timestamp::Config>::Moment;
+pub type CommitmentOf<T> = Commitment<<T as frame_system::Config>::AccountId, BalanceOf<T>, MomentOf<T>>;
 
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, Clone, PartialEq)]
-pub enum LockState<BlockNumber> {
+pub enum LockState<Moment> {
     /// Locked /w voting power
     Committed,
-    /// BlockNumber when Unbonding period started
-    Unbonding(BlockNumber),
+    /// Timestamp when Unbonding period started
+    Unbonding(Moment),
 }
 
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -52,8 +61,8 @@ pub enum LockDuration {
 
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, Clone, Default)]
-pub struct Commitment<AccountId, BalanceOf, BlockNumber> {
-    pub state: LockState<BlockNumber>,
+pub struct Commitment<AccountId, BalanceOf, Moment> {
+    pub state: LockState<Moment>,
     pub duration: LockDuration,
     pub amount: BalanceOf,
     pub candidate: AccountId,
@@ -61,12 +70,12 @@ pub struct Commitment<AccountId, BalanceOf, BlockNumber> {
 
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 #[derive(Encode, Decode, Clone, Default)]
-pub struct Era<BlockNumber> {
+pub struct Era<Moment> {
     pub index: EraIndex,
-    pub start: BlockNumber,
+    pub start: Moment,
 }
 
-impl<BlockNumber> Default for LockState<BlockNumber> {
+impl<Moment> Default for LockState<Moment> {
     fn default() -> Self {
         Self::Committed
     }
@@ -78,6 +87,18 @@ impl Default for LockDuration {
     }
 }
 
+impl LockDuration {
+    /// How long (in milliseconds) the funds stay locked after unbonding.
+    pub fn lock_period(&self) -> primitives::Moment {
+        let days = match self {
+            LockDuration::OneMonth => 30,
+            LockDuration::OneYear => 365,
+            LockDuration::TenYears => 3650,
+        };
+        days * primitives::time::MILLISECS_PER_DAY
+    }
+}
+
 pub trait WeightInfo {
     fn start_candidacy() -> Weight;
     fn stop_candidacy() -> Weight;
@@ -86,6 +107,8 @@ pub trait WeightInfo {
     fn unbond() -> Weight;
     fn withdraw() -> Weight;
     fn vote_candidate() -> Weight;
+    fn claim_rewards() -> Weight;
+    fn slash_candidate(b: u32) -> Weight;
     fn on_initialize_era(c: u32) -> Weight;
     fn on_initialize_empty() -> Weight;
 }
@@ -93,18 +116,19 @@ pub trait WeightInfo {
 #[frame_support::pallet]
 pub mod module {
     use super::*;
-    use sp_std::collections::btree_map::BTreeMap;
-    use sp_std::iter::FromIterator;
 
     #[pallet::config]
-    pub trait Config: frame_system::Config {
+    pub trait Config: frame_system::Config + pallet_timestamp::Config {
         type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
         type WeightInfo: WeightInfo;
         /// Reservable currency for Candidacy bonds
         type Currency: Currency<Self::AccountId> + ReservableCurrency<Self::AccountId>;
-        /// How long (in block count) is the era
+        /// How long (in milliseconds) is the era
+        #[pallet::constant]
+        type EraDuration: Get<primitives::Moment>;
+        /// The expected block time (in milliseconds), used to convert block numbers to timestamps
         #[pallet::constant]
-        type EraDuration: Get<primitives::BlockNumber>;
+        type MillisecsPerBlock: Get<primitives::Moment>;
         /// Yearly nominator returns in % APY
         #[pallet::constant]
         type NominatorAPY: Get<Perbill>;
@@ -126,10 +150,23 @@ pub mod module {
         /// How many tech council members are we voting in.
         #[pallet::constant]
         type MaxMembers: Get<u32>;
+        /// How many ended eras of voter rewards can be claimed.
+        #[pallet::constant]
+        type HistoryDepth: Get<EraIndex>;
+        /// How many commitments an account can have at once.
+        #[pallet::constant]
+        type MaxCommitmentsPerAccount: Get<u32>;
         /// The receiver of the signal for when the membership has changed.
         type MembershipChanged: ChangeMembers<Self::AccountId>;
+        /// The origin which may slash candidates.
+        type SlashOrigin: EnsureOrigin<Self::Origin>;
+        /// Handler for the slashed deposits and commitments.
+        type Slash: OnUnbalanced<NegativeImbalanceOf<Self>>;
     }
 
+    /// The current storage version.
+    const STORAGE_VERSION: StorageVersion = StorageVersion::new(4);
+
     #[pallet::error]
     pub enum Error<T> {
         /// Account is already running as a candidate
@@ -140,7 +177,7 @@ pub mod module {
         CannotLeave,
         /// Already have maximum allowed number of candidates
         MaxCandidatesReached,
-        /// Account already has an active commitment
+        /// The commitment is still active
         AlreadyCommitted,
         /// Cannot operate on a non existing commitment
         CommitmentNotFound,
@@ -152,6 +189,14 @@ pub mod module {
         InsufficientAmount,
         /// The PoC system already has maximum amount committed
         OverSubscribed,
+        /// The backers do not cover all the commitments voting for the candidate
+        IncompleteBackers,
+        /// The account already has the maximum allowed number of commitments
+        MaxCommitmentsReached,
+        /// The account has run out of commitment ids
+        CommitmentIdOverflow,
+        /// The storage is being migrated
+        Migrating,
     }
 
     #[pallet::event]
@@ -163,23 +208,27 @@ pub mod module {
         /// Stop candidacy
         CandidateRemoved(T::AccountId),
         /// Created a new committment
-        Committed(T::AccountId, BalanceOf<T>),
+        Committed(T::AccountId, CommitmentId, BalanceOf<T>),
         /// Add more funds to existing commitment
-        FundsAdded(T::AccountId, BalanceOf<T>),
+        FundsAdded(T::AccountId, CommitmentId, BalanceOf<T>),
         /// The user has started the unbonding process
-        UnbondingStarted(T::AccountId, BalanceOf<T>),
+        UnbondingStarted(T::AccountId, CommitmentId, BalanceOf<T>),
         /// Bond has been withdrawn
-        BondWithdrawn(T::AccountId, BalanceOf<T>),
-        /// Voter,Candidate,VotingPower
-        Voted(T::AccountId, T::AccountId, BalanceOf<T>),
-        /// Voter,Reward
-        VoterRewarded(EraIndex, T::AccountId, BalanceOf<T>),
+        BondWithdrawn(T::AccountId, CommitmentId, BalanceOf<T>),
+        /// Voter,Commitment,Candidate,VotingPower
+        Voted(T::AccountId, CommitmentId, T::AccountId, BalanceOf<T>),
+        /// Voter,Commitment,Reward
+        VoterRewarded(T::AccountId, CommitmentId, BalanceOf<T>),
         /// Era, Winner,Weight
         Elected(EraIndex, T::AccountId, BalanceOf<T>),
+        /// Candidate,Slashed
+        CandidateSlashed(T::AccountId, BalanceOf<T>),
+        /// Voter,Commitment,Slashed
+        CommitmentSlashed(T::AccountId, CommitmentId, BalanceOf<T>),
     }
 
     #[pallet::type_value]
-    pub(super) fn FirstEra<T: Config>() -> Era<T::BlockNumber> {
+    pub(super) fn FirstEra<T: Config>() -> Era<MomentOf<T>> {
         Era {
             index: (0 as u32),
             start: (0 as u32).into(),
@@ -188,16 +237,45 @@ pub mod module {
 
     #[pallet::storage]
     #[pallet::getter(fn current_era)]
-    pub(super) type CurrentEra<T: Config> = StorageValue<_, Era<T::BlockNumber>, ValueQuery, FirstEra<T>>;
+    pub(super) type CurrentEra<T: Config> = StorageValue<_, Era<MomentOf<T>>, ValueQuery, FirstEra<T>>;
+
+    /// The cumulative voter reward per committed unit of the eras before an
+    /// era, kept for the last `HistoryDepth` eras.
+    ///
+    /// RewardIndex: map EraIndex => FixedU128
+    #[pallet::storage]
+    #[pallet::getter(fn reward_index)]
+    pub type RewardIndex<T: Config> = StorageMap<_, Twox64Concat, EraIndex, FixedU128, ValueQuery>;
 
+    /// The first era a commitment has not been rewarded for.
+    ///
+    /// RewardCheckpoints: double_map AccountId, CommitmentId => EraIndex
     #[pallet::storage]
-    #[pallet::getter(fn voter_rewards)]
-    pub(crate) type VoterRewards<T: Config> =
-        StorageDoubleMap<_, Blake2_128Concat, EraIndex, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+    #[pallet::getter(fn reward_checkpoints)]
+    pub type RewardCheckpoints<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, CommitmentId, EraIndex, ValueQuery>;
 
+    /// The commitments of an account.
+    ///
+    /// Commitments: double_map AccountId, CommitmentId => Commitment
     #[pallet::storage]
     #[pallet::getter(fn commitments)]
-    pub(crate) type Commitments<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, CommitmentOf<T>, ValueQuery>;
+    pub(crate) type Commitments<T: Config> =
+        StorageDoubleMap<_, Blake2_128Concat, T::AccountId, Twox64Concat, CommitmentId, CommitmentOf<T>, ValueQuery>;
+
+    /// The id of the next commitment of an account.
+    ///
+    /// NextCommitmentId: map AccountId => CommitmentId
+    #[pallet::storage]
+    #[pallet::getter(fn next_commitment_id)]
+    pub type NextCommitmentId<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, CommitmentId, ValueQuery>;
+
+    /// The number of commitments of an account.
+    ///
+    /// CommitmentsCount: map AccountId => u32
+    #[pallet::storage]
+    #[pallet::getter(fn commitments_count)]
+    pub type CommitmentsCount<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, u32, ValueQuery>;
 
     #[pallet::storage]
     #[pallet::getter(fn members)]
@@ -207,6 +285,14 @@ pub mod module {
     #[pallet::getter(fn candidates)]
     pub type Candidates<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
 
+    /// The running vote tally of the accounts voted for, by the voting weight
+    /// of the commitments voting for them.
+    ///
+    /// CandidateVotes: map AccountId => Balance
+    #[pallet::storage]
+    #[pallet::getter(fn candidate_votes)]
+    pub type CandidateVotes<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, BalanceOf<T>, ValueQuery>;
+
     #[pallet::storage]
     #[pallet::getter(fn n_candidates)]
     pub type CandidatesCount<T: Config> = StorageValue<_, u32, ValueQuery, DefaultCandidates<T>>;
@@ -225,50 +311,75 @@ pub mod module {
         Zero::zero()
     }
 
+    /// The progress of the `v4` migration, present while it runs.
+    #[pallet::storage]
+    pub(crate) type MigrationProgress<T: Config> = StorageValue<_, migrations::v4::ProgressOf<T>, OptionQuery>;
+
     #[pallet::pallet]
+    #[pallet::storage_version(STORAGE_VERSION)]
     pub struct Pallet<T>(PhantomData<T>);
 
     #[pallet::hooks]
     impl<T: Config> Hooks<T::BlockNumber> for Pallet<T> {
-        fn on_initialize(n: T::BlockNumber) -> Weight {
+        fn on_runtime_upgrade() -> Weight {
+            let mut weight = T::DbWeight::get().reads(1);
+            let version = StorageVersion::get::<Pallet<T>>();
+            if version < 4 {
+                weight = weight.saturating_add(migrations::v4::start::<T>());
+            }
+            if version < STORAGE_VERSION {
+                STORAGE_VERSION.put::<Pallet<T>>();
+                weight = weight.saturating_add(T::DbWeight::get().writes(1));
+            }
+            weight
+        }
+
+        fn on_idle(_n: T::BlockNumber, remaining_weight: Weight) -> Weight {
+            migrations::v4::step::<T>(remaining_weight)
+        }
+
+        fn on_initialize(_n: T::BlockNumber) -> Weight {
+            // the elections wait for the votes to be tallied
+            if <MigrationProgress<T>>::exists() {
+                return T::WeightInfo::on_initialize_empty();
+            }
+
             let current_era = <CurrentEra<T>>::get();
-            let era_duration: T::BlockNumber = T::BlockNumber::from(T::EraDuration::get());
+            let era_duration = MomentOf::<T>::unique_saturated_from(T::EraDuration::get());
+            // the timestamp is not set yet, this is the one of the previous block
+            let now = pallet_timestamp::Pallet::<T>::get();
 
             // baseline weight for execution without era change
             let mut weight: Weight = T::WeightInfo::on_initialize_empty();
 
-            if n >= current_era.start + era_duration {
+            if now >= current_era.start.saturating_add(era_duration) {
                 // move the era forward
                 let new_era_index = current_era.index.saturating_add(1);
                 let new_era = Era {
                     index: new_era_index,
-                    start: n,
+                    start: now,
                 };
                 <CurrentEra<T>>::set(new_era);
 
-                // clear old voter rewards (to save space)
-                <VoterRewards<T>>::remove_prefix(&current_era.index, None);
-
-                // set winners on new era
-                let mut counter: BTreeMap<T::AccountId, BalanceOf<T>> = BTreeMap::new();
-                let mut commitment_count: u32 = 0;
-                for (_, c) in <Commitments<T>>::iter() {
-                    // check if the candidate is running
-                    if !<Candidates<T>>::contains_key(&c.candidate) {
-                        continue;
-                    }
-                    // accumulate the votes by appropriate voting power
-                    if counter.contains_key(&c.candidate) {
-                        let acc_w = *counter.get(&c.candidate).unwrap();
-                        counter.insert(c.candidate.clone(), Self::voting_weight(&c).saturating_add(acc_w));
-                    } else {
-                        counter.insert(c.candidate.clone(), Self::voting_weight(&c));
-                    }
-                    // used for weight calc
-                    commitment_count += 1;
+                // add the voter rewards of the ended era to the index
+                let index = <RewardIndex<T>>::get(current_era.index).saturating_add(Self::era_voter_reward_rate());
+                <RewardIndex<T>>::insert(new_era_index, index);
+                // prune the eras out of the history depth
+                if let Some(expired) = new_era_index.checked_sub(T::HistoryDepth::get().saturating_add(1)) {
+                    <RewardIndex<T>>::remove(expired);
                 }
-                let mut sorted = Vec::from_iter(counter);
-                sorted.sort_by(|&(_, a), &(_, b)| b.cmp(&a));
+
+                // set winners on new era, by the running vote tallies of the candidates
+                let candidate_count = <CandidatesCount<T>>::get();
+                let mut sorted: Vec<(T::AccountId, BalanceOf<T>)> = <Candidates<T>>::iter_keys()
+                    .map(|candidate| {
+                        let votes = <CandidateVotes<T>>::get(&candidate);
+                        (candidate, votes)
+                    })
+                    .filter(|(_, votes)| !votes.is_zero())
+                    .collect();
+                // most votes first, ties broken by account to stay deterministic
+                sorted.sort_by(|(a, a_votes), (b, b_votes)| b_votes.cmp(a_votes).then_with(|| a.cmp(b)));
 
                 let mut winners: Vec<T::AccountId> = Vec::new();
                 for (candidate, weight) in sorted.iter().take(T::MaxMembers::get() as usize) {
@@ -299,7 +410,7 @@ pub mod module {
                 }
 
                 // accumulate the worst-case weights
-                weight = T::WeightInfo::on_initialize_era(commitment_count);
+                weight = T::WeightInfo::on_initialize_era(candidate_count);
             }
             weight
         }
@@ -352,12 +463,17 @@ pub mod module {
             candidate: T::AccountId,
         ) -> DispatchResultWithPostInfo {
             let origin = ensure_signed(origin)?;
-
-            ensure!(!<Commitments<T>>::contains_key(&origin), Error::<T>::AlreadyCommitted);
+            ensure!(!<MigrationProgress<T>>::exists(), Error::<T>::Migrating);
 
             // impose a minimum bond size (to make election computation faster)
             ensure!(amount >= T::MinLockAmount::get(), Error::<T>::InsufficientAmount);
 
+            let count = <CommitmentsCount<T>>::get(&origin);
+            ensure!(
+                count < T::MaxCommitmentsPerAccount::get(),
+                Error::<T>::MaxCommitmentsReached
+            );
+
             // check if at total locking capacity
             let locked_total = <LockedAmount<T>>::get().saturating_add(amount);
             ensure!(locked_total < T::TotalLockedCap::get(), Error::<T>::OverSubscribed);
@@ -373,29 +489,45 @@ pub mod module {
             <LockedAmount<T>>::set(locked_total);
 
             // create a new commitment
-            <Commitments<T>>::insert(
-                &origin,
-                Commitment {
-                    duration,
-                    amount,
-                    candidate,
-                    ..Default::default()
-                },
-            );
-            Self::deposit_event(Event::Committed(origin, amount));
+            let id = <NextCommitmentId<T>>::get(&origin);
+            let next_id = id.checked_add(1).ok_or(Error::<T>::CommitmentIdOverflow)?;
+            <NextCommitmentId<T>>::insert(&origin, next_id);
+            <CommitmentsCount<T>>::insert(&origin, count.saturating_add(1));
+            let commitment = Commitment {
+                duration,
+                amount,
+                candidate,
+                ..Default::default()
+            };
+            Self::update_votes(None, Some(&commitment));
+            <Commitments<T>>::insert(&origin, id, commitment);
+            <RewardCheckpoints<T>>::insert(&origin, id, <CurrentEra<T>>::get().index);
+            Self::deposit_event(Event::Committed(origin, id, amount));
             Ok(().into())
         }
 
         #[pallet::weight(T::WeightInfo::add_funds())]
         #[transactional]
-        pub fn add_funds(origin: OriginFor<T>, #[pallet::compact] amount: BalanceOf<T>) -> DispatchResultWithPostInfo {
+        pub fn add_funds(
+            origin: OriginFor<T>,
+            id: CommitmentId,
+            #[pallet::compact] amount: BalanceOf<T>,
+        ) -> DispatchResultWithPostInfo {
             let origin = ensure_signed(origin)?;
+            ensure!(!<MigrationProgress<T>>::exists(), Error::<T>::Migrating);
 
-            ensure!(<Commitments<T>>::contains_key(&origin), Error::<T>::CommitmentNotFound);
-            let mut commitment = <Commitments<T>>::get(&origin);
+            ensure!(
+                <Commitments<T>>::contains_key(&origin, id),
+                Error::<T>::CommitmentNotFound
+            );
+            let mut commitment = <Commitments<T>>::get(&origin, id);
+            let old_commitment = commitment.clone();
 
             ensure!(amount >= Zero::zero(), Error::<T>::InsufficientAmount);
 
+            // the rewards so far are for the previous amount
+            Self::pay_rewards(&origin, id, &commitment)?;
+
             // check if at total locking capacity
             let locked_total = <LockedAmount<T>>::get().saturating_add(amount);
             ensure!(locked_total < T::TotalLockedCap::get(), Error::<T>::OverSubscribed);
@@ -415,61 +547,76 @@ pub mod module {
             commitment.state = LockState::Committed;
 
             // save the commitment
-            <Commitments<T>>::insert(&origin, commitment);
+            Self::update_votes(Some(&old_commitment), Some(&commitment));
+            <Commitments<T>>::insert(&origin, id, commitment);
 
-            Self::deposit_event(Event::FundsAdded(origin, amount));
+            Self::deposit_event(Event::FundsAdded(origin, id, amount));
             Ok(().into())
         }
 
         #[pallet::weight(T::WeightInfo::unbond())]
-        pub fn unbond(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+        #[transactional]
+        pub fn unbond(origin: OriginFor<T>, id: CommitmentId) -> DispatchResultWithPostInfo {
             let origin = ensure_signed(origin)?;
+            ensure!(!<MigrationProgress<T>>::exists(), Error::<T>::Migrating);
 
-            ensure!(<Commitments<T>>::contains_key(&origin), Error::<T>::CommitmentNotFound);
-            let mut commitment = <Commitments<T>>::get(&origin);
+            ensure!(
+                <Commitments<T>>::contains_key(&origin, id),
+                Error::<T>::CommitmentNotFound
+            );
+            let mut commitment = <Commitments<T>>::get(&origin, id);
             ensure!(commitment.state == LockState::Committed, Error::<T>::NotCommitted);
+            let old_commitment = commitment.clone();
 
-            // record the unbonding block number
-            let current_block: T::BlockNumber = frame_system::Pallet::<T>::block_number();
-            commitment.state = LockState::Unbonding(current_block);
+            // unbonding commitments are not rewarded anymore
+            Self::pay_rewards(&origin, id, &commitment)?;
 
-            <Commitments<T>>::insert(&origin, commitment.clone());
-            Self::deposit_event(Event::UnbondingStarted(origin, commitment.amount));
+            // record the unbonding timestamp
+            let now = pallet_timestamp::Pallet::<T>::get();
+            commitment.state = LockState::Unbonding(now);
+            Self::update_votes(Some(&old_commitment), Some(&commitment));
+
+            <Commitments<T>>::insert(&origin, id, commitment.clone());
+            Self::deposit_event(Event::UnbondingStarted(origin, id, commitment.amount));
             Ok(().into())
         }
 
         #[pallet::weight(T::WeightInfo::withdraw())]
         #[transactional]
-        pub fn withdraw(origin: OriginFor<T>) -> DispatchResultWithPostInfo {
+        pub fn withdraw(origin: OriginFor<T>, id: CommitmentId) -> DispatchResultWithPostInfo {
             let origin = ensure_signed(origin)?;
+            ensure!(!<MigrationProgress<T>>::exists(), Error::<T>::Migrating);
 
-            ensure!(<Commitments<T>>::contains_key(&origin), Error::<T>::CommitmentNotFound);
-            let commitment = <Commitments<T>>::get(&origin);
+            ensure!(
+                <Commitments<T>>::contains_key(&origin, id),
+                Error::<T>::CommitmentNotFound
+            );
+            let commitment = <Commitments<T>>::get(&origin, id);
             ensure!(commitment.state != LockState::Committed, Error::<T>::AlreadyCommitted);
 
             // check if Unbonding period is over
-            // WARN: if block times are altered, this calculation will become invalid
-            if let LockState::Unbonding(start_block) = commitment.state {
-                let lock_period = match commitment.duration {
-                    LockDuration::OneMonth => 30,
-                    LockDuration::OneYear => 365,
-                    LockDuration::TenYears => 3650,
-                } * primitives::time::DAYS;
-                let lock_period: T::BlockNumber = lock_period.into();
-                let current_block: T::BlockNumber = frame_system::Pallet::<T>::block_number();
-
-                if start_block + lock_period <= current_block {
+            if let LockState::Unbonding(start) = commitment.state {
+                let lock_period = MomentOf::<T>::unique_saturated_from(commitment.duration.lock_period());
+                let now = pallet_timestamp::Pallet::<T>::get();
+
+                if start.saturating_add(lock_period) <= now {
                     // credit the user his funds
                     T::Currency::deposit_into_existing(&origin, commitment.amount)?;
 
                     // delete the commitment
-                    <Commitments<T>>::remove(&origin);
+                    <Commitments<T>>::remove(&origin, id);
+                    <RewardCheckpoints<T>>::remove(&origin, id);
+                    <CommitmentsCount<T>>::mutate_exists(&origin, |count| {
+                        *count = count
+                            .map(|count| count.saturating_sub(1))
+                            .filter(|count| !count.is_zero())
+                    });
 
                     // decrease the total locked amt after currency is released
                     let locked_total = <LockedAmount<T>>::get().saturating_sub(commitment.amount);
                     <LockedAmount<T>>::set(locked_total);
 
-                    Self::deposit_event(Event::BondWithdrawn(origin, commitment.amount));
+                    Self::deposit_event(Event::BondWithdrawn(origin, id, commitment.amount));
                     return Ok(().into());
                 }
             }
@@ -478,33 +625,135 @@ pub mod module {
 
         #[pallet::weight(T::WeightInfo::vote_candidate())]
         #[transactional]
-        pub fn vote_candidate(origin: OriginFor<T>, candidate: T::AccountId) -> DispatchResultWithPostInfo {
+        pub fn vote_candidate(
+            origin: OriginFor<T>,
+            id: CommitmentId,
+            candidate: T::AccountId,
+        ) -> DispatchResultWithPostInfo {
             let origin = ensure_signed(origin)?;
+            ensure!(!<MigrationProgress<T>>::exists(), Error::<T>::Migrating);
 
-            ensure!(<Commitments<T>>::contains_key(&origin), Error::<T>::CommitmentNotFound);
-            let mut commitment = <Commitments<T>>::get(&origin);
+            ensure!(
+                <Commitments<T>>::contains_key(&origin, id),
+                Error::<T>::CommitmentNotFound
+            );
+            let mut commitment = <Commitments<T>>::get(&origin, id);
             ensure!(commitment.state == LockState::Committed, Error::<T>::NotCommitted);
 
             if commitment.candidate != candidate {
+                let old_commitment = commitment.clone();
                 commitment.candidate = candidate.clone();
-                <Commitments<T>>::insert(&origin, &commitment);
-                Self::deposit_event(Event::Voted(
-                    origin.clone(),
-                    candidate,
-                    Self::voting_weight(&commitment),
-                ));
+                Self::update_votes(Some(&old_commitment), Some(&commitment));
+                <Commitments<T>>::insert(&origin, id, &commitment);
+                Self::deposit_event(Event::Voted(origin, id, candidate, Self::voting_weight(&commitment)));
             }
+            Ok(().into())
+        }
 
-            let era_reward = Self::era_voter_reward(&commitment);
-            if era_reward > Zero::zero() {
-                // check if nominator has been rewarded already for this era
-                let current_era = <CurrentEra<T>>::get();
-                if !<VoterRewards<T>>::contains_key(&current_era.index, &origin) {
-                    <VoterRewards<T>>::insert(&current_era.index, &origin, &era_reward);
-                    T::Currency::deposit_into_existing(&origin, era_reward)?;
-                    Self::deposit_event(Event::VoterRewarded(current_era.index, origin, era_reward));
-                }
+        #[pallet::weight(T::WeightInfo::claim_rewards())]
+        #[transactional]
+        pub fn claim_rewards(origin: OriginFor<T>, id: CommitmentId) -> DispatchResultWithPostInfo {
+            let origin = ensure_signed(origin)?;
+            ensure!(!<MigrationProgress<T>>::exists(), Error::<T>::Migrating);
+
+            ensure!(
+                <Commitments<T>>::contains_key(&origin, id),
+                Error::<T>::CommitmentNotFound
+            );
+            let commitment = <Commitments<T>>::get(&origin, id);
+
+            Self::pay_rewards(&origin, id, &commitment)?;
+            Ok(().into())
+        }
+
+        /// Slash `deposit_slash` of the candidacy deposit of `who`, and
+        /// `backers_slash` of the `backers` commitments still voting for it.
+        /// The candidacy ends, and `who` is dropped from the council.
+        ///
+        /// Unless `backers_slash` is zero, `backers` must list every
+        /// commitment voting for `who`, which is checked against its votes.
+        /// Their pending rewards are paid before they are slashed.
+        ///
+        /// There is no automatic slashing: the collective does not report the
+        /// votes cast by its members, so absent or malicious members are left
+        /// to the `SlashOrigin`.
+        #[pallet::weight(T::WeightInfo::slash_candidate(backers.len() as u32))]
+        #[transactional]
+        pub fn slash_candidate(
+            origin: OriginFor<T>,
+            who: T::AccountId,
+            deposit_slash: Perbill,
+            backers_slash: Perbill,
+            mut backers: Vec<(T::AccountId, CommitmentId)>,
+        ) -> DispatchResultWithPostInfo {
+            T::SlashOrigin::ensure_origin(origin)?;
+            ensure!(!<MigrationProgress<T>>::exists(), Error::<T>::Migrating);
+            ensure!(<Candidates<T>>::contains_key(&who), Error::<T>::NotCandidate);
+
+            // the commitments still voting for the candidate, once each
+            backers.sort();
+            backers.dedup();
+            let backers: Vec<_> = if backers_slash.is_zero() {
+                Vec::new()
+            } else {
+                let backers: Vec<_> = backers
+                    .into_iter()
+                    .filter(|(backer, id)| <Commitments<T>>::contains_key(backer, id))
+                    .map(|(backer, id)| {
+                        let commitment = <Commitments<T>>::get(&backer, id);
+                        (backer, id, commitment)
+                    })
+                    .filter(|(_, _, commitment)| {
+                        commitment.state == LockState::Committed && commitment.candidate == who
+                    })
+                    .collect();
+                let votes = backers
+                    .iter()
+                    .fold(Zero::zero(), |acc: BalanceOf<T>, (_, _, commitment)| {
+                        acc.saturating_add(Self::voting_weight(commitment))
+                    });
+                ensure!(votes == <CandidateVotes<T>>::get(&who), Error::<T>::IncompleteBackers);
+                backers
+            };
+
+            // slash the deposit and return the rest of it
+            let deposit = <Candidates<T>>::take(&who);
+            let (imbalance, _) = T::Currency::slash_reserved(&who, deposit_slash * deposit);
+            let slashed = imbalance.peek();
+            T::Currency::unreserve(&who, deposit.saturating_sub(slashed));
+            T::Slash::on_unbalanced(imbalance);
+            <CandidatesCount<T>>::set(<CandidatesCount<T>>::get().saturating_sub(1));
+            Self::deposit_event(Event::CandidateSlashed(who.clone(), slashed));
+
+            // slash the backing commitments, after paying their rewards
+            let mut backers_slashed: BalanceOf<T> = Zero::zero();
+            for (backer, id, mut commitment) in backers {
+                Self::pay_rewards(&backer, id, &commitment)?;
+
+                let old_commitment = commitment.clone();
+                let amount = backers_slash * commitment.amount;
+                commitment.amount = commitment.amount.saturating_sub(amount);
+                Self::update_votes(Some(&old_commitment), Some(&commitment));
+                <Commitments<T>>::insert(&backer, id, commitment);
+
+                backers_slashed = backers_slashed.saturating_add(amount);
+                Self::deposit_event(Event::CommitmentSlashed(backer, id, amount));
             }
+            if !backers_slashed.is_zero() {
+                <LockedAmount<T>>::set(<LockedAmount<T>>::get().saturating_sub(backers_slashed));
+                // the committed funds are out of the issuance until withdrawn
+                T::Slash::on_unbalanced(T::Currency::issue(backers_slashed));
+            }
+
+            // drop the candidate from the council
+            let mut members = <Members<T>>::get();
+            if let Ok(index) = members.binary_search(&who) {
+                members.remove(index);
+                T::MembershipChanged::change_members_sorted(&[], &[who.clone()], &members);
+                <Members<T>>::put(members);
+            }
+
+            Self::deposit_event(Event::CandidateRemoved(who));
             Ok(().into())
         }
     }
@@ -513,7 +762,7 @@ pub mod module {
 impl<T: Config> Pallet<T> {
     /// Voting shares based on currently committed amount.
     /// Monthly locks have 1x voting power, yearly 10x and 10 yearly 100x.
-    pub fn voting_weight(commitment: &Commitment<T::AccountId, BalanceOf<T>, T::BlockNumber>) -> BalanceOf<T> {
+    pub fn voting_weight(commitment: &CommitmentOf<T>) -> BalanceOf<T> {
         if commitment.state != LockState::Committed {
             return BalanceOf::<T>::from(0 as u32);
         }
@@ -525,17 +774,83 @@ impl<T: Config> Pallet<T> {
         commitment.amount * BalanceOf::<T>::from(multiplier as u32)
     }
 
-    /// Era reward amount based on currently committed amount.
+    /// Move the voting weight of a commitment, from the candidate of its
+    /// `old` state to the candidate of its `new` state.
+    fn update_votes(old: Option<&CommitmentOf<T>>, new: Option<&CommitmentOf<T>>) {
+        if let Some(old) = old {
+            let weight = Self::voting_weight(old);
+            if !weight.is_zero() {
+                <CandidateVotes<T>>::mutate_exists(&old.candidate, |votes| {
+                    let remaining = votes.unwrap_or_else(Zero::zero).saturating_sub(weight);
+                    *votes = Some(remaining).filter(|votes| !votes.is_zero());
+                });
+            }
+        }
+        if let Some(new) = new {
+            let weight = Self::voting_weight(new);
+            if !weight.is_zero() {
+                <CandidateVotes<T>>::mutate(&new.candidate, |votes| *votes = votes.saturating_add(weight));
+            }
+        }
+    }
+
+    /// Voting shares of all the commitments of `who`.
+    pub fn account_voting_weight(who: &T::AccountId) -> BalanceOf<T> {
+        <Commitments<T>>::iter_prefix_values(who).fold(Zero::zero(), |acc: BalanceOf<T>, c| {
+            acc.saturating_add(Self::voting_weight(&c))
+        })
+    }
+
+    /// Era reward per committed unit.
     /// Montly locks yield 0% APY. Longer locks yield fixed 10% APY.
-    pub fn era_voter_reward(commitment: &Commitment<T::AccountId, BalanceOf<T>, T::BlockNumber>) -> BalanceOf<T> {
-        if commitment.state != LockState::Committed {
+    pub fn era_voter_reward_rate() -> FixedU128 {
+        FixedU128::from(T::NominatorAPY::get()).saturating_mul(FixedU128::from(Self::proportion_of_era_to_year()))
+    }
+
+    /// Rewards of a commitment for the ended eras since its `checkpoint`,
+    /// within the last `HistoryDepth` eras.
+    fn commitment_rewards(commitment: &CommitmentOf<T>, checkpoint: EraIndex) -> BalanceOf<T> {
+        if commitment.state != LockState::Committed || commitment.duration == LockDuration::OneMonth {
+            return Zero::zero();
+        }
+
+        let current_era = <CurrentEra<T>>::get().index;
+        let from = checkpoint.max(current_era.saturating_sub(T::HistoryDepth::get()));
+        if from >= current_era {
             return Zero::zero();
         }
 
-        match commitment.duration {
-            LockDuration::OneMonth => Zero::zero(),
-            _ => T::NominatorAPY::get() * (Self::proportion_of_era_to_year() * commitment.amount),
+        let rate = <RewardIndex<T>>::get(current_era).saturating_sub(<RewardIndex<T>>::get(from));
+        let amount: u128 = commitment.amount.unique_saturated_into();
+        rate.saturating_mul_int(amount).unique_saturated_into()
+    }
+
+    /// Unclaimed rewards of the commitment `id` of `who`.
+    pub fn pending_rewards(who: &T::AccountId, id: CommitmentId) -> BalanceOf<T> {
+        Self::commitment_rewards(&<Commitments<T>>::get(who, id), <RewardCheckpoints<T>>::get(who, id))
+    }
+
+    /// Unclaimed rewards of all the commitments of `who`, by commitment id.
+    pub fn account_pending_rewards(who: &T::AccountId) -> Vec<(CommitmentId, BalanceOf<T>)> {
+        <Commitments<T>>::iter_prefix(who)
+            .map(|(id, commitment)| {
+                let rewards = Self::commitment_rewards(&commitment, <RewardCheckpoints<T>>::get(who, id));
+                (id, rewards)
+            })
+            .collect()
+    }
+
+    /// Pay the unclaimed rewards of the commitment `id` of `who`, and move
+    /// its checkpoint to the current era.
+    fn pay_rewards(who: &T::AccountId, id: CommitmentId, commitment: &CommitmentOf<T>) -> DispatchResult {
+        let rewards = Self::commitment_rewards(commitment, <RewardCheckpoints<T>>::get(who, id));
+        <RewardCheckpoints<T>>::insert(who, id, <CurrentEra<T>>::get().index);
+
+        if !rewards.is_zero() {
+            T::Currency::deposit_into_existing(who, rewards)?;
+            Self::deposit_event(Event::VoterRewarded(who.clone(), id, rewards));
         }
+        Ok(())
     }
 
     /// Era reward for the whole council. Needs to be divided by n of council
@@ -548,6 +863,6 @@ impl<T: Config> Pallet<T> {
 
     /// example: 7/365
     pub fn proportion_of_era_to_year() -> Perbill {
-        PerThing::from_rational(T::EraDuration::get(), 365 * primitives::time::DAYS)
+        PerThing::from_rational(T::EraDuration::get(), 365 * primitives::time::MILLISECS_PER_DAY)
     }
 }
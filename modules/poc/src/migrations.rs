@@ -0,0 +1,190 @@
+//! Storage migrations of the PoC module.
+//!
+//! The chains before `v4` have the single commitment layout, the versions in
+//! between were never released.
+
+use crate::*;
+
+/// A commitment before `v4`, unbonding from a block number.
+pub type BlockNumberCommitmentOf<T> =
+    Commitment<<T as frame_system::Config>::AccountId, BalanceOf<T>, <T as frame_system::Config>::BlockNumber>;
+
+/// Move the single commitment of each account to `Commitments`, keyed by
+/// account and commitment id, tally their votes in `CandidateVotes`, replace
+/// the `VoterRewards` paid on vote with the claimable rewards of
+/// `RewardCheckpoints`, and move the unbonding starts and the era start from
+/// block numbers to timestamps.
+///
+/// The era start is migrated on the upgrade, the commitments in `on_idle`
+/// as far as the remaining weight allows. The PoC calls and the elections
+/// wait for the migration to end.
+pub mod v4 {
+    use super::*;
+    use frame_support::{
+        storage::{migration, unhashed},
+        traits::PalletInfoAccess,
+        StorageHasher, Twox128,
+    };
+    use sp_runtime::traits::One;
+
+    /// The progress of the migration.
+    #[derive(Encode, Decode, Clone, PartialEq)]
+    pub struct Progress<BlockNumber, Moment> {
+        /// The block number and the timestamp the block numbers are converted
+        /// from, going back at the expected block time.
+        pub block: BlockNumber,
+        pub now: Moment,
+        /// The era the accounts rewarded on vote were rewarded for.
+        pub era: EraIndex,
+        /// The storage key following the migrated commitments, `None` once
+        /// they all are and the `VoterRewards` are removed.
+        pub next_key: Option<Vec<u8>>,
+    }
+
+    pub type ProgressOf<T> = Progress<<T as frame_system::Config>::BlockNumber, MomentOf<T>>;
+
+    fn storage_prefix<T: Config>(storage: &[u8]) -> Vec<u8> {
+        let pallet_name = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+        [Twox128::hash(pallet_name), Twox128::hash(storage)].concat()
+    }
+
+    /// Migrate the era start, and start migrating the commitments.
+    pub fn start<T: Config>() -> Weight {
+        let pallet_name = <Pallet<T> as PalletInfoAccess>::name().as_bytes();
+        // the timestamp is not set yet, this is the one of the previous block
+        let progress = ProgressOf::<T> {
+            block: frame_system::Pallet::<T>::block_number().saturating_sub(One::one()),
+            now: pallet_timestamp::Pallet::<T>::get(),
+            era: 0,
+            next_key: Some(storage_prefix::<T>(b"Commitments")),
+        };
+
+        let era = migration::take_storage_value::<Era<T::BlockNumber>>(pallet_name, b"CurrentEra", &[]);
+        let progress = match era {
+            Some(era) => {
+                <CurrentEra<T>>::put(Era {
+                    index: era.index,
+                    start: moment_at::<T>(&progress, era.start),
+                });
+                ProgressOf::<T> {
+                    era: era.index,
+                    ..progress
+                }
+            }
+            None => progress,
+        };
+        <MigrationProgress<T>>::put(progress);
+
+        T::DbWeight::get().reads_writes(3, 3)
+    }
+
+    fn moment_at<T: Config>(progress: &ProgressOf<T>, at: T::BlockNumber) -> MomentOf<T> {
+        let blocks: primitives::Moment = progress.block.saturating_sub(at).unique_saturated_into();
+        progress.now.saturating_sub(MomentOf::<T>::unique_saturated_from(
+            blocks.saturating_mul(T::MillisecsPerBlock::get()),
+        ))
+    }
+
+    /// Migrate the commitments, then remove the `VoterRewards`, within
+    /// `limit`.
+    pub fn step<T: Config>(limit: Weight) -> Weight {
+        let mut weight = T::DbWeight::get().reads(1);
+        let mut progress = match <MigrationProgress<T>>::get() {
+            Some(progress) => progress,
+            None => return weight,
+        };
+
+        // reading the next key and the commitment, the `VoterRewards` of its
+        // account and its candidate votes, writing them all but the rewards
+        let commitment_weight = T::DbWeight::get().reads_writes(4, 6);
+        let voter_rewards = storage_prefix::<T>(b"VoterRewards");
+        weight = weight.saturating_add(T::DbWeight::get().writes(1));
+
+        if let Some(mut key) = progress.next_key.take() {
+            let prefix = storage_prefix::<T>(b"Commitments");
+            loop {
+                if weight.saturating_add(commitment_weight) > limit {
+                    progress.next_key = Some(key);
+                    break;
+                }
+                weight = weight.saturating_add(commitment_weight);
+
+                key = match sp_io::storage::next_key(&key).filter(|key| key.starts_with(&prefix)) {
+                    Some(key) => key,
+                    None => break,
+                };
+                // the migrated commitments are keyed by an id after the account
+                let account_key = &key[prefix.len()..];
+                let who = match account_key
+                    .get(16..)
+                    .and_then(|mut raw| T::AccountId::decode(&mut raw).ok().filter(|_| raw.is_empty()))
+                {
+                    Some(who) => who,
+                    None => continue,
+                };
+                let commitment = match unhashed::take::<BlockNumberCommitmentOf<T>>(&key) {
+                    Some(commitment) => commitment,
+                    None => continue,
+                };
+                migrate_commitment::<T>(&progress, &voter_rewards, account_key, who, commitment);
+            }
+            if progress.next_key.is_some() {
+                <MigrationProgress<T>>::put(progress);
+                return weight;
+            }
+        }
+
+        let max_entries = limit.saturating_sub(weight) / T::DbWeight::get().writes(1).max(1);
+        if max_entries.is_zero() {
+            <MigrationProgress<T>>::put(progress);
+            return weight;
+        }
+        match unhashed::kill_prefix(&voter_rewards, Some(max_entries.unique_saturated_into())) {
+            sp_io::KillStorageResult::AllRemoved(removed) => {
+                weight = weight.saturating_add(T::DbWeight::get().writes(removed.into()));
+                <MigrationProgress<T>>::kill();
+            }
+            sp_io::KillStorageResult::SomeRemaining(removed) => {
+                weight = weight.saturating_add(T::DbWeight::get().writes(removed.into()));
+                <MigrationProgress<T>>::put(progress);
+            }
+        }
+        weight
+    }
+
+    /// The single commitment of `who` becomes its commitment `0`.
+    fn migrate_commitment<T: Config>(
+        progress: &ProgressOf<T>,
+        voter_rewards: &[u8],
+        account_key: &[u8],
+        who: T::AccountId,
+        commitment: BlockNumberCommitmentOf<T>,
+    ) {
+        let state = match commitment.state {
+            LockState::Committed => LockState::Committed,
+            LockState::Unbonding(start) => LockState::Unbonding(moment_at::<T>(progress, start)),
+        };
+        let commitment = Commitment {
+            state,
+            duration: commitment.duration,
+            amount: commitment.amount,
+            candidate: commitment.candidate,
+        };
+        Pallet::<T>::update_votes(None, Some(&commitment));
+        <Commitments<T>>::insert(&who, 0, commitment);
+        <NextCommitmentId<T>>::insert(&who, 1);
+        <CommitmentsCount<T>>::insert(&who, 1);
+
+        // the accounts rewarded on vote in the current era are rewarded from
+        // the next era on
+        let mut rewarded_key = voter_rewards.to_vec();
+        rewarded_key.extend(Blake2_128Concat::hash(&progress.era.encode()));
+        rewarded_key.extend(account_key);
+        let checkpoint = if unhashed::exists(&rewarded_key) {
+            progress.era.saturating_add(1)
+        } else {
+            progress.era
+        };
+        <RewardCheckpoints<T>>::insert(&who, 0, checkpoint);
+    }
+}
@@ -1,8 +1,13 @@
 #![cfg(test)]
 
 use crate as module_poc;
-use frame_support::{construct_runtime, parameter_types};
-pub use primitives::{currency::*, time::*, BlockNumber};
+use frame_support::{
+    construct_runtime, parameter_types,
+    traits::{Currency, OnUnbalanced},
+    weights::RuntimeDbWeight,
+};
+use frame_system::EnsureRoot;
+pub use primitives::{currency::*, time::*, BlockNumber, Moment};
 use sp_runtime::Perbill;
 
 type Balance = u64;
@@ -10,6 +15,7 @@ type TechCouncilInstance = pallet_collective::Instance1;
 
 parameter_types!(
     pub const BlockHashCount: u32 = 250;
+    pub const DbWeight: RuntimeDbWeight = RuntimeDbWeight { read: 1, write: 1 };
 );
 impl frame_system::Config for Runtime {
     type BaseCallFilter = frame_support::traits::Everything;
@@ -26,7 +32,7 @@ impl frame_system::Config for Runtime {
     type BlockHashCount = BlockHashCount;
     type BlockWeights = ();
     type BlockLength = ();
-    type DbWeight = ();
+    type DbWeight = DbWeight;
     type Version = ();
     type PalletInfo = PalletInfo;
     type AccountData = pallet_balances::AccountData<Balance>;
@@ -54,6 +60,13 @@ impl pallet_balances::Config for Runtime {
     type ReserveIdentifier = [u8; 8];
 }
 
+impl pallet_timestamp::Config for Runtime {
+    type Moment = u64;
+    type OnTimestampSet = ();
+    type MinimumPeriod = ();
+    type WeightInfo = ();
+}
+
 parameter_types! {
     pub const TechCouncilMotionDuration: BlockNumber = 7 * HOURS;
     pub const TechCouncilMaxProposals: u32 = 100;
@@ -73,18 +86,32 @@ impl pallet_collective::Config<TechCouncilInstance> for Runtime {
 }
 
 parameter_types! {
-    pub const EraDuration: BlockNumber = 7 * HOURS;
+    pub const EraDuration: Moment = 7 * MILLISECS_PER_HOUR;
+    pub const MillisecsPerBlock: Moment = MILLISECS_PER_BLOCK;
     pub const NominatorAPY: Perbill = Perbill::from_percent(10);
     pub const CouncilInflation: Perbill = Perbill::from_percent(1);
     pub const CandidacyDeposit: Balance = 250_000;
     pub const MinLockAmount: Balance = 100;
     pub const TotalLockedCap: Balance = 10_000_000;
+    pub const HistoryDepth: u32 = 4;
+    pub const MaxCommitmentsPerAccount: u32 = 4;
+}
+
+pub const TREASURY: u64 = 99;
+
+/// Sends the slashed funds to the `TREASURY` account.
+pub struct SlashToTreasury;
+impl OnUnbalanced<pallet_balances::NegativeImbalance<Runtime>> for SlashToTreasury {
+    fn on_nonzero_unbalanced(amount: pallet_balances::NegativeImbalance<Runtime>) {
+        Balances::resolve_creating(&TREASURY, amount);
+    }
 }
 
 impl module_poc::Config for Runtime {
     type Event = Event;
     type Currency = Balances;
     type EraDuration = EraDuration;
+    type MillisecsPerBlock = MillisecsPerBlock;
     type NominatorAPY = NominatorAPY;
     type CouncilInflation = CouncilInflation;
     type CandidacyDeposit = CandidacyDeposit;
@@ -92,7 +119,11 @@ impl module_poc::Config for Runtime {
     type TotalLockedCap = TotalLockedCap;
     type MaxCandidates = TechCouncilMaxCandidates;
     type MaxMembers = TechCouncilMaxMembers;
+    type HistoryDepth = HistoryDepth;
+    type MaxCommitmentsPerAccount = MaxCommitmentsPerAccount;
     type MembershipChanged = TechCouncil;
+    type SlashOrigin = EnsureRoot<u64>;
+    type Slash = SlashToTreasury;
     type WeightInfo = ();
 }
 
@@ -107,6 +138,7 @@ construct_runtime!(
     {
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
         Balances: pallet_balances::{Pallet, Call, Storage, Event<T>},
+        Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
         TechCouncil: pallet_collective::<Instance1>::{Pallet, Call, Storage, Origin<T>, Event<T>, Config<T>},
         Poc: module_poc::{Pallet, Call, Storage, Event<T>},
     }
@@ -130,7 +162,10 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
     .unwrap();
 
     let mut ext = sp_io::TestExternalities::new(t);
-    ext.execute_with(|| System::set_block_number(1));
+    ext.execute_with(|| {
+        System::set_block_number(1);
+        Timestamp::set_timestamp(MILLISECS_PER_BLOCK);
+    });
 
     ext
 }
@@ -1,7 +1,14 @@
 #![cfg(test)]
 
 use crate::mock::*;
-use frame_support::{assert_err, assert_ok};
+use codec::Encode;
+use frame_support::{
+    assert_err, assert_ok,
+    storage::migration,
+    traits::{Get, OnIdle, OnInitialize, OnRuntimeUpgrade, StorageVersion},
+    Blake2_128Concat, StorageHasher,
+};
+use sp_runtime::{traits::BadOrigin, Perbill};
 
 #[test]
 fn test_setup() {
@@ -48,24 +55,19 @@ fn commits() {
             crate::LockDuration::OneYear,
             bob,
         ));
-        assert!(Poc::commitments(alice).state == crate::LockState::Committed);
+        assert!(Poc::commitments(alice, 0).state == crate::LockState::Committed);
 
-        // cannot commit again
-        assert_err!(
-            Poc::commit(
-                Origin::signed(alice),
-                (100_000 as u64).into(),
-                crate::LockDuration::OneYear,
-                bob,
-            ),
-            crate::Error::<Runtime>::AlreadyCommitted
-        );
-
-        // we can however add more funds
-        assert_ok!(Poc::add_funds(Origin::signed(alice), (1_000 as u64).into(),));
-        let balance = Poc::commitments(alice).amount;
+        // we can add more funds
+        assert_ok!(Poc::add_funds(Origin::signed(alice), 0, (1_000 as u64).into(),));
+        let balance = Poc::commitments(alice, 0).amount;
         assert!(balance as u64 == 101_000 as u64);
         assert_eq!(Poc::locked_amount(), 101_000 as u64);
+
+        // but not to a non existing commitment
+        assert_err!(
+            Poc::add_funds(Origin::signed(alice), 1, (1_000 as u64).into(),),
+            crate::Error::<Runtime>::CommitmentNotFound
+        );
     });
 }
 
@@ -84,25 +86,25 @@ fn withdrawals() {
 
         // she cannot withdraw an active commitment
         assert_err!(
-            Poc::withdraw(Origin::signed(alice)),
+            Poc::withdraw(Origin::signed(alice), 0),
             crate::Error::<Runtime>::AlreadyCommitted
         );
 
         // she starts the unbonding
-        assert_ok!(Poc::unbond(Origin::signed(alice)));
+        assert_ok!(Poc::unbond(Origin::signed(alice), 0));
 
         // her voting power is now 0
-        assert_eq!(Poc::voting_weight(&Poc::commitments(&alice)), 0);
+        assert_eq!(Poc::voting_weight(&Poc::commitments(&alice, 0)), 0);
 
         // still to early to withdraw
         assert_err!(
-            Poc::withdraw(Origin::signed(alice)),
+            Poc::withdraw(Origin::signed(alice), 0),
             crate::Error::<Runtime>::CannotWithdrawLocked
         );
 
         // after the unboding period we can withdraw
         skip_blocks(31 * DAYS);
-        assert_ok!(Poc::withdraw(Origin::signed(alice)));
+        assert_ok!(Poc::withdraw(Origin::signed(alice), 0));
 
         // the funds are returned to alice
         let balance = Balances::free_balance(&alice);
@@ -110,7 +112,7 @@ fn withdrawals() {
 
         // storage checks
         assert_eq!(Poc::locked_amount(), 0 as u64);
-        assert_eq!(Poc::commitments(&alice).amount, 0 as u64);
+        assert_eq!(Poc::commitments(&alice, 0).amount, 0 as u64);
 
         // alice can make a new commitment
         assert_ok!(Poc::commit(
@@ -119,12 +121,46 @@ fn withdrawals() {
             crate::LockDuration::OneMonth,
             bob,
         ));
-        assert_eq!(Poc::commitments(&alice).amount, 100_000 as u64);
+        assert_eq!(Poc::commitments(&alice, 1).amount, 100_000 as u64);
         let balance = Balances::free_balance(&alice);
         assert_eq!(balance, 900_000 as u64);
     });
 }
 
+#[test]
+fn withdrawals_follow_timestamps() {
+    new_test_ext().execute_with(|| {
+        let alice = 0 as u64;
+        let bob = 1 as u64;
+        assert_ok!(Poc::commit(
+            Origin::signed(alice),
+            (100_000 as u64).into(),
+            crate::LockDuration::OneYear,
+            bob,
+        ));
+
+        // the unbonding start is the current timestamp
+        assert_ok!(Poc::unbond(Origin::signed(alice), 0));
+        assert!(Poc::commitments(&alice, 0).state == crate::LockState::Unbonding(MILLISECS_PER_BLOCK));
+
+        // the lock period is a year whatever the block count, here with slower blocks
+        System::set_block_number(2 * DAYS as u64);
+        Timestamp::set_timestamp(MILLISECS_PER_BLOCK + 365 * MILLISECS_PER_DAY - 1);
+        assert_err!(
+            Poc::withdraw(Origin::signed(alice), 0),
+            crate::Error::<Runtime>::CannotWithdrawLocked
+        );
+        Timestamp::set_timestamp(MILLISECS_PER_BLOCK + 365 * MILLISECS_PER_DAY);
+        assert_ok!(Poc::withdraw(Origin::signed(alice), 0));
+        assert_eq!(Balances::free_balance(&alice), 1_000_000 as u64);
+
+        // eras start at timestamps too
+        run_blocks(1);
+        assert_eq!(Poc::current_era().index, 1);
+        assert_eq!(Poc::current_era().start, MILLISECS_PER_BLOCK + 365 * MILLISECS_PER_DAY);
+    });
+}
+
 #[test]
 fn voting_rewards() {
     new_test_ext().execute_with(|| {
@@ -156,44 +192,86 @@ fn voting_rewards() {
             bob,
         ));
 
-        // all 3 vote
-        assert_ok!(Poc::vote_candidate(Origin::signed(alice), bob));
-        assert_ok!(Poc::vote_candidate(Origin::signed(bob), bob));
-        assert_ok!(Poc::vote_candidate(Origin::signed(charlie), bob));
+        // voting does not pay rewards
+        assert_ok!(Poc::vote_candidate(Origin::signed(bob), 0, bob));
+        assert_eq!(Balances::free_balance(&bob), 900_000 as u64);
 
-        // alice should not have received a reward
-        let balance = Balances::free_balance(&alice);
-        assert_eq!(balance, 900_000 as u64);
+        // nothing is pending before the era ends
+        assert_eq!(Poc::pending_rewards(&bob, 0), 0 as u64);
+        run_blocks(7 * HOURS + 1);
+
+        // alice should not be rewarded
+        assert_eq!(Poc::pending_rewards(&alice, 0), 0 as u64);
 
         // bob should receive 10% APY
         // In [1]: (7/(24*365)) * 10000
         // Out[1]: 7.990867579908676
-        let balance = Balances::free_balance(&bob);
-        assert_eq!(balance, 900_008 as u64);
+        assert_eq!(Poc::pending_rewards(&bob, 0), 7 as u64);
 
         // charlie should also receive 10% APY
-        let balance = Balances::free_balance(&charlie);
-        assert_eq!(balance, 900_008 as u64);
+        assert_eq!(Poc::pending_rewards(&charlie, 0), 7 as u64);
+        assert_eq!(Poc::account_pending_rewards(&charlie), vec![(0, 7 as u64)]);
+
+        // bob claims his rewards
+        assert_ok!(Poc::claim_rewards(Origin::signed(bob), 0));
+        assert_eq!(Balances::free_balance(&bob), 900_007 as u64);
+        assert_eq!(Poc::pending_rewards(&bob, 0), 0 as u64);
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == Event::Poc(crate::Event::VoterRewarded(bob, 0, 7))));
+
+        // claiming twice in the same era does not double rewards
+        assert_ok!(Poc::claim_rewards(Origin::signed(bob), 0));
+        assert_eq!(Balances::free_balance(&bob), 900_007 as u64);
+        assert_err!(
+            Poc::claim_rewards(Origin::signed(bob), 1),
+            crate::Error::<Runtime>::CommitmentNotFound
+        );
+
+        // unclaimed rewards add up over the eras
+        run_blocks(7 * HOURS);
+        assert_eq!(Poc::pending_rewards(&bob, 0), 7 as u64);
+        assert_eq!(Poc::pending_rewards(&charlie, 0), 15 as u64);
 
-        // charlie starts unbonding
-        assert_ok!(Poc::unbond(Origin::signed(charlie)));
+        // charlie starts unbonding, which pays his rewards
+        assert_ok!(Poc::unbond(Origin::signed(charlie), 0));
+        assert_eq!(Balances::free_balance(&charlie), 900_015 as u64);
 
-        // so he can no longer vote
+        // so he can no longer vote, nor be rewarded
         assert_err!(
-            Poc::vote_candidate(Origin::signed(charlie), bob),
+            Poc::vote_candidate(Origin::signed(charlie), 0, bob),
             crate::Error::<Runtime>::NotCommitted
         );
+        run_blocks(7 * HOURS);
+        assert_eq!(Poc::pending_rewards(&charlie, 0), 0 as u64);
+        assert_eq!(Poc::pending_rewards(&bob, 0), 15 as u64);
+    });
+}
 
-        // voting twice in the same era does not double rewards
-        assert_ok!(Poc::vote_candidate(Origin::signed(bob), bob));
-        let balance = Balances::free_balance(&bob);
-        assert_eq!(balance, 900_008 as u64);
+#[test]
+fn voting_rewards_history_depth() {
+    new_test_ext().execute_with(|| {
+        let alice = 0 as u64;
+        let bob = 1 as u64;
 
-        // voting in the next era yields more rewards
-        run_blocks(7 * HOURS + 1);
-        assert_ok!(Poc::vote_candidate(Origin::signed(bob), bob));
-        let balance = Balances::free_balance(&bob);
-        assert_eq!(balance, 900_016 as u64);
+        assert_ok!(Poc::commit(
+            Origin::signed(alice),
+            (100_000 as u64).into(),
+            crate::LockDuration::OneYear,
+            bob,
+        ));
+
+        // six eras go unclaimed
+        run_blocks(6 * 7 * HOURS + 1);
+        assert_eq!(Poc::current_era().index, 6);
+
+        // only the last four are rewarded
+        assert_eq!(Poc::pending_rewards(&alice, 0), 31 as u64);
+        assert!(!crate::RewardIndex::<Runtime>::contains_key(1));
+        assert!(crate::RewardIndex::<Runtime>::contains_key(2));
+
+        assert_ok!(Poc::claim_rewards(Origin::signed(alice), 0));
+        assert_eq!(Balances::free_balance(&alice), 900_031 as u64);
     });
 }
 
@@ -247,7 +325,7 @@ fn elections() {
             alice,
         ));
         // she gets 1x voting power
-        assert_eq!(Poc::voting_weight(&Poc::commitments(&alice)), 100_000,);
+        assert_eq!(Poc::voting_weight(&Poc::commitments(&alice, 0)), 100_000,);
 
         // bob commits for a year
         assert_ok!(Poc::commit(
@@ -257,7 +335,7 @@ fn elections() {
             bob,
         ));
         // he gets 10x voting power
-        assert_eq!(Poc::voting_weight(&Poc::commitments(&bob)), 10 * 100_000,);
+        assert_eq!(Poc::voting_weight(&Poc::commitments(&bob, 0)), 10 * 100_000,);
 
         // charlie commits for 10 years
         assert_ok!(Poc::commit(
@@ -267,7 +345,7 @@ fn elections() {
             charlie,
         ));
         // he gets 100x voting power
-        assert_eq!(Poc::voting_weight(&Poc::commitments(&charlie)), 100 * 100_000,);
+        assert_eq!(Poc::voting_weight(&Poc::commitments(&charlie, 0)), 100 * 100_000,);
 
         // eve votes most heavily for non-candidate
         assert_ok!(Poc::commit(
@@ -276,7 +354,7 @@ fn elections() {
             crate::LockDuration::TenYears,
             nobody,
         ));
-        assert_eq!(Poc::voting_weight(&Poc::commitments(&eve)), 100 * 200_000,);
+        assert_eq!(Poc::voting_weight(&Poc::commitments(&eve, 0)), 100 * 200_000,);
 
         // check current supply for rewards = 4m - 500k committed
         let total_supply = Balances::total_issuance();
@@ -300,10 +378,10 @@ fn elections() {
         assert_eq!(Balances::free_balance(&charlie), 650_009 as u64);
         assert_eq!(Balances::free_balance(&nobody), 0 as u64);
 
-        // eve votes herself (and gets voting reward)
+        // eve votes herself
+        assert_eq!(Balances::free_balance(&eve), 550_000 as u64);
+        assert_ok!(Poc::vote_candidate(Origin::signed(eve), 0, eve));
         assert_eq!(Balances::free_balance(&eve), 550_000 as u64);
-        assert_ok!(Poc::vote_candidate(Origin::signed(eve), eve));
-        assert_eq!(Balances::free_balance(&eve), 550_016 as u64);
 
         // run the elections
         run_blocks(7 * HOURS);
@@ -316,22 +394,463 @@ fn elections() {
         assert_eq!(Balances::free_balance(&alice), 650_009 as u64);
         assert_eq!(Balances::free_balance(&bob), 650_018 as u64);
         assert_eq!(Balances::free_balance(&charlie), 650_018 as u64);
-        assert_eq!(Balances::free_balance(&eve), 550_025 as u64);
+        assert_eq!(Balances::free_balance(&eve), 550_009 as u64);
+
+        // her voting rewards are left to claim
+        assert_eq!(Poc::pending_rewards(&eve, 0), 31 as u64);
 
         // TODO: cannot vote empty / too small quorum
-        // assert_ok!(Poc::unbond(Origin::signed(alice)));
-        // assert_ok!(Poc::unbond(Origin::signed(bob)));
+        // assert_ok!(Poc::unbond(Origin::signed(alice), 0));
+        // assert_ok!(Poc::unbond(Origin::signed(bob), 0));
+    });
+}
+
+#[test]
+fn slashing() {
+    new_test_ext().execute_with(|| {
+        let alice = 0 as u64;
+        let bob = 1 as u64;
+        let charlie = 2 as u64;
+        let eve = 3 as u64;
+
+        assert_ok!(Poc::start_candidacy(Origin::signed(alice)));
+        assert_ok!(Poc::start_candidacy(Origin::signed(charlie)));
+
+        // bob and charlie back alice, eve backs charlie
+        assert_ok!(Poc::commit(
+            Origin::signed(bob),
+            (100_000 as u64).into(),
+            crate::LockDuration::OneYear,
+            alice,
+        ));
+        assert_ok!(Poc::commit(
+            Origin::signed(charlie),
+            (100_000 as u64).into(),
+            crate::LockDuration::OneMonth,
+            alice,
+        ));
+        assert_ok!(Poc::commit(
+            Origin::signed(eve),
+            (100_000 as u64).into(),
+            crate::LockDuration::OneYear,
+            charlie,
+        ));
+
+        run_blocks(7 * HOURS);
+        assert_eq!(Poc::members(), vec![alice, charlie]);
+
+        // only the slash origin can slash
+        assert_err!(
+            Poc::slash_candidate(Origin::signed(bob), alice, Perbill::one(), Perbill::one(), vec![]),
+            BadOrigin
+        );
+        assert_err!(
+            Poc::slash_candidate(Origin::root(), bob, Perbill::one(), Perbill::one(), vec![]),
+            crate::Error::<Runtime>::NotCandidate
+        );
+
+        // the backers must cover every commitment voting for alice
+        assert_err!(
+            Poc::slash_candidate(
+                Origin::root(),
+                alice,
+                Perbill::from_percent(50),
+                Perbill::from_percent(10),
+                vec![(bob, 0), (eve, 0)],
+            ),
+            crate::Error::<Runtime>::IncompleteBackers
+        );
+
+        // half of the deposit and a tenth of the backing commitments, listed twice or not backing alice
+        let free_balance = Balances::free_balance(&alice);
+        let bob_balance = Balances::free_balance(&bob);
+        assert_eq!(Poc::pending_rewards(&bob, 0), 7 as u64);
+        assert_ok!(Poc::slash_candidate(
+            Origin::root(),
+            alice,
+            Perbill::from_percent(50),
+            Perbill::from_percent(10),
+            vec![(bob, 0), (charlie, 0), (eve, 0), (bob, 0), (bob, 1)],
+        ));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == Event::Poc(crate::Event::CandidateSlashed(alice, 125_000))));
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == Event::Poc(crate::Event::CommitmentSlashed(bob, 0, 10_000))));
+
+        // the backers are paid their rewards before being slashed
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == Event::Poc(crate::Event::VoterRewarded(bob, 0, 7))));
+        assert_eq!(Poc::pending_rewards(&bob, 0), 0 as u64);
+        assert_eq!(Balances::free_balance(&bob), bob_balance + 7);
+
+        // the rest of the deposit is returned
+        assert_eq!(Balances::reserved_balance(&alice), 0 as u64);
+        assert_eq!(Balances::free_balance(&alice), free_balance + 125_000);
+
+        // the backing commitments lose their funds and votes, once
+        assert_eq!(Poc::commitments(&bob, 0).amount, 90_000 as u64);
+        assert_eq!(Poc::commitments(&charlie, 0).amount, 90_000 as u64);
+        assert_eq!(Poc::commitments(&eve, 0).amount, 100_000 as u64);
+        assert_eq!(Poc::candidate_votes(&alice), 10 * 90_000 + 90_000);
+        assert_eq!(Poc::candidate_votes(&charlie), 10 * 100_000);
+        assert_eq!(Poc::locked_amount(), 280_000 as u64);
+
+        // the slashed funds go to the handler
+        assert_eq!(Balances::free_balance(&TREASURY), 145_000 as u64);
+
+        // alice is dropped from the council, and is no candidate anymore
+        assert_eq!(Poc::members(), vec![charlie]);
+        assert_eq!(TechCouncil::members(), vec![charlie]);
+        assert!(!crate::Candidates::<Runtime>::contains_key(&alice));
+        assert_eq!(Poc::n_candidates(), 1);
+        assert_err!(
+            Poc::stop_candidacy(Origin::signed(alice)),
+            crate::Error::<Runtime>::NotCandidate
+        );
+
+        // nor is she elected again
+        run_blocks(7 * HOURS);
+        assert_eq!(Poc::members(), vec![charlie]);
+    });
+}
+
+#[test]
+fn multiple_commitments() {
+    new_test_ext().execute_with(|| {
+        let alice = 0 as u64;
+        let bob = 1 as u64;
+        let charlie = 2 as u64;
+
+        // alice commits twice, with different durations and candidates
+        assert_ok!(Poc::commit(
+            Origin::signed(alice),
+            (100_000 as u64).into(),
+            crate::LockDuration::OneMonth,
+            bob,
+        ));
+        assert_ok!(Poc::commit(
+            Origin::signed(alice),
+            (200_000 as u64).into(),
+            crate::LockDuration::TenYears,
+            charlie,
+        ));
+        assert_eq!(Poc::next_commitment_id(&alice), 2);
+        assert_eq!(Poc::commitments(&alice, 0).candidate, bob);
+        assert_eq!(Poc::commitments(&alice, 1).candidate, charlie);
+        assert_eq!(Poc::voting_weight(&Poc::commitments(&alice, 1)), 100 * 200_000);
+        assert_eq!(Poc::locked_amount(), 300_000 as u64);
+        assert_eq!(Balances::free_balance(&alice), 700_000 as u64);
+
+        // unbonding one commitment leaves the other committed
+        assert_ok!(Poc::unbond(Origin::signed(alice), 0));
+        assert!(Poc::commitments(&alice, 0).state != crate::LockState::Committed);
+        assert!(Poc::commitments(&alice, 1).state == crate::LockState::Committed);
+        assert_err!(
+            Poc::vote_candidate(Origin::signed(alice), 0, alice),
+            crate::Error::<Runtime>::NotCommitted
+        );
+
+        // the committed one can still vote
+        assert_ok!(Poc::vote_candidate(Origin::signed(alice), 1, alice));
+        assert_eq!(Poc::commitments(&alice, 1).candidate, alice);
+        assert_eq!(Balances::free_balance(&alice), 700_000 as u64);
+
+        // the unbonded one is withdrawn on its own
+        skip_blocks(31 * DAYS);
+        assert_ok!(Poc::withdraw(Origin::signed(alice), 0));
+        assert_err!(
+            Poc::withdraw(Origin::signed(alice), 0),
+            crate::Error::<Runtime>::CommitmentNotFound
+        );
+        assert_eq!(Poc::commitments(&alice, 1).amount, 200_000 as u64);
+        assert_eq!(Poc::locked_amount(), 200_000 as u64);
+        assert_eq!(Balances::free_balance(&alice), 800_000 as u64);
+    });
+}
+
+#[test]
+fn commitments_are_bounded() {
+    new_test_ext().execute_with(|| {
+        let alice = 0 as u64;
+        let bob = 1 as u64;
+
+        // alice commits up to the maximum
+        for _ in 0..MaxCommitmentsPerAccount::get() {
+            assert_ok!(Poc::commit(
+                Origin::signed(alice),
+                (100_000 as u64).into(),
+                crate::LockDuration::OneMonth,
+                bob,
+            ));
+        }
+        assert_eq!(Poc::commitments_count(&alice), 4);
+        assert_err!(
+            Poc::commit(
+                Origin::signed(alice),
+                (100_000 as u64).into(),
+                crate::LockDuration::OneMonth,
+                bob,
+            ),
+            crate::Error::<Runtime>::MaxCommitmentsReached
+        );
+
+        // withdrawing one makes room for another
+        assert_ok!(Poc::unbond(Origin::signed(alice), 0));
+        skip_blocks(31 * DAYS);
+        assert_ok!(Poc::withdraw(Origin::signed(alice), 0));
+        assert_eq!(Poc::commitments_count(&alice), 3);
+        assert_ok!(Poc::commit(
+            Origin::signed(alice),
+            (100_000 as u64).into(),
+            crate::LockDuration::OneMonth,
+            bob,
+        ));
+        assert_eq!(Poc::next_commitment_id(&alice), 5);
+    });
+}
+
+#[test]
+fn commitment_ids_do_not_overflow() {
+    new_test_ext().execute_with(|| {
+        let alice = 0 as u64;
+        let bob = 1 as u64;
+
+        crate::NextCommitmentId::<Runtime>::insert(&alice, crate::CommitmentId::MAX);
+        assert_err!(
+            Poc::commit(
+                Origin::signed(alice),
+                (100_000 as u64).into(),
+                crate::LockDuration::OneMonth,
+                bob,
+            ),
+            crate::Error::<Runtime>::CommitmentIdOverflow
+        );
+    });
+}
+
+#[test]
+fn vote_tallies() {
+    new_test_ext().execute_with(|| {
+        let alice = 0 as u64;
+        let bob = 1 as u64;
+        let charlie = 2 as u64;
+
+        // commitments add their voting weight to the candidate
+        assert_ok!(Poc::commit(
+            Origin::signed(alice),
+            (100_000 as u64).into(),
+            crate::LockDuration::OneYear,
+            bob,
+        ));
+        assert_ok!(Poc::commit(
+            Origin::signed(charlie),
+            (100_000 as u64).into(),
+            crate::LockDuration::OneMonth,
+            bob,
+        ));
+        assert_eq!(Poc::candidate_votes(&bob), 10 * 100_000 + 100_000);
+
+        // adding funds adds the extra weight
+        assert_ok!(Poc::add_funds(Origin::signed(alice), 0, (1_000 as u64).into()));
+        assert_eq!(Poc::candidate_votes(&bob), 10 * 101_000 + 100_000);
+
+        // voting moves the weight
+        assert_ok!(Poc::vote_candidate(Origin::signed(alice), 0, charlie));
+        assert_eq!(Poc::candidate_votes(&bob), 100_000);
+        assert_eq!(Poc::candidate_votes(&charlie), 10 * 101_000);
+
+        // unbonding removes the weight
+        assert_ok!(Poc::unbond(Origin::signed(charlie), 0));
+        assert_eq!(Poc::candidate_votes(&bob), 0);
+        assert!(!crate::CandidateVotes::<Runtime>::contains_key(&bob));
+
+        // re-committing by adding funds restores it
+        assert_ok!(Poc::add_funds(Origin::signed(charlie), 0, (1_000 as u64).into()));
+        assert_eq!(Poc::candidate_votes(&bob), 101_000);
+
+        // withdrawing an unbonded commitment leaves the tallies alone
+        assert_ok!(Poc::unbond(Origin::signed(alice), 0));
+        assert_eq!(Poc::candidate_votes(&charlie), 0);
+        skip_blocks(366 * DAYS);
+        assert_ok!(Poc::withdraw(Origin::signed(alice), 0));
+        assert_eq!(Poc::candidate_votes(&bob), 101_000);
+        assert_eq!(Poc::candidate_votes(&charlie), 0);
+    });
+}
+
+#[test]
+fn elections_break_ties_by_account() {
+    new_test_ext().execute_with(|| {
+        let alice = 0 as u64;
+        let bob = 1 as u64;
+        let charlie = 2 as u64;
+        let eve = 3 as u64;
+
+        // all are candidates
+        assert_ok!(Poc::start_candidacy(Origin::signed(alice)));
+        assert_ok!(Poc::start_candidacy(Origin::signed(bob)));
+        assert_ok!(Poc::start_candidacy(Origin::signed(charlie)));
+        assert_ok!(Poc::start_candidacy(Origin::signed(eve)));
+
+        // charlie gets the most votes, alice and bob tie
+        assert_ok!(Poc::commit(
+            Origin::signed(charlie),
+            (100_000 as u64).into(),
+            crate::LockDuration::OneYear,
+            charlie,
+        ));
+        assert_ok!(Poc::commit(
+            Origin::signed(bob),
+            (100_000 as u64).into(),
+            crate::LockDuration::OneMonth,
+            bob,
+        ));
+        assert_ok!(Poc::commit(
+            Origin::signed(alice),
+            (100_000 as u64).into(),
+            crate::LockDuration::OneMonth,
+            alice,
+        ));
+        // a fourth commitment ties with them, for the last seat
+        assert_ok!(Poc::commit(
+            Origin::signed(eve),
+            (100_000 as u64).into(),
+            crate::LockDuration::OneMonth,
+            eve,
+        ));
+
+        run_blocks(7 * HOURS);
+
+        // the tie is broken by account
+        assert_eq!(Poc::members(), vec![alice, bob, charlie]);
+        assert!(System::events()
+            .iter()
+            .any(|record| record.event == Event::Poc(crate::Event::Elected(1, charlie, 10 * 100_000))));
+    });
+}
+
+#[test]
+fn migrates_single_commitments() {
+    new_test_ext().execute_with(|| {
+        let alice = 0 as u64;
+        let bob = 1 as u64;
+        let charlie = 2 as u64;
+
+        // alice and bob have commitments in the single commitment layout
+        let key = Blake2_128Concat::hash(&alice.encode());
+        let commitment: crate::migrations::BlockNumberCommitmentOf<Runtime> = crate::Commitment {
+            state: crate::LockState::Unbonding(5),
+            duration: crate::LockDuration::OneYear,
+            amount: 100_000,
+            candidate: bob,
+        };
+        migration::put_storage_value(b"Poc", b"Commitments", &key, commitment);
+        let commitment: crate::migrations::BlockNumberCommitmentOf<Runtime> = crate::Commitment {
+            state: crate::LockState::Committed,
+            duration: crate::LockDuration::OneYear,
+            amount: 200_000,
+            candidate: charlie,
+        };
+        migration::put_storage_value(
+            b"Poc",
+            b"Commitments",
+            &Blake2_128Concat::hash(&bob.encode()),
+            commitment,
+        );
+
+        // bob was rewarded on vote in the current era
+        let mut rewarded_key = Blake2_128Concat::hash(&(0 as u32).encode());
+        rewarded_key.extend(Blake2_128Concat::hash(&bob.encode()));
+        migration::put_storage_value(b"Poc", b"VoterRewards", &rewarded_key, 8 as u64);
+
+        // the era started at block 2
+        let era = crate::Era::<u64> { index: 0, start: 2 };
+        migration::put_storage_value(b"Poc", b"CurrentEra", &[], era);
+        System::set_block_number(11);
+        Timestamp::set_timestamp(1_000_000);
+        assert_eq!(StorageVersion::get::<Poc>(), 0);
+
+        Poc::on_runtime_upgrade();
+
+        // the era start is migrated on the upgrade
+        assert_eq!(Poc::current_era().index, 0);
+        assert_eq!(Poc::current_era().start, 920_000);
+        assert_eq!(StorageVersion::get::<Poc>(), 4);
+
+        // the commitments as far as the weight allows, one of them here
+        assert_eq!(Poc::on_idle(11, 12), 12);
+        let migrated = [alice, bob]
+            .iter()
+            .filter(|who| Poc::next_commitment_id(who) == 1)
+            .count();
+        assert_eq!(migrated, 1);
+
+        // the calls and the elections wait for the migration
+        assert_err!(
+            Poc::commit(Origin::signed(charlie), 100_000, crate::LockDuration::OneMonth, bob),
+            crate::Error::<Runtime>::Migrating
+        );
+        assert_err!(
+            Poc::claim_rewards(Origin::signed(alice), 0),
+            crate::Error::<Runtime>::Migrating
+        );
+        Timestamp::set_timestamp(1_000_000 + 24 * MILLISECS_PER_HOUR);
+        Poc::on_initialize(11);
+        assert_eq!(Poc::current_era().index, 0);
+
+        Poc::on_idle(11, 1_000);
+
+        // it becomes her commitment 0
+        assert!(!migration::have_storage_value(b"Poc", b"Commitments", &key));
+        assert!(Poc::commitments(&alice, 0).state == crate::LockState::Unbonding(950_000));
+        assert_eq!(Poc::commitments(&alice, 0).duration, crate::LockDuration::OneYear);
+        assert_eq!(Poc::commitments(&alice, 0).amount, 100_000 as u64);
+        assert_eq!(Poc::commitments(&alice, 0).candidate, bob);
+        assert_eq!(Poc::next_commitment_id(&alice), 1);
+        assert_eq!(Poc::commitments_count(&alice), 1);
+        assert_eq!(Poc::commitments(&bob, 0).amount, 200_000 as u64);
+        assert_eq!(Poc::next_commitment_id(&bob), 1);
+
+        // the votes of the committed commitments are tallied
+        assert_eq!(Poc::candidate_votes(&bob), 0);
+        assert_eq!(Poc::candidate_votes(&charlie), 10 * 200_000);
+
+        // bob is rewarded from the next era on
+        assert_eq!(Poc::reward_checkpoints(&alice, 0), 0);
+        assert_eq!(Poc::reward_checkpoints(&bob, 0), 1);
+        assert!(!migration::have_storage_value(b"Poc", b"VoterRewards", &rewarded_key));
+
+        // block numbers become timestamps, going back from block 10
+        assert!(Poc::commitments(&bob, 0).state == crate::LockState::Committed);
+        assert_ok!(Poc::claim_rewards(Origin::signed(bob), 0));
+
+        // the migrations run once
+        Poc::on_runtime_upgrade();
+        Poc::on_idle(11, 1_000);
+        assert_eq!(Poc::commitments(&alice, 0).amount, 100_000 as u64);
+        assert!(Poc::commitments(&alice, 0).state == crate::LockState::Unbonding(950_000));
+        assert_eq!(Poc::next_commitment_id(&alice), 1);
+        assert_eq!(Poc::candidate_votes(&charlie), 10 * 200_000);
+        assert_eq!(Poc::current_era().start, 920_000);
     });
 }
 
 fn run_blocks(n: u32) {
-    use frame_support::traits::OnInitialize;
     for _ in 0..n {
         Poc::on_initialize(System::block_number());
-        System::set_block_number(System::block_number() + 1);
+        set_block(System::block_number() + 1);
     }
 }
 
 fn skip_blocks(n: u32) {
-    System::set_block_number(System::block_number() + n as u64);
+    set_block(System::block_number() + n as u64);
+}
+
+/// Move to block `n`, at the expected block time.
+fn set_block(n: u64) {
+    System::set_block_number(n);
+    Timestamp::set_timestamp(n * MILLISECS_PER_BLOCK);
 }
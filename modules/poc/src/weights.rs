@@ -28,16 +28,15 @@ use sp_std::marker::PhantomData;
 pub struct WeightInfo<T>(PhantomData<T>);
 impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
     fn on_initialize_empty() -> Weight {
-        (19_557_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(2 as Weight))
-            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+        (19_557_000 as Weight).saturating_add(T::DbWeight::get().reads(3 as Weight))
     }
     fn on_initialize_era(c: u32) -> Weight {
-        (21_538_473_000 as Weight)
+        (412_960_000 as Weight)
             // Standard Error: 27_000
             .saturating_add((63_000 as Weight).saturating_mul(c as Weight))
-            .saturating_add(T::DbWeight::get().reads(2025 as Weight))
-            .saturating_add(T::DbWeight::get().writes(25 as Weight))
+            .saturating_add(T::DbWeight::get().reads(17 as Weight))
+            .saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(T::DbWeight::get().writes(16 as Weight))
     }
     fn start_candidacy() -> Weight {
         (66_356_000 as Weight)
@@ -51,44 +50,56 @@ impl<T: frame_system::Config> crate::WeightInfo for WeightInfo<T> {
     }
     fn commit() -> Weight {
         (65_845_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(3 as Weight))
-            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+            .saturating_add(T::DbWeight::get().reads(9 as Weight))
+            .saturating_add(T::DbWeight::get().writes(8 as Weight))
     }
     fn add_funds() -> Weight {
-        (70_473_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(3 as Weight))
-            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+        (98_612_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(11 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
     }
     fn unbond() -> Weight {
-        (40_417_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(1 as Weight))
-            .saturating_add(T::DbWeight::get().writes(1 as Weight))
+        (68_904_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(10 as Weight))
+            .saturating_add(T::DbWeight::get().writes(5 as Weight))
     }
     fn withdraw() -> Weight {
-        (68_720_000 as Weight)
-            .saturating_add(T::DbWeight::get().reads(3 as Weight))
-            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+        (72_155_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(7 as Weight))
+            .saturating_add(T::DbWeight::get().writes(6 as Weight))
     }
     fn vote_candidate() -> Weight {
         (87_216_000 as Weight)
             .saturating_add(T::DbWeight::get().reads(4 as Weight))
             .saturating_add(T::DbWeight::get().writes(3 as Weight))
     }
+    fn claim_rewards() -> Weight {
+        (54_387_000 as Weight)
+            .saturating_add(T::DbWeight::get().reads(8 as Weight))
+            .saturating_add(T::DbWeight::get().writes(3 as Weight))
+    }
+    fn slash_candidate(b: u32) -> Weight {
+        (96_480_000 as Weight)
+            .saturating_add((48_915_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(T::DbWeight::get().reads(14 as Weight))
+            .saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(b as Weight)))
+            .saturating_add(T::DbWeight::get().writes(10 as Weight))
+            .saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(b as Weight)))
+    }
 }
 
 use frame_support::weights::constants::RocksDbWeight as DbWeight;
 impl crate::WeightInfo for () {
     fn on_initialize_empty() -> Weight {
-        (19_557_000 as Weight)
-            .saturating_add(DbWeight::get().reads(2 as Weight))
-            .saturating_add(DbWeight::get().writes(1 as Weight))
+        (19_557_000 as Weight).saturating_add(DbWeight::get().reads(3 as Weight))
     }
     fn on_initialize_era(c: u32) -> Weight {
-        (21_538_473_000 as Weight)
+        (412_960_000 as Weight)
             // Standard Error: 27_000
             .saturating_add((63_000 as Weight).saturating_mul(c as Weight))
-            .saturating_add(DbWeight::get().reads(2025 as Weight))
-            .saturating_add(DbWeight::get().writes(25 as Weight))
+            .saturating_add(DbWeight::get().reads(17 as Weight))
+            .saturating_add(DbWeight::get().reads((2 as Weight).saturating_mul(c as Weight)))
+            .saturating_add(DbWeight::get().writes(16 as Weight))
     }
     fn start_candidacy() -> Weight {
         (66_356_000 as Weight)
@@ -102,27 +113,40 @@ impl crate::WeightInfo for () {
     }
     fn commit() -> Weight {
         (65_845_000 as Weight)
-            .saturating_add(DbWeight::get().reads(3 as Weight))
-            .saturating_add(DbWeight::get().writes(3 as Weight))
+            .saturating_add(DbWeight::get().reads(9 as Weight))
+            .saturating_add(DbWeight::get().writes(8 as Weight))
     }
     fn add_funds() -> Weight {
-        (70_473_000 as Weight)
-            .saturating_add(DbWeight::get().reads(3 as Weight))
-            .saturating_add(DbWeight::get().writes(3 as Weight))
+        (98_612_000 as Weight)
+            .saturating_add(DbWeight::get().reads(11 as Weight))
+            .saturating_add(DbWeight::get().writes(6 as Weight))
     }
     fn unbond() -> Weight {
-        (40_417_000 as Weight)
-            .saturating_add(DbWeight::get().reads(1 as Weight))
-            .saturating_add(DbWeight::get().writes(1 as Weight))
+        (68_904_000 as Weight)
+            .saturating_add(DbWeight::get().reads(10 as Weight))
+            .saturating_add(DbWeight::get().writes(5 as Weight))
     }
     fn withdraw() -> Weight {
-        (68_720_000 as Weight)
-            .saturating_add(DbWeight::get().reads(3 as Weight))
-            .saturating_add(DbWeight::get().writes(3 as Weight))
+        (72_155_000 as Weight)
+            .saturating_add(DbWeight::get().reads(7 as Weight))
+            .saturating_add(DbWeight::get().writes(6 as Weight))
     }
     fn vote_candidate() -> Weight {
         (87_216_000 as Weight)
             .saturating_add(DbWeight::get().reads(4 as Weight))
             .saturating_add(DbWeight::get().writes(3 as Weight))
     }
+    fn claim_rewards() -> Weight {
+        (54_387_000 as Weight)
+            .saturating_add(DbWeight::get().reads(8 as Weight))
+            .saturating_add(DbWeight::get().writes(3 as Weight))
+    }
+    fn slash_candidate(b: u32) -> Weight {
+        (96_480_000 as Weight)
+            .saturating_add((48_915_000 as Weight).saturating_mul(b as Weight))
+            .saturating_add(DbWeight::get().reads(14 as Weight))
+            .saturating_add(DbWeight::get().reads((3 as Weight).saturating_mul(b as Weight)))
+            .saturating_add(DbWeight::get().writes(10 as Weight))
+            .saturating_add(DbWeight::get().writes((3 as Weight).saturating_mul(b as Weight)))
+    }
 }
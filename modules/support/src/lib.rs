@@ -3,8 +3,8 @@
 
 use codec::{Decode, Encode};
 use frame_support::pallet_prelude::Weight;
-use primitives::evm::{CallInfo, EvmAddress};
-use sp_core::H160;
+use primitives::evm::{CallInfo, Erc20Metadata, EvmAddress};
+use sp_core::{H160, H256};
 use sp_runtime::{
     traits::{AtLeast32BitUnsigned, MaybeSerializeDeserialize},
     transaction_validity::TransactionValidityError,
@@ -24,6 +24,13 @@ pub trait PrecompileCallerFilter {
     fn is_allowed(caller: H160) -> bool;
 }
 
+/// No filter, every caller is allowed.
+impl PrecompileCallerFilter for () {
+    fn is_allowed(_caller: H160) -> bool {
+        true
+    }
+}
+
 /// An abstraction of EVM for EVMBridge
 pub trait EVM<AccountId> {
     type Balance: AtLeast32BitUnsigned + Copy + MaybeSerializeDeserialize + Default;
@@ -76,12 +83,78 @@ pub trait EVMBridge<AccountId, Balance> {
     fn balance_of(context: InvokeContext, address: EvmAddress) -> Result<Balance, DispatchError>;
     /// Execute ERC20.transfer(address, uint256) to transfer value to `to`
     fn transfer(context: InvokeContext, to: EvmAddress, value: Balance) -> DispatchResult;
+    /// Execute ERC20.approve(address, uint256) to allow `spender` to withdraw
+    /// up to `value` from `context.sender`
+    fn approve(context: InvokeContext, spender: EvmAddress, value: Balance) -> DispatchResult;
+    /// Execute ERC20.allowance(address, address) to read the amount `spender`
+    /// is still allowed to withdraw from `owner`
+    fn allowance(context: InvokeContext, owner: EvmAddress, spender: EvmAddress) -> Result<Balance, DispatchError>;
+    /// Execute ERC20.transferFrom(address, address, uint256) to transfer value
+    /// from `from` to `to` using the allowance of `context.sender`
+    fn transfer_from(context: InvokeContext, from: EvmAddress, to: EvmAddress, value: Balance) -> DispatchResult;
     /// Get the real origin account and charge storage rent from the origin.
     fn get_origin() -> Option<AccountId>;
     /// Provide a method to set origin for `on_initialize`
     fn set_origin(origin: AccountId);
 }
 
+/// The registry of the ERC20 tokens usable as `CurrencyId::ERC20`.
+pub trait Erc20Registry {
+    /// Whether `contract` is a registered ERC20 token.
+    fn is_registered(contract: EvmAddress) -> bool;
+    /// The cached metadata of a registered ERC20 token.
+    fn metadata(contract: EvmAddress) -> Option<Erc20Metadata>;
+    /// The registered ERC20 tokens.
+    fn contracts() -> Vec<EvmAddress>;
+}
+
+/// No registry, every contract is accepted.
+impl Erc20Registry for () {
+    fn is_registered(_contract: EvmAddress) -> bool {
+        true
+    }
+
+    fn metadata(_contract: EvmAddress) -> Option<Erc20Metadata> {
+        None
+    }
+
+    fn contracts() -> Vec<EvmAddress> {
+        Vec::new()
+    }
+}
+
+/// Emit EVM logs outside of the EVM execution, e.g. from precompiles.
+pub trait EVMLog {
+    /// Emit the log of `address`, as its `LOG` opcodes do.
+    fn log(address: EvmAddress, topics: Vec<H256>, data: Vec<u8>);
+}
+
+/// ERC20 like allowances of a multi-currency.
+pub trait MultiCurrencyAllowance<AccountId> {
+    type CurrencyId;
+    type Balance;
+
+    /// The amount of `owner`'s `currency_id` that `spender` is allowed to
+    /// transfer.
+    fn allowance(currency_id: Self::CurrencyId, owner: &AccountId, spender: &AccountId) -> Self::Balance;
+    /// Allow `spender` to transfer up to `amount` of `owner`'s `currency_id`.
+    fn approve(
+        currency_id: Self::CurrencyId,
+        owner: &AccountId,
+        spender: &AccountId,
+        amount: Self::Balance,
+    ) -> DispatchResult;
+    /// Transfer `amount` of `from`'s `currency_id` to `to` on behalf of
+    /// `spender`, consuming its allowance.
+    fn transfer_from(
+        currency_id: Self::CurrencyId,
+        spender: &AccountId,
+        from: &AccountId,
+        to: &AccountId,
+        amount: Self::Balance,
+    ) -> DispatchResult;
+}
+
 /// An abstraction of EVMStateRentTrait
 pub trait EVMStateRentTrait<AccountId, Balance> {
     /// Query the constants `NewContractExtraBytes` value from evm module.
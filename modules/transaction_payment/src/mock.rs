@@ -6,9 +6,13 @@ use super::*;
 use crate as transaction_payment;
 use frame_support::{construct_runtime, ord_parameter_types, parameter_types, weights::WeightToFeeCoefficients};
 use orml_traits::parameter_type_with_key;
-use primitives::{evm::EvmAddress, mocks::MockAddressMapping, Amount, TokenSymbol};
+use primitives::{
+    evm::{EvmAddress, ERC20_HOLDING_ACCOUNT},
+    mocks::MockAddressMapping,
+    Amount, TokenSymbol,
+};
 use smallvec::smallvec;
-use sp_core::{crypto::AccountId32, H256};
+use sp_core::{crypto::AccountId32, H160, H256};
 use sp_runtime::{testing::Header, traits::IdentityLookup, DispatchError, DispatchResult, Perbill};
 use sp_std::cell::RefCell;
 use support::{EVMBridge, InvokeContext};
@@ -136,6 +140,18 @@ where
         Ok(())
     }
 
+    fn approve(_context: InvokeContext, _spender: EvmAddress, _value: Balance) -> DispatchResult {
+        Ok(())
+    }
+
+    fn allowance(_context: InvokeContext, _owner: EvmAddress, _spender: EvmAddress) -> Result<Balance, DispatchError> {
+        Ok(Default::default())
+    }
+
+    fn transfer_from(_context: InvokeContext, _from: EvmAddress, _to: EvmAddress, _value: Balance) -> DispatchResult {
+        Ok(())
+    }
+
     fn get_origin() -> Option<AccountId> {
         None
     }
@@ -147,6 +163,11 @@ parameter_types! {
     pub const GetNativeCurrencyId: CurrencyId = OVR;
 }
 
+parameter_types! {
+    pub const Erc20HoldingAccount: H160 = ERC20_HOLDING_ACCOUNT;
+    pub const Erc20SlashBeneficiary: AccountId = AccountId::new([9u8; 32]);
+}
+
 impl module_currencies::Config for Runtime {
     type Event = Event;
     type MultiCurrency = Tokens;
@@ -154,6 +175,10 @@ impl module_currencies::Config for Runtime {
     type WeightInfo = ();
     type AddressMapping = MockAddressMapping;
     type EVMBridge = MockEVMBridge;
+    type Erc20HoldingAccount = Erc20HoldingAccount;
+    type Erc20SlashBeneficiary = Erc20SlashBeneficiary;
+    type Erc20Registry = ();
+    type Erc20ReserveAccounts = ();
 }
 
 thread_local! {
@@ -214,7 +239,7 @@ construct_runtime!(
         TransactionPayment: transaction_payment::{Pallet, Call, Storage},
         PalletBalances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
         Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
-        Currencies: module_currencies::{Pallet, Call, Event<T>},
+        Currencies: module_currencies::{Pallet, Call, Storage, Event<T>},
     }
 );
 
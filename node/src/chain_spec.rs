@@ -2,7 +2,7 @@ use ovr_runtime::{
     get_all_module_accounts, opaque::SessionKeys, AccountId, AuthorityDiscoveryConfig, AuthorityDiscoveryId,
     BabeConfig, BalancesConfig, CurrencyId, EVMConfig, GenesisConfig, ImOnlineId, IndicesConfig,
     MaxNativeTokenExistentialDeposit, SessionConfig, StakerStatus, StakingConfig, SudoConfig, SystemConfig,
-    TokenSymbol, TokensConfig, OVR, WASM_BINARY,
+    TokenSymbol, TokensConfig, MIRRORED_CURRENCIES, MIRRORED_TOKEN_CODE, OVR, WASM_BINARY,
 };
 use sc_service::{ChainType, Properties};
 use sc_telemetry::TelemetryEndpoints;
@@ -472,6 +472,7 @@ fn testnet_genesis(
         evm: EVMConfig {
             accounts: evm_genesis_accounts,
         },
+        asset_registry: Default::default(),
         sudo: SudoConfig { key: root_key },
         tech_council: Default::default(),
     }
@@ -567,6 +568,7 @@ fn mainnet_genesis(
         evm: EVMConfig {
             accounts: evm_genesis_accounts,
         },
+        asset_registry: Default::default(),
         sudo: SudoConfig { key: root_key },
         tech_council: Default::default(),
     }
@@ -601,5 +603,19 @@ pub fn evm_genesis() -> BTreeMap<H160, module_evm::GenesisAccount<Balance, Nonce
         );
         accounts.insert(addr, account);
     }
+
+    // ERC20 contracts mirroring the native currencies
+    for currency_id in MIRRORED_CURRENCIES {
+        let account = module_evm::GenesisAccount {
+            nonce: 0,
+            balance: existential_deposit,
+            storage: Default::default(),
+            code: MIRRORED_TOKEN_CODE.to_vec(),
+        };
+        let addr = currency_id
+            .mirrored_token_address()
+            .expect("native currencies have a mirrored token");
+        accounts.insert(addr, account);
+    }
     accounts
 }
@@ -8,6 +8,7 @@
 use std::sync::Arc;
 
 pub use sc_rpc_api::DenyUnsafe;
+use sc_client_api::Backend;
 use sc_transaction_pool_api::TransactionPool;
 use sp_api::ProvideRuntimeApi;
 use sp_block_builder::BlockBuilder;
@@ -15,7 +16,11 @@ use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 
 use ovr_runtime::{opaque::Block, AccountId, Balance, BlockNumber, Hash, Nonce};
 
-pub use evm_rpc::{EVMApi, EVMApiServer, EVMRuntimeRPCApi};
+pub use asset_registry_rpc::{AssetRegistry, AssetRegistryApiServer, AssetRegistryRuntimeApi};
+
+pub use evm_rpc::{
+    DebugApi, DebugApiServer, EVMApi, EVMApiServer, EVMRuntimeRPCApi, EthApi, EthApiServer, IndexerStorage,
+};
 use sc_consensus_babe::{Config, Epoch};
 use sc_consensus_epochs::SharedEpochChanges;
 use sc_finality_grandpa::{FinalityProofProvider, GrandpaJustificationStream, SharedAuthoritySet, SharedVoterState};
@@ -54,6 +59,8 @@ pub struct FullDeps<C, P, SC, B> {
     pub pool: Arc<P>,
     /// The SelectChain Strategy
     pub select_chain: SC,
+    /// The backend instance, providing the EVM index.
+    pub backend: Arc<B>,
     /// Whether to deny unsafe calls
     pub deny_unsafe: DenyUnsafe,
     /// BABE specific dependencies.
@@ -69,13 +76,15 @@ pub fn create_full<C, P, SC, B>(
 where
     C: ProvideRuntimeApi<Block>,
     C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError> + 'static,
+    C: sc_client_api::BlockBackend<Block>,
     C: Send + Sync + 'static,
     C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
     C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
     C::Api: EVMRuntimeRPCApi<Block, Balance>,
+    C::Api: AssetRegistryRuntimeApi<Block>,
     C::Api: sp_consensus_babe::BabeApi<Block>,
     C::Api: BlockBuilder<Block>,
-    P: TransactionPool + Sync + Send + 'static,
+    P: TransactionPool<Block = Block> + Sync + Send + 'static,
     SC: sp_consensus::SelectChain<Block> + 'static,
     B: sc_client_api::Backend<Block> + Send + Sync + 'static,
     B::State: sc_client_api::StateBackend<sp_runtime::traits::HashFor<Block>>,
@@ -91,6 +100,7 @@ where
         client,
         pool,
         select_chain,
+        backend,
         deny_unsafe,
         babe,
         grandpa,
@@ -110,7 +120,7 @@ where
 
     io.extend_with(SystemApi::to_delegate(FullSystem::new(
         client.clone(),
-        pool,
+        pool.clone(),
         deny_unsafe,
     )));
 
@@ -139,7 +149,18 @@ where
         subscription_executor,
         finality_provider,
     )));
-    io.extend_with(EVMApiServer::to_delegate(EVMApi::new(client)));
+    io.extend_with(EthApiServer::to_delegate(EthApi::new(
+        client.clone(),
+        pool,
+        backend.offchain_storage().map(IndexerStorage::new),
+    )));
+    io.extend_with(DebugApiServer::to_delegate(DebugApi::new(
+        client.clone(),
+        backend.offchain_storage().map(IndexerStorage::new),
+        deny_unsafe,
+    )));
+    io.extend_with(EVMApiServer::to_delegate(EVMApi::new(client.clone())));
+    io.extend_with(AssetRegistryApiServer::to_delegate(AssetRegistry::new(client)));
 
     Ok(io)
 }
@@ -2,7 +2,7 @@
 //! substrate service.
 
 use ovr_runtime::{self, opaque::Block, RuntimeApi};
-use sc_client_api::{ExecutorProvider, RemoteBackend};
+use sc_client_api::{Backend, ExecutorProvider, RemoteBackend};
 use sc_executor::native_executor_instance;
 pub use sc_executor::NativeExecutor;
 use sc_finality_grandpa::SharedVoterState;
@@ -188,6 +188,14 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
         sc_service::build_offchain_workers(&config, task_manager.spawn_handle(), client.clone(), network.clone());
     }
 
+    if let Some(storage) = backend.offchain_storage() {
+        // indexing runs the runtime api, keep it off the async executor
+        task_manager.spawn_handle().spawn_blocking(
+            "evm-indexer",
+            evm_rpc::EvmIndexer::<Block, _, _, ovr_runtime::Balance>::new(client.clone(), storage).run(),
+        );
+    }
+
     let role = config.role.clone();
     let force_authoring = config.force_authoring;
     let backoff_authoring_blocks = Some(sc_consensus_slots::BackoffAuthoringOnFinalizedHeadLagging::default());
@@ -210,12 +218,14 @@ pub fn new_full(mut config: Configuration) -> Result<TaskManager, ServiceError>
         let pool = transaction_pool.clone();
         let keystore = keystore_container.sync_keystore();
         let select_chain = select_chain.clone();
+        let backend = backend.clone();
 
         Box::new(move |deny_unsafe, subscription_executor| {
             let deps = crate::rpc::FullDeps {
                 client: client.clone(),
                 pool: pool.clone(),
                 select_chain: select_chain.clone(),
+                backend: backend.clone(),
                 deny_unsafe,
                 babe: crate::rpc::BabeDeps {
                     babe_config: babe_config.clone(),
@@ -1,6 +1,6 @@
-use crate::Balance;
+use crate::{Balance, Nonce};
 use codec::{Decode, Encode};
-use ethereum_types::{H160, U256};
+use ethereum_types::{H160, H256, U256};
 use evm::ExitReason;
 use sp_runtime::RuntimeDebug;
 use sp_std::vec::Vec;
@@ -14,6 +14,15 @@ use serde::{Deserialize, Serialize};
 /// Evm Address.
 pub type EvmAddress = H160;
 
+/// The EVM address that holds reserved and locked ERC20 tokens on behalf of
+/// `module_currencies`.
+pub const ERC20_HOLDING_ACCOUNT: EvmAddress = H160([
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0xff, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+]);
+
+/// Addresses and storage slots a transaction plans to access (EIP-2930).
+pub type AccessList = Vec<(EvmAddress, Vec<H256>)>;
+
 #[derive(Clone, Eq, PartialEq, Encode, Decode, Default)]
 #[cfg_attr(feature = "std", derive(Debug, Serialize, Deserialize))]
 /// External input from the transaction.
@@ -42,6 +51,111 @@ pub struct CallInfo {
     pub used_gas: U256,
     pub used_storage: i32,
 }
+
+/// EVM executions of an extrinsic, collected from the events of a block.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct ExtrinsicReceipt {
+    /// Index of the extrinsic in the block.
+    pub extrinsic_index: u32,
+    /// Hash of the Ethereum transaction applied by the extrinsic, if any.
+    pub transaction_hash: Option<H256>,
+    /// Sender of the first execution.
+    pub from: EvmAddress,
+    /// Target of the first execution, if it is a call.
+    pub to: Option<EvmAddress>,
+    /// Created contract of the first execution, if it is a create.
+    pub contract_address: Option<EvmAddress>,
+    /// Gas used by all the executions.
+    pub used_gas: u64,
+    /// Whether all the executions succeeded.
+    pub succeed: bool,
+    /// Logs emitted by the executions.
+    pub logs: Vec<Log>,
+}
+
+/// Kind of trace collected while tracing an EVM execution.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum TracerConfig {
+    /// Log every executed opcode, with the state of the machine.
+    StructLogger {
+        disable_stack: bool,
+        disable_memory: bool,
+        disable_storage: bool,
+        /// Maximum number of struct logs, 0 for no limit.
+        limit: u32,
+    },
+    /// Only collect the tree of call frames.
+    CallTracer,
+}
+
+/// An opcode executed by the EVM, and the state of the machine before its
+/// execution.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct StructLog {
+    /// Program counter.
+    pub pc: u32,
+    /// Opcode.
+    pub op: u8,
+    /// Gas left before the opcode.
+    pub gas: u64,
+    /// Gas cost of the opcode.
+    pub gas_cost: u64,
+    /// Depth of the call frame, starting at 1.
+    pub depth: u32,
+    /// Stack, `None` if disabled.
+    pub stack: Option<Vec<H256>>,
+    /// Memory, `None` if disabled.
+    pub memory: Option<Vec<u8>>,
+    /// Storage slots of the contract accessed so far, only for `SLOAD` and
+    /// `SSTORE`.
+    pub storage: Option<Vec<(H256, H256)>>,
+}
+
+/// Kind of a call frame.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub enum CallType {
+    Call,
+    CallCode,
+    DelegateCall,
+    StaticCall,
+    Create,
+    Create2,
+}
+
+/// A call frame of a traced EVM execution.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct CallFrame {
+    pub call_type: CallType,
+    pub from: EvmAddress,
+    /// Called or created contract.
+    pub to: EvmAddress,
+    pub input: Vec<u8>,
+    pub output: Vec<u8>,
+    pub value: U256,
+    /// Gas given to the frame.
+    pub gas: u64,
+    pub gas_used: u64,
+    /// Exit reason, `None` if the frame didn't exit.
+    pub exit_reason: Option<ExitReason>,
+    /// Nested call frames.
+    pub calls: Vec<CallFrame>,
+}
+
+/// Trace of an EVM execution.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Trace {
+    /// Top level call frame.
+    pub call: CallFrame,
+    /// Executed opcodes, empty unless traced with the struct logger.
+    pub struct_logs: Vec<StructLog>,
+}
+
 /// A mapping between `AccountId` and `EvmAddress`.
 pub trait AddressMapping<AccountId> {
     fn get_account_id(evm: &EvmAddress) -> AccountId;
@@ -51,6 +165,22 @@ pub trait AddressMapping<AccountId> {
     fn is_linked(account_id: &AccountId, evm: &EvmAddress) -> bool;
 }
 
+/// Override of the state of an account, applied before an RPC call.
+#[derive(Clone, Default, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct AccountOverride {
+    /// Free balance.
+    pub balance: Option<Balance>,
+    /// Nonce.
+    pub nonce: Option<Nonce>,
+    /// Code, deployed as a contract.
+    pub code: Option<Vec<u8>>,
+    /// Storage slots replacing the whole storage of the account.
+    pub state: Option<Vec<(H256, H256)>>,
+    /// Storage slots set over the existing storage.
+    pub state_diff: Vec<(H256, H256)>,
+}
+
 #[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub struct EstimateResourcesRequest {
@@ -67,3 +197,16 @@ pub struct EstimateResourcesRequest {
     /// Data
     pub data: Option<Vec<u8>>,
 }
+
+/// Metadata of a registered ERC20 token, read from the contract on
+/// registration.
+#[derive(Clone, Default, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+#[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
+pub struct Erc20Metadata {
+    /// `name()` of the token.
+    pub name: Vec<u8>,
+    /// `symbol()` of the token.
+    pub symbol: Vec<u8>,
+    /// `decimals()` of the token.
+    pub decimals: u8,
+}
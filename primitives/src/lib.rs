@@ -13,7 +13,10 @@ use sp_runtime::{
     traits::{BlakeTwo256, IdentifyAccount, Verify},
     MultiSignature, RuntimeDebug,
 };
-use sp_std::convert::{TryFrom, TryInto};
+use sp_std::{
+    convert::{TryFrom, TryInto},
+    vec::Vec,
+};
 
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
@@ -64,6 +67,11 @@ pub mod time {
     pub const HOURS: BlockNumber = MINUTES * 60;
     pub const DAYS: BlockNumber = HOURS * 24;
 
+    // These time units are defined in milliseconds.
+    pub const MILLISECS_PER_MINUTE: Moment = 60 * 1000;
+    pub const MILLISECS_PER_HOUR: Moment = MILLISECS_PER_MINUTE * 60;
+    pub const MILLISECS_PER_DAY: Moment = MILLISECS_PER_HOUR * 24;
+
     pub const SLOT_DURATION: Moment = MILLISECS_PER_BLOCK;
 
     // 1 in 4 blocks (on average, not counting collisions) will be primary BABE
@@ -147,6 +155,29 @@ impl TryFrom<u8> for TokenSymbol {
     }
 }
 
+impl TokenSymbol {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TokenSymbol::OVR => "Ovr",
+            TokenSymbol::OUSD => "Ovr Dollar",
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            TokenSymbol::OVR => "OVR",
+            TokenSymbol::OUSD => "OUSD",
+        }
+    }
+
+    pub fn decimals(&self) -> u8 {
+        match self {
+            TokenSymbol::OVR => 18,
+            TokenSymbol::OUSD => 18,
+        }
+    }
+}
+
 #[derive(Encode, Decode, Eq, PartialEq, Copy, Clone, RuntimeDebug, PartialOrd, Ord)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
 pub enum CurrencyId {
@@ -181,6 +212,70 @@ impl CurrencyId {
             _ => None,
         }
     }
+
+    /// The name of the mirrored ERC20 token, `None` for ERC20 tokens.
+    pub fn name(&self) -> Option<Vec<u8>> {
+        match self {
+            CurrencyId::Token(symbol) => Some(symbol.name().as_bytes().to_vec()),
+            CurrencyId::DEXShare(left, right) => {
+                Some([&b"LP "[..], left.name().as_bytes(), b" - ", right.name().as_bytes()].concat())
+            }
+            CurrencyId::ERC20(_) => None,
+        }
+    }
+
+    /// The symbol of the mirrored ERC20 token, `None` for ERC20 tokens.
+    pub fn symbol(&self) -> Option<Vec<u8>> {
+        match self {
+            CurrencyId::Token(symbol) => Some(symbol.symbol().as_bytes().to_vec()),
+            CurrencyId::DEXShare(left, right) => {
+                Some([&b"LP_"[..], left.symbol().as_bytes(), b"_", right.symbol().as_bytes()].concat())
+            }
+            CurrencyId::ERC20(_) => None,
+        }
+    }
+
+    /// The decimals of the mirrored ERC20 token, `None` for ERC20 tokens.
+    pub fn decimals(&self) -> Option<u8> {
+        match self {
+            CurrencyId::Token(symbol) => Some(symbol.decimals()),
+            CurrencyId::DEXShare(left, _) => Some(left.decimals()),
+            CurrencyId::ERC20(_) => None,
+        }
+    }
+
+    /// The address of the ERC20 contract mirroring this currency, at
+    /// `MIRRORED_TOKENS_ADDRESS_START` plus the token symbol for tokens, which
+    /// keeps the addresses of the first predeployed tokens, and plus the last
+    /// 3 bytes of the `[u8; 32]` encoding for DEX shares. `None` for ERC20
+    /// tokens, which are contracts already.
+    pub fn mirrored_token_address(&self) -> Option<EvmAddress> {
+        let offset = match *self {
+            CurrencyId::Token(symbol) => symbol as u64,
+            CurrencyId::DEXShare(left, right) => 1 << 16 | (left as u64) << 8 | right as u64,
+            CurrencyId::ERC20(_) => return None,
+        };
+        Some(EvmAddress::from_low_u64_be(MIRRORED_TOKENS_ADDRESS_START + offset))
+    }
+
+    /// The currency mirrored by the ERC20 contract at `address`, the inverse
+    /// of `mirrored_token_address`.
+    pub fn from_mirrored_token_address(address: EvmAddress) -> Option<Self> {
+        if !address.as_bytes().starts_with(&[0u8; 12][..]) {
+            return None;
+        }
+
+        let offset = address.to_low_u64_be().checked_sub(MIRRORED_TOKENS_ADDRESS_START)?;
+        if offset > 0x00ff_ffff {
+            return None;
+        }
+        let [_, _, _, _, _, kind, left, right] = offset.to_be_bytes();
+        match (kind, left) {
+            (0, 0) => right.try_into().map(CurrencyId::Token).ok(),
+            (1, _) => Some(CurrencyId::DEXShare(left.try_into().ok()?, right.try_into().ok()?)),
+            _ => None,
+        }
+    }
 }
 
 /// Note the pre-deployed ERC20 contracts depend on `CurrencyId` implementation,
@@ -1,5 +1,6 @@
 use codec::Encode;
-use sp_core::{crypto::AccountId32, H160};
+use frame_support::traits::Randomness;
+use sp_core::{crypto::AccountId32, H160, H256};
 use sp_io::hashing::blake2_256;
 
 pub struct MockAddressMapping;
@@ -36,3 +37,11 @@ impl crate::evm::AddressMapping<AccountId32> for MockAddressMapping {
         Self::get_or_create_evm_address(account_id) == *evm
     }
 }
+
+pub struct MockRandomness;
+
+impl<BlockNumber: Default> Randomness<H256, BlockNumber> for MockRandomness {
+    fn random(subject: &[u8]) -> (H256, BlockNumber) {
+        (H256::from(blake2_256(subject)), Default::default())
+    }
+}
@@ -36,3 +36,67 @@ fn currency_id_encode_decode_bytes_works() {
     let bytes: [u8; 32] = currency_id.into();
     assert_ok!(bytes.try_into(), currency_id)
 }
+
+#[test]
+fn mirrored_token_address_works() {
+    assert_eq!(
+        CurrencyId::Token(TokenSymbol::OVR).mirrored_token_address(),
+        Some(EvmAddress::from_low_u64_be(0x01000000))
+    );
+    assert_eq!(
+        CurrencyId::Token(TokenSymbol::OUSD).mirrored_token_address(),
+        Some(EvmAddress::from_low_u64_be(0x01000001))
+    );
+    assert_eq!(
+        CurrencyId::DEXShare(TokenSymbol::OVR, TokenSymbol::OUSD).mirrored_token_address(),
+        Some(EvmAddress::from_low_u64_be(0x01010001))
+    );
+    assert_eq!(CurrencyId::ERC20(Default::default()).mirrored_token_address(), None);
+}
+
+#[test]
+fn from_mirrored_token_address_works() {
+    for currency_id in [
+        CurrencyId::Token(TokenSymbol::OVR),
+        CurrencyId::Token(TokenSymbol::OUSD),
+        CurrencyId::DEXShare(TokenSymbol::OUSD, TokenSymbol::OVR),
+    ] {
+        let address = currency_id.mirrored_token_address().unwrap();
+        assert_eq!(CurrencyId::from_mirrored_token_address(address), Some(currency_id));
+    }
+
+    assert_eq!(
+        CurrencyId::from_mirrored_token_address(EvmAddress::from_low_u64_be(0x01000002)),
+        None
+    );
+    assert_eq!(
+        CurrencyId::from_mirrored_token_address(EvmAddress::from_low_u64_be(0x01000100)),
+        None
+    );
+    assert_eq!(
+        CurrencyId::from_mirrored_token_address(EvmAddress::from_low_u64_be(0x800)),
+        None
+    );
+    assert_eq!(
+        CurrencyId::from_mirrored_token_address(EvmAddress::from_low_u64_be(0x02000000)),
+        None
+    );
+}
+
+#[test]
+fn currency_id_metadata_works() {
+    let ovr = CurrencyId::Token(TokenSymbol::OVR);
+    assert_eq!(ovr.name(), Some(b"Ovr".to_vec()));
+    assert_eq!(ovr.symbol(), Some(b"OVR".to_vec()));
+    assert_eq!(ovr.decimals(), Some(18));
+
+    let lp = CurrencyId::DEXShare(TokenSymbol::OVR, TokenSymbol::OUSD);
+    assert_eq!(lp.name(), Some(b"LP Ovr - Ovr Dollar".to_vec()));
+    assert_eq!(lp.symbol(), Some(b"LP_OVR_OUSD".to_vec()));
+    assert_eq!(lp.decimals(), Some(18));
+
+    let erc20 = CurrencyId::ERC20(Default::default());
+    assert_eq!(erc20.name(), None);
+    assert_eq!(erc20.symbol(), None);
+    assert_eq!(erc20.decimals(), None);
+}
@@ -13,13 +13,17 @@ use frame_system::limits;
 use primitives::{PRECOMPILE_ADDRESS_START, PREDEPLOY_ADDRESS_START};
 use sp_core::H160;
 use sp_runtime::{traits::Convert, FixedU128, Perbill};
+use sp_std::marker::PhantomData;
 
 use static_assertions::const_assert;
 
 pub use module_support::PrecompileCallerFilter;
 
 pub mod precompile;
-pub use precompile::{AllPrecompiles, MultiCurrencyPrecompile, ScheduleCallPrecompile, StateRentPrecompile};
+pub use precompile::{
+    AllPrecompiles, DispatchGasCost, DispatchPrecompile, MultiCurrencyPrecompile, PocPrecompile,
+    ScheduleCallPrecompile, StateRentPrecompile, MIRRORED_CURRENCIES, MIRRORED_TOKEN_CODE,
+};
 
 pub type Price = FixedU128;
 pub type Ratio = FixedU128;
@@ -47,6 +51,16 @@ impl PrecompileCallerFilter for SystemContractsFilter {
     }
 }
 
+/// The call is allowed only if caller is a contract, excluding the ones
+/// still running their constructor.
+pub struct ContractsFilter<Runtime>(PhantomData<Runtime>);
+impl<Runtime: module_evm::Config> PrecompileCallerFilter for ContractsFilter<Runtime> {
+    fn is_allowed(caller: H160) -> bool {
+        module_evm::Pallet::<Runtime>::accounts(caller)
+            .map_or(false, |account_info| account_info.contract_info.is_some())
+    }
+}
+
 /// Convert gas to weight
 pub struct GasToWeight;
 impl Convert<u64, Weight> for GasToWeight {
@@ -55,6 +69,14 @@ impl Convert<u64, Weight> for GasToWeight {
     }
 }
 
+/// Convert weight to gas, the inverse of `GasToWeight`
+pub struct WeightToGas;
+impl Convert<Weight, u64> for WeightToGas {
+    fn convert(a: Weight) -> u64 {
+        a as u64
+    }
+}
+
 pub const AVERAGE_ON_INITIALIZE_RATIO: Perbill = Perbill::from_perthousand(25);
 /// We allow `Normal` extrinsics to fill up the block up to 75%, the rest can be
 /// used by  Operational  extrinsics.
@@ -101,6 +123,17 @@ parameter_types! {
         .saturating_sub(BlockExecutionWeight::get());
 }
 
+parameter_types! {
+    /// The gas limit of a block, the weight `Normal` extrinsics can fill up
+    /// converted to gas.
+    pub BlockGasLimit: u64 = WeightToGas::convert(
+        BlockWeights::get()
+            .get(DispatchClass::Normal)
+            .max_total
+            .unwrap_or(MAXIMUM_BLOCK_WEIGHT),
+    );
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -143,4 +176,11 @@ mod tests {
         assert!(!is_core_precompile(H160::from_low_u64_be(PREDEPLOY_ADDRESS_START)));
         assert!(!is_core_precompile([1u8; 20].into()));
     }
+    #[test]
+    fn block_gas_limit_works() {
+        assert_eq!(
+            GasToWeight::convert(BlockGasLimit::get()),
+            NORMAL_DISPATCH_RATIO * MAXIMUM_BLOCK_WEIGHT
+        );
+    }
 }
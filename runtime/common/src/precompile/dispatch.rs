@@ -0,0 +1,107 @@
+use codec::{Decode, DecodeLimit, Encode};
+use frame_support::{
+    dispatch::{Dispatchable, PostDispatchInfo},
+    traits::{Contains, Get},
+    weights::{GetDispatchInfo, Pays, Weight},
+};
+use module_evm::{precompiles::PrecompileGasCost, Context, ExitError, ExitSucceed, Precompile};
+use module_evm_abi::{encode_params, Bytes};
+use primitives::evm::AddressMapping as AddressMappingT;
+use sp_runtime::traits::Convert;
+use sp_std::{marker::PhantomData, prelude::*, result};
+
+use super::{storage_gas_cost, DispatchPrecompileAddress};
+
+/// The maximum depth of the nested calls, e.g. of `utility.batch`.
+const MAX_DECODE_DEPTH: u32 = 8;
+
+/// The `Dispatch` precompile.
+///
+/// `input` data is the SCALE encoded `Call`, dispatched with the caller as
+/// signed origin if allowed by `CallFilter`. The weight of the call is
+/// charged as gas. The output is the dispatch result, its actual weight and
+/// if it pays a fee, and the SCALE encoded events deposited by the call, ABI
+/// encoded as `(uint64, bool, bytes[])`. A failed dispatch reverts.
+///
+/// The precompile can't be called with `DELEGATECALL` or `CALLCODE`, as the
+/// calling contract would dispatch on behalf of its own caller, nor in a
+/// static context.
+pub struct DispatchPrecompile<AccountId, AddressMapping, Runtime, Call, CallFilter, WeightToGas>(
+    PhantomData<(AccountId, AddressMapping, Runtime, Call, CallFilter, WeightToGas)>,
+);
+
+/// The gas cost of the `Dispatch` precompile, before the weight of the call:
+/// the read of the caller filter, the decoding of the input, and the reads
+/// of the event count and of the events.
+pub struct DispatchGasCost;
+
+impl PrecompileGasCost for DispatchGasCost {
+    fn gas_cost(input: &[u8]) -> u64 {
+        storage_gas_cost(input, 3, 0)
+    }
+}
+
+impl<AccountId, AddressMapping, Runtime, Call, CallFilter, WeightToGas> Precompile
+    for DispatchPrecompile<AccountId, AddressMapping, Runtime, Call, CallFilter, WeightToGas>
+where
+    AddressMapping: AddressMappingT<AccountId>,
+    Runtime: frame_system::Config,
+    Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo + Decode,
+    <Call as Dispatchable>::Origin: From<Option<AccountId>>,
+    CallFilter: Contains<Call>,
+    WeightToGas: Convert<Weight, u64>,
+{
+    fn execute(
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+        is_static: bool,
+    ) -> result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
+        log::debug!(target: "evm", "input: {:?}", input);
+
+        if context.address != DispatchPrecompileAddress::get() {
+            return Err(ExitError::Other("no delegate call".into()));
+        }
+        if is_static {
+            return Err(ExitError::Other("no static call".into()));
+        }
+
+        let call = Call::decode_all_with_depth_limit(MAX_DECODE_DEPTH, input)
+            .map_err(|_| ExitError::Other("invalid call".into()))?;
+        if !CallFilter::contains(&call) {
+            return Err(ExitError::Other("call filtered".into()));
+        }
+
+        let info = call.get_dispatch_info();
+        let cost = WeightToGas::convert(info.weight);
+        if let Some(target_gas) = target_gas {
+            if cost > target_gas {
+                return Err(ExitError::OutOfGas);
+            }
+        }
+
+        let origin = AddressMapping::get_account_id(&context.caller);
+        let event_count = frame_system::Pallet::<Runtime>::event_count();
+        let post_info = call.dispatch(Some(origin).into()).map_err(|e| {
+            let err_msg: &str = e.error.into();
+            ExitError::Other(err_msg.into())
+        })?;
+
+        log::debug!(target: "evm", "dispatch success!");
+
+        // the events after the count are the ones deposited by the call
+        let events = frame_system::Pallet::<Runtime>::read_events_no_consensus()
+            .into_iter()
+            .skip(event_count as usize)
+            .map(|record| Bytes(record.event.encode()))
+            .collect::<Vec<_>>();
+        let actual_weight = post_info.calc_actual_weight(&info);
+        let pays_fee = post_info.pays_fee(&info) == Pays::Yes;
+
+        Ok((
+            ExitSucceed::Returned,
+            encode_params((actual_weight, pays_fee, events)),
+            WeightToGas::convert(actual_weight),
+        ))
+    }
+}
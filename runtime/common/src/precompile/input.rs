@@ -2,23 +2,18 @@ use frame_support::ensure;
 use sp_std::{
     convert::{TryFrom, TryInto},
     marker::PhantomData,
-    mem,
     result::Result,
     vec::Vec,
 };
 
 use module_evm::ExitError;
+use module_evm_abi::{decode_params, Tokenizable};
 use primitives::{evm::AddressMapping as AddressMappingT, Amount, Balance, CurrencyId};
 use sp_core::H160;
 
 pub const PER_PARAM_BYTES: usize = 32;
 pub const ACTION_INDEX: usize = 0;
 
-pub const BALANCE_BYTES: usize = mem::size_of::<Balance>();
-pub const AMOUNT_BYTES: usize = mem::size_of::<Amount>();
-pub const U64_BYTES: usize = mem::size_of::<u64>();
-pub const U32_BYTES: usize = mem::size_of::<u32>();
-
 pub trait InputT {
     type Error;
     type Action;
@@ -27,6 +22,9 @@ pub trait InputT {
     fn nth_param(&self, n: usize) -> Result<&[u8], Self::Error>;
     fn action(&self) -> Result<Self::Action, Self::Error>;
 
+    /// Decode the ABI encoded static param at `index`.
+    fn param_at<T: Tokenizable>(&self, index: usize) -> Result<T, Self::Error>;
+
     fn account_id_at(&self, index: usize) -> Result<Self::AccountId, Self::Error>;
     fn evm_address_at(&self, index: usize) -> Result<H160, Self::Error>;
     fn currency_id_at(&self, index: usize) -> Result<CurrencyId, Self::Error>;
@@ -80,22 +78,29 @@ where
             .map_err(|_| ExitError::Other("invalid action".into()))
     }
 
-    fn account_id_at(&self, index: usize) -> Result<Self::AccountId, Self::Error> {
-        let param = self.nth_param(index)?;
+    fn param_at<T: Tokenizable>(&self, index: usize) -> Result<T, Self::Error> {
+        // dynamic params are encoded behind an offset, which the positional layout doesn't have
+        ensure!(
+            !T::param_type().is_dynamic(),
+            ExitError::Other("invalid param type".into())
+        );
 
-        let mut address = [0u8; 20];
-        address.copy_from_slice(&param[12..]);
+        let param = self.nth_param(index)?;
 
-        Ok(AddressMapping::get_account_id(&address.into()))
+        decode_params::<T>(param).map_err(|e| {
+            let err_msg: &str = e.into();
+            ExitError::Other(err_msg.into())
+        })
     }
 
-    fn evm_address_at(&self, index: usize) -> Result<H160, Self::Error> {
-        let param = self.nth_param(index)?;
+    fn account_id_at(&self, index: usize) -> Result<Self::AccountId, Self::Error> {
+        let address = self.evm_address_at(index)?;
 
-        let mut address = [0u8; 20];
-        address.copy_from_slice(&param[12..]);
+        Ok(AddressMapping::get_account_id(&address))
+    }
 
-        Ok(H160::from_slice(&address))
+    fn evm_address_at(&self, index: usize) -> Result<H160, Self::Error> {
+        self.param_at::<H160>(index)
     }
 
     fn currency_id_at(&self, index: usize) -> Result<CurrencyId, Self::Error> {
@@ -111,43 +116,19 @@ where
     }
 
     fn balance_at(&self, index: usize) -> Result<Balance, Self::Error> {
-        let param = self.nth_param(index)?;
-
-        let mut balance = [0u8; BALANCE_BYTES];
-        let start = PER_PARAM_BYTES - BALANCE_BYTES;
-        balance[..].copy_from_slice(&param[start..]);
-
-        Ok(Balance::from_be_bytes(balance))
+        self.param_at::<Balance>(index)
     }
 
     fn amount_at(&self, index: usize) -> Result<Amount, Self::Error> {
-        let param = self.nth_param(index)?;
-
-        let mut amount = [0u8; AMOUNT_BYTES];
-        let start = PER_PARAM_BYTES - AMOUNT_BYTES;
-        amount[..].copy_from_slice(&param[start..]);
-
-        Ok(Amount::from_be_bytes(amount))
+        self.param_at::<Amount>(index)
     }
 
     fn u64_at(&self, index: usize) -> Result<u64, Self::Error> {
-        let param = self.nth_param(index)?;
-
-        let mut num = [0u8; U64_BYTES];
-        let start = PER_PARAM_BYTES - U64_BYTES;
-        num[..].copy_from_slice(&param[start..]);
-
-        Ok(u64::from_be_bytes(num))
+        self.param_at::<u64>(index)
     }
 
     fn u32_at(&self, index: usize) -> Result<u32, Self::Error> {
-        let param = self.nth_param(index)?;
-
-        let mut num = [0u8; U32_BYTES];
-        let start = PER_PARAM_BYTES - U32_BYTES;
-        num[..].copy_from_slice(&param[start..]);
-
-        Ok(u32::from_be_bytes(num))
+        self.param_at::<u32>(index)
     }
 
     fn bytes_at(&self, start: usize, len: usize) -> Result<Vec<u8>, Self::Error> {
@@ -169,7 +150,7 @@ mod tests {
     use super::*;
 
     use frame_support::{assert_err, assert_ok};
-    use sp_core::H160;
+    use sp_core::{H160, U256};
 
     use primitives::{mocks::MockAddressMapping, AccountId, CurrencyId, TokenSymbol};
 
@@ -269,6 +250,9 @@ mod tests {
         raw_input[16..].copy_from_slice(&amount_bytes);
         let input = TestInput::new(&raw_input[..]);
         assert_ok!(input.amount_at(0), amount);
+
+        let input = TestInput::new(&[0xffu8; 32][..]);
+        assert_ok!(input.amount_at(0), -1);
     }
 
     #[test]
@@ -281,4 +265,22 @@ mod tests {
         let input = TestInput::new(&raw_input[..]);
         assert_ok!(input.u64_at(0), u64_num);
     }
+
+    #[test]
+    fn param_at_works() {
+        let mut raw_input = [0u8; 64];
+        raw_input[31] = 1;
+        raw_input[32..].copy_from_slice(&[0xffu8; 32]);
+        let input = TestInput::new(&raw_input[..]);
+
+        assert_ok!(input.param_at::<bool>(0), true);
+        assert_ok!(input.param_at::<U256>(1), U256::MAX);
+        assert_err!(input.param_at::<u32>(1), ExitError::Other("invalid abi data".into()));
+        assert_err!(input.evm_address_at(1), ExitError::Other("invalid abi data".into()));
+        assert_err!(
+            input.param_at::<module_evm_abi::Bytes>(0),
+            ExitError::Other("invalid param type".into())
+        );
+        assert_err!(input.param_at::<bool>(2), ExitError::Other("invalid input".into()));
+    }
 }
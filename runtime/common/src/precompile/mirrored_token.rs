@@ -0,0 +1,202 @@
+//! The code of the ERC20 contracts mirroring the `MultiCurrency` tokens.
+
+use frame_support::{
+    traits::{Currency, Get, OnRuntimeUpgrade},
+    weights::Weight,
+};
+use frame_system::RawOrigin;
+use module_evm::{code_hash, AddressMapping, ContractsToRemove, EvmAddress};
+use primitives::{CurrencyId, TokenSymbol};
+use sp_runtime::traits::Zero;
+use sp_std::marker::PhantomData;
+
+/// The currencies mirrored by an ERC20 contract with `MIRRORED_TOKEN_CODE`,
+/// predeployed at genesis and by `ReplaceMirroredTokenCode` on live chains.
+pub const MIRRORED_CURRENCIES: [CurrencyId; 3] = [
+    CurrencyId::Token(TokenSymbol::OVR),
+    CurrencyId::Token(TokenSymbol::OUSD),
+    CurrencyId::DEXShare(TokenSymbol::OVR, TokenSymbol::OUSD),
+];
+
+/// The runtime code of the predeployed ERC20 contract mirroring the currency
+/// at `CurrencyId::mirrored_token_address`.
+///
+/// It holds no state: every call is forwarded as is to the `MultiCurrency`
+/// precompile with action `MirroredTokenCall`, along with the token address
+/// and the caller. The precompile finds the currency from the token address.
+/// The output of the precompile is returned, and reverted with if it fails.
+///
+/// Precompiles don't know whether they are called in a static context, so
+/// the selectors changing balances or allowances first write a storage slot
+/// with its own value. The write is a no-op, but fails in a `STATICCALL`,
+/// which keeps a view call from moving tokens of its caller.
+#[rustfmt::skip]
+pub const MIRRORED_TOKEN_CODE: [u8; 132] = [
+    // reject calls carrying value
+    0x34,                           // 0x00 CALLVALUE
+    0x15,                           // 0x01 ISZERO
+    0x60, 0x09,                     // 0x02 PUSH1 nonpayable
+    0x57,                           // 0x04 JUMPI
+    0x60, 0x00,                     // 0x05 PUSH1 0x00
+    0x80,                           // 0x07 DUP1
+    0xfd,                           // 0x08 REVERT
+    // nonpayable: load the selector
+    0x5b,                           // 0x09 JUMPDEST
+    0x60, 0x00,                     // 0x0a PUSH1 0x00
+    0x35,                           // 0x0c CALLDATALOAD
+    0x60, 0xe0,                     // 0x0d PUSH1 0xe0
+    0x1c,                           // 0x0f SHR
+    0x80,                           // 0x10 DUP1
+    // guard the selectors changing balances or allowances: transfer(address,uint256),
+    // approve(address,uint256), transferFrom(address,address,uint256),
+    // increaseAllowance(address,uint256) and decreaseAllowance(address,uint256)
+    0x63, 0xa9, 0x05, 0x9c, 0xbb,   // 0x11 PUSH4 0xa9059cbb
+    0x14,                           // 0x16 EQ
+    0x60, 0x45,                     // 0x17 PUSH1 guard
+    0x57,                           // 0x19 JUMPI
+    0x80,                           // 0x1a DUP1
+    0x63, 0x09, 0x5e, 0xa7, 0xb3,   // 0x1b PUSH4 0x095ea7b3
+    0x14,                           // 0x20 EQ
+    0x60, 0x45,                     // 0x21 PUSH1 guard
+    0x57,                           // 0x23 JUMPI
+    0x80,                           // 0x24 DUP1
+    0x63, 0x23, 0xb8, 0x72, 0xdd,   // 0x25 PUSH4 0x23b872dd
+    0x14,                           // 0x2a EQ
+    0x60, 0x45,                     // 0x2b PUSH1 guard
+    0x57,                           // 0x2d JUMPI
+    0x80,                           // 0x2e DUP1
+    0x63, 0x39, 0x50, 0x93, 0x51,   // 0x2f PUSH4 0x39509351
+    0x14,                           // 0x34 EQ
+    0x60, 0x45,                     // 0x35 PUSH1 guard
+    0x57,                           // 0x37 JUMPI
+    0x80,                           // 0x38 DUP1
+    0x63, 0xa4, 0x57, 0xc2, 0xd7,   // 0x39 PUSH4 0xa457c2d7
+    0x14,                           // 0x3e EQ
+    0x60, 0x45,                     // 0x3f PUSH1 guard
+    0x57,                           // 0x41 JUMPI
+    0x60, 0x4c,                     // 0x42 PUSH1 forward
+    0x56,                           // 0x44 JUMP
+    // guard: a no-op write, which fails in a static call
+    0x5b,                           // 0x45 JUMPDEST
+    0x60, 0x00,                     // 0x46 PUSH1 0x00
+    0x54,                           // 0x48 SLOAD
+    0x60, 0x00,                     // 0x49 PUSH1 0x00
+    0x55,                           // 0x4b SSTORE
+    // forward: input of the precompile at mem[0..]: action, token address, caller, call data
+    0x5b,                           // 0x4c JUMPDEST
+    0x50,                           // 0x4d POP
+    0x60, 0x03,                     // 0x4e PUSH1 0x03
+    0x60, 0x00,                     // 0x50 PUSH1 0x00
+    0x52,                           // 0x52 MSTORE
+    0x30,                           // 0x53 ADDRESS
+    0x60, 0x20,                     // 0x54 PUSH1 0x20
+    0x52,                           // 0x56 MSTORE
+    0x33,                           // 0x57 CALLER
+    0x60, 0x40,                     // 0x58 PUSH1 0x40
+    0x52,                           // 0x5a MSTORE
+    0x36,                           // 0x5b CALLDATASIZE
+    0x60, 0x00,                     // 0x5c PUSH1 0x00
+    0x60, 0x60,                     // 0x5e PUSH1 0x60
+    0x37,                           // 0x60 CALLDATACOPY
+    // call the `MultiCurrency` precompile with all the gas left
+    0x60, 0x00,                     // 0x61 PUSH1 0x00
+    0x60, 0x00,                     // 0x63 PUSH1 0x00
+    0x36,                           // 0x65 CALLDATASIZE
+    0x60, 0x60,                     // 0x66 PUSH1 0x60
+    0x01,                           // 0x68 ADD
+    0x60, 0x00,                     // 0x69 PUSH1 0x00
+    0x60, 0x00,                     // 0x6b PUSH1 0x00
+    0x61, 0x04, 0x00,               // 0x6d PUSH2 0x0400
+    0x5a,                           // 0x70 GAS
+    0xf1,                           // 0x71 CALL
+    // copy its output, then revert with it on failure
+    0x3d,                           // 0x72 RETURNDATASIZE
+    0x60, 0x00,                     // 0x73 PUSH1 0x00
+    0x60, 0x00,                     // 0x75 PUSH1 0x00
+    0x3e,                           // 0x77 RETURNDATACOPY
+    0x60, 0x7f,                     // 0x78 PUSH1 ok
+    0x57,                           // 0x7a JUMPI
+    0x3d,                           // 0x7b RETURNDATASIZE
+    0x60, 0x00,                     // 0x7c PUSH1 0x00
+    0xfd,                           // 0x7e REVERT
+    // ok: return the output of the precompile
+    0x5b,                           // 0x7f JUMPDEST
+    0x3d,                           // 0x80 RETURNDATASIZE
+    0x60, 0x00,                     // 0x81 PUSH1 0x00
+    0xf3,                           // 0x83 RETURN
+];
+
+/// Replace the ERC20 contracts predeployed for OVR and OUSD on live chains
+/// with `MIRRORED_TOKEN_CODE`, and predeploy the missing ones of
+/// `MIRRORED_CURRENCIES` like at genesis.
+///
+/// Their balances live in `MultiCurrency` from now on. The storage of the old
+/// contracts, their allowances, is removed in `on_idle` of `module_evm`,
+/// which releases its storage deposit to the maintainer.
+pub struct ReplaceMirroredTokenCode<T>(PhantomData<T>);
+
+impl<T: module_evm::Config> OnRuntimeUpgrade for ReplaceMirroredTokenCode<T> {
+    fn on_runtime_upgrade() -> Weight {
+        let new_code_hash = code_hash(&MIRRORED_TOKEN_CODE);
+        let mut weight: Weight = 0;
+
+        for currency_id in MIRRORED_CURRENCIES {
+            let address = match currency_id.mirrored_token_address() {
+                Some(address) => address,
+                None => continue,
+            };
+            weight = weight.saturating_add(T::DbWeight::get().reads(1));
+            let contract_info = module_evm::Pallet::<T>::accounts(&address).and_then(|account| account.contract_info);
+            let maintainer = match contract_info {
+                Some(contract_info) if contract_info.code_hash == new_code_hash => continue,
+                Some(contract_info) => contract_info.maintainer,
+                None => {
+                    predeploy::<T>(address);
+                    weight = weight.saturating_add(T::DbWeight::get().reads_writes(6, 6));
+                    continue;
+                }
+            };
+
+            match module_evm::Pallet::<T>::set_code(RawOrigin::Root.into(), address, MIRRORED_TOKEN_CODE.to_vec()) {
+                Ok(_) => {
+                    ContractsToRemove::<T>::insert(address, maintainer);
+                    weight = weight.saturating_add(T::DbWeight::get().reads_writes(5, 6));
+                }
+                Err(e) => log::warn!(
+                    target: "evm",
+                    "ReplaceMirroredTokenCode: failed to set the code of {:?}: {:?}",
+                    address,
+                    e
+                ),
+            }
+        }
+
+        weight
+    }
+}
+
+/// Predeploy `MIRRORED_TOKEN_CODE` at `address`, like the genesis accounts of
+/// `module_evm`.
+fn predeploy<T: module_evm::Config>(address: EvmAddress) {
+    let account_id = T::AddressMapping::get_account_id(&address);
+    if T::Currency::total_balance(&account_id).is_zero() {
+        T::Currency::deposit_creating(&account_id, T::Currency::minimum_balance());
+    }
+
+    if let Err(e) = module_evm::Pallet::<T>::on_contract_initialization(
+        &address,
+        &EvmAddress::default(),
+        MIRRORED_TOKEN_CODE.to_vec(),
+    ) {
+        log::warn!(
+            target: "evm",
+            "ReplaceMirroredTokenCode: failed to predeploy {:?}: {:?}",
+            address,
+            e
+        );
+        return;
+    }
+
+    #[cfg(not(feature = "with-ethereum-compatibility"))]
+    let _ = module_evm::Pallet::<T>::mark_deployed(address, None);
+}
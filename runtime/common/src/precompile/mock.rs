@@ -1,21 +1,27 @@
 #![cfg(test)]
 
-use crate::{AllPrecompiles, BlockWeights, SystemContractsFilter, Weight};
+use crate::{
+    AllPrecompiles, BlockWeights, ContractsFilter, SystemContractsFilter, Weight, WeightToGas, MIRRORED_CURRENCIES,
+    MIRRORED_TOKEN_CODE,
+};
 use codec::{Decode, Encode, MaxEncodedLen};
 use frame_support::{
     assert_ok, ord_parameter_types, parameter_types,
-    traits::{GenesisBuild, InstanceFilter, OnFinalize, OnInitialize},
+    traits::{Contains, GenesisBuild, InstanceFilter, OnFinalize, OnInitialize},
     weights::IdentityFee,
     RuntimeDebug,
 };
 use frame_system::{EnsureRoot, EnsureSignedBy};
 use orml_traits::parameter_type_with_key;
 pub use primitives::{
-    evm::AddressMapping, mocks::MockAddressMapping, Amount, BlockNumber, CurrencyId, Header, Nonce, TokenSymbol,
+    evm::{AddressMapping, ERC20_HOLDING_ACCOUNT},
+    mocks::{MockAddressMapping, MockRandomness},
+    time::{MILLISECS_PER_BLOCK, MILLISECS_PER_HOUR},
+    Amount, BlockNumber, CurrencyId, Header, Moment, Nonce, TokenSymbol,
 };
 use sp_core::{bytes::from_hex, crypto::AccountId32, Bytes, H160, H256};
 use sp_runtime::{
-    traits::{BlakeTwo256, Convert, IdentityLookup},
+    traits::{BlakeTwo256, Convert, Identity, IdentityLookup},
     Perbill,
 };
 use sp_std::{collections::btree_map::BTreeMap, str::FromStr};
@@ -102,6 +108,11 @@ parameter_types! {
     pub const GetNativeCurrencyId: CurrencyId = OVR;
 }
 
+parameter_types! {
+    pub const Erc20HoldingAccount: H160 = ERC20_HOLDING_ACCOUNT;
+    pub const Erc20SlashBeneficiary: AccountId = AccountId::new([9u8; 32]);
+}
+
 impl module_currencies::Config for Test {
     type Event = Event;
     type MultiCurrency = Tokens;
@@ -109,6 +120,10 @@ impl module_currencies::Config for Test {
     type WeightInfo = ();
     type AddressMapping = MockAddressMapping;
     type EVMBridge = EVMBridge;
+    type Erc20HoldingAccount = Erc20HoldingAccount;
+    type Erc20SlashBeneficiary = Erc20SlashBeneficiary;
+    type Erc20Registry = ();
+    type Erc20ReserveAccounts = ();
 }
 
 impl module_evm_bridge::Config for Test {
@@ -209,7 +224,7 @@ impl pallet_scheduler::Config for Test {
 
 pub type AdaptedBasicCurrency = module_currencies::BasicCurrencyAdapter<Test, Balances, Amount, BlockNumber>;
 
-pub type MultiCurrencyPrecompile = crate::MultiCurrencyPrecompile<AccountId, MockAddressMapping, Currencies>;
+pub type MultiCurrencyPrecompile = crate::MultiCurrencyPrecompile<AccountId, MockAddressMapping, Currencies, ModuleEVM>;
 
 pub type StateRentPrecompile = crate::StateRentPrecompile<AccountId, MockAddressMapping, ModuleEVM>;
 pub type ScheduleCallPrecompile = crate::ScheduleCallPrecompile<
@@ -223,6 +238,18 @@ pub type ScheduleCallPrecompile = crate::ScheduleCallPrecompile<
     Test,
 >;
 
+pub struct DispatchCallFilter;
+impl Contains<Call> for DispatchCallFilter {
+    fn contains(call: &Call) -> bool {
+        matches!(call, Call::Balances(_))
+    }
+}
+
+pub type DispatchPrecompile =
+    crate::DispatchPrecompile<AccountId, MockAddressMapping, Test, Call, DispatchCallFilter, WeightToGas>;
+
+pub type PocPrecompile = crate::PocPrecompile<AccountId, MockAddressMapping, Test, WeightToGas>;
+
 parameter_types! {
     pub NetworkContractSource: H160 = alice();
 }
@@ -235,6 +262,7 @@ ord_parameter_types! {
     pub const DeveloperDeposit: u64 = 1000;
     pub const DeploymentFee: u64 = 200;
     pub const MaxCodeSize: u32 = 60 * 1024;
+    pub const MaxCodeHistory: u32 = 10;
     pub const ChainId: u64 = 1;
 }
 
@@ -252,17 +280,66 @@ impl module_evm::Config for Test {
     type NewContractExtraBytes = NewContractExtraBytes;
     type StorageDepositPerByte = StorageDepositPerByte;
     type MaxCodeSize = MaxCodeSize;
+    type MaxCodeHistory = MaxCodeHistory;
     type Event = Event;
-    type Precompiles =
-        AllPrecompiles<SystemContractsFilter, MultiCurrencyPrecompile, StateRentPrecompile, ScheduleCallPrecompile>;
+    type Precompiles = AllPrecompiles<
+        SystemContractsFilter,
+        ContractsFilter<Test>,
+        MultiCurrencyPrecompile,
+        StateRentPrecompile,
+        ScheduleCallPrecompile,
+        DispatchPrecompile,
+        PocPrecompile,
+    >;
     type ChainId = ChainId;
+    type MinimumGasPrice = ();
+    type TransactionByteFee = ();
     type GasToWeight = GasToWeight;
+    type BaseFeeUpdate = Identity;
+    type FindAuthor = ();
+    type BlockGasLimit = ();
+    type Randomness = MockRandomness;
     type ChargeTransactionPayment = ChargeTransactionPayment;
     type NetworkContractOrigin = EnsureSignedBy<NetworkContractAccount, AccountId>;
     type NetworkContractSource = NetworkContractSource;
     type DeveloperDeposit = DeveloperDeposit;
     type DeploymentFee = DeploymentFee;
     type FreeDeploymentOrigin = EnsureSignedBy<CouncilAccount, AccountId>;
+    type PrecompileOrigin = EnsureRoot<AccountId>;
+    type WeightInfo = ();
+}
+
+parameter_types! {
+    pub const EraDuration: Moment = 7 * MILLISECS_PER_HOUR;
+    pub const MillisecsPerBlock: Moment = MILLISECS_PER_BLOCK;
+    pub const NominatorAPY: Perbill = Perbill::from_percent(10);
+    pub const CouncilInflation: Perbill = Perbill::from_percent(1);
+    pub const CandidacyDeposit: Balance = 250_000;
+    pub const MinLockAmount: Balance = 100;
+    pub const TotalLockedCap: Balance = 10_000_000;
+    pub const MaxCandidates: u32 = 100;
+    pub const MaxMembers: u32 = 3;
+    pub const HistoryDepth: u32 = 4;
+    pub const MaxCommitmentsPerAccount: u32 = 4;
+}
+
+impl module_poc::Config for Test {
+    type Event = Event;
+    type Currency = Balances;
+    type EraDuration = EraDuration;
+    type MillisecsPerBlock = MillisecsPerBlock;
+    type NominatorAPY = NominatorAPY;
+    type CouncilInflation = CouncilInflation;
+    type CandidacyDeposit = CandidacyDeposit;
+    type MinLockAmount = MinLockAmount;
+    type TotalLockedCap = TotalLockedCap;
+    type MaxCandidates = MaxCandidates;
+    type MaxMembers = MaxMembers;
+    type HistoryDepth = HistoryDepth;
+    type MaxCommitmentsPerAccount = MaxCommitmentsPerAccount;
+    type MembershipChanged = ();
+    type SlashOrigin = EnsureRoot<AccountId>;
+    type Slash = ();
     type WeightInfo = ();
 }
 
@@ -294,6 +371,15 @@ pub fn evm_genesis() -> BTreeMap<H160, module_evm::GenesisAccount<Balance, u64>>
         );
         accounts.insert(addr, account);
     }
+    for currency_id in MIRRORED_CURRENCIES {
+        let account = module_evm::GenesisAccount {
+            nonce: 0,
+            balance: 0u128,
+            storage: Default::default(),
+            code: MIRRORED_TOKEN_CODE.to_vec(),
+        };
+        accounts.insert(currency_id.mirrored_token_address().unwrap(), account);
+    }
     accounts
 }
 
@@ -313,13 +399,14 @@ frame_support::construct_runtime!(
         Timestamp: pallet_timestamp::{Pallet, Call, Storage, Inherent},
         Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>},
         Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
-        Currencies: module_currencies::{Pallet, Call, Event<T>},
+        Currencies: module_currencies::{Pallet, Call, Storage, Event<T>},
         EVMBridge: module_evm_bridge::{Pallet},
         TransactionPayment: module_transaction_payment::{Pallet, Call, Storage},
         Proxy: pallet_proxy::{Pallet, Call, Storage, Event<T>},
         Utility: pallet_utility::{Pallet, Call, Event},
         Scheduler: pallet_scheduler::{Pallet, Call, Storage, Event<T>},
         ModuleEVM: module_evm::{Pallet, Config<T>, Call, Storage, Event<T>},
+        Poc: module_poc::{Pallet, Call, Storage, Event<T>},
     }
 );
 
@@ -1,5 +1,16 @@
 //! The precompiles for EVM, includes standard Ethereum precompiles, and more:
 //! - MultiCurrency at address `H160::from_low_u64_be(1024)`.
+//! - StateRent at address `H160::from_low_u64_be(1026)`.
+//! - ScheduleCall at address `H160::from_low_u64_be(1028)`.
+//! - Dispatch at address `H160::from_low_u64_be(1030)`, callable by any
+//!   contract.
+//! - Poc at address `H160::from_low_u64_be(1032)`, callable by any contract.
+//!
+//! Each precompile is registered with its address, caller filter and gas cost
+//! in `AllPrecompiles`, and can be disabled by governance in `module_evm`.
+//!
+//! The ERC20 contracts mirroring the `MultiCurrency` tokens forward to it, see
+//! `mirrored_token`.
 
 #![allow(clippy::upper_case_acronyms)]
 
@@ -7,69 +18,133 @@ mod mock;
 mod tests;
 
 use crate::is_core_precompile;
+use frame_support::parameter_types;
 use module_evm::{
     precompiles::{
-        ECRecover, ECRecoverPublicKey, EvmPrecompiles, Identity, Precompile, Precompiles, Ripemd160, Sha256,
-        Sha3FIPS256, Sha3FIPS512,
+        Blake2F, Bn128Add, Bn128Mul, Bn128Pairing, ECRecover, ECRecoverPublicKey, EvmPrecompiles, Identity, Modexp,
+        Precompiles, RegisteredPrecompile, Ripemd160, Sha256, Sha3FIPS256, Sha3FIPS512,
     },
     Context, ExitError, ExitSucceed,
 };
 use module_support::PrecompileCallerFilter as PrecompileCallerFilterT;
 use primitives::PRECOMPILE_ADDRESS_START;
 use sp_core::H160;
-use sp_std::{marker::PhantomData, prelude::*};
+use sp_std::{convert::TryFrom, marker::PhantomData, prelude::*};
 
+pub mod dispatch;
 pub mod input;
+pub mod mirrored_token;
 pub mod multicurrency;
+pub mod poc;
 pub mod schedule_call;
 pub mod state_rent;
 
-pub use multicurrency::MultiCurrencyPrecompile;
-pub use schedule_call::ScheduleCallPrecompile;
-pub use state_rent::StateRentPrecompile;
+pub use dispatch::{DispatchGasCost, DispatchPrecompile};
+pub use mirrored_token::{ReplaceMirroredTokenCode, MIRRORED_CURRENCIES, MIRRORED_TOKEN_CODE};
+pub use multicurrency::{MultiCurrencyGasCost, MultiCurrencyPrecompile};
+pub use poc::{PocGasCost, PocPrecompile};
+pub use schedule_call::{ScheduleCallGasCost, ScheduleCallPrecompile};
+pub use state_rent::{StateRentGasCost, StateRentPrecompile};
 
-pub struct AllPrecompiles<PrecompileCallerFilter, MultiCurrencyPrecompile, StateRentPrecompile, ScheduleCallPrecompile>(
-    PhantomData<(
-        PrecompileCallerFilter,
+/// Gas of a storage read, the cold `SLOAD` cost.
+pub const STORAGE_READ_GAS: u64 = 2_100;
+/// Gas of a storage write, the `SSTORE` cost of a new value.
+pub const STORAGE_WRITE_GAS: u64 = 20_000;
+/// Gas per 32 bytes word of the input.
+pub const INPUT_WORD_GAS: u64 = 3;
+
+parameter_types! {
+    pub MultiCurrencyPrecompileAddress: H160 = H160::from_low_u64_be(PRECOMPILE_ADDRESS_START);
+    pub StateRentPrecompileAddress: H160 = H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 2);
+    pub ScheduleCallPrecompileAddress: H160 = H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 4);
+    pub DispatchPrecompileAddress: H160 = H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 6);
+    pub PocPrecompileAddress: H160 = H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 8);
+}
+
+/// The registry of all the precompiles: the standard Ethereum ones, the core
+/// precompiles, callable by the callers allowed by `PrecompileCallerFilter`
+/// only, the dispatch precompile, callable by the callers allowed by
+/// `DispatchCallerFilter`, and the PoC precompile, callable by any contract.
+/// The rest of the core precompile range is guarded by `CorePrecompileGuard`.
+pub type AllPrecompiles<
+    PrecompileCallerFilter,
+    DispatchCallerFilter,
+    MultiCurrencyPrecompile,
+    StateRentPrecompile,
+    ScheduleCallPrecompile,
+    DispatchPrecompile,
+    PocPrecompile,
+> = (
+    EvmPrecompiles<
+        ECRecover,
+        Sha256,
+        Ripemd160,
+        Identity,
+        Modexp,
+        Bn128Add,
+        Bn128Mul,
+        Bn128Pairing,
+        Blake2F,
+        ECRecoverPublicKey,
+        Sha3FIPS256,
+        Sha3FIPS512,
+    >,
+    RegisteredPrecompile<
+        MultiCurrencyPrecompileAddress,
         MultiCurrencyPrecompile,
-        StateRentPrecompile,
+        PrecompileCallerFilter,
+        MultiCurrencyGasCost,
+    >,
+    RegisteredPrecompile<StateRentPrecompileAddress, StateRentPrecompile, PrecompileCallerFilter, StateRentGasCost>,
+    RegisteredPrecompile<
+        ScheduleCallPrecompileAddress,
         ScheduleCallPrecompile,
-    )>,
+        PrecompileCallerFilter,
+        ScheduleCallGasCost,
+    >,
+    RegisteredPrecompile<DispatchPrecompileAddress, DispatchPrecompile, DispatchCallerFilter, DispatchGasCost>,
+    RegisteredPrecompile<PocPrecompileAddress, PocPrecompile, (), PocGasCost>,
+    CorePrecompileGuard<PrecompileCallerFilter>,
 );
 
-impl<PrecompileCallerFilter, MultiCurrencyPrecompile, StateRentPrecompile, ScheduleCallPrecompile> Precompiles
-    for AllPrecompiles<PrecompileCallerFilter, MultiCurrencyPrecompile, StateRentPrecompile, ScheduleCallPrecompile>
-where
-    MultiCurrencyPrecompile: Precompile,
-    StateRentPrecompile: Precompile,
-    ScheduleCallPrecompile: Precompile,
-    PrecompileCallerFilter: PrecompileCallerFilterT,
-{
+/// Calls to the addresses of the core precompile range without a precompile
+/// fail with "no permission" for the callers not allowed by
+/// `PrecompileCallerFilter`, like calls to the core precompiles.
+pub struct CorePrecompileGuard<PrecompileCallerFilter>(PhantomData<PrecompileCallerFilter>);
+
+impl<PrecompileCallerFilter: PrecompileCallerFilterT> Precompiles for CorePrecompileGuard<PrecompileCallerFilter> {
     #[allow(clippy::type_complexity)]
     fn execute(
         address: H160,
-        input: &[u8],
-        target_gas: Option<u64>,
+        _input: &[u8],
+        _target_gas: Option<u64>,
         context: &Context,
+        _is_static: bool,
     ) -> Option<core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError>> {
-        EvmPrecompiles::<ECRecover, Sha256, Ripemd160, Identity, ECRecoverPublicKey, Sha3FIPS256, Sha3FIPS512>::execute(
-            address, input, target_gas, context,
-        )
-        .or_else(|| {
-            if is_core_precompile(address) && !PrecompileCallerFilter::is_allowed(context.caller) {
-                log::debug!(target: "evm", "Precompile no permission");
-                return Some(Err(ExitError::Other("no permission".into())));
-            }
+        if is_core_precompile(address) && !PrecompileCallerFilter::is_allowed(context.caller) {
+            log::debug!(target: "evm", "Precompile no permission");
+            return Some(Err(ExitError::Other("no permission".into())));
+        }
 
-            if address == H160::from_low_u64_be(PRECOMPILE_ADDRESS_START) {
-                Some(MultiCurrencyPrecompile::execute(input, target_gas, context))
-            } else if address == H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 2) {
-                Some(StateRentPrecompile::execute(input, target_gas, context))
-            } else if address == H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 4) {
-                Some(ScheduleCallPrecompile::execute(input, target_gas, context))
-            } else {
-                None
-            }
-        })
+        None
     }
+
+    fn is_precompile(_address: H160) -> bool {
+        false
+    }
+}
+
+/// The action of the precompile `input`, if valid.
+fn action<Action: TryFrom<u8>>(input: &[u8]) -> Option<Action> {
+    input.get(31).and_then(|action| Action::try_from(*action).ok())
+}
+
+/// The gas of a precompile call with `input`, doing `reads` storage reads
+/// and `writes` storage writes.
+fn storage_gas_cost(input: &[u8], reads: u64, writes: u64) -> u64 {
+    let words = (input.len() as u64).saturating_add(31) / 32;
+    STORAGE_READ_GAS
+        .saturating_mul(reads)
+        .saturating_add(STORAGE_WRITE_GAS.saturating_mul(writes))
+        .saturating_add(INPUT_WORD_GAS.saturating_mul(words))
 }
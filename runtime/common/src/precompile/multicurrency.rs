@@ -1,30 +1,112 @@
-use module_evm::{Context, ExitError, ExitSucceed, Precompile};
-use primitives::evm::AddressMapping as AddressMappingT;
-use sp_core::U256;
+use module_evm::{precompiles::PrecompileGasCost, Context, ExitError, ExitSucceed, Precompile};
+use module_evm_abi::{decode_params, encode_params, event_topic, SolString, Tokenizable};
+use primitives::evm::{AddressMapping as AddressMappingT, EvmAddress};
+use sp_core::H256;
 use sp_std::{convert::TryFrom, fmt::Debug, marker::PhantomData, prelude::*, result};
 
-use orml_traits::MultiCurrency as MultiCurrencyT;
+use module_support::{EVMLog, MultiCurrencyAllowance};
+use orml_traits::{MultiCurrency as MultiCurrencyT, MultiReservableCurrency};
 
-use super::input::{Input, InputT};
+use super::{
+    action,
+    input::{Input, InputT},
+    storage_gas_cost,
+};
 use primitives::{Balance, CurrencyId};
 
+/// The ERC20 functions of the mirrored tokens.
+enum Erc20Method {
+    /// `name()`
+    Name,
+    /// `symbol()`
+    Symbol,
+    /// `decimals()`
+    Decimals,
+    /// `totalSupply()`
+    TotalSupply,
+    /// `balanceOf(address)`
+    BalanceOf,
+    /// `transfer(address,uint256)`
+    Transfer,
+    /// `allowance(address,address)`
+    Allowance,
+    /// `approve(address,uint256)`
+    Approve,
+    /// `transferFrom(address,address,uint256)`
+    TransferFrom,
+    /// `increaseAllowance(address,uint256)`
+    IncreaseAllowance,
+    /// `decreaseAllowance(address,uint256)`
+    DecreaseAllowance,
+    /// `currencyId()`
+    CurrencyId,
+}
+
+impl TryFrom<&[u8]> for Erc20Method {
+    type Error = ();
+
+    fn try_from(selector: &[u8]) -> Result<Self, Self::Error> {
+        match selector {
+            [0x06, 0xfd, 0xde, 0x03] => Ok(Erc20Method::Name),
+            [0x95, 0xd8, 0x9b, 0x41] => Ok(Erc20Method::Symbol),
+            [0x31, 0x3c, 0xe5, 0x67] => Ok(Erc20Method::Decimals),
+            [0x18, 0x16, 0x0d, 0xdd] => Ok(Erc20Method::TotalSupply),
+            [0x70, 0xa0, 0x82, 0x31] => Ok(Erc20Method::BalanceOf),
+            [0xa9, 0x05, 0x9c, 0xbb] => Ok(Erc20Method::Transfer),
+            [0xdd, 0x62, 0xed, 0x3e] => Ok(Erc20Method::Allowance),
+            [0x09, 0x5e, 0xa7, 0xb3] => Ok(Erc20Method::Approve),
+            [0x23, 0xb8, 0x72, 0xdd] => Ok(Erc20Method::TransferFrom),
+            [0x39, 0x50, 0x93, 0x51] => Ok(Erc20Method::IncreaseAllowance),
+            [0xa4, 0x57, 0xc2, 0xd7] => Ok(Erc20Method::DecreaseAllowance),
+            [0x1f, 0xee, 0xec, 0xe2] => Ok(Erc20Method::CurrencyId),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Start of the ERC20 call data in the input of a mirrored token call.
+const MIRRORED_CALL_DATA_START: usize = 3 * 32;
+
 /// The `MultiCurrency` impl precompile.
 ///
 ///
-/// `input` data starts with `action` and `currency_id`.
+/// `input` data starts with `action` and `currency_id`, or the address of the
+/// mirrored token for mirrored token calls.
 ///
 /// Actions:
 /// - Query total issuance.
 /// - Query balance. Rest `input` bytes: `account_id`.
 /// - Transfer. Rest `input` bytes: `from`, `to`, `amount`.
-pub struct MultiCurrencyPrecompile<AccountId, AddressMapping, MultiCurrency>(
-    PhantomData<(AccountId, AddressMapping, MultiCurrency)>,
+/// - Mirrored token call, made by the ERC20 contract mirroring the currency.
+///   Rest `input` bytes: `caller`, ERC20 call data. Supports the baseline
+///   ERC20 surface: `increaseAllowance`, `decreaseAllowance` and
+///   `currencyId` besides the standard functions.
+/// - Query reserved balance. Rest `input` bytes: `account_id`.
+/// - Query minimum balance.
+/// - Query symbol.
+/// - Query decimals.
+/// - Query allowance. Rest `input` bytes: `owner`, `spender`.
+/// - Approve. Rest `input` bytes: `owner`, `spender`, `amount`.
+/// - Transfer from. Rest `input` bytes: `spender`, `from`, `to`, `amount`.
+///
+/// Transfers and approvals emit the ERC20 `Transfer` and `Approval` logs of
+/// the contract mirroring the currency.
+pub struct MultiCurrencyPrecompile<AccountId, AddressMapping, MultiCurrency, EVM>(
+    PhantomData<(AccountId, AddressMapping, MultiCurrency, EVM)>,
 );
 
 enum Action {
     QueryTotalIssuance,
     QueryBalance,
     Transfer,
+    MirroredTokenCall,
+    QueryReservedBalance,
+    QueryMinimumBalance,
+    QuerySymbol,
+    QueryDecimals,
+    QueryAllowance,
+    Approve,
+    TransferFrom,
 }
 
 impl TryFrom<u8> for Action {
@@ -35,37 +117,93 @@ impl TryFrom<u8> for Action {
             0 => Ok(Action::QueryTotalIssuance),
             1 => Ok(Action::QueryBalance),
             2 => Ok(Action::Transfer),
+            3 => Ok(Action::MirroredTokenCall),
+            4 => Ok(Action::QueryReservedBalance),
+            5 => Ok(Action::QueryMinimumBalance),
+            6 => Ok(Action::QuerySymbol),
+            7 => Ok(Action::QueryDecimals),
+            8 => Ok(Action::QueryAllowance),
+            9 => Ok(Action::Approve),
+            10 => Ok(Action::TransferFrom),
             _ => Err(()),
         }
     }
 }
 
-impl<AccountId, AddressMapping, MultiCurrency> Precompile
-    for MultiCurrencyPrecompile<AccountId, AddressMapping, MultiCurrency>
+/// The gas cost of the `MultiCurrency` precompile, by the storage reads and
+/// writes of the action.
+pub struct MultiCurrencyGasCost;
+
+impl PrecompileGasCost for MultiCurrencyGasCost {
+    fn gas_cost(input: &[u8]) -> u64 {
+        let (reads, writes) = match action(input) {
+            Some(Action::QueryTotalIssuance)
+            | Some(Action::QueryBalance)
+            | Some(Action::QueryReservedBalance)
+            | Some(Action::QueryAllowance) => (1, 0),
+            Some(Action::Transfer) => (2, 2),
+            Some(Action::Approve) => (0, 1),
+            Some(Action::TransferFrom) => (3, 3),
+            Some(Action::MirroredTokenCall) => {
+                let selector = input.get(MIRRORED_CALL_DATA_START..MIRRORED_CALL_DATA_START + 4);
+                match selector.and_then(|selector| Erc20Method::try_from(selector).ok()) {
+                    Some(Erc20Method::TotalSupply) | Some(Erc20Method::BalanceOf) | Some(Erc20Method::Allowance) => {
+                        (1, 0)
+                    }
+                    Some(Erc20Method::Transfer) => (2, 2),
+                    Some(Erc20Method::Approve) => (0, 1),
+                    Some(Erc20Method::IncreaseAllowance) | Some(Erc20Method::DecreaseAllowance) => (1, 1),
+                    Some(Erc20Method::TransferFrom) => (3, 3),
+                    _ => (0, 0),
+                }
+            }
+            _ => (0, 0),
+        };
+
+        storage_gas_cost(input, reads, writes)
+    }
+}
+
+impl<AccountId, AddressMapping, MultiCurrency, EVM> Precompile
+    for MultiCurrencyPrecompile<AccountId, AddressMapping, MultiCurrency, EVM>
 where
     AccountId: Debug + Clone,
     AddressMapping: AddressMappingT<AccountId>,
-    MultiCurrency: MultiCurrencyT<AccountId, Balance = Balance, CurrencyId = CurrencyId>,
+    MultiCurrency: MultiCurrencyT<AccountId, Balance = Balance, CurrencyId = CurrencyId>
+        + MultiReservableCurrency<AccountId>
+        + MultiCurrencyAllowance<AccountId, Balance = Balance, CurrencyId = CurrencyId>,
+    EVM: EVMLog,
 {
     fn execute(
         input: &[u8],
         _target_gas: Option<u64>,
-        _context: &Context,
+        context: &Context,
+        _is_static: bool,
     ) -> result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
-        //TODO: evaluate cost
-
         log::debug!(target: "evm", "input: {:?}", input);
 
+        let call_data = input.get(MIRRORED_CALL_DATA_START..).unwrap_or_default();
         let input = Input::<Action, AccountId, AddressMapping>::new(input);
 
         let action = input.action()?;
-        let currency_id = input.currency_id_at(1)?;
+        let currency_id = match action {
+            Action::MirroredTokenCall => {
+                // only the contract mirroring the currency may act on behalf of its callers
+                let token = input.evm_address_at(1)?;
+                if token != context.caller {
+                    return Err(ExitError::Other("not the mirrored token".into()));
+                }
+                CurrencyId::from_mirrored_token_address(token)
+                    .ok_or_else(|| ExitError::Other("not the mirrored token".into()))?
+            }
+            _ => input.currency_id_at(1)?,
+        };
 
         log::debug!(target: "evm", "currency id: {:?}", currency_id);
 
         match action {
             Action::QueryTotalIssuance => {
-                let total_issuance = vec_u8_from_balance(MultiCurrency::total_issuance(currency_id));
+                let total_issuance = encode_params(MultiCurrency::total_issuance(currency_id));
                 log::debug!(target: "evm", "total issuance: {:?}", total_issuance);
 
                 Ok((ExitSucceed::Returned, total_issuance, 0))
@@ -74,35 +212,249 @@ where
                 let who = input.account_id_at(2)?;
                 log::debug!(target: "evm", "who: {:?}", who);
 
-                let balance = vec_u8_from_balance(MultiCurrency::total_balance(currency_id, &who));
+                let balance = encode_params(MultiCurrency::total_balance(currency_id, &who));
                 log::debug!(target: "evm", "balance: {:?}", balance);
 
                 Ok((ExitSucceed::Returned, balance, 0))
             }
             Action::Transfer => {
-                let from = input.account_id_at(2)?;
-                let to = input.account_id_at(3)?;
+                let from = input.evm_address_at(2)?;
+                let to = input.evm_address_at(3)?;
                 let amount = input.balance_at(4)?;
 
                 log::debug!(target: "evm", "from: {:?}", from);
                 log::debug!(target: "evm", "to: {:?}", to);
                 log::debug!(target: "evm", "amount: {:?}", amount);
 
-                MultiCurrency::transfer(currency_id, &from, &to, amount).map_err(|e| {
-                    let err_msg: &str = e.into();
-                    ExitError::Other(err_msg.into())
-                })?;
+                Self::transfer(currency_id, from, to, amount)?;
 
                 log::debug!(target: "evm", "transfer success!");
 
                 Ok((ExitSucceed::Returned, vec![], 0))
             }
+            Action::MirroredTokenCall => {
+                let caller = input.evm_address_at(2)?;
+                log::debug!(target: "evm", "caller: {:?}", caller);
+
+                let output = Self::mirrored_token_call(currency_id, caller, call_data)?;
+
+                Ok((ExitSucceed::Returned, output, 0))
+            }
+            Action::QueryReservedBalance => {
+                let who = input.account_id_at(2)?;
+                log::debug!(target: "evm", "who: {:?}", who);
+
+                let reserved_balance = encode_params(MultiCurrency::reserved_balance(currency_id, &who));
+                log::debug!(target: "evm", "reserved balance: {:?}", reserved_balance);
+
+                Ok((ExitSucceed::Returned, reserved_balance, 0))
+            }
+            Action::QueryMinimumBalance => {
+                let minimum_balance = encode_params(MultiCurrency::minimum_balance(currency_id));
+                log::debug!(target: "evm", "minimum balance: {:?}", minimum_balance);
+
+                Ok((ExitSucceed::Returned, minimum_balance, 0))
+            }
+            Action::QuerySymbol => {
+                let symbol = currency_id
+                    .symbol()
+                    .ok_or_else(|| ExitError::Other("no currency metadata".into()))?;
+
+                Ok((ExitSucceed::Returned, encode_params(SolString(symbol)), 0))
+            }
+            Action::QueryDecimals => {
+                let decimals = currency_id
+                    .decimals()
+                    .ok_or_else(|| ExitError::Other("no currency metadata".into()))?;
+
+                Ok((ExitSucceed::Returned, encode_params(decimals), 0))
+            }
+            Action::QueryAllowance => {
+                let owner = input.account_id_at(2)?;
+                let spender = input.account_id_at(3)?;
+
+                let allowance = encode_params(MultiCurrency::allowance(currency_id, &owner, &spender));
+                log::debug!(target: "evm", "allowance: {:?}", allowance);
+
+                Ok((ExitSucceed::Returned, allowance, 0))
+            }
+            Action::Approve => {
+                let owner = input.evm_address_at(2)?;
+                let spender = input.evm_address_at(3)?;
+                let amount = input.balance_at(4)?;
+
+                log::debug!(target: "evm", "owner: {:?}", owner);
+                log::debug!(target: "evm", "spender: {:?}", spender);
+                log::debug!(target: "evm", "amount: {:?}", amount);
+
+                Self::approve(currency_id, owner, spender, amount)?;
+
+                Ok((ExitSucceed::Returned, vec![], 0))
+            }
+            Action::TransferFrom => {
+                let spender = input.evm_address_at(2)?;
+                let from = input.evm_address_at(3)?;
+                let to = input.evm_address_at(4)?;
+                let amount = input.balance_at(5)?;
+
+                log::debug!(target: "evm", "spender: {:?}", spender);
+                log::debug!(target: "evm", "from: {:?}", from);
+                log::debug!(target: "evm", "to: {:?}", to);
+                log::debug!(target: "evm", "amount: {:?}", amount);
+
+                Self::transfer_from(currency_id, spender, from, to, amount)?;
+
+                Ok((ExitSucceed::Returned, vec![], 0))
+            }
+        }
+    }
+}
+
+impl<AccountId, AddressMapping, MultiCurrency, EVM>
+    MultiCurrencyPrecompile<AccountId, AddressMapping, MultiCurrency, EVM>
+where
+    AccountId: Debug + Clone,
+    AddressMapping: AddressMappingT<AccountId>,
+    MultiCurrency: MultiCurrencyT<AccountId, Balance = Balance, CurrencyId = CurrencyId>
+        + MultiReservableCurrency<AccountId>
+        + MultiCurrencyAllowance<AccountId, Balance = Balance, CurrencyId = CurrencyId>,
+    EVM: EVMLog,
+{
+    fn mirrored_token_call(
+        currency_id: CurrencyId,
+        caller: EvmAddress,
+        call_data: &[u8],
+    ) -> result::Result<Vec<u8>, ExitError> {
+        let (selector, args) = (call_data.get(..4), call_data.get(4..).unwrap_or_default());
+        let method = selector
+            .and_then(|selector| Erc20Method::try_from(selector).ok())
+            .ok_or_else(|| ExitError::Other("invalid ERC20 method".into()))?;
+
+        Ok(match method {
+            Erc20Method::Name => encode_params(SolString(currency_id.name().unwrap_or_default())),
+            Erc20Method::Symbol => encode_params(SolString(currency_id.symbol().unwrap_or_default())),
+            Erc20Method::Decimals => encode_params(currency_id.decimals().unwrap_or_default()),
+            Erc20Method::TotalSupply => encode_params(MultiCurrency::total_issuance(currency_id)),
+            Erc20Method::BalanceOf => {
+                let who = AddressMapping::get_account_id(&decode_args::<EvmAddress>(args)?);
+                encode_params(MultiCurrency::total_balance(currency_id, &who))
+            }
+            Erc20Method::Transfer => {
+                let (to, amount) = decode_args::<(EvmAddress, Balance)>(args)?;
+                Self::transfer(currency_id, caller, to, amount)?;
+                encode_params(true)
+            }
+            Erc20Method::Allowance => {
+                let (owner, spender) = decode_args::<(EvmAddress, EvmAddress)>(args)?;
+                encode_params(Self::allowance(currency_id, owner, spender))
+            }
+            Erc20Method::Approve => {
+                let (spender, amount) = decode_args::<(EvmAddress, Balance)>(args)?;
+                Self::approve(currency_id, caller, spender, amount)?;
+                encode_params(true)
+            }
+            Erc20Method::TransferFrom => {
+                let (from, to, amount) = decode_args::<(EvmAddress, EvmAddress, Balance)>(args)?;
+                Self::transfer_from(currency_id, caller, from, to, amount)?;
+                encode_params(true)
+            }
+            Erc20Method::IncreaseAllowance => {
+                let (spender, added) = decode_args::<(EvmAddress, Balance)>(args)?;
+                let allowance = Self::allowance(currency_id, caller, spender)
+                    .checked_add(added)
+                    .ok_or_else(|| ExitError::Other("allowance overflow".into()))?;
+                Self::approve(currency_id, caller, spender, allowance)?;
+                encode_params(true)
+            }
+            Erc20Method::DecreaseAllowance => {
+                let (spender, subtracted) = decode_args::<(EvmAddress, Balance)>(args)?;
+                let allowance = Self::allowance(currency_id, caller, spender)
+                    .checked_sub(subtracted)
+                    .ok_or_else(|| ExitError::Other("decreased allowance below zero".into()))?;
+                Self::approve(currency_id, caller, spender, allowance)?;
+                encode_params(true)
+            }
+            Erc20Method::CurrencyId => encode_params(H256::from(<[u8; 32]>::from(currency_id))),
+        })
+    }
+
+    fn allowance(currency_id: CurrencyId, owner: EvmAddress, spender: EvmAddress) -> Balance {
+        let owner_account = AddressMapping::get_account_id(&owner);
+        let spender_account = AddressMapping::get_account_id(&spender);
+        MultiCurrency::allowance(currency_id, &owner_account, &spender_account)
+    }
+
+    fn transfer(
+        currency_id: CurrencyId,
+        from: EvmAddress,
+        to: EvmAddress,
+        amount: Balance,
+    ) -> result::Result<(), ExitError> {
+        let from_account = AddressMapping::get_account_id(&from);
+        let to_account = AddressMapping::get_account_id(&to);
+        MultiCurrency::transfer(currency_id, &from_account, &to_account, amount).map_err(|e| {
+            let err_msg: &str = e.into();
+            ExitError::Other(err_msg.into())
+        })?;
+
+        Self::log(currency_id, "Transfer(address,address,uint256)", from, to, amount);
+        Ok(())
+    }
+
+    fn approve(
+        currency_id: CurrencyId,
+        owner: EvmAddress,
+        spender: EvmAddress,
+        amount: Balance,
+    ) -> result::Result<(), ExitError> {
+        let owner_account = AddressMapping::get_account_id(&owner);
+        let spender_account = AddressMapping::get_account_id(&spender);
+        MultiCurrency::approve(currency_id, &owner_account, &spender_account, amount).map_err(|e| {
+            let err_msg: &str = e.into();
+            ExitError::Other(err_msg.into())
+        })?;
+
+        Self::log(currency_id, "Approval(address,address,uint256)", owner, spender, amount);
+        Ok(())
+    }
+
+    fn transfer_from(
+        currency_id: CurrencyId,
+        spender: EvmAddress,
+        from: EvmAddress,
+        to: EvmAddress,
+        amount: Balance,
+    ) -> result::Result<(), ExitError> {
+        let spender_account = AddressMapping::get_account_id(&spender);
+        let from_account = AddressMapping::get_account_id(&from);
+        let to_account = AddressMapping::get_account_id(&to);
+        MultiCurrency::transfer_from(currency_id, &spender_account, &from_account, &to_account, amount).map_err(
+            |e| {
+                let err_msg: &str = e.into();
+                ExitError::Other(err_msg.into())
+            },
+        )?;
+
+        Self::log(currency_id, "Transfer(address,address,uint256)", from, to, amount);
+        Ok(())
+    }
+
+    /// Emit the ERC20 `event` of the contract mirroring `currency_id`. `ERC20`
+    /// currencies are contracts, which emit their own logs.
+    fn log(currency_id: CurrencyId, event: &str, from: EvmAddress, to: EvmAddress, amount: Balance) {
+        if let Some(address) = currency_id.mirrored_token_address() {
+            EVM::log(
+                address,
+                vec![event_topic(event), from.into(), to.into()],
+                encode_params(amount),
+            );
         }
     }
 }
 
-fn vec_u8_from_balance(balance: Balance) -> Vec<u8> {
-    let mut be_bytes = [0u8; 32];
-    U256::from(balance).to_big_endian(&mut be_bytes[..]);
-    be_bytes.to_vec()
+fn decode_args<T: Tokenizable>(args: &[u8]) -> result::Result<T, ExitError> {
+    decode_params::<T>(args).map_err(|e| {
+        let err_msg: &str = e.into();
+        ExitError::Other(err_msg.into())
+    })
 }
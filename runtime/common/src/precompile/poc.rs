@@ -0,0 +1,331 @@
+use frame_support::{dispatch::DispatchResultWithPostInfo, traits::Currency, weights::Weight};
+use module_evm::{precompiles::PrecompileGasCost, Context, ExitError, ExitSucceed, Precompile};
+use module_evm_abi::encode_params;
+use module_poc::{LockDuration, LockState, WeightInfo};
+use primitives::{evm::AddressMapping as AddressMappingT, Balance};
+use sp_core::H256;
+use sp_runtime::traits::{Convert, UniqueSaturatedInto};
+use sp_std::{convert::TryFrom, marker::PhantomData, prelude::*, result};
+
+use super::{
+    action,
+    input::{Input, InputT},
+    storage_gas_cost, PocPrecompileAddress, STORAGE_READ_GAS,
+};
+
+/// The `Poc` impl precompile.
+///
+/// `input` data starts with `action`. The commitments are made, and voted
+/// with, by the calling contract itself. Accounts are passed and returned as
+/// their 32 bytes `AccountId`.
+///
+/// Actions:
+/// - QueryCommitment. Rest `input` bytes: `account_id`, `commitment_id`.
+///   Returns `state` (0: committed, 1: unbonding), `unbonding_start` (a
+///   timestamp in milliseconds), `duration`, `amount`, `candidate`, with a
+///   zero `amount` if there is no commitment.
+/// - QueryVotingWeight. Rest `input` bytes: `account_id`. Returns the voting
+///   weight of all its commitments. Charged a storage read per commitment.
+/// - QueryMembers. Charged `MEMBER_GAS` per returned member.
+/// - QueryCandidate. Rest `input` bytes: `account_id`. Returns if the account
+///   is a candidate, and its deposit.
+/// - QueryCurrentEra. Returns the era `index` and `start` timestamp.
+/// - QueryPendingRewards. Rest `input` bytes: `account_id`, `commitment_id`.
+///   Returns the unclaimed voter rewards of the commitment.
+/// - Commit. Rest `input` bytes: `amount`, `duration` (0: one month, 1: one
+///   year, 2: ten years), `candidate`. Returns the `commitment_id`.
+/// - AddFunds. Rest `input` bytes: `commitment_id`, `amount`.
+/// - Unbond. Rest `input` bytes: `commitment_id`.
+/// - Withdraw. Rest `input` bytes: `commitment_id`.
+/// - VoteCandidate. Rest `input` bytes: `commitment_id`, `candidate`.
+/// - ClaimRewards. Rest `input` bytes: `commitment_id`.
+///
+/// The actions other than queries are charged the weight of their call as
+/// gas, like the `Dispatch` precompile.
+///
+/// The precompile can't be called with `DELEGATECALL` or `CALLCODE`, as the
+/// calling contract would commit on behalf of its own caller. The actions
+/// other than queries can't be called in a static context.
+pub struct PocPrecompile<AccountId, AddressMapping, Runtime, WeightToGas>(
+    PhantomData<(AccountId, AddressMapping, Runtime, WeightToGas)>,
+);
+
+enum Action {
+    QueryCommitment,
+    QueryVotingWeight,
+    QueryMembers,
+    QueryCandidate,
+    QueryCurrentEra,
+    QueryPendingRewards,
+    Commit,
+    AddFunds,
+    Unbond,
+    Withdraw,
+    VoteCandidate,
+    ClaimRewards,
+}
+
+impl Action {
+    fn is_query(&self) -> bool {
+        matches!(
+            self,
+            Action::QueryCommitment
+                | Action::QueryVotingWeight
+                | Action::QueryMembers
+                | Action::QueryCandidate
+                | Action::QueryCurrentEra
+                | Action::QueryPendingRewards
+        )
+    }
+}
+
+/// The weights of the `module_poc` calls.
+type PocWeightInfo<Runtime> = <Runtime as module_poc::Config>::WeightInfo;
+
+/// Gas per member returned by `QueryMembers`, the part of reading and
+/// encoding the members growing with their number.
+pub const MEMBER_GAS: u64 = 200;
+
+impl TryFrom<u8> for Action {
+    type Error = ();
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        // reserve 0 - 127 for query, 128 - 255 for action
+        match value {
+            0 => Ok(Action::QueryCommitment),
+            1 => Ok(Action::QueryVotingWeight),
+            2 => Ok(Action::QueryMembers),
+            3 => Ok(Action::QueryCandidate),
+            4 => Ok(Action::QueryCurrentEra),
+            5 => Ok(Action::QueryPendingRewards),
+            128 => Ok(Action::Commit),
+            129 => Ok(Action::AddFunds),
+            130 => Ok(Action::Unbond),
+            131 => Ok(Action::Withdraw),
+            132 => Ok(Action::VoteCandidate),
+            133 => Ok(Action::ClaimRewards),
+            _ => Err(()),
+        }
+    }
+}
+
+/// The gas cost of the `Poc` precompile, before the weight of the call: the
+/// storage reads of the queries, of the commitment count for
+/// `QueryVotingWeight`, and of the next commitment id for `Commit`.
+pub struct PocGasCost;
+
+impl PrecompileGasCost for PocGasCost {
+    fn gas_cost(input: &[u8]) -> u64 {
+        let reads = match action(input) {
+            Some(Action::QueryCommitment)
+            | Some(Action::QueryVotingWeight)
+            | Some(Action::QueryMembers)
+            | Some(Action::QueryCurrentEra)
+            | Some(Action::Commit) => 1,
+            Some(Action::QueryCandidate) => 2,
+            Some(Action::QueryPendingRewards) => 5,
+            Some(Action::AddFunds)
+            | Some(Action::Unbond)
+            | Some(Action::Withdraw)
+            | Some(Action::VoteCandidate)
+            | Some(Action::ClaimRewards)
+            | None => 0,
+        };
+        storage_gas_cost(input, reads, 0)
+    }
+}
+
+impl<AccountId, AddressMapping, Runtime, WeightToGas> Precompile
+    for PocPrecompile<AccountId, AddressMapping, Runtime, WeightToGas>
+where
+    AccountId: Clone + From<[u8; 32]> + Into<[u8; 32]>,
+    AddressMapping: AddressMappingT<AccountId>,
+    Runtime: module_poc::Config + frame_system::Config<AccountId = AccountId>,
+    Runtime::Currency: Currency<AccountId, Balance = Balance>,
+    WeightToGas: Convert<Weight, u64>,
+{
+    fn execute(
+        input: &[u8],
+        target_gas: Option<u64>,
+        context: &Context,
+        is_static: bool,
+    ) -> result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
+        log::debug!(target: "evm", "poc input: {:?}", input);
+
+        if context.address != PocPrecompileAddress::get() {
+            return Err(ExitError::Other("no delegate call".into()));
+        }
+
+        let input = Input::<Action, AccountId, AddressMapping>::new(input);
+        let action = input.action()?;
+        if is_static && !action.is_query() {
+            return Err(ExitError::Other("no static call".into()));
+        }
+        let origin = || -> <Runtime as frame_system::Config>::Origin {
+            frame_system::RawOrigin::Signed(AddressMapping::get_account_id(&context.caller)).into()
+        };
+
+        match action {
+            Action::QueryCommitment => {
+                let who = account_id_at(&input, 1)?;
+                let id = input.u32_at(2)?;
+                let commitment = module_poc::Pallet::<Runtime>::commitments(who, id);
+
+                let (state, unbonding_start) = match commitment.state {
+                    LockState::Committed => (0u8, 0u64),
+                    LockState::Unbonding(start) => (1u8, UniqueSaturatedInto::<u64>::unique_saturated_into(start)),
+                };
+                let duration = match commitment.duration {
+                    LockDuration::OneMonth => 0u8,
+                    LockDuration::OneYear => 1u8,
+                    LockDuration::TenYears => 2u8,
+                };
+                let candidate = H256(commitment.candidate.into());
+
+                Ok((
+                    ExitSucceed::Returned,
+                    encode_params((state, unbonding_start, duration, commitment.amount, candidate)),
+                    0,
+                ))
+            }
+            Action::QueryVotingWeight => {
+                let who = account_id_at(&input, 1)?;
+                let count = module_poc::Pallet::<Runtime>::commitments_count(&who);
+                let used_gas = STORAGE_READ_GAS.saturating_mul(count.into());
+                if target_gas.map_or(false, |target_gas| used_gas > target_gas) {
+                    return Err(ExitError::OutOfGas);
+                }
+                let weight = module_poc::Pallet::<Runtime>::account_voting_weight(&who);
+
+                Ok((ExitSucceed::Returned, encode_params(weight), used_gas))
+            }
+            Action::QueryMembers => {
+                let members = module_poc::Pallet::<Runtime>::members();
+                let used_gas = MEMBER_GAS.saturating_mul(members.len() as u64);
+                if target_gas.map_or(false, |target_gas| used_gas > target_gas) {
+                    return Err(ExitError::OutOfGas);
+                }
+
+                let members = members
+                    .into_iter()
+                    .map(|member| H256(member.into()))
+                    .collect::<Vec<_>>();
+
+                Ok((ExitSucceed::Returned, encode_params(members), used_gas))
+            }
+            Action::QueryCandidate => {
+                let who = account_id_at(&input, 1)?;
+                let is_candidate = module_poc::Candidates::<Runtime>::contains_key(&who);
+                let deposit = module_poc::Pallet::<Runtime>::candidates(&who);
+
+                Ok((ExitSucceed::Returned, encode_params((is_candidate, deposit)), 0))
+            }
+            Action::QueryCurrentEra => {
+                let era = module_poc::Pallet::<Runtime>::current_era();
+                let start = UniqueSaturatedInto::<u64>::unique_saturated_into(era.start);
+
+                Ok((ExitSucceed::Returned, encode_params((era.index, start)), 0))
+            }
+            Action::QueryPendingRewards => {
+                let who = account_id_at(&input, 1)?;
+                let id = input.u32_at(2)?;
+                let rewards = module_poc::Pallet::<Runtime>::pending_rewards(&who, id);
+
+                Ok((ExitSucceed::Returned, encode_params(rewards), 0))
+            }
+            Action::Commit => {
+                let amount = input.balance_at(1)?;
+                let duration = match input.param_at::<u8>(2)? {
+                    0 => LockDuration::OneMonth,
+                    1 => LockDuration::OneYear,
+                    2 => LockDuration::TenYears,
+                    _ => return Err(ExitError::Other("invalid lock duration".into())),
+                };
+                let candidate = account_id_at(&input, 3)?;
+                let id =
+                    module_poc::Pallet::<Runtime>::next_commitment_id(AddressMapping::get_account_id(&context.caller));
+
+                let used_gas = dispatch::<WeightToGas>(PocWeightInfo::<Runtime>::commit(), target_gas, || {
+                    module_poc::Pallet::<Runtime>::commit(origin(), amount, duration, candidate)
+                })?;
+
+                Ok((ExitSucceed::Returned, encode_params(id), used_gas))
+            }
+            Action::AddFunds => {
+                let id = input.u32_at(1)?;
+                let amount = input.balance_at(2)?;
+                let used_gas = dispatch::<WeightToGas>(PocWeightInfo::<Runtime>::add_funds(), target_gas, || {
+                    module_poc::Pallet::<Runtime>::add_funds(origin(), id, amount)
+                })?;
+
+                Ok((ExitSucceed::Returned, vec![], used_gas))
+            }
+            Action::Unbond => {
+                let id = input.u32_at(1)?;
+                let used_gas = dispatch::<WeightToGas>(PocWeightInfo::<Runtime>::unbond(), target_gas, || {
+                    module_poc::Pallet::<Runtime>::unbond(origin(), id)
+                })?;
+
+                Ok((ExitSucceed::Returned, vec![], used_gas))
+            }
+            Action::Withdraw => {
+                let id = input.u32_at(1)?;
+                let used_gas = dispatch::<WeightToGas>(PocWeightInfo::<Runtime>::withdraw(), target_gas, || {
+                    module_poc::Pallet::<Runtime>::withdraw(origin(), id)
+                })?;
+
+                Ok((ExitSucceed::Returned, vec![], used_gas))
+            }
+            Action::VoteCandidate => {
+                let id = input.u32_at(1)?;
+                let candidate = account_id_at(&input, 2)?;
+                let used_gas = dispatch::<WeightToGas>(PocWeightInfo::<Runtime>::vote_candidate(), target_gas, || {
+                    module_poc::Pallet::<Runtime>::vote_candidate(origin(), id, candidate)
+                })?;
+
+                Ok((ExitSucceed::Returned, vec![], used_gas))
+            }
+            Action::ClaimRewards => {
+                let id = input.u32_at(1)?;
+                let used_gas = dispatch::<WeightToGas>(PocWeightInfo::<Runtime>::claim_rewards(), target_gas, || {
+                    module_poc::Pallet::<Runtime>::claim_rewards(origin(), id)
+                })?;
+
+                Ok((ExitSucceed::Returned, vec![], used_gas))
+            }
+        }
+    }
+}
+
+/// The 32 bytes `AccountId` at `index`.
+fn account_id_at<AccountId, AddressMapping>(
+    input: &Input<Action, AccountId, AddressMapping>,
+    index: usize,
+) -> result::Result<AccountId, ExitError>
+where
+    AccountId: From<[u8; 32]>,
+    AddressMapping: AddressMappingT<AccountId>,
+{
+    let account_id = input.param_at::<H256>(index)?;
+    Ok(AccountId::from(account_id.0))
+}
+
+/// Dispatch a call of `weight` if its gas is within `target_gas`, and return
+/// the gas of its actual weight.
+fn dispatch<WeightToGas: Convert<Weight, u64>>(
+    weight: Weight,
+    target_gas: Option<u64>,
+    call: impl FnOnce() -> DispatchResultWithPostInfo,
+) -> result::Result<u64, ExitError> {
+    if target_gas.map_or(false, |target_gas| WeightToGas::convert(weight) > target_gas) {
+        return Err(ExitError::OutOfGas);
+    }
+
+    let post_info = call().map_err(|e| {
+        let err_msg: &str = e.error.into();
+        ExitError::Other(err_msg.into())
+    })?;
+
+    let weight = post_info.actual_weight.map_or(weight, |actual| actual.min(weight));
+    Ok(WeightToGas::convert(weight))
+}
@@ -9,14 +9,17 @@ use frame_support::{
         Currency, IsType, OriginTrait,
     },
 };
-use module_evm::{Context, ExitError, ExitSucceed, Precompile};
+use module_evm::{precompiles::PrecompileGasCost, Context, ExitError, ExitSucceed, Precompile};
 use module_support::TransactionPayment;
 use primitives::{evm::AddressMapping as AddressMappingT, Balance, BlockNumber};
 use sp_core::{H160, U256};
 use sp_runtime::RuntimeDebug;
 use sp_std::{convert::TryFrom, fmt::Debug, marker::PhantomData, prelude::*, result};
 
-use super::input::{Input, InputT, PER_PARAM_BYTES};
+use super::{
+    input::{Input, InputT, PER_PARAM_BYTES},
+    storage_gas_cost,
+};
 use codec::{Decode, Encode};
 use pallet_scheduler::TaskAddress;
 
@@ -82,6 +85,16 @@ impl TryFrom<u8> for Action {
     }
 }
 
+/// The gas cost of the `ScheduleCall` precompile: each action reads and
+/// writes the scheduler agenda and the reserved fee.
+pub struct ScheduleCallGasCost;
+
+impl PrecompileGasCost for ScheduleCallGasCost {
+    fn gas_cost(input: &[u8]) -> u64 {
+        storage_gas_cost(input, 2, 3)
+    }
+}
+
 type PalletBalanceOf<T> =
     <<T as module_evm::Config>::Currency as Currency<<T as frame_system::Config>::AccountId>>::Balance;
 type NegativeImbalanceOf<T> =
@@ -114,6 +127,7 @@ where
         input: &[u8],
         _target_gas: Option<u64>,
         _context: &Context,
+        _is_static: bool,
     ) -> result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
         log::debug!(target: "evm", "schedule call: input: {:?}", input);
 
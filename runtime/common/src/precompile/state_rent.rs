@@ -1,10 +1,14 @@
-use module_evm::{Context, ExitError, ExitSucceed, Precompile};
-use sp_core::U256;
+use module_evm::{precompiles::PrecompileGasCost, Context, ExitError, ExitSucceed, Precompile};
+use module_evm_abi::encode_params;
 use sp_std::{borrow::Cow, convert::TryFrom, marker::PhantomData, prelude::*, result};
 
 use module_support::EVMStateRentTrait;
 
-use super::input::{Input, InputT};
+use super::{
+    action,
+    input::{Input, InputT},
+    storage_gas_cost,
+};
 use primitives::{evm::AddressMapping as AddressMappingT, Balance};
 
 /// The `EVM` impl precompile.
@@ -47,6 +51,19 @@ impl TryFrom<u8> for Action {
     }
 }
 
+/// The gas cost of the `StateRent` precompile.
+pub struct StateRentGasCost;
+
+impl PrecompileGasCost for StateRentGasCost {
+    fn gas_cost(input: &[u8]) -> u64 {
+        match action(input) {
+            Some(Action::QueryMaintainer) => storage_gas_cost(input, 1, 0),
+            Some(Action::TransferMaintainer) => storage_gas_cost(input, 1, 1),
+            _ => storage_gas_cost(input, 0, 0),
+        }
+    }
+}
+
 impl<AccountId, AddressMapping, EVM> Precompile for StateRentPrecompile<AccountId, AddressMapping, EVM>
 where
     AccountId: Clone,
@@ -57,6 +74,7 @@ where
         input: &[u8],
         _target_gas: Option<u64>,
         _context: &Context,
+        _is_static: bool,
     ) -> result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
         log::debug!(target: "evm", "state_rent input: {:?}", input);
         let input = Input::<Action, AccountId, AddressMapping>::new(input);
@@ -65,11 +83,11 @@ where
 
         match action {
             Action::QueryNewContractExtraBytes => {
-                let bytes = vec_u8_from_u32(EVM::query_new_contract_extra_bytes());
+                let bytes = encode_params(EVM::query_new_contract_extra_bytes());
                 Ok((ExitSucceed::Returned, bytes, 0))
             }
             Action::QueryStorageDepositPerByte => {
-                let deposit = vec_u8_from_balance(EVM::query_storage_deposit_per_byte());
+                let deposit = encode_params(EVM::query_storage_deposit_per_byte());
                 Ok((ExitSucceed::Returned, deposit, 0))
             }
             Action::QueryMaintainer => {
@@ -78,17 +96,14 @@ where
                 let maintainer =
                     EVM::query_maintainer(contract).map_err(|e| ExitError::Other(Cow::Borrowed(e.into())))?;
 
-                let mut address = [0u8; 32];
-                address[12..].copy_from_slice(&maintainer.as_bytes().to_vec());
-
-                Ok((ExitSucceed::Returned, address.to_vec(), 0))
+                Ok((ExitSucceed::Returned, encode_params(maintainer), 0))
             }
             Action::QueryDeveloperDeposit => {
-                let deposit = vec_u8_from_balance(EVM::query_developer_deposit());
+                let deposit = encode_params(EVM::query_developer_deposit());
                 Ok((ExitSucceed::Returned, deposit, 0))
             }
             Action::QueryDeploymentFee => {
-                let fee = vec_u8_from_balance(EVM::query_deployment_fee());
+                let fee = encode_params(EVM::query_deployment_fee());
                 Ok((ExitSucceed::Returned, fee, 0))
             }
             Action::TransferMaintainer => {
@@ -104,15 +119,3 @@ where
         }
     }
 }
-
-fn vec_u8_from_balance(b: Balance) -> Vec<u8> {
-    let mut be_bytes = [0u8; 32];
-    U256::from(b).to_big_endian(&mut be_bytes[..]);
-    be_bytes.to_vec()
-}
-
-fn vec_u8_from_u32(b: u32) -> Vec<u8> {
-    let mut be_bytes = [0u8; 32];
-    U256::from(b).to_big_endian(&mut be_bytes[..]);
-    be_bytes.to_vec()
-}
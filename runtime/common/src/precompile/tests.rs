@@ -2,16 +2,30 @@
 use super::*;
 use crate::precompile::{
     mock::{
-        alice, bob, get_task_id, new_test_ext, run_to_block, Balances, Event as TestEvent, ScheduleCallPrecompile,
-        System, Test, OVR_ERC20_ADDRESS,
+        alice, bob, get_task_id, new_test_ext, run_to_block, AccountId, Balances, Call, Currencies, DispatchPrecompile,
+        Event as TestEvent, MockAddressMapping, ModuleEVM, MultiCurrencyPrecompile, Origin, Poc, PocPrecompile,
+        ScheduleCallPrecompile, System, Test, ALICE, INITIAL_BALANCE, OUSD, OVR, OVR_ERC20_ADDRESS,
     },
     schedule_call::TaskInfo,
 };
-use codec::Encode;
+use codec::{Decode, Encode};
+use frame_support::{
+    assert_ok,
+    traits::{OnIdle, OnRuntimeUpgrade},
+    weights::GetDispatchInfo,
+};
 use hex_literal::hex;
-use module_evm::ExitError;
-use primitives::{evm::AddressMapping, Balance, PREDEPLOY_ADDRESS_START};
-use sp_core::{H160, U256};
+use module_evm::{
+    precompiles::PrecompileGasCost, Context, ExitError, ExitReason, ExitRevert, ExitSucceed, Precompile, Precompiles,
+    Runner,
+};
+use module_evm_abi::{decode_params, encode_function_call, encode_params, event_topic, Bytes, SolString};
+use orml_traits::{MultiCurrency, MultiReservableCurrency};
+use primitives::{
+    evm::{AddressMapping, Log},
+    Balance, CurrencyId, TokenSymbol, PREDEPLOY_ADDRESS_START,
+};
+use sp_core::{H160, H256, U256};
 
 pub struct DummyPrecompile;
 impl Precompile for DummyPrecompile {
@@ -19,13 +33,21 @@ impl Precompile for DummyPrecompile {
         _input: &[u8],
         _target_gas: Option<u64>,
         _context: &Context,
+        _is_static: bool,
     ) -> core::result::Result<(ExitSucceed, Vec<u8>, u64), ExitError> {
         Ok((ExitSucceed::Stopped, vec![], 0))
     }
 }
 
-pub type WithSystemContractFilter =
-    AllPrecompiles<crate::SystemContractsFilter, DummyPrecompile, DummyPrecompile, DummyPrecompile>;
+pub type WithSystemContractFilter = AllPrecompiles<
+    crate::SystemContractsFilter,
+    crate::SystemContractsFilter,
+    DummyPrecompile,
+    DummyPrecompile,
+    DummyPrecompile,
+    DummyPrecompile,
+    DummyPrecompile,
+>;
 
 #[test]
 fn precompile_filter_works_on_core_precompiles() {
@@ -40,11 +62,37 @@ fn precompile_filter_works_on_core_precompiles() {
         apparent_value: 0.into(),
     };
     assert_eq!(
-        WithSystemContractFilter::execute(precompile, &[0u8; 1], None, &non_system_caller_context),
+        WithSystemContractFilter::execute(precompile, &[0u8; 1], None, &non_system_caller_context, false),
         Some(Err(ExitError::Other("no permission".into()))),
     );
 }
 
+#[test]
+fn precompile_filter_works_on_unregistered_core_precompiles() {
+    let unregistered = H160::from_low_u64_be(PRECOMPILE_ADDRESS_START + 1);
+
+    let mut non_system = [0u8; 20];
+    non_system[0] = 1;
+
+    let non_system_caller_context = Context {
+        address: unregistered,
+        caller: non_system.into(),
+        apparent_value: 0.into(),
+    };
+    assert_eq!(
+        WithSystemContractFilter::execute(unregistered, &[0u8; 1], None, &non_system_caller_context, false),
+        Some(Err(ExitError::Other("no permission".into()))),
+    );
+    assert!(!WithSystemContractFilter::is_precompile(unregistered));
+
+    let system_caller_context = Context {
+        address: unregistered,
+        caller: H160::from_low_u64_be(PREDEPLOY_ADDRESS_START),
+        apparent_value: 0.into(),
+    };
+    assert!(WithSystemContractFilter::execute(unregistered, &[0u8; 1], None, &system_caller_context, false).is_none());
+}
+
 #[test]
 fn precompile_filter_does_not_work_on_system_contracts() {
     let system = H160::from_low_u64_be(PREDEPLOY_ADDRESS_START);
@@ -58,7 +106,8 @@ fn precompile_filter_does_not_work_on_system_contracts() {
         apparent_value: 0.into(),
     };
     assert!(
-        WithSystemContractFilter::execute(non_system.into(), &[0u8; 1], None, &non_system_caller_context).is_none()
+        WithSystemContractFilter::execute(non_system.into(), &[0u8; 1], None, &non_system_caller_context, false)
+            .is_none()
     );
 }
 
@@ -75,7 +124,8 @@ fn precompile_filter_does_not_work_on_non_system_contracts() {
         apparent_value: 0.into(),
     };
     assert!(
-        WithSystemContractFilter::execute(non_system.into(), &[0u8; 1], None, &non_system_caller_context).is_none()
+        WithSystemContractFilter::execute(non_system.into(), &[0u8; 1], None, &non_system_caller_context, false)
+            .is_none()
     );
 }
 
@@ -121,7 +171,7 @@ fn schedule_call_precompile_should_work() {
         U256::from(&transfer_to_bob[32..64]).to_big_endian(&mut input[10 * 32..11 * 32]);
         input[11 * 32..11 * 32 + 4].copy_from_slice(&transfer_to_bob[64..68]);
 
-        let (reason, output, used_gas) = ScheduleCallPrecompile::execute(&input, None, &context).unwrap();
+        let (reason, output, used_gas) = ScheduleCallPrecompile::execute(&input, None, &context, false).unwrap();
         assert_eq!(reason, ExitSucceed::Returned);
         assert_eq!(used_gas, 0);
         let event = TestEvent::Scheduler(pallet_scheduler::Event::Scheduled(3, 0));
@@ -141,13 +191,14 @@ fn schedule_call_precompile_should_work() {
         // task_id
         cancel_input[4 * 32..4 * 32 + task_id.len()].copy_from_slice(&task_id[..]);
 
-        let (reason, _output, used_gas) = ScheduleCallPrecompile::execute(&cancel_input, None, &context).unwrap();
+        let (reason, _output, used_gas) =
+            ScheduleCallPrecompile::execute(&cancel_input, None, &context, false).unwrap();
         assert_eq!(reason, ExitSucceed::Returned);
         assert_eq!(used_gas, 0);
         let event = TestEvent::Scheduler(pallet_scheduler::Event::Canceled(3, 0));
         assert!(System::events().iter().any(|record| record.event == event));
 
-        let (reason, output, used_gas) = ScheduleCallPrecompile::execute(&input, None, &context).unwrap();
+        let (reason, output, used_gas) = ScheduleCallPrecompile::execute(&input, None, &context, false).unwrap();
         assert_eq!(reason, ExitSucceed::Returned);
         assert_eq!(used_gas, 0);
 
@@ -169,7 +220,8 @@ fn schedule_call_precompile_should_work() {
         // task_id
         reschedule_input[5 * 32..5 * 32 + task_id.len()].copy_from_slice(&task_id[..]);
 
-        let (reason, _output, used_gas) = ScheduleCallPrecompile::execute(&reschedule_input, None, &context).unwrap();
+        let (reason, _output, used_gas) =
+            ScheduleCallPrecompile::execute(&reschedule_input, None, &context, false).unwrap();
         assert_eq!(reason, ExitSucceed::Returned);
         assert_eq!(used_gas, 0);
         let event = TestEvent::Scheduler(pallet_scheduler::Event::Scheduled(5, 0));
@@ -238,7 +290,7 @@ fn schedule_call_precompile_should_handle_invalid_input() {
         // input_data = 0x12
         input[9 * 32] = hex!("12")[0];
 
-        let (reason, output, used_gas) = ScheduleCallPrecompile::execute(&input, None, &context).unwrap();
+        let (reason, output, used_gas) = ScheduleCallPrecompile::execute(&input, None, &context, false).unwrap();
         assert_eq!(reason, ExitSucceed::Returned);
         assert_eq!(used_gas, 0);
 
@@ -272,7 +324,7 @@ fn schedule_call_precompile_should_handle_invalid_input() {
         cancel_input[4 * 32..4 * 32 + task_id.len()].copy_from_slice(&task_id[..]);
 
         assert_eq!(
-            ScheduleCallPrecompile::execute(&cancel_input, None, &context),
+            ScheduleCallPrecompile::execute(&cancel_input, None, &context, false),
             Err(ExitError::Other("NoPermission".into()))
         );
 
@@ -283,6 +335,819 @@ fn schedule_call_precompile_should_handle_invalid_input() {
     });
 }
 
+fn erc20_log(currency_id: CurrencyId, event: &str, from: H160, to: H160, amount: Balance) -> TestEvent {
+    TestEvent::ModuleEVM(module_evm::Event::Log(Log {
+        address: currency_id.mirrored_token_address().unwrap(),
+        topics: vec![event_topic(event), from.into(), to.into()],
+        data: encode_params(amount),
+    }))
+}
+
+fn multicurrency_input(action: u8, currency_id: CurrencyId, params: Vec<U256>) -> Vec<u8> {
+    let mut input = vec![0u8; (2 + params.len()) * 32];
+    // action
+    U256::from(action).to_big_endian(&mut input[0 * 32..1 * 32]);
+    // currency_id
+    input[1 * 32..2 * 32].copy_from_slice(&Into::<[u8; 32]>::into(currency_id));
+    // rest params
+    for (i, param) in params.into_iter().enumerate() {
+        param.to_big_endian(&mut input[(2 + i) * 32..(3 + i) * 32]);
+    }
+    input
+}
+
+#[test]
+fn multicurrency_precompile_should_work() {
+    new_test_ext().execute_with(|| {
+        let context = Context {
+            address: Default::default(),
+            caller: alice(),
+            apparent_value: Default::default(),
+        };
+        let alice_account = <Test as module_evm::Config>::AddressMapping::get_account_id(&alice());
+        let bob_account = <Test as module_evm::Config>::AddressMapping::get_account_id(&bob());
+        let address = |address: H160| U256::from(address.as_bytes());
+
+        // reserved balance
+        assert_ok!(Currencies::reserve(OUSD, &alice_account, 100));
+        let input = multicurrency_input(4, OUSD, vec![address(alice())]);
+        let (reason, output, used_gas) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(reason, ExitSucceed::Returned);
+        assert_eq!(used_gas, 0);
+        assert_eq!(decode_params::<Balance>(&output), Ok(100));
+        assert_eq!(Currencies::unreserve(OUSD, &alice_account, 100), 0);
+
+        // minimum balance
+        let input = multicurrency_input(5, OVR, vec![]);
+        let (_, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(decode_params::<Balance>(&output), Ok(Currencies::minimum_balance(OVR)));
+
+        // symbol and decimals
+        let input = multicurrency_input(6, OUSD, vec![]);
+        let (_, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(decode_params::<SolString>(&output), Ok(SolString(b"OUSD".to_vec())));
+        let input = multicurrency_input(7, OUSD, vec![]);
+        let (_, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(decode_params::<u8>(&output), Ok(18));
+        let input = multicurrency_input(6, CurrencyId::ERC20(Default::default()), vec![]);
+        assert_eq!(
+            MultiCurrencyPrecompile::execute(&input, None, &context, false),
+            Err(ExitError::Other("no currency metadata".into()))
+        );
+
+        // transfer
+        let input = multicurrency_input(2, OUSD, vec![address(alice()), address(bob()), U256::from(100)]);
+        let (reason, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(reason, ExitSucceed::Returned);
+        assert_eq!(output, Vec::<u8>::new());
+        assert_eq!(Currencies::free_balance(OUSD, &bob_account), 100);
+        let event = erc20_log(OUSD, "Transfer(address,address,uint256)", alice(), bob(), 100);
+        assert!(System::events().iter().any(|record| record.event == event));
+
+        // approve, allowance and transfer from
+        let input = multicurrency_input(9, OUSD, vec![address(alice()), address(bob()), U256::from(300)]);
+        let (reason, _, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(reason, ExitSucceed::Returned);
+        let event = erc20_log(OUSD, "Approval(address,address,uint256)", alice(), bob(), 300);
+        assert!(System::events().iter().any(|record| record.event == event));
+
+        let input = multicurrency_input(8, OUSD, vec![address(alice()), address(bob())]);
+        let (_, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(decode_params::<Balance>(&output), Ok(300));
+
+        let input = multicurrency_input(
+            10,
+            OUSD,
+            vec![address(bob()), address(alice()), address(bob()), U256::from(200)],
+        );
+        let (reason, _, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(reason, ExitSucceed::Returned);
+        assert_eq!(Currencies::free_balance(OUSD, &alice_account), 700);
+        assert_eq!(Currencies::free_balance(OUSD, &bob_account), 300);
+        assert_eq!(Currencies::allowance(OUSD, &alice_account, &bob_account), 100);
+        let event = erc20_log(OUSD, "Transfer(address,address,uint256)", alice(), bob(), 200);
+        assert!(System::events().iter().any(|record| record.event == event));
+
+        assert_eq!(
+            MultiCurrencyPrecompile::execute(&input, None, &context, false),
+            Err(ExitError::Other("AllowanceTooLow".into()))
+        );
+    });
+}
+
+fn mirrored_token_input(currency_id: CurrencyId, caller: H160, call_data: Vec<u8>) -> Vec<u8> {
+    let mut input = vec![0u8; 3 * 32];
+    // action
+    U256::from(3).to_big_endian(&mut input[0 * 32..1 * 32]);
+    // token address
+    let token = currency_id.mirrored_token_address().unwrap();
+    U256::from(token.as_bytes()).to_big_endian(&mut input[1 * 32..2 * 32]);
+    // caller
+    U256::from(caller.as_bytes()).to_big_endian(&mut input[2 * 32..3 * 32]);
+    // ERC20 call data
+    input.extend(call_data);
+    input
+}
+
+#[test]
+fn multicurrency_precompile_mirrored_token_call_should_work() {
+    new_test_ext().execute_with(|| {
+        let context = Context {
+            address: Default::default(),
+            caller: OUSD.mirrored_token_address().unwrap(),
+            apparent_value: Default::default(),
+        };
+        let alice_account = <Test as module_evm::Config>::AddressMapping::get_account_id(&alice());
+        let bob_account = <Test as module_evm::Config>::AddressMapping::get_account_id(&bob());
+
+        let input = mirrored_token_input(OUSD, alice(), encode_function_call("name()", ()));
+        let (reason, output, used_gas) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(reason, ExitSucceed::Returned);
+        assert_eq!(used_gas, 0);
+        assert_eq!(
+            decode_params::<SolString>(&output),
+            Ok(SolString(b"Ovr Dollar".to_vec()))
+        );
+
+        let input = mirrored_token_input(OUSD, alice(), encode_function_call("symbol()", ()));
+        let (_, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(decode_params::<SolString>(&output), Ok(SolString(b"OUSD".to_vec())));
+
+        let input = mirrored_token_input(OUSD, alice(), encode_function_call("decimals()", ()));
+        let (_, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(decode_params::<u8>(&output), Ok(18));
+
+        let input = mirrored_token_input(OUSD, alice(), encode_function_call("totalSupply()", ()));
+        let (_, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(decode_params::<Balance>(&output), Ok(Currencies::total_issuance(OUSD)));
+
+        let input = mirrored_token_input(OUSD, alice(), encode_function_call("balanceOf(address)", (alice(),)));
+        let (_, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(decode_params::<Balance>(&output), Ok(1_000));
+
+        let input = mirrored_token_input(
+            OUSD,
+            alice(),
+            encode_function_call("transfer(address,uint256)", (bob(), U256::from(400))),
+        );
+        let (reason, output, used_gas) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(reason, ExitSucceed::Returned);
+        assert_eq!(used_gas, 0);
+        assert_eq!(output, encode_params(true));
+        assert_eq!(Currencies::free_balance(OUSD, &alice_account), 600);
+        assert_eq!(Currencies::free_balance(OUSD, &bob_account), 400);
+        let event = erc20_log(OUSD, "Transfer(address,address,uint256)", alice(), bob(), 400);
+        assert!(System::events().iter().any(|record| record.event == event));
+
+        let input = mirrored_token_input(
+            OUSD,
+            alice(),
+            encode_function_call("transfer(address,uint256)", (bob(), U256::from(601))),
+        );
+        assert_eq!(
+            MultiCurrencyPrecompile::execute(&input, None, &context, false),
+            Err(ExitError::Other("BalanceTooLow".into()))
+        );
+
+        let input = mirrored_token_input(
+            OUSD,
+            alice(),
+            encode_function_call("approve(address,uint256)", (bob(), U256::from(500))),
+        );
+        let (_, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(output, encode_params(true));
+        let event = erc20_log(OUSD, "Approval(address,address,uint256)", alice(), bob(), 500);
+        assert!(System::events().iter().any(|record| record.event == event));
+
+        let input = mirrored_token_input(
+            OUSD,
+            alice(),
+            encode_function_call("allowance(address,address)", (alice(), bob())),
+        );
+        let (_, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(decode_params::<Balance>(&output), Ok(500));
+
+        let input = mirrored_token_input(
+            OUSD,
+            bob(),
+            encode_function_call(
+                "transferFrom(address,address,uint256)",
+                (alice(), bob(), U256::from(200)),
+            ),
+        );
+        let (_, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(output, encode_params(true));
+        assert_eq!(Currencies::free_balance(OUSD, &alice_account), 400);
+        assert_eq!(Currencies::free_balance(OUSD, &bob_account), 600);
+        assert_eq!(Currencies::allowance(OUSD, &alice_account, &bob_account), 300);
+        let event = erc20_log(OUSD, "Transfer(address,address,uint256)", alice(), bob(), 200);
+        assert!(System::events().iter().any(|record| record.event == event));
+    });
+}
+
+#[test]
+fn multicurrency_precompile_mirrored_token_call_should_support_baseline_erc20_methods() {
+    new_test_ext().execute_with(|| {
+        let context = Context {
+            address: Default::default(),
+            caller: OUSD.mirrored_token_address().unwrap(),
+            apparent_value: Default::default(),
+        };
+        let alice_account = <Test as module_evm::Config>::AddressMapping::get_account_id(&alice());
+        let bob_account = <Test as module_evm::Config>::AddressMapping::get_account_id(&bob());
+
+        let input = mirrored_token_input(OUSD, alice(), encode_function_call("currencyId()", ()));
+        let (_, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(output, Into::<[u8; 32]>::into(OUSD).to_vec());
+
+        let input = mirrored_token_input(
+            OUSD,
+            alice(),
+            encode_function_call("increaseAllowance(address,uint256)", (bob(), U256::from(500))),
+        );
+        let (_, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(output, encode_params(true));
+        let (_, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(output, encode_params(true));
+        assert_eq!(Currencies::allowance(OUSD, &alice_account, &bob_account), 1_000);
+        let event = erc20_log(OUSD, "Approval(address,address,uint256)", alice(), bob(), 1_000);
+        assert!(System::events().iter().any(|record| record.event == event));
+
+        let input = mirrored_token_input(
+            OUSD,
+            alice(),
+            encode_function_call("decreaseAllowance(address,uint256)", (bob(), U256::from(300))),
+        );
+        let (_, output, _) = MultiCurrencyPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(output, encode_params(true));
+        assert_eq!(Currencies::allowance(OUSD, &alice_account, &bob_account), 700);
+        let event = erc20_log(OUSD, "Approval(address,address,uint256)", alice(), bob(), 700);
+        assert!(System::events().iter().any(|record| record.event == event));
+
+        let input = mirrored_token_input(
+            OUSD,
+            alice(),
+            encode_function_call("decreaseAllowance(address,uint256)", (bob(), U256::from(701))),
+        );
+        assert_eq!(
+            MultiCurrencyPrecompile::execute(&input, None, &context, false),
+            Err(ExitError::Other("decreased allowance below zero".into()))
+        );
+        assert_eq!(Currencies::allowance(OUSD, &alice_account, &bob_account), 700);
+    });
+}
+
+#[test]
+fn multicurrency_precompile_mirrored_token_call_should_handle_invalid_input() {
+    new_test_ext().execute_with(|| {
+        let call_data = encode_function_call("transfer(address,uint256)", (bob(), U256::from(400)));
+
+        // only the contract mirroring the currency may call
+        for caller in [alice(), OVR.mirrored_token_address().unwrap()] {
+            let context = Context {
+                address: Default::default(),
+                caller,
+                apparent_value: Default::default(),
+            };
+            let input = mirrored_token_input(OUSD, alice(), call_data.clone());
+            assert_eq!(
+                MultiCurrencyPrecompile::execute(&input, None, &context, false),
+                Err(ExitError::Other("not the mirrored token".into()))
+            );
+        }
+
+        // nor a contract outside of the mirrored tokens
+        let context = Context {
+            address: Default::default(),
+            caller: H160::from_low_u64_be(PREDEPLOY_ADDRESS_START),
+            apparent_value: Default::default(),
+        };
+        let mut input = mirrored_token_input(OUSD, alice(), call_data.clone());
+        U256::from(context.caller.as_bytes()).to_big_endian(&mut input[1 * 32..2 * 32]);
+        assert_eq!(
+            MultiCurrencyPrecompile::execute(&input, None, &context, false),
+            Err(ExitError::Other("not the mirrored token".into()))
+        );
+
+        let context = Context {
+            address: Default::default(),
+            caller: OUSD.mirrored_token_address().unwrap(),
+            apparent_value: Default::default(),
+        };
+        for call_data in [vec![], hex!("a9059c").to_vec(), hex!("12345678").to_vec()] {
+            let input = mirrored_token_input(OUSD, alice(), call_data);
+            assert_eq!(
+                MultiCurrencyPrecompile::execute(&input, None, &context, false),
+                Err(ExitError::Other("invalid ERC20 method".into()))
+            );
+        }
+    });
+}
+
+#[test]
+fn mirrored_token_should_forward_to_multicurrency_precompile() {
+    new_test_ext().execute_with(|| {
+        let ousd_token = OUSD.mirrored_token_address().unwrap();
+        let alice_account = <Test as module_evm::Config>::AddressMapping::get_account_id(&alice());
+        let bob_account = <Test as module_evm::Config>::AddressMapping::get_account_id(&bob());
+        let config = <Test as module_evm::Config>::config();
+
+        let info = Runner::<Test>::call(
+            alice(),
+            alice(),
+            ousd_token,
+            encode_function_call("balanceOf(address)", (alice(),)),
+            0,
+            1_000_000,
+            1_000,
+            config,
+        )
+        .unwrap();
+        assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+        assert_eq!(decode_params::<Balance>(&info.output), Ok(1_000));
+
+        let transfer = encode_function_call("transfer(address,uint256)", (bob(), U256::from(400)));
+        let info = Runner::<Test>::call(
+            alice(),
+            alice(),
+            ousd_token,
+            transfer.clone(),
+            0,
+            1_000_000,
+            1_000,
+            config,
+        )
+        .unwrap();
+        assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+        assert_eq!(info.output, encode_params(true));
+        assert_eq!(Currencies::free_balance(OUSD, &alice_account), 600);
+        assert_eq!(Currencies::free_balance(OUSD, &bob_account), 400);
+
+        // the mirrored tokens aren't payable
+        let info = Runner::<Test>::call(alice(), alice(), ousd_token, transfer, 10, 1_000_000, 1_000, config).unwrap();
+        assert_eq!(info.exit_reason, ExitReason::Revert(ExitRevert::Reverted));
+        assert_eq!(Currencies::free_balance(OUSD, &alice_account), 600);
+    });
+}
+
+#[test]
+fn replace_mirrored_token_code_should_work() {
+    new_test_ext().execute_with(|| {
+        let ovr_token = OVR.mirrored_token_address().unwrap();
+        let ousd_token = OUSD.mirrored_token_address().unwrap();
+        let dex_share_token = CurrencyId::DEXShare(TokenSymbol::OVR, TokenSymbol::OUSD)
+            .mirrored_token_address()
+            .unwrap();
+
+        // the baseline contract, with an allowance in its storage
+        let old_code = hex!("608060405234801561001057600080fd5b50").to_vec();
+        assert_ok!(ModuleEVM::set_code(Origin::root(), ovr_token, old_code));
+        module_evm::AccountStorages::<Test>::insert(ovr_token, H256::repeat_byte(1), H256::repeat_byte(2));
+        // the DEX share token wasn't predeployed
+        module_evm::Accounts::<Test>::remove(dex_share_token);
+
+        ReplaceMirroredTokenCode::<Test>::on_runtime_upgrade();
+        let new_code_hash = module_evm::code_hash(&MIRRORED_TOKEN_CODE);
+        assert_eq!(ModuleEVM::code_hash_at_address(&ovr_token), new_code_hash);
+        assert_eq!(ModuleEVM::code_hash_at_address(&ousd_token), new_code_hash);
+        assert_eq!(ModuleEVM::code_hash_at_address(&dex_share_token), new_code_hash);
+        assert!(
+            ModuleEVM::accounts(&dex_share_token)
+                .unwrap()
+                .contract_info
+                .unwrap()
+                .deployed
+        );
+
+        // the old storage is removed in `on_idle`
+        assert_eq!(ModuleEVM::contracts_to_remove(ovr_token), Some(H160::default()));
+        assert_eq!(module_evm::AccountStorages::<Test>::iter_prefix(ovr_token).count(), 1);
+        ModuleEVM::on_idle(1, 1_000_000_000_000);
+        assert_eq!(ModuleEVM::contracts_to_remove(ovr_token), None);
+        assert_eq!(module_evm::AccountStorages::<Test>::iter_prefix(ovr_token).count(), 0);
+        assert_eq!(ModuleEVM::code_hash_at_address(&ovr_token), new_code_hash);
+
+        // nothing left to replace
+        System::reset_events();
+        ReplaceMirroredTokenCode::<Test>::on_runtime_upgrade();
+        assert!(System::events().is_empty());
+    });
+}
+
+type TestPrecompiles = <Test as module_evm::Config>::Precompiles;
+
+#[test]
+fn registered_precompiles_should_charge_gas() {
+    new_test_ext().execute_with(|| {
+        let context = Context {
+            address: Default::default(),
+            caller: alice(),
+            apparent_value: Default::default(),
+        };
+        let precompile = H160::from_low_u64_be(PRECOMPILE_ADDRESS_START);
+        let address = |address: H160| U256::from(address.as_bytes());
+
+        assert!(TestPrecompiles::is_precompile(precompile));
+        assert!(TestPrecompiles::is_precompile(H160::from_low_u64_be(4)));
+        assert!(!TestPrecompiles::is_precompile(H160::from_low_u64_be(
+            PRECOMPILE_ADDRESS_START + 1
+        )));
+
+        // query total issuance
+        let input = multicurrency_input(0, OVR, vec![]);
+        let (_, _, used_gas) = TestPrecompiles::execute(precompile, &input, Some(10_000), &context, false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(used_gas, STORAGE_READ_GAS + 2 * INPUT_WORD_GAS);
+        assert_eq!(
+            TestPrecompiles::execute(precompile, &input, Some(STORAGE_READ_GAS), &context, false),
+            Some(Err(ExitError::OutOfGas))
+        );
+
+        // transfer
+        let input = multicurrency_input(2, OUSD, vec![address(alice()), address(bob()), U256::from(100)]);
+        let (_, _, used_gas) = TestPrecompiles::execute(precompile, &input, Some(100_000), &context, false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            used_gas,
+            2 * STORAGE_READ_GAS + 2 * STORAGE_WRITE_GAS + 5 * INPUT_WORD_GAS
+        );
+
+        // mirrored token transfer
+        let input = mirrored_token_input(
+            OUSD,
+            alice(),
+            encode_function_call("transfer(address,uint256)", (bob(), U256::from(100))),
+        );
+        let context = Context {
+            caller: OUSD.mirrored_token_address().unwrap(),
+            ..context
+        };
+        let (_, _, used_gas) = TestPrecompiles::execute(precompile, &input, Some(100_000), &context, false)
+            .unwrap()
+            .unwrap();
+        assert_eq!(used_gas, MultiCurrencyGasCost::gas_cost(&input));
+        assert_eq!(
+            used_gas,
+            2 * STORAGE_READ_GAS + 2 * STORAGE_WRITE_GAS + 6 * INPUT_WORD_GAS
+        );
+    });
+}
+
+#[test]
+fn disabled_precompile_should_fail() {
+    new_test_ext().execute_with(|| {
+        let ousd_token = OUSD.mirrored_token_address().unwrap();
+        let precompile = H160::from_low_u64_be(PRECOMPILE_ADDRESS_START);
+        let config = <Test as module_evm::Config>::config();
+        let balance_of = encode_function_call("balanceOf(address)", (alice(),));
+
+        assert_ok!(ModuleEVM::set_precompile_enabled(Origin::root(), precompile, false));
+        let info = Runner::<Test>::call(
+            alice(),
+            alice(),
+            ousd_token,
+            balance_of.clone(),
+            0,
+            1_000_000,
+            1_000,
+            config,
+        )
+        .unwrap();
+        assert_eq!(info.exit_reason, ExitReason::Revert(ExitRevert::Reverted));
+
+        assert_ok!(ModuleEVM::set_precompile_enabled(Origin::root(), precompile, true));
+        let info = Runner::<Test>::call(alice(), alice(), ousd_token, balance_of, 0, 1_000_000, 1_000, config).unwrap();
+        assert_eq!(info.exit_reason, ExitReason::Succeed(ExitSucceed::Returned));
+        assert_eq!(decode_params::<Balance>(&info.output), Ok(1_000));
+    });
+}
+
+#[test]
+fn dispatch_precompile_should_work() {
+    new_test_ext().execute_with(|| {
+        let alice_account = MockAddressMapping::get_account_id(&alice());
+        let bob_account = MockAddressMapping::get_account_id(&bob());
+        let context = Context {
+            address: DispatchPrecompileAddress::get(),
+            caller: alice(),
+            apparent_value: Default::default(),
+        };
+
+        let call = Call::Balances(pallet_balances::Call::transfer(bob_account.clone(), 1_000));
+        let weight = call.get_dispatch_info().weight;
+        System::deposit_event(TestEvent::Poc(module_poc::Event::CandidateAdded(alice_account.clone())));
+        let (reason, output, used_gas) = DispatchPrecompile::execute(&call.encode(), None, &context, false).unwrap();
+        assert_eq!(reason, ExitSucceed::Returned);
+        assert_eq!(used_gas, weight);
+
+        // the output has the events of the call only
+        let event = TestEvent::Balances(pallet_balances::Event::Transfer(
+            alice_account,
+            bob_account.clone(),
+            1_000,
+        ));
+        assert_eq!(
+            decode_params::<(u64, bool, Vec<Bytes>)>(&output),
+            Ok((weight, true, vec![Bytes(event.encode())]))
+        );
+        assert!(System::events().iter().any(|record| record.event == event));
+        assert_eq!(Balances::free_balance(&bob_account), INITIAL_BALANCE + 1_000);
+    });
+}
+
+#[test]
+fn dispatch_precompile_should_only_be_called_by_contracts() {
+    new_test_ext().execute_with(|| {
+        let bob_account = MockAddressMapping::get_account_id(&bob());
+        let precompile = DispatchPrecompileAddress::get();
+        let context = Context {
+            address: precompile,
+            caller: alice(),
+            apparent_value: Default::default(),
+        };
+        let call = Call::Balances(pallet_balances::Call::transfer(bob_account.clone(), 1_000)).encode();
+
+        assert_eq!(
+            TestPrecompiles::execute(precompile, &call, None, &context, false),
+            Some(Err(ExitError::Other("no permission".into())))
+        );
+
+        // a contract, charged the gas cost on top of the weight of the call
+        let ousd_token = OUSD.mirrored_token_address().unwrap();
+        let context = Context {
+            caller: ousd_token,
+            ..context
+        };
+        let ousd_token_account = MockAddressMapping::get_account_id(&ousd_token);
+        assert_ok!(Balances::transfer(
+            Origin::signed(MockAddressMapping::get_account_id(&alice())),
+            ousd_token_account,
+            10_000
+        ));
+        let (_, _, used_gas) = TestPrecompiles::execute(precompile, &call, None, &context, false)
+            .unwrap()
+            .unwrap();
+        let weight = Call::decode(&mut &call[..]).unwrap().get_dispatch_info().weight;
+        assert_eq!(used_gas, weight + DispatchGasCost::gas_cost(&call));
+        assert_eq!(Balances::free_balance(&bob_account), INITIAL_BALANCE + 1_000);
+    });
+}
+
+#[test]
+fn dispatch_precompile_should_handle_invalid_input() {
+    new_test_ext().execute_with(|| {
+        let bob_account = MockAddressMapping::get_account_id(&bob());
+        let context = Context {
+            address: DispatchPrecompileAddress::get(),
+            caller: alice(),
+            apparent_value: Default::default(),
+        };
+        let call = Call::Balances(pallet_balances::Call::transfer(bob_account.clone(), 1_000));
+
+        // delegate call
+        assert_eq!(
+            DispatchPrecompile::execute(
+                &call.encode(),
+                None,
+                &Context {
+                    address: alice(),
+                    ..context.clone()
+                },
+                false
+            ),
+            Err(ExitError::Other("no delegate call".into()))
+        );
+
+        // static call
+        assert_eq!(
+            DispatchPrecompile::execute(&call.encode(), None, &context, true),
+            Err(ExitError::Other("no static call".into()))
+        );
+
+        // invalid call
+        assert_eq!(
+            DispatchPrecompile::execute(&[0xff, 0xff], None, &context, false),
+            Err(ExitError::Other("invalid call".into()))
+        );
+
+        // filtered call
+        let remark = Call::System(frame_system::Call::remark(vec![1, 2, 3]));
+        assert_eq!(
+            DispatchPrecompile::execute(&remark.encode(), None, &context, false),
+            Err(ExitError::Other("call filtered".into()))
+        );
+
+        // out of gas
+        let weight = call.get_dispatch_info().weight;
+        assert_eq!(
+            DispatchPrecompile::execute(&call.encode(), Some(weight - 1), &context, false),
+            Err(ExitError::OutOfGas)
+        );
+
+        // dispatch error
+        let call = Call::Balances(pallet_balances::Call::transfer(
+            bob_account.clone(),
+            INITIAL_BALANCE * 2,
+        ));
+        assert_eq!(
+            DispatchPrecompile::execute(&call.encode(), None, &context, false),
+            Err(ExitError::Other("InsufficientBalance".into()))
+        );
+        assert_eq!(Balances::free_balance(&bob_account), INITIAL_BALANCE);
+    });
+}
+
+fn poc_input(action: u8, params: Vec<U256>) -> Vec<u8> {
+    let mut input = vec![0u8; (1 + params.len()) * 32];
+    // action
+    U256::from(action).to_big_endian(&mut input[0 * 32..1 * 32]);
+    // rest params
+    for (i, param) in params.into_iter().enumerate() {
+        param.to_big_endian(&mut input[(1 + i) * 32..(2 + i) * 32]);
+    }
+    input
+}
+
+#[test]
+fn poc_precompile_should_work() {
+    new_test_ext().execute_with(|| {
+        let context = Context {
+            address: PocPrecompileAddress::get(),
+            caller: alice(),
+            apparent_value: Default::default(),
+        };
+        let alice_account = MockAddressMapping::get_account_id(&alice());
+        let account = |account_id: AccountId| U256::from(AsRef::<[u8]>::as_ref(&account_id));
+        let candidate = AccountId::from([2u8; 32]);
+        let query_commitment = |id: u32| {
+            let input = poc_input(0, vec![account(alice_account.clone()), U256::from(id)]);
+            let (_, output, _) = PocPrecompile::execute(&input, None, &context, false).unwrap();
+            decode_params::<(u8, u64, u8, Balance, H256)>(&output).unwrap()
+        };
+
+        assert_ok!(Poc::start_candidacy(Origin::signed(ALICE)));
+
+        // commit
+        let input = poc_input(128, vec![U256::from(1_000), U256::from(1), account(ALICE)]);
+        let (_, output, _) = PocPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(decode_params::<u32>(&output), Ok(0));
+        assert_eq!(query_commitment(0), (0, 0, 1, 1_000, H256([1u8; 32])));
+        assert_eq!(Balances::free_balance(&alice_account), INITIAL_BALANCE - 1_000);
+
+        // commit again
+        let input = poc_input(128, vec![U256::from(2_000), U256::from(0), account(ALICE)]);
+        let (_, output, _) = PocPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(decode_params::<u32>(&output), Ok(1));
+        assert_eq!(query_commitment(1), (0, 0, 0, 2_000, H256([1u8; 32])));
+
+        // voting weight, charged per commitment
+        let input = poc_input(1, vec![account(alice_account.clone())]);
+        let (_, output, used_gas) = PocPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(decode_params::<Balance>(&output), Ok(12_000));
+        assert_eq!(used_gas, 2 * STORAGE_READ_GAS);
+        assert_eq!(
+            PocPrecompile::execute(&input, Some(2 * STORAGE_READ_GAS - 1), &context, false),
+            Err(ExitError::OutOfGas)
+        );
+
+        // candidate
+        let input = poc_input(3, vec![account(ALICE)]);
+        let (_, output, _) = PocPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(decode_params::<(bool, Balance)>(&output), Ok((true, 250_000)));
+        let input = poc_input(3, vec![account(candidate.clone())]);
+        let (_, output, _) = PocPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(decode_params::<(bool, Balance)>(&output), Ok((false, 0)));
+
+        // members and current era
+        let (_, output, used_gas) = PocPrecompile::execute(&poc_input(2, vec![]), None, &context, false).unwrap();
+        assert_eq!(decode_params::<Vec<H256>>(&output), Ok(vec![]));
+        assert_eq!(used_gas, 0);
+        module_poc::Members::<Test>::put(vec![ALICE, candidate.clone()]);
+        let (_, output, used_gas) = PocPrecompile::execute(&poc_input(2, vec![]), None, &context, false).unwrap();
+        assert_eq!(
+            decode_params::<Vec<H256>>(&output),
+            Ok(vec![H256([1u8; 32]), H256([2u8; 32])])
+        );
+        assert_eq!(used_gas, 2 * poc::MEMBER_GAS);
+        assert_eq!(
+            PocPrecompile::execute(&poc_input(2, vec![]), Some(2 * poc::MEMBER_GAS - 1), &context, false),
+            Err(ExitError::OutOfGas)
+        );
+        let (_, output, _) = PocPrecompile::execute(&poc_input(4, vec![]), None, &context, false).unwrap();
+        assert_eq!(decode_params::<(u32, u64)>(&output), Ok((0, 0)));
+
+        // add funds and vote, charged the weight of the calls
+        let input = poc_input(129, vec![U256::from(0), U256::from(500)]);
+        let add_funds_gas = <() as module_poc::WeightInfo>::add_funds();
+        assert_eq!(
+            PocPrecompile::execute(&input, Some(add_funds_gas - 1), &context, false),
+            Err(ExitError::OutOfGas)
+        );
+        let (_, _, used_gas) = PocPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(used_gas, add_funds_gas);
+        let input = poc_input(132, vec![U256::from(0), account(candidate)]);
+        assert_ok!(PocPrecompile::execute(&input, None, &context, false));
+        assert_eq!(query_commitment(0), (0, 0, 1, 1_500, H256([2u8; 32])));
+        assert_eq!(query_commitment(1), (0, 0, 0, 2_000, H256([1u8; 32])));
+
+        // pending rewards and claim
+        let input = poc_input(5, vec![account(alice_account.clone()), U256::from(0)]);
+        let (_, output, _) = PocPrecompile::execute(&input, None, &context, false).unwrap();
+        assert_eq!(decode_params::<Balance>(&output), Ok(0));
+        assert_ok!(PocPrecompile::execute(
+            &poc_input(133, vec![U256::from(0)]),
+            None,
+            &context,
+            false
+        ));
+        assert_eq!(
+            PocPrecompile::execute(&poc_input(133, vec![U256::from(2)]), None, &context, false),
+            Err(ExitError::Other("CommitmentNotFound".into()))
+        );
+
+        // unbond
+        assert_ok!(PocPrecompile::execute(
+            &poc_input(130, vec![U256::from(0)]),
+            None,
+            &context,
+            false
+        ));
+        assert_eq!(query_commitment(0), (1, 1, 1, 1_500, H256([2u8; 32])));
+
+        // withdraw
+        assert_eq!(
+            PocPrecompile::execute(&poc_input(131, vec![U256::from(0)]), None, &context, false),
+            Err(ExitError::Other("CannotWithdrawLocked".into()))
+        );
+    });
+}
+
+#[test]
+fn poc_precompile_should_handle_invalid_input() {
+    new_test_ext().execute_with(|| {
+        let context = Context {
+            address: PocPrecompileAddress::get(),
+            caller: alice(),
+            apparent_value: Default::default(),
+        };
+        let candidate = U256::from(&[1u8; 32][..]);
+
+        // delegate call
+        assert_eq!(
+            PocPrecompile::execute(
+                &poc_input(2, vec![]),
+                None,
+                &Context {
+                    address: alice(),
+                    ..context.clone()
+                },
+                false
+            ),
+            Err(ExitError::Other("no delegate call".into()))
+        );
+
+        // static call
+        let input = poc_input(128, vec![U256::from(1_000), U256::from(1), candidate]);
+        assert_eq!(
+            PocPrecompile::execute(&input, None, &context, true),
+            Err(ExitError::Other("no static call".into()))
+        );
+        assert_ok!(PocPrecompile::execute(&poc_input(2, vec![]), None, &context, true));
+
+        // invalid action
+        assert_eq!(
+            PocPrecompile::execute(&poc_input(6, vec![]), None, &context, false),
+            Err(ExitError::Other("invalid action".into()))
+        );
+
+        // invalid input
+        assert_eq!(
+            PocPrecompile::execute(&poc_input(128, vec![U256::from(1_000)]), None, &context, false),
+            Err(ExitError::Other("invalid input".into()))
+        );
+
+        // invalid lock duration
+        let input = poc_input(128, vec![U256::from(1_000), U256::from(3), candidate]);
+        assert_eq!(
+            PocPrecompile::execute(&input, None, &context, false),
+            Err(ExitError::Other("invalid lock duration".into()))
+        );
+
+        // dispatch error
+        let input = poc_input(128, vec![U256::from(10), U256::from(1), candidate]);
+        assert_eq!(
+            PocPrecompile::execute(&input, None, &context, false),
+            Err(ExitError::Other("InsufficientAmount".into()))
+        );
+        assert_eq!(
+            PocPrecompile::execute(&poc_input(130, vec![U256::from(0)]), None, &context, false),
+            Err(ExitError::Other("CommitmentNotFound".into()))
+        );
+    });
+}
+
 #[test]
 fn task_id_max_and_min() {
     let task_id = TaskInfo {
@@ -39,7 +39,7 @@ fn deploy_contract(caller: AccountId) -> Result<H160, DispatchError> {
     EVM::create(Origin::signed(caller), contract, 0, 1000000000, 1000000000)
         .map_or_else(|e| Err(e.error), |_| Ok(()))?;
 
-    if let Event::EVM(module_evm::Event::Created(address)) = System::events().iter().last().unwrap().event {
+    if let Event::EVM(module_evm::Event::Created(_, address, _)) = System::events().iter().last().unwrap().event {
         Ok(address)
     } else {
         Err("deploy_contract failed".into())
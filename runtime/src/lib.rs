@@ -17,10 +17,11 @@ pub use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
 use sp_core::{
     crypto::KeyTypeId,
     u32_trait::{_2, _3, _4},
-    Decode, OpaqueMetadata, H160,
+    Decode, OpaqueMetadata, H160, H256, U256,
 };
 use sp_runtime::traits::{
-    BadOrigin, BlakeTwo256, Block as BlockT, NumberFor, OpaqueKeys, SaturatedConversion, StaticLookup, Zero,
+    AccountIdConversion, BadOrigin, BlakeTwo256, Block as BlockT, NumberFor, OpaqueKeys, SaturatedConversion,
+    StaticLookup, Zero,
 };
 use sp_runtime::{
     create_runtime_str,
@@ -40,14 +41,14 @@ use sp_version::RuntimeVersion;
 pub use frame_support::{
     construct_runtime, parameter_types,
     traits::{
-        schedule::Priority, EnsureOrigin, KeyOwnerProofSystem, OriginTrait, Randomness, U128CurrencyToVote,
-        WithdrawReasons,
+        schedule::Priority, Contains, Currency, EnsureOrigin, KeyOwnerProofSystem, OnUnbalanced, OriginTrait,
+        Randomness, U128CurrencyToVote, WithdrawReasons,
     },
     weights::{
         constants::{BlockExecutionWeight, ExtrinsicBaseWeight, RocksDbWeight, WEIGHT_PER_SECOND},
         IdentityFee, Weight,
     },
-    StorageValue,
+    PalletId, StorageValue,
 };
 pub use frame_system::{ensure_root, EnsureOneOf, EnsureRoot, RawOrigin};
 pub use pallet_balances::Call as BalancesCall;
@@ -58,7 +59,7 @@ use orml_authority::EnsureDelayed;
 use orml_traits::parameter_type_with_key;
 
 use module_currencies::BasicCurrencyAdapter;
-use module_evm::{CallInfo, CreateInfo};
+use module_evm::{Account, AccountOverride, CallInfo, CreateInfo, ExtrinsicReceipt, Trace, TracerConfig};
 use module_evm_accounts::EvmAddressMapping;
 use module_transaction_payment::{Multiplier, TargetedFeeAdjustment};
 
@@ -66,12 +67,14 @@ use module_transaction_payment::{Multiplier, TargetedFeeAdjustment};
 
 pub use pallet_staking::StakerStatus;
 pub use primitives::{
-    evm::EstimateResourcesRequest, AccountId, AccountIndex, Amount, AuthoritysOriginId, Balance, BlockNumber,
-    CurrencyId, EraIndex, Hash, Moment, Nonce, Signature, TokenSymbol,
+    evm::{Erc20Metadata, EstimateResourcesRequest, ERC20_HOLDING_ACCOUNT},
+    AccountId, AccountIndex, Amount, AuthoritysOriginId, Balance, BlockNumber, CurrencyId, EraIndex, Hash, Moment,
+    Nonce, Signature, TokenSymbol,
 };
 
 pub use runtime_common::{
-    BlockLength, BlockWeights, GasToWeight, OffchainSolutionWeightLimit, Price, Rate, Ratio, SystemContractsFilter,
+    BlockGasLimit, BlockLength, BlockWeights, GasToWeight, OffchainSolutionWeightLimit, Price, Rate, Ratio,
+    SystemContractsFilter, WeightToGas, MIRRORED_CURRENCIES, MIRRORED_TOKEN_CODE,
 };
 
 pub use primitives::{currency::*, time::*};
@@ -87,11 +90,12 @@ mod benchmarking;
 parameter_types! {
     pub BurnAccount: AccountId = AccountId::from([0u8; 32]);
     pub const SevenDays: BlockNumber = 7 * DAYS;
-    // pub TreasuryModuleAccount: AccountId = OvrTreasuryModuleId::get().into_account();
+    pub const OvrTreasuryModuleId: PalletId = PalletId(*b"ovr/trsy");
+    pub TreasuryModuleAccount: AccountId = OvrTreasuryModuleId::get().into_account();
 }
 
 pub fn get_all_module_accounts() -> Vec<AccountId> {
-    vec![BurnAccount::get()]
+    vec![BurnAccount::get(), TreasuryModuleAccount::get()]
 }
 
 pub struct AuthorityConfigImpl;
@@ -214,10 +218,10 @@ pub const VERSION: RuntimeVersion = RuntimeVersion {
     spec_name: create_runtime_str!("ovr"),
     impl_name: create_runtime_str!("ovr"),
     authoring_version: 1,
-    spec_version: 9,
-    impl_version: 9,
+    spec_version: 10,
+    impl_version: 10,
     apis: RUNTIME_API_VERSIONS,
-    transaction_version: 2,
+    transaction_version: 3,
 };
 
 /// The version information used to identify this runtime when compiled
@@ -485,6 +489,10 @@ impl pallet_indices::Config for Runtime {
     type WeightInfo = ();
 }
 
+parameter_types! {
+    pub const Erc20HoldingAccount: H160 = ERC20_HOLDING_ACCOUNT;
+}
+
 impl module_currencies::Config for Runtime {
     type Event = Event;
     type MultiCurrency = Tokens;
@@ -492,6 +500,30 @@ impl module_currencies::Config for Runtime {
     type WeightInfo = ();
     type AddressMapping = EvmAddressMapping<Runtime>;
     type EVMBridge = EVMBridge;
+    type Erc20HoldingAccount = Erc20HoldingAccount;
+    type Erc20SlashBeneficiary = TreasuryModuleAccount;
+    type Erc20Registry = AssetRegistry;
+    type Erc20ReserveAccounts = EvmLinkedAccounts;
+}
+
+/// The accounts with a claimed EVM address, the only ones which could
+/// reserve ERC20 tokens before the holding account.
+pub struct EvmLinkedAccounts;
+impl module_currencies::migrations::v1::Erc20ReserveAccounts<AccountId> for EvmLinkedAccounts {
+    fn accounts(start: Option<AccountId>, limit: u32) -> Vec<(AccountId, H160)> {
+        type EvmAddresses = module_evm_accounts::EvmAddresses<Runtime>;
+        match start {
+            Some(start) => EvmAddresses::iter_from(EvmAddresses::hashed_key_for(&start))
+                .take(limit as usize)
+                .collect(),
+            None => EvmAddresses::iter().take(limit as usize).collect(),
+        }
+    }
+
+    fn reserve_weight() -> Weight {
+        // reading the reserve and transferring it
+        <GasToWeight as sp_runtime::traits::Convert<u64, Weight>>::convert(2 * module_evm_bridge::GAS_LIMIT)
+    }
 }
 
 parameter_type_with_key! {
@@ -565,24 +597,28 @@ impl module_evm_accounts::Config for Runtime {
     type WeightInfo = weights::evm_accounts::WeightInfo<Runtime>;
 }
 
-#[cfg(feature = "with-ethereum-compatibility")]
-static ISTANBUL_CONFIG: evm::Config = evm::Config::istanbul();
-
 parameter_types! {
     //In [3]: random.randint(1000, 100_000)
     //Out[3]: 13939
     pub const ChainId: u64 = 13939;
+    // the fee of one gas is the fee of its weight
+    pub MinimumGasPrice: Balance = MILLI_OVR / Balance::from(ExtrinsicBaseWeight::get());
     // 10 OVR minimum storage deposit
     pub const NewContractExtraBytes: u32 = 10_000;
     pub const StorageDepositPerByte: Balance = 1 * MILLI_OVR;
     pub const MaxCodeSize: u32 = 60 * 1024;
+    pub const MaxCodeHistory: u32 = 10;
     pub NetworkContractSource: H160 = H160::from_low_u64_be(0);
     pub const DeveloperDeposit: Balance = 1_000 * OVR;
     pub const DeploymentFee: Balance    = 100 * OVR;
+    // the base fee changes by an eighth of the deviation of the block fullness from the target
+    pub BaseFeeAdjustmentVariable: Multiplier = Multiplier::saturating_from_rational(1, 8);
+    // the base fee is at least the minimum gas price
+    pub MinimumBaseFeeMultiplier: Multiplier = Multiplier::saturating_from_integer(1);
 }
 
 pub type MultiCurrencyPrecompile =
-    runtime_common::MultiCurrencyPrecompile<AccountId, EvmAddressMapping<Runtime>, Currencies>;
+    runtime_common::MultiCurrencyPrecompile<AccountId, EvmAddressMapping<Runtime>, Currencies, EVM>;
 pub type StateRentPrecompile = runtime_common::StateRentPrecompile<AccountId, EvmAddressMapping<Runtime>, EVM>;
 pub type ScheduleCallPrecompile = runtime_common::ScheduleCallPrecompile<
     AccountId,
@@ -595,6 +631,53 @@ pub type ScheduleCallPrecompile = runtime_common::ScheduleCallPrecompile<
     Runtime,
 >;
 
+/// The calls Solidity contracts can dispatch through the dispatch
+/// precompile. Transfers of ERC20 currencies are left out, they would call
+/// back into the EVM.
+pub struct DispatchPrecompileCallFilter;
+impl Contains<Call> for DispatchPrecompileCallFilter {
+    fn contains(call: &Call) -> bool {
+        matches!(
+            call,
+            Call::Balances(pallet_balances::Call::transfer(..))
+                | Call::Balances(pallet_balances::Call::transfer_keep_alive(..))
+                | Call::Currencies(module_currencies::Call::transfer(_, CurrencyId::Token(_), _))
+                | Call::Currencies(module_currencies::Call::transfer(_, CurrencyId::DEXShare(..), _))
+                | Call::Currencies(module_currencies::Call::transfer_native_currency(..))
+                | Call::Staking(pallet_staking::Call::bond(..))
+                | Call::Staking(pallet_staking::Call::bond_extra(..))
+                | Call::Staking(pallet_staking::Call::unbond(..))
+                | Call::Staking(pallet_staking::Call::withdraw_unbonded(..))
+                | Call::Staking(pallet_staking::Call::rebond(..))
+                | Call::Staking(pallet_staking::Call::nominate(..))
+                | Call::Staking(pallet_staking::Call::chill(..))
+                | Call::Staking(pallet_staking::Call::set_payee(..))
+                | Call::Staking(pallet_staking::Call::payout_stakers(..))
+                | Call::Identity(pallet_identity::Call::set_identity(..))
+                | Call::Identity(pallet_identity::Call::clear_identity(..))
+                | Call::Identity(pallet_identity::Call::request_judgement(..))
+                | Call::Identity(pallet_identity::Call::cancel_request(..))
+                | Call::Poc(module_poc::Call::commit(..))
+                | Call::Poc(module_poc::Call::add_funds(..))
+                | Call::Poc(module_poc::Call::unbond(..))
+                | Call::Poc(module_poc::Call::withdraw(..))
+                | Call::Poc(module_poc::Call::vote_candidate(..))
+                | Call::Poc(module_poc::Call::claim_rewards(..))
+        )
+    }
+}
+
+pub type DispatchPrecompile = runtime_common::DispatchPrecompile<
+    AccountId,
+    EvmAddressMapping<Runtime>,
+    Runtime,
+    Call,
+    DispatchPrecompileCallFilter,
+    WeightToGas,
+>;
+
+pub type PocPrecompile = runtime_common::PocPrecompile<AccountId, EvmAddressMapping<Runtime>, Runtime, WeightToGas>;
+
 impl module_evm::Config for Runtime {
     type AddressMapping = EvmAddressMapping<Runtime>;
     type Currency = Balances;
@@ -602,26 +685,38 @@ impl module_evm::Config for Runtime {
     type NewContractExtraBytes = NewContractExtraBytes;
     type StorageDepositPerByte = StorageDepositPerByte;
     type MaxCodeSize = MaxCodeSize;
+    type MaxCodeHistory = MaxCodeHistory;
     type Event = Event;
     type Precompiles = runtime_common::AllPrecompiles<
         SystemContractsFilter,
+        runtime_common::ContractsFilter<Runtime>,
         MultiCurrencyPrecompile,
         StateRentPrecompile,
         ScheduleCallPrecompile,
+        DispatchPrecompile,
+        PocPrecompile,
     >;
     type ChainId = ChainId;
+    type MinimumGasPrice = MinimumGasPrice;
+    type TransactionByteFee = TransactionByteFee;
     type GasToWeight = GasToWeight;
+    type BaseFeeUpdate =
+        TargetedFeeAdjustment<Self, TargetBlockFullness, BaseFeeAdjustmentVariable, MinimumBaseFeeMultiplier>;
+    type FindAuthor = pallet_session::FindAccountFromAuthorIndex<Self, Babe>;
+    type BlockGasLimit = BlockGasLimit;
+    type Randomness = pallet_babe::RandomnessFromOneEpochAgo<Runtime>;
     type ChargeTransactionPayment = module_transaction_payment::ChargeTransactionPayment<Runtime>;
     type NetworkContractOrigin = EnsureRoot<AccountId>; // todo: EnsureRootOrTwoThridsTechCouncil
     type NetworkContractSource = NetworkContractSource;
     type DeveloperDeposit = DeveloperDeposit;
     type DeploymentFee = DeploymentFee;
     type FreeDeploymentOrigin = EnsureRoot<AccountId>; // todo: EnsureRootOrTwoThridsTechCouncil
+    type PrecompileOrigin = EnsureRootOrTwoThridsTechCouncil;
     type WeightInfo = weights::evm::WeightInfo<Runtime>;
 
     #[cfg(feature = "with-ethereum-compatibility")]
     fn config() -> &'static evm::Config {
-        &ISTANBUL_CONFIG
+        &module_evm::LONDON_CONFIG
     }
 }
 
@@ -629,6 +724,13 @@ impl module_evm_bridge::Config for Runtime {
     type EVM = EVM;
 }
 
+impl module_asset_registry::Config for Runtime {
+    type Event = Event;
+    type EVMBridge = EVMBridge;
+    type RegisterOrigin = EnsureRootOrTwoThridsTechCouncil;
+    type WeightInfo = ();
+}
+
 parameter_types! {
     // note: if we add other native tokens (OUSD) we have to set native
     // existential deposit to 0 or check for other tokens on account pruning
@@ -698,7 +800,7 @@ type EnsureRootOrThreeFourthsTechCouncil = EnsureOneOf<
 >;
 
 parameter_types! {
-    pub const EraDuration: BlockNumber = 7 * DAYS;
+    pub const EraDuration: Moment = 7 * MILLISECS_PER_DAY;
     pub const TechCouncilMotionDuration: BlockNumber = 7 * DAYS;
 
     pub const TechCouncilMaxMembers: u32 = 9; // 21 eventually
@@ -710,6 +812,8 @@ parameter_types! {
     pub const CandidacyDeposit: Balance =   1_000_000 * primitives::currency::OVR;
     pub const MinLockAmount: Balance =        100_000 * primitives::currency::OVR;
     pub const TotalLockedCap: Balance = 2_000_000_000 * primitives::currency::OVR;
+    pub const VoterRewardsHistoryDepth: u32 = 52;
+    pub const MaxCommitmentsPerAccount: u32 = 16;
 }
 
 impl pallet_collective::Config<TechCouncilInstance> for Runtime {
@@ -723,10 +827,19 @@ impl pallet_collective::Config<TechCouncilInstance> for Runtime {
     type WeightInfo = ();
 }
 
+/// Sends the slashed funds to the treasury.
+pub struct SlashToTreasury;
+impl OnUnbalanced<pallet_balances::NegativeImbalance<Runtime>> for SlashToTreasury {
+    fn on_nonzero_unbalanced(amount: pallet_balances::NegativeImbalance<Runtime>) {
+        Balances::resolve_creating(&TreasuryModuleAccount::get(), amount);
+    }
+}
+
 impl module_poc::Config for Runtime {
     type Event = Event;
     type Currency = Balances;
     type EraDuration = EraDuration;
+    type MillisecsPerBlock = ExpectedBlockTime;
     type NominatorAPY = NominatorAPY;
     type CouncilInflation = CouncilInflation;
     type CandidacyDeposit = CandidacyDeposit;
@@ -734,7 +847,11 @@ impl module_poc::Config for Runtime {
     type TotalLockedCap = TotalLockedCap;
     type MaxCandidates = TechCouncilMaxCandidates;
     type MaxMembers = TechCouncilMaxMembers;
+    type HistoryDepth = VoterRewardsHistoryDepth;
+    type MaxCommitmentsPerAccount = MaxCommitmentsPerAccount;
     type MembershipChanged = TechCouncil;
+    type SlashOrigin = EnsureRootOrThreeFourthsTechCouncil;
+    type Slash = SlashToTreasury;
     type WeightInfo = ();
 }
 
@@ -773,7 +890,7 @@ construct_runtime!(
 
         // Tokens & Fees
         Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>} = 6,
-        Currencies: module_currencies::{Pallet, Call, Event<T>} = 7,
+        Currencies: module_currencies::{Pallet, Call, Storage, Event<T>} = 7,
         Tokens: orml_tokens::{Pallet, Storage, Event<T>, Config<T>} = 8,
         TransactionPayment: module_transaction_payment::{Pallet, Call, Storage} = 9,
 
@@ -783,8 +900,9 @@ construct_runtime!(
 
         // Smart contracts
         EvmAccounts: module_evm_accounts::{Pallet, Call, Storage, Event<T>} = 20,
-        EVM: module_evm::{Pallet, Config<T>, Call, Storage, Event<T>} = 21,
+        EVM: module_evm::{Pallet, Config<T>, Call, Storage, Event<T>, ValidateUnsigned} = 21,
         EVMBridge: module_evm_bridge::{Pallet} = 22,
+        AssetRegistry: module_asset_registry::{Pallet, Call, Storage, Config, Event} = 23,
 
         // Consensus
         Authorship: pallet_authorship::{Pallet, Call, Storage, Inherent} = 30,
@@ -834,8 +952,26 @@ pub type SignedPayload = generic::SignedPayload<Call, SignedExtra>;
 /// Extrinsic type that has already been checked.
 pub type CheckedExtrinsic = generic::CheckedExtrinsic<AccountId, Call, SignedExtra>;
 /// Executive: handles dispatch to the various modules.
-pub type Executive =
-    frame_executive::Executive<Runtime, Block, frame_system::ChainContext<Runtime>, Runtime, AllPallets>;
+pub type Executive = frame_executive::Executive<
+    Runtime,
+    Block,
+    frame_system::ChainContext<Runtime>,
+    Runtime,
+    AllPallets,
+    (
+        runtime_common::precompile::ReplaceMirroredTokenCode<Runtime>,
+        module_asset_registry::migrations::v1::RegisterErc20Contracts<Runtime, Erc20ContractsInUse>,
+        module_currencies::migrations::v1::MigrateErc20Reserves<Runtime>,
+    ),
+>;
+
+parameter_types! {
+    /// The ERC20 tokens used as `CurrencyId::ERC20` before the asset
+    /// registry, as agreed by governance for the upgrade. Their reserves are
+    /// moved to the holding account, other tokens are registered with
+    /// `register_erc20`.
+    pub Erc20ContractsInUse: Vec<H160> = vec![];
+}
 
 impl<LocalCall> frame_system::offchain::CreateSignedTransaction<LocalCall> for Runtime
 where
@@ -1071,6 +1207,18 @@ impl_runtime_apis! {
         }
     }
 
+    impl module_asset_registry_rpc_runtime_api::AssetRegistryApi<Block> for Runtime {
+        fn erc20_assets() -> Vec<(H160, Erc20Metadata)> {
+            AssetRegistry::erc20_assets_list()
+        }
+    }
+
+    impl module_poc_rpc_runtime_api::PocApi<Block, AccountId, Balance> for Runtime {
+        fn pending_rewards(who: AccountId) -> Vec<(u32, Balance)> {
+            Poc::account_pending_rewards(&who)
+        }
+    }
+
     impl module_evm_rpc_runtime_api::EVMRuntimeRPCApi<Block, Balance> for Runtime {
         fn call(
             from: H160,
@@ -1080,21 +1228,24 @@ impl_runtime_apis! {
             gas_limit: u64,
             storage_limit: u32,
             estimate: bool,
+            state_override: Vec<(H160, AccountOverride)>,
         ) -> Result<CallInfo, sp_runtime::DispatchError> {
             let mut config = <Runtime as module_evm::Config>::config().clone();
             if estimate {
                 config.estimate = true;
             }
-            module_evm::Runner::<Runtime>::call(
-                from,
-                from,
-                to,
-                data,
-                value,
-                gas_limit,
-                storage_limit,
-                &config,
-            )
+            module_evm::Pallet::<Runtime>::with_state_override(state_override, || {
+                module_evm::Runner::<Runtime>::call(
+                    from,
+                    from,
+                    to,
+                    data,
+                    value,
+                    gas_limit,
+                    storage_limit,
+                    &config,
+                )
+            })
         }
 
         fn create(
@@ -1104,19 +1255,22 @@ impl_runtime_apis! {
             gas_limit: u64,
             storage_limit: u32,
             estimate: bool,
+            state_override: Vec<(H160, AccountOverride)>,
         ) -> Result<CreateInfo, sp_runtime::DispatchError> {
             let mut config = <Runtime as module_evm::Config>::config().clone();
             if estimate {
                 config.estimate = true;
             }
-            module_evm::Runner::<Runtime>::create(
-                from,
-                data,
-                value,
-                gas_limit,
-                storage_limit,
-                &config,
-            )
+            module_evm::Pallet::<Runtime>::with_state_override(state_override, || {
+                module_evm::Runner::<Runtime>::create(
+                    from,
+                    data,
+                    value,
+                    gas_limit,
+                    storage_limit,
+                    &config,
+                )
+            })
         }
 
         fn get_estimate_resources_request(
@@ -1153,6 +1307,87 @@ impl_runtime_apis! {
             request.ok_or(sp_runtime::DispatchError::Other("Invalid parameter extrinsic, not evm Call"))
         }
 
+        fn chain_id() -> u64 {
+            ChainId::get()
+        }
+
+        fn account_basic(address: H160) -> Account {
+            module_evm::Pallet::<Runtime>::account_basic(&address)
+        }
+
+        fn get_code(address: H160) -> Vec<u8> {
+            module_evm::Pallet::<Runtime>::code_at_address(&address)
+        }
+
+        fn get_storage_at(address: H160, index: H256) -> H256 {
+            module_evm::Pallet::<Runtime>::account_storages(address, index)
+        }
+
+        fn gas_price() -> U256 {
+            EVM::base_fee_per_gas()
+        }
+
+        fn block_receipts() -> Vec<ExtrinsicReceipt> {
+            let events = System::events().into_iter().filter_map(|record| match (record.phase, record.event) {
+                (frame_system::Phase::ApplyExtrinsic(index), Event::EVM(event)) => Some((index, event)),
+                _ => None,
+            });
+            module_evm::Pallet::<Runtime>::extrinsic_receipts(events)
+        }
+
+        fn block_timestamp() -> u64 {
+            Timestamp::now() / 1000
+        }
+
+        fn block_coinbase() -> H160 {
+            EVM::block_coinbase()
+        }
+
+        fn block_gas_limit() -> u64 {
+            BlockGasLimit::get()
+        }
+
+        fn convert_transaction(transaction: Vec<u8>) -> <Block as BlockT>::Extrinsic {
+            UncheckedExtrinsic::new_unsigned(Call::EVM(module_evm::Call::eth_transact(transaction)))
+        }
+
+        fn trace_call(
+            from: H160,
+            to: Option<H160>,
+            data: Vec<u8>,
+            value: Balance,
+            gas_limit: u64,
+            storage_limit: u32,
+            tracer: TracerConfig,
+        ) -> Result<Trace, sp_runtime::DispatchError> {
+            module_evm::Pallet::<Runtime>::trace(from, to, data, value, gas_limit, storage_limit, tracer)
+        }
+
+        fn trace_extrinsic(
+            extrinsics: Vec<<Block as BlockT>::Extrinsic>,
+            index: u32,
+            tracer: TracerConfig,
+        ) -> Result<Trace, sp_runtime::DispatchError> {
+            let index = index as usize;
+            let extrinsic = extrinsics
+                .get(index)
+                .cloned()
+                .ok_or(sp_runtime::DispatchError::Other("Invalid parameter index"))?;
+
+            // the state of the extrinsic is the state after the extrinsics before it
+            for extrinsic in extrinsics.into_iter().take(index) {
+                let _ = Executive::apply_extrinsic(extrinsic);
+            }
+
+            // the whole extrinsic is applied, with its signed extensions, and
+            // the EVM executions nested in its dispatch are traced
+            module_evm::Pallet::<Runtime>::trace_executions(tracer, || {
+                Executive::apply_extrinsic(extrinsic)
+                    .map(|_| ())
+                    .map_err(|_| sp_runtime::DispatchError::Other("Invalid transaction"))
+            })
+        }
+
     }
 
     #[cfg(feature = "runtime-benchmarks")]
@@ -41,12 +41,20 @@ impl<T: frame_system::Config> module_evm::WeightInfo for WeightInfo<T> {
     }
     fn set_code() -> Weight {
         (238_383_000 as Weight)
-            .saturating_add(DbWeight::get().reads(4 as Weight))
-            .saturating_add(DbWeight::get().writes(3 as Weight))
+            .saturating_add(DbWeight::get().reads(5 as Weight))
+            .saturating_add(DbWeight::get().writes(4 as Weight))
+    }
+    fn rollback_code() -> Weight {
+        (230_000_000 as Weight)
+            .saturating_add(DbWeight::get().reads(3 as Weight))
+            .saturating_add(DbWeight::get().writes(2 as Weight))
     }
     fn selfdestruct() -> Weight {
         (544_511_000 as Weight)
             .saturating_add(DbWeight::get().reads(7 as Weight))
             .saturating_add(DbWeight::get().writes(5 as Weight))
     }
+    fn set_precompile_enabled() -> Weight {
+        (25_000_000 as Weight).saturating_add(DbWeight::get().writes(1 as Weight))
+    }
 }
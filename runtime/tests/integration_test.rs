@@ -147,7 +147,7 @@ fn deploy_contract(account: AccountId) -> Result<H160, DispatchError> {
     EVM::create(Origin::signed(account), contract, 0, 1000000000, 1000000000)
         .map_or_else(|e| Err(e.error), |_| Ok(()))?;
 
-    if let Event::EVM(module_evm::Event::Created(address)) = System::events().iter().last().unwrap().event {
+    if let Event::EVM(module_evm::Event::Created(_, address, _)) = System::events().iter().last().unwrap().event {
         Ok(address)
     } else {
         Err("deploy_contract failed".into())
@@ -458,8 +458,10 @@ fn test_evm_module() {
             let bob_address = EvmAccounts::eth_address(&bob());
 
             let contract = deploy_contract(alice_account_id()).unwrap();
-            let event = Event::EVM(module_evm::Event::Created(contract));
-            assert_eq!(last_event(), event);
+            assert!(matches!(
+                last_event(),
+                Event::EVM(module_evm::Event::Created(_, address, _)) if address == contract
+            ));
 
             assert_ok!(EVM::transfer_maintainer(
                 Origin::signed(alice_account_id()),
@@ -542,7 +544,7 @@ fn test_evm_module() {
                 ));
 
                 match System::events().iter().last().unwrap().event {
-                    Event::module_evm(module_evm::Event::Created(_)) => {}
+                    Event::module_evm(module_evm::Event::Created(..)) => {}
                     _ => {
                         println!(
                             "contract {:?} create failed, event: {:?}",